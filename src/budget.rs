@@ -0,0 +1,91 @@
+//! Work budget of one transaction, so a single pathological one can't run the Marine call into
+//! its timeout and stall the wall. Work is counted in bytes scanned or read from storage, never
+//! in time: every peer has to reach the same verdict, and a clock would let a slow peer reject
+//! what a fast one accepts. Scanners charge what they look at and stop early once the budget is
+//! gone, on_execute then rejects the transaction whatever the handler returned.
+
+use crate::defaults::DEFAULT_WORK_BUDGET;
+use std::cell::Cell;
+
+thread_local! {
+    static STARTED: Cell<u64> = const { Cell::new(u64::MAX) };
+    static LEFT: Cell<u64> = const { Cell::new(u64::MAX) };
+    static OVERRUN: Cell<bool> = const { Cell::new(false) };
+}
+
+/**
+ * Starts a transaction with the default budget
+ */
+pub fn start() {
+    start_with(DEFAULT_WORK_BUDGET);
+}
+
+pub fn start_with(units: u64) {
+    STARTED.with(|started| started.set(units));
+    LEFT.with(|left| left.set(units));
+    OVERRUN.with(|overrun| overrun.set(false));
+}
+
+/**
+ * Spends `units`, false once the budget is gone and the caller should stop
+ */
+pub fn charge(units: usize) -> bool {
+    let units = units as u64;
+    let within = LEFT.with(|left| match left.get().checked_sub(units) {
+        Some(rest) => {
+            left.set(rest);
+            true
+        }
+        None => {
+            left.set(0);
+            false
+        }
+    });
+    if !within {
+        OVERRUN.with(|overrun| overrun.set(true));
+    }
+    within
+}
+
+/**
+ * Units charged since `start`, the same on every peer running the transaction
+ */
+pub fn spent() -> u64 {
+    STARTED.with(Cell::get) - LEFT.with(Cell::get)
+}
+
+/**
+ * True when some work was cut short since `start`
+ */
+pub fn overrun() -> bool {
+    OVERRUN.with(Cell::get)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_until_the_budget_is_gone() {
+        start_with(10);
+        assert!(charge(4));
+        assert_eq!(spent(), 4);
+        assert!(charge(6));
+        assert!(!overrun());
+        assert!(!charge(1));
+        assert!(overrun());
+        start();
+        assert!(!overrun());
+        assert!(charge(1024));
+    }
+
+    #[test]
+    fn storage_is_not_read_past_the_budget() {
+        crate::storage::ipfs::test_store::put("budget-post", "gm");
+        start_with(1);
+        assert!(crate::storage::ipfs::get_metadata("budget-post").is_err());
+        assert!(overrun());
+        start();
+        assert!(crate::storage::ipfs::get_metadata("budget-post").is_ok());
+    }
+}
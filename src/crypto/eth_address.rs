@@ -0,0 +1,113 @@
+//! Ethereum addresses as they reach the contract: `0x` and 40 hex digits, in one case or in the
+//! mixed case of an EIP-55 checksum. A mixed-case address whose checksum is off is a typo, not
+//! a different account, so it's refused rather than lowercased into someone else's key.
+//! Addresses are compared and stored lowercase, and shown checksummed.
+
+use super::hash::keccak256;
+
+fn hex_digits(address: &str) -> Option<&str> {
+    address
+        .strip_prefix("0x")
+        .filter(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn checksummed(hex: &str) -> String {
+    let hex = hex.to_lowercase();
+    let hash = keccak256(&hex);
+    let digits: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", digits)
+}
+
+/**
+ * The address lowercased, refusing anything else and mixed case with a wrong checksum
+ */
+pub fn parse_address(address: &str) -> Result<String, &'static str> {
+    let address = address.trim();
+    let hex = hex_digits(address).ok_or("Not an address, expected 0x and 40 hex digits.")?;
+    let single_case = hex == hex.to_lowercase() || hex == hex.to_uppercase();
+    if !single_case && checksummed(hex) != address {
+        return Err("Address checksum does not match, check it for a typo.");
+    }
+    Ok(address.to_lowercase())
+}
+
+pub fn is_address(address: &str) -> bool {
+    parse_address(address).is_ok()
+}
+
+/**
+ * EIP-55 form of an address given in any case
+ */
+pub fn checksum_address(address: &str) -> Option<String> {
+    hex_digits(address.trim()).map(checksummed)
+}
+
+/**
+ * `0x7E5F…5Bdf` for display, keys that aren't addresses come back as they are
+ */
+pub fn short_address(address: &str) -> String {
+    match checksum_address(address) {
+        Some(checksum) => format!("{}…{}", &checksum[..6], &checksum[38..]),
+        None => address.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the EIP-55 reference vectors
+    const CHECKSUMMED: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn checksums_match_eip55() {
+        for address in CHECKSUMMED {
+            assert_eq!(
+                checksum_address(&address.to_lowercase()).as_deref(),
+                Some(address)
+            );
+            assert_eq!(parse_address(address), Ok(address.to_lowercase()));
+        }
+        assert_eq!(checksum_address("0x5aAeb6"), None);
+    }
+
+    #[test]
+    fn parsing_refuses_typos() {
+        let lower = CHECKSUMMED[0].to_lowercase();
+        assert_eq!(parse_address(&format!(" {} ", lower)), Ok(lower.clone()));
+        assert!(is_address(
+            &CHECKSUMMED[0].to_uppercase().replace("0X", "0x")
+        ));
+        // one digit's case flipped
+        assert!(!is_address("0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert!(!is_address(&lower[2..]));
+        assert!(!is_address("0xzz5F4552091A69125d5DfCb7b8C2659029395Bdf"));
+        assert!(!is_address(&lower[..41]));
+        assert_eq!(
+            parse_address("0xabc"),
+            Err("Not an address, expected 0x and 40 hex digits.")
+        );
+    }
+
+    #[test]
+    fn short_addresses_keep_the_checksum() {
+        assert_eq!(short_address(&CHECKSUMMED[0].to_lowercase()), "0x5aAe…eAed");
+        assert_eq!(short_address("0xmod"), "0xmod");
+    }
+}
@@ -0,0 +1,119 @@
+//! The hashes the contract computes, in one place: keccak256 for everything Ethereum reads
+//! (content hashes, proof of work, Merkle roots, addresses), sha256 for what IPFS reads, and
+//! multihash / CIDv1 framing so a content hash can be compared with a stored CID.
+
+use crate::encoding::base32;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+pub fn keccak256(data: impl AsRef<[u8]>) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/**
+ * keccak256 over `parts` one after the other, without joining them first
+ */
+pub fn keccak256_concat(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/**
+ * 0x-prefixed keccak256 of `data`, how content hashes are referenced in payloads
+ */
+pub fn keccak_hex(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(keccak256(data)))
+}
+
+/**
+ * FIPS 180-4 SHA-256
+ */
+pub fn sha256(data: impl AsRef<[u8]>) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/**
+ * Multicodec code of sha256 in a multihash
+ */
+pub static MULTIHASH_SHA2_256: u64 = 0x12;
+/**
+ * Multicodec of raw bytes, the codec of a CID naming a file added without UnixFS chunking
+ */
+pub static MULTICODEC_RAW: u64 = 0x55;
+
+fn push_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/**
+ * `<code><length><digest>`, both prefixes unsigned varints
+ */
+pub fn multihash(code: u64, digest: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    push_varint(&mut out, code);
+    push_varint(&mut out, digest.len() as u64);
+    out.extend_from_slice(digest);
+    out
+}
+
+pub fn sha256_multihash(data: impl AsRef<[u8]>) -> Vec<u8> {
+    multihash(MULTIHASH_SHA2_256, &sha256(data))
+}
+
+/**
+ * CIDv1 of `data` stored as a single raw block, in the default base32 multibase. Matches what
+ * `ipfs add --raw-leaves --cid-version 1` prints for files up to one block.
+ */
+pub fn raw_cid(data: impl AsRef<[u8]>) -> String {
+    let mut cid = vec![];
+    push_varint(&mut cid, 1);
+    push_varint(&mut cid, MULTICODEC_RAW);
+    cid.extend(sha256_multihash(data));
+    format!("b{}", base32::encode(&cid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_matches_the_fips_vectors() {
+        assert_eq!(
+            hex::encode(sha256("")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(sha256("abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex::encode(sha256(
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn keccak_and_framing() {
+        assert_eq!(
+            keccak_hex(b""),
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(keccak256_concat(&[b"a", b"bc"]), keccak256("abc"));
+        assert_eq!(&sha256_multihash("abc")[..2], &[0x12, 0x20]);
+        assert_eq!(multihash(0x300, &[1]), vec![0x80, 0x06, 0x01, 0x01]);
+        // ipfs add --raw-leaves --cid-version 1 of "hello world"
+        assert_eq!(
+            raw_cid("hello world"),
+            "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Binary keccak256 Merkle tree laid out like OpenZeppelin's MerkleProof: each pair is sorted
+//! before hashing, so a proof is just the sibling hashes and L1 contracts can verify it as is.
+//! An odd node at the end of a level is carried up unchanged.
+
+use super::hash::keccak256_concat;
+
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    keccak256_concat(&[low, high])
+}
+
+/**
+ * Root over `leaves` in the order given, all zeroes when there are none
+ */
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash::keccak256;
+
+    fn leaf(data: &str) -> [u8; 32] {
+        keccak256(data)
+    }
+
+    #[test]
+    fn pairs_are_hashed_sorted() {
+        let (a, b, c) = (leaf("a"), leaf("b"), leaf("c"));
+        assert_eq!(merkle_root(&[]), [0u8; 32]);
+        assert_eq!(merkle_root(&[a]), a);
+        assert_eq!(merkle_root(&[a, b]), merkle_root(&[b, a]));
+        assert_eq!(merkle_root(&[a, b, c]), hash_pair(&hash_pair(&a, &b), &c));
+        assert_ne!(merkle_root(&[a, b, c]), merkle_root(&[a, c, b]));
+    }
+}
@@ -0,0 +1,89 @@
+pub mod eth_address;
+pub mod hash;
+pub mod merkle;
+pub mod pow;
+pub mod rng;
+pub mod secp256k1;
+
+pub use hash::keccak_hex;
+
+use crate::encoding::decode_hex;
+
+/**
+ * EIP-191 personal_sign digest, what wallets sign for a plain text message
+ */
+pub fn personal_message_hash(message: &str) -> [u8; 32] {
+    let prefix = format!("\x19Ethereum Signed Message:\n{}", message.len());
+    hash::keccak256_concat(&[prefix.as_bytes(), message.as_bytes()])
+}
+
+/**
+ * True when `signature` (hex, 65 bytes) is a personal_sign of `message` by `address`
+ */
+pub fn verify_personal_signature(address: &str, message: &str, signature: &str) -> bool {
+    let signature = match decode_hex(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+
+    match secp256k1::recover_address(&personal_message_hash(message), &signature) {
+        Some(signer) => signer.eq_ignore_ascii_case(address),
+        None => false,
+    }
+}
+
+/**
+ * Test wallet with the private key `secret`: its address and a personal_sign helper
+ */
+#[cfg(test)]
+pub mod test_wallet {
+    use primitive_types::U256;
+
+    pub fn address(secret: u64) -> String {
+        super::secp256k1::address_of(U256::from(secret))
+    }
+
+    pub fn sign(secret: u64, message: &str) -> String {
+        let hash = super::personal_message_hash(message);
+        format!(
+            "0x{}",
+            hex::encode(super::secp256k1::sign(&hash, U256::from(secret)))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // personal_sign("hello") with the private key 0x...01
+    const ADDRESS: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+    const SIGNATURE: &str = "0x9377c312145a5afb911bf9e8c067bcf6094c533603687850df502b61290bbf5e181a1884db35f4f0fc3aa22d50bddc114664e771b3baf737e96b5a1564702f6b1c";
+
+    #[test]
+    fn personal_message_hash_matches_eip191() {
+        assert_eq!(
+            hex::encode(personal_message_hash("hello")),
+            "50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"
+        );
+    }
+
+    #[test]
+    fn verifies_personal_signatures() {
+        assert!(verify_personal_signature(ADDRESS, "hello", SIGNATURE));
+        assert!(!verify_personal_signature(ADDRESS, "hellO", SIGNATURE));
+        assert!(!verify_personal_signature(ADDRESS, "hello", "0x1234"));
+    }
+
+    #[test]
+    fn test_wallet_signatures_verify() {
+        assert!(test_wallet::address(1).eq_ignore_ascii_case(ADDRESS));
+        let signature = test_wallet::sign(7, "gm");
+        assert!(verify_personal_signature(
+            &test_wallet::address(7),
+            "gm",
+            &signature
+        ));
+        assert!(!verify_personal_signature(ADDRESS, "gm", &signature));
+    }
+}
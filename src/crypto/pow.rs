@@ -0,0 +1,92 @@
+//! Hashcash style proof of work: the client searches a nonce so that
+//! keccak256(len || data_key || len || sender || keccak256(post) || len || nonce) starts with
+//! `difficulty` zero bits, every `len` being the byte length of the field after it as a 4 byte
+//! big endian integer, and `post` the payload as sent without its `nonce` field, compact JSON
+//! with sorted keys. Binding it to the thread, the sender and the content makes every post cost
+//! its own work, a nonce found once is worth nothing for the next post, and the length prefixes
+//! keep one field from running into the next.
+
+use super::hash::{keccak256, keccak256_concat};
+use serde_json::Value;
+
+/**
+ * What the work is done over, the payload as parsed (before any migration or lenient
+ * stripping, which the client can't know about) with the nonce field taken out
+ */
+pub fn work_content(payload: &Value) -> Vec<u8> {
+    let mut payload = payload.clone();
+    if let Some(fields) = payload.as_object_mut() {
+        fields.remove("nonce");
+    }
+    payload.to_string().into_bytes()
+}
+
+pub fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        if *byte == 0 {
+            bits += 8;
+        } else {
+            return bits + byte.leading_zeros();
+        }
+    }
+    bits
+}
+
+pub fn verify(data_key: &str, sender: &str, content: &[u8], nonce: &str, difficulty: u32) -> bool {
+    let sender = sender.to_lowercase();
+    let len = |field: &[u8]| (field.len() as u32).to_be_bytes();
+    let hash = keccak256_concat(&[
+        &len(data_key.as_bytes()),
+        data_key.as_bytes(),
+        &len(sender.as_bytes()),
+        sender.as_bytes(),
+        &keccak256(content),
+        &len(nonce.as_bytes()),
+        nonce.as_bytes(),
+    ]);
+    leading_zero_bits(&hash) >= difficulty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0, 0x10, 0xff]), 11);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0, 0]), 16);
+    }
+
+    #[test]
+    fn found_nonce_verifies_for_one_post_only() {
+        let nonce = (0u64..)
+            .map(|n| n.to_string())
+            .find(|n| verify("topic", "0xa", b"gm", n, 8))
+            .unwrap();
+        assert!(verify("topic", "0xA", b"gm", &nonce, 8));
+        assert!(verify("topic", "0xa", b"anything", "anything", 0));
+
+        // another post, sender or thread needs its own nonce
+        assert!(!verify("topic", "0xa", b"gn", &nonce, 8));
+        assert!(!verify("topic", "0xb", b"gm", &nonce, 8));
+        assert!(!verify("other", "0xa", b"gm", &nonce, 8));
+    }
+
+    #[test]
+    fn fields_cannot_shift_into_each_other() {
+        let nonce = (0u64..)
+            .map(|n| n.to_string())
+            .find(|n| verify("topic", "0xa", b"gm", n, 12))
+            .unwrap();
+        assert!(!verify("topic0", "xa", b"gm", &nonce, 12));
+        assert!(!verify("topi", "c0xa", b"gm", &nonce, 12));
+    }
+
+    #[test]
+    fn work_content_leaves_the_nonce_out() {
+        let payload = serde_json::json!({ "text": "gm", "nonce": "42", "image": "x" });
+        assert_eq!(work_content(&payload), br#"{"image":"x","text":"gm"}"#);
+    }
+}
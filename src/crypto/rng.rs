@@ -0,0 +1,108 @@
+//! Deterministic rolls for features that need chance, trait generation or picking a featured
+//! post. Every peer has to reach the same outcome, so a roll never reads a clock or the OS: it
+//! is keccak256 of its seed (the parts joined with `:`), then keccak256(seed || n) for the n-th
+//! further 32 bytes once those run out. Seed from what all peers agree on, the transaction
+//! hash and data_key, and add a part per use so unrelated features don't roll alike.
+
+use super::hash::{keccak256, keccak256_concat};
+use crate::types::Transaction;
+
+pub struct Roll {
+    seed: [u8; 32],
+    block: [u8; 32],
+    blocks: u64,
+    offset: usize,
+}
+
+impl Roll {
+    pub fn new(parts: &[&str]) -> Self {
+        let seed = keccak256(parts.join(":"));
+        Roll {
+            seed,
+            block: seed,
+            blocks: 0,
+            offset: 0,
+        }
+    }
+
+    /**
+     * Seeded by the transaction hash and data_key, plus `purpose`
+     */
+    pub fn from_transaction(transaction: &Transaction, purpose: &str) -> Self {
+        Roll::new(&[&transaction.hash, &transaction.data_key, purpose])
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        if self.offset == self.block.len() {
+            self.blocks += 1;
+            self.block = keccak256_concat(&[&self.seed, &self.blocks.to_be_bytes()]);
+            self.offset = 0;
+        }
+        let bytes = self.block[self.offset..self.offset + 8].try_into().unwrap();
+        self.offset += 8;
+        u64::from_be_bytes(bytes)
+    }
+
+    /**
+     * In `0..n`, 0 when n is 0. The modulo bias is below 2^-32 for any n a wall uses.
+     */
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n.max(1)
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        let i = self.below(items.len() as u64);
+        items.get(i as usize)
+    }
+
+    /**
+     * Value of a `(value, weight)` table, a value twice the weight comes up twice as often
+     */
+    pub fn weighted<'a, T>(&mut self, table: &'a [(T, u64)]) -> Option<&'a T> {
+        weighted_index(table, self.next_u64()).map(|i| &table[i].0)
+    }
+}
+
+fn weighted_index<T>(table: &[(T, u64)], roll: u64) -> Option<usize> {
+    let total: u64 = table.iter().map(|(_, weight)| weight).sum();
+    let mut roll = roll % total.max(1);
+    for (i, (_, weight)) in table.iter().enumerate() {
+        if roll < *weight {
+            return Some(i);
+        }
+        roll -= weight;
+    }
+    table.len().checked_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_are_reproducible_and_follow_the_weights() {
+        let mut roll = Roll::new(&["a", "b"]);
+        let first: Vec<u64> = (0..6).map(|_| roll.next_u64()).collect();
+        let mut again = Roll::new(&["a", "b"]);
+        assert!(first.iter().all(|n| *n == again.next_u64()));
+        assert_ne!(Roll::new(&["a", "c"]).next_u64(), first[0]);
+        // the first 32 bytes are the seed digest itself
+        let digest = keccak256("a:b");
+        assert_eq!(
+            first[0],
+            u64::from_be_bytes(digest[..8].try_into().unwrap())
+        );
+
+        let table = [("common", 3), ("rare", 1)];
+        assert_eq!(weighted_index(&table, 0), Some(0));
+        assert_eq!(weighted_index(&table, 2), Some(0));
+        assert_eq!(weighted_index(&table, 3), Some(1));
+        assert_eq!(weighted_index(&table, 4), Some(0));
+        assert_eq!(weighted_index::<&str>(&[], 4), None);
+
+        let mut roll = Roll::new(&["dice"]);
+        assert!((0..100).all(|_| roll.below(6) < 6));
+        assert_eq!(roll.below(0), 0);
+        assert_eq!(roll.pick::<u8>(&[]), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! secp256k1 public key recovery (ecrecover) to check Ethereum wallet signatures, on the pure Rust
+//! k256 crate. Like Ethereum since EIP-2, only low-s signatures with a recovery id of 0 or 1
+//! (27 or 28) are accepted, so a signature has exactly one valid encoding.
+
+use super::hash::keccak256;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+fn address_of_key(key: &VerifyingKey) -> String {
+    let point = key.to_encoded_point(false);
+    // the uncompressed encoding is 0x04 || x || y, the address hashes x || y
+    format!(
+        "0x{}",
+        hex::encode(&keccak256(&point.as_bytes()[1..])[12..])
+    )
+}
+
+/**
+ * Recovers the 0x-prefixed lowercase address that produced a 65 byte r || s || v signature
+ */
+pub fn recover_address(hash: &[u8; 32], signature: &[u8]) -> Option<String> {
+    if signature.len() != 65 {
+        return None;
+    }
+    let recovery_id = match signature[64] {
+        0 | 27 => RecoveryId::new(false, false),
+        1 | 28 => RecoveryId::new(true, false),
+        _ => return None,
+    };
+    // rejects r or s of zero or past the curve order
+    let signature = Signature::from_slice(&signature[..64]).ok()?;
+    if signature.normalize_s().is_some() {
+        return None;
+    }
+
+    VerifyingKey::recover_from_prehash(hash, &signature, recovery_id)
+        .ok()
+        .map(|key| address_of_key(&key))
+}
+
+#[cfg(test)]
+fn signing_key(secret: primitive_types::U256) -> k256::ecdsa::SigningKey {
+    let mut bytes = [0u8; 32];
+    secret.to_big_endian(&mut bytes);
+    k256::ecdsa::SigningKey::from_slice(&bytes).unwrap()
+}
+
+/**
+ * Test-only signer, RFC 6979 nonces so signatures are deterministic
+ */
+#[cfg(test)]
+pub fn sign(hash: &[u8; 32], secret: primitive_types::U256) -> Vec<u8> {
+    let (signature, recovery_id) = signing_key(secret).sign_prehash_recoverable(hash).unwrap();
+    let mut signature = signature.to_bytes().to_vec();
+    signature.push(27 + recovery_id.to_byte());
+    signature
+}
+
+#[cfg(test)]
+pub fn address_of(secret: primitive_types::U256) -> String {
+    address_of_key(signing_key(secret).verifying_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::elliptic_curve::sec1::FromEncodedPoint;
+    use k256::{AffinePoint, EncodedPoint};
+    use primitive_types::U256;
+
+    const ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+    fn signed() -> ([u8; 32], Vec<u8>) {
+        let hash = keccak256(b"gm");
+        (hash, sign(&hash, U256::from(3)))
+    }
+
+    fn with_s(signature: &[u8], s: U256) -> Vec<u8> {
+        let mut signature = signature.to_vec();
+        s.to_big_endian(&mut signature[32..64]);
+        signature
+    }
+
+    #[test]
+    fn recovers_the_signer() {
+        let (hash, signature) = signed();
+        let signer = address_of(U256::from(3));
+        assert_eq!(recover_address(&hash, &signature), Some(signer.clone()));
+
+        let mut zero_based = signature.clone();
+        zero_based[64] -= 27;
+        assert_eq!(recover_address(&hash, &zero_based), Some(signer.clone()));
+
+        // the other parity recovers some other key
+        let mut flipped = signature.clone();
+        flipped[64] ^= 1;
+        assert_ne!(recover_address(&hash, &flipped), Some(signer));
+    }
+
+    #[test]
+    fn high_s_is_rejected() {
+        let (hash, signature) = signed();
+        let n = U256::from_str_radix(ORDER, 16).unwrap();
+        let s = U256::from_big_endian(&signature[32..64]);
+        // (r, n - s) with the other parity is the same signature, malleated
+        let mut high = with_s(&signature, n - s);
+        high[64] ^= 1;
+        assert_eq!(recover_address(&hash, &high), None);
+    }
+
+    #[test]
+    fn out_of_range_scalars_are_rejected() {
+        let (hash, signature) = signed();
+        let n = U256::from_str_radix(ORDER, 16).unwrap();
+        assert_eq!(
+            recover_address(&hash, &with_s(&signature, U256::zero())),
+            None
+        );
+        assert_eq!(recover_address(&hash, &with_s(&signature, n)), None);
+
+        let mut r_zero = signature.clone();
+        r_zero[..32].fill(0);
+        assert_eq!(recover_address(&hash, &r_zero), None);
+        let mut r_past_order = signature.clone();
+        n.to_big_endian(&mut r_past_order[..32]);
+        assert_eq!(recover_address(&hash, &r_past_order), None);
+    }
+
+    #[test]
+    fn invalid_recovery_ids_and_lengths_are_rejected() {
+        let (hash, signature) = signed();
+        for v in [2, 3, 26, 29, 0xff] {
+            let mut other = signature.clone();
+            other[64] = v;
+            assert_eq!(recover_address(&hash, &other), None);
+        }
+        assert_eq!(recover_address(&hash, &signature[..64]), None);
+        assert_eq!(
+            recover_address(&hash, &[signature.clone(), vec![0]].concat()),
+            None
+        );
+    }
+
+    #[test]
+    fn r_off_the_curve_is_rejected() {
+        let (hash, signature) = signed();
+        // the first x with no point on the curve
+        let x = (1u64..)
+            .map(U256::from)
+            .find(|x| {
+                let mut bytes = [0u8; 32];
+                x.to_big_endian(&mut bytes);
+                let compressed = EncodedPoint::from_bytes([&[2u8][..], &bytes].concat()).unwrap();
+                bool::from(AffinePoint::from_encoded_point(&compressed).is_none())
+            })
+            .unwrap();
+        let mut off_curve = signature.clone();
+        x.to_big_endian(&mut off_curve[..32]);
+        assert_eq!(recover_address(&hash, &off_curve), None);
+    }
+}
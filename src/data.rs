@@ -1,14 +1,1068 @@
+use crate::defaults::{
+    DEFAULT_ACTIVITYSTREAMS_ENABLED, DEFAULT_ATTACHMENT_TYPES, DEFAULT_BLOCKED_LABELS,
+    DEFAULT_BOARDS, DEFAULT_CLASSIFICATION_REQUIRED, DEFAULT_DAILY_POST_QUOTA,
+    DEFAULT_DESCRIPTION_TEMPLATE, DEFAULT_DIGESTS_ENABLED, DEFAULT_DUPLICATE_MAX_DISTANCE,
+    DEFAULT_DUPLICATE_WINDOW, DEFAULT_EMBED_PROVIDERS, DEFAULT_FEE_RECIPIENT,
+    DEFAULT_GOVERNANCE_QUORUM, DEFAULT_IMAGE_TYPES, DEFAULT_JSONLD_ENABLED, DEFAULT_KARMA_ENABLED,
+    DEFAULT_KARMA_MIN_FOR_LINKS, DEFAULT_KARMA_MIN_REACTOR_AGE_SEC, DEFAULT_LENS_ENABLED,
+    DEFAULT_MAX_ATTACHMENTS, DEFAULT_MAX_CLOCK_SKEW_SEC, DEFAULT_MAX_LINKS,
+    DEFAULT_MAX_MEDIA_DIMENSION, DEFAULT_MAX_MEDIA_SIZE_BYTES, DEFAULT_MAX_POST_AGE_SEC,
+    DEFAULT_MAX_TEXT_LENGTH, DEFAULT_MEDIA_GATEWAYS, DEFAULT_METRICS_ENABLED,
+    DEFAULT_OWNER_THRESHOLD, DEFAULT_POW_DIFFICULTY, DEFAULT_QUARANTINE_HOURS,
+    DEFAULT_QUARANTINE_POSTS, DEFAULT_REQUIRED_FIELDS, DEFAULT_REQUIRE_HUMANITY,
+    DEFAULT_ROLLUPS_ENABLED, DEFAULT_SEARCH_INDEX_ENABLED, DEFAULT_SELLER_FEE_BASIS_POINTS,
+    DEFAULT_SPAM_LIMIT_MARGIN, DEFAULT_SPAM_MAX_CAPS_PERCENT, DEFAULT_SPAM_MAX_EMOJI,
+    DEFAULT_SPAM_MAX_LINKS, DEFAULT_SPAM_MAX_REPEATED_LINES, DEFAULT_SPAM_THRESHOLD,
+    DEFAULT_VOTING_HOURS, FEATURE_METHODS, OPTIONAL_FEATURES,
+};
+#[cfg(feature = "tipping")]
+use crate::metadata::tips::TIP_ALIAS_PREFIX;
+use crate::moderation::confusables::normalize;
+use crate::storage::alias::Alias;
+use crate::types::Metadata;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 
-#[derive(Deserialize, Clone)]
-pub struct DataStructFork {
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenSeaAttributes {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: AttributeValue,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    Number(u64),
+    Text(String),
+}
+
+impl OpenSeaAttributes {
+    pub fn text(trait_type: &str, value: &str) -> Self {
+        OpenSeaAttributes {
+            display_type: None,
+            trait_type: trait_type.to_string(),
+            value: AttributeValue::Text(value.to_string()),
+        }
+    }
+
+    pub fn number(trait_type: &str, value: u64) -> Self {
+        Self::display(trait_type, "number", value)
+    }
+
+    pub fn boost_percentage(trait_type: &str, value: u64) -> Self {
+        Self::display(trait_type, "boost_percentage", value)
+    }
+
+    /**
+     * Date attributes are unix timestamps in seconds
+     */
+    pub fn date(trait_type: &str, value: u64) -> Self {
+        Self::display(trait_type, "date", value)
+    }
+
+    fn display(trait_type: &str, display_type: &str, value: u64) -> Self {
+        OpenSeaAttributes {
+            display_type: Some(display_type.to_string()),
+            trait_type: trait_type.to_string(),
+            value: AttributeValue::Number(value),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionMetadata {
+    pub name: String,
+    pub description: String,
+    pub image: String,
+    pub banner_image: String,
+    pub external_link: String,
+    pub seller_fee_basis_points: u64,
+    pub fee_recipient: String,
+}
+
+#[derive(Default)]
+pub struct ThreadActivity {
+    /**
+     * Topic token id, 0 when unknown
+     */
+    pub token_id: u64,
+    pub replies: u64,
+    pub participants: u64,
+    pub tips: u64,
+    pub created: u64,
+    pub last_activity: u64,
+    /**
+     * Decayed heat in thousandths, see metadata::trending
+     */
+    pub trending: u64,
+    /**
+     * Words in the topic body, 0 when unknown
+     */
+    pub words: u64,
+    /**
+     * Content license of the topic, declared at mint
+     */
+    pub license: Option<String>,
+}
+
+impl ThreadActivity {
+    /**
+     * Posts on the wall are stored under the empty alias, one record per author
+     */
+    pub fn from_metadatas(metadatas: &[Metadata]) -> Self {
+        let posts: Vec<&Metadata> = metadatas
+            .iter()
+            .filter(|m| m.alias == Alias::Post)
+            .collect();
+        let authors: HashSet<&str> = posts.iter().map(|m| m.public_key.as_str()).collect();
+        #[cfg(feature = "tipping")]
+        let tips = metadatas
+            .iter()
+            .filter(|m| m.alias.starts_with(TIP_ALIAS_PREFIX))
+            .count();
+        #[cfg(not(feature = "tipping"))]
+        let tips = 0;
+
+        ThreadActivity {
+            replies: posts.len() as u64,
+            participants: authors.len() as u64,
+            tips: tips as u64,
+            ..Default::default()
+        }
+    }
+}
+
+/**
+ * Wall configuration bundle, stored under the `config` alias by the contract owner
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WallConfig {
     pub owner: String,
+    /**
+     * Co-owners, together with `owner` they approve destructive actions `owner_threshold` of N
+     */
+    pub owners: Vec<String>,
+    pub owner_threshold: usize,
+    pub moderators: Vec<String>,
+    pub rules: WallRules,
+    pub seller_fee_basis_points: u64,
+    pub fee_recipient: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+    pub metrics: bool,
+    /**
+     * Keep the `w3wall.rollup` thread totals up to date on every post
+     */
+    pub rollups: bool,
+    /**
+     * Keep the `w3wall.digest` of the latest replies up to date on every post
+     */
+    pub digests: bool,
+    /**
+     * Emit the post's search terms under `search_index`, for gateway or client-side search
+     */
+    pub search_index: bool,
+    pub karma: KarmaConfig,
+    /**
+     * Keys whose signed attestations are trusted, e.g. for "held by author" NFT embeds
+     */
+    pub attesters: Vec<String>,
+    /**
+     * Rights holders allowed to file copyright claims without a credential
+     */
+    pub claimants: Vec<String>,
+    /**
+     * Keys whose posts skip the profanity filter, spam scoring and the new key quarantine,
+     * structural validation still applies
+     */
+    pub trusted: Vec<String>,
+    /**
+     * Emoji a `react` may use, each a single grapheme cluster, empty leaves reactions free
+     */
+    pub reactions: Vec<String>,
+    pub governance: Governance,
+    /**
+     * Optional subsystems switched off on this wall, out of OPTIONAL_FEATURES
+     */
+    pub disabled_features: Vec<String>,
+    pub attachments: AttachmentRules,
+    pub media: MediaRules,
+    pub classification: ClassificationRules,
+    pub syndication: Syndication,
+    /**
+     * How gzip bodies are stored: inflated (default) or as sent, with a hash of the inflated text
+     */
+    pub compressed_storage: CompressedStorage,
+    /**
+     * Named post templates, set with `set_template` and referenced by a post's `template`
+     */
+    pub templates: BTreeMap<String, PostTemplate>,
+    /**
+     * Boards a post's `category` must name, managed with `add_board` and `remove_board`
+     */
+    pub boards: Vec<String>,
+    pub locale: Locale,
+    /**
+     * `reply_only` keeps top-level posts to the owners and moderators, anyone may still reply,
+     * `approval` holds every post of an author not yet approved for a moderator to review
+     */
+    pub mode: WallMode,
+    /**
+     * Topic `description`, with `{title}`, `{author}` and `{reply_count}` placeholders
+     */
+    pub description_template: String,
+    /**
+     * Per post type field groups, replacing the DEFAULT_REQUIRED_FIELDS entry of that type
+     */
+    pub required_fields: BTreeMap<String, Vec<Vec<String>>>,
+    /**
+     * MIME types accepted for images, out of png, jpeg, gif, webp and svg+xml
+     */
+    pub image_types: Vec<String>,
+    /**
+     * Gateway preference stored with every post image, clients fail over in this order
+     */
+    pub gateways: Vec<String>,
+    /**
+     * Providers links are described for in a post's `embeds`, out of youtube, twitter and image
+     */
+    pub embed_providers: Vec<String>,
+    /**
+     * Project names reserved for owners and moderators, on top of DEFAULT_RESERVED_NAMES
+     */
+    pub reserved_names: Vec<String>,
+}
+
+impl Default for WallConfig {
+    fn default() -> Self {
+        WallConfig {
+            owner: "".to_string(),
+            owners: Vec::new(),
+            owner_threshold: DEFAULT_OWNER_THRESHOLD,
+            moderators: Vec::new(),
+            rules: WallRules::default(),
+            seller_fee_basis_points: DEFAULT_SELLER_FEE_BASIS_POINTS,
+            fee_recipient: DEFAULT_FEE_RECIPIENT.to_string(),
+            log_level: None,
+            metrics: DEFAULT_METRICS_ENABLED,
+            rollups: DEFAULT_ROLLUPS_ENABLED,
+            digests: DEFAULT_DIGESTS_ENABLED,
+            search_index: DEFAULT_SEARCH_INDEX_ENABLED,
+            karma: KarmaConfig::default(),
+            attesters: Vec::new(),
+            claimants: Vec::new(),
+            trusted: Vec::new(),
+            reactions: Vec::new(),
+            governance: Governance::default(),
+            disabled_features: Vec::new(),
+            attachments: AttachmentRules::default(),
+            media: MediaRules::default(),
+            classification: ClassificationRules::default(),
+            syndication: Syndication::default(),
+            compressed_storage: CompressedStorage::default(),
+            templates: BTreeMap::new(),
+            boards: DEFAULT_BOARDS.iter().map(|b| b.to_string()).collect(),
+            locale: Locale::default(),
+            mode: WallMode::default(),
+            description_template: DEFAULT_DESCRIPTION_TEMPLATE.to_string(),
+            required_fields: BTreeMap::new(),
+            image_types: DEFAULT_IMAGE_TYPES.iter().map(|t| t.to_string()).collect(),
+            gateways: DEFAULT_MEDIA_GATEWAYS
+                .iter()
+                .map(|g| g.to_string())
+                .collect(),
+            embed_providers: DEFAULT_EMBED_PROVIDERS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            reserved_names: Vec::new(),
+        }
+    }
+}
+
+impl WallConfig {
+    pub fn is_owner(&self, public_key: &str) -> bool {
+        self.owner.eq_ignore_ascii_case(public_key)
+            || self
+                .owners
+                .iter()
+                .any(|o| o.eq_ignore_ascii_case(public_key))
+    }
+
+    /**
+     * Field groups a post of `kind` needs, see DEFAULT_REQUIRED_FIELDS
+     */
+    pub fn required_fields(&self, kind: &str) -> Vec<Vec<String>> {
+        if let Some(groups) = self.required_fields.get(kind) {
+            return groups.clone();
+        }
+        let mut groups: Vec<Vec<String>> = DEFAULT_REQUIRED_FIELDS
+            .iter()
+            .find(|(name, _)| *name == kind)
+            .map(|(_, groups)| {
+                groups
+                    .iter()
+                    .map(|group| group.iter().map(|f| f.to_string()).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        // events fall back to the wall's timezone
+        if kind == "event" && self.locale.timezone.is_some() {
+            groups.retain(|group| group != &["timezone"]);
+        }
+        groups
+    }
+
+    /**
+     * Owners count as moderators
+     */
+    pub fn is_moderator(&self, public_key: &str) -> bool {
+        self.is_owner(public_key)
+            || self
+                .moderators
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case(public_key))
+    }
+
+    pub fn is_attester(&self, public_key: &str) -> bool {
+        self.attesters
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(public_key))
+    }
+
+    /**
+     * Whether the optional subsystem `name` is built in and not switched off here
+     */
+    pub fn has_feature(&self, name: &str) -> bool {
+        OPTIONAL_FEATURES
+            .iter()
+            .any(|(feature, built)| *built && *feature == name)
+            && !self.disabled_features.iter().any(|d| d == name)
+    }
+
+    /**
+     * False for the methods of a subsystem that is off, see FEATURE_METHODS
+     */
+    pub fn has_method(&self, method: &str) -> bool {
+        FEATURE_METHODS
+            .iter()
+            .filter(|(name, _)| *name == method)
+            .all(|(_, feature)| self.has_feature(feature))
+    }
+
+    pub fn is_trusted(&self, public_key: &str) -> bool {
+        self.trusted
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(public_key))
+    }
+}
+
+/**
+ * Karma records are only emitted when enabled, link gating applies either way (0 turns it off)
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct KarmaConfig {
+    pub enabled: bool,
+    pub min_for_links: u64,
+    /**
+     * Reactions count toward karma and trending once the reacting key's first post on the wall
+     * is this old, so freshly made keys can't farm votes
+     */
+    pub min_reactor_age_sec: u64,
+}
+
+impl Default for KarmaConfig {
+    fn default() -> Self {
+        KarmaConfig {
+            enabled: DEFAULT_KARMA_ENABLED,
+            min_for_links: DEFAULT_KARMA_MIN_FOR_LINKS,
+            min_reactor_age_sec: DEFAULT_KARMA_MIN_REACTOR_AGE_SEC,
+        }
+    }
+}
+
+/**
+ * Token voting on `proposal`s, off until an attester is set. The attester signs voters' balances
+ * of `token` at a proposal's snapshot block, `quorum` is a decimal balance in base units.
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Governance {
+    pub attester: String,
+    /**
+     * Contract of the governance token, part of every signed snapshot
+     */
+    pub token: String,
+    pub quorum: String,
+    pub voting_hours: u64,
+}
+
+impl Default for Governance {
+    fn default() -> Self {
+        Governance {
+            attester: "".to_string(),
+            token: "".to_string(),
+            quorum: DEFAULT_GOVERNANCE_QUORUM.to_string(),
+            voting_hours: DEFAULT_VOTING_HOURS,
+        }
+    }
+}
+
+/**
+ * Rules of a post template: fields the post must have, tags added to it and attributes pinned
+ * to every post made from it
+ */
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct PostTemplate {
+    pub required: Vec<String>,
+    pub tags: Vec<String>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum WallMode {
+    #[default]
+    Open,
+    ReplyOnly,
+    Approval,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressedStorage {
+    #[default]
+    Decompressed,
+    Compressed,
+}
+
+/**
+ * A post stored compressed. `content_hash` is keccak256 of the inflated JSON, fields are in
+ * this order so stored records can be told apart by their prefix.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CompressedBody {
+    pub encoding: String,
+    pub data: String,
+    pub content_hash: String,
+}
+
+/**
+ * Extra representations of each post, stored next to it for bridges and gateways
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Syndication {
+    /**
+     * `activitystreams` alias with an AS2 Create/Note, for fediverse bridges
+     */
+    pub activitystreams: bool,
+    /**
+     * `jsonld` alias with a schema.org DiscussionForumPosting, for rich search results
+     */
+    pub jsonld: bool,
+    /**
+     * `lens` alias with Lens publication metadata v2, so Lens apps can mirror posts
+     */
+    pub lens: bool,
+}
+
+impl Default for Syndication {
+    fn default() -> Self {
+        Syndication {
+            activitystreams: DEFAULT_ACTIVITYSTREAMS_ENABLED,
+            jsonld: DEFAULT_JSONLD_ENABLED,
+            lens: DEFAULT_LENS_ENABLED,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WallRules {
+    pub max_text_length: usize,
+    pub profanity_action: ProfanityAction,
+    pub spam: SpamRules,
+    /**
+     * Leading zero bits required of keccak256(data_key || nonce), 0 means no proof of work
+     */
+    pub pow_difficulty: u32,
+    /**
+     * Posts need a proof-of-humanity attestation from one of the wall's attesters
+     */
+    pub require_humanity: bool,
+    /**
+     * How far ahead of the transaction a post's own timestamp may be
+     */
+    pub max_clock_skew_sec: u64,
+    /**
+     * How far behind the transaction a post's own timestamp may be, stops backdating
+     */
+    pub max_post_age_sec: u64,
+    /**
+     * Posts a key may make per UTC day, moderators are exempt, 0 means unlimited
+     */
+    pub daily_post_quota: u64,
+    /**
+     * Outbound links a post may carry, 0 means unlimited
+     */
+    pub max_links: usize,
+    /**
+     * Posts over `max_links` are rejected, or stored with their `link_count`
+     */
+    pub link_action: SpamAction,
+    pub validation_mode: ValidationMode,
+    pub quarantine: QuarantineRules,
+    pub duplicates: DuplicateRules,
+}
+
+/**
+ * What happens to a post containing a listed word: rejected, or bleeped and stored as filtered
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfanityAction {
+    #[default]
+    Reject,
+    Mask,
+}
+
+impl Default for WallRules {
+    fn default() -> Self {
+        WallRules {
+            max_text_length: DEFAULT_MAX_TEXT_LENGTH,
+            profanity_action: ProfanityAction::default(),
+            spam: SpamRules::default(),
+            pow_difficulty: DEFAULT_POW_DIFFICULTY,
+            require_humanity: DEFAULT_REQUIRE_HUMANITY,
+            max_clock_skew_sec: DEFAULT_MAX_CLOCK_SKEW_SEC,
+            max_post_age_sec: DEFAULT_MAX_POST_AGE_SEC,
+            daily_post_quota: DEFAULT_DAILY_POST_QUOTA,
+            max_links: DEFAULT_MAX_LINKS,
+            link_action: SpamAction::default(),
+            validation_mode: ValidationMode::default(),
+            quarantine: QuarantineRules::default(),
+            duplicates: DuplicateRules::default(),
+        }
+    }
+}
+
+/**
+ * A key's first `posts` posts within `hours` of its first record on the wall are stored with
+ * `"new_account": true`, strict walls hold them for a moderator's approval instead. `posts` of 0 turns it off, owners
+ * and moderators are never new.
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct QuarantineRules {
+    pub posts: usize,
+    pub hours: u64,
+}
+
+/**
+ * Posts whose text is within `max_distance` simhash bits of one of the wall's last `window`
+ * posts are rejected, or stored with `"near_duplicate": true`. A window of 0 turns it off.
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DuplicateRules {
+    pub window: usize,
+    pub max_distance: u32,
+    pub action: SpamAction,
+}
+
+impl Default for DuplicateRules {
+    fn default() -> Self {
+        DuplicateRules {
+            window: DEFAULT_DUPLICATE_WINDOW,
+            max_distance: DEFAULT_DUPLICATE_MAX_DISTANCE,
+            action: SpamAction::default(),
+        }
+    }
+}
+
+impl Default for QuarantineRules {
+    fn default() -> Self {
+        QuarantineRules {
+            posts: DEFAULT_QUARANTINE_POSTS,
+            hours: DEFAULT_QUARANTINE_HOURS,
+        }
+    }
+}
+
+/**
+ * Spam heuristics thresholds. A post scoring `threshold` points or more is rejected or tagged
+ * with its score, a threshold of 0 turns the detector off
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SpamRules {
+    pub threshold: u32,
+    pub action: SpamAction,
+    pub max_links: usize,
+    pub max_caps_percent: usize,
+    pub max_repeated_lines: usize,
+    pub max_emoji: usize,
+    /**
+     * Points past `threshold` a post may score and still be stored, tagged
+     * `visibility: "limited"` for clients to demote, 0 treats every score alike
+     */
+    pub limit_margin: u32,
+}
+
+impl SpamRules {
+    pub fn is_spam(&self, score: u32) -> bool {
+        self.threshold > 0 && score >= self.threshold
+    }
+
+    /**
+     * Spam, but only just: within `limit_margin` points of the threshold
+     */
+    pub fn is_borderline(&self, score: u32) -> bool {
+        self.is_spam(score) && score - self.threshold < self.limit_margin
+    }
+}
+
+impl Default for SpamRules {
+    fn default() -> Self {
+        SpamRules {
+            threshold: DEFAULT_SPAM_THRESHOLD,
+            action: SpamAction::default(),
+            max_links: DEFAULT_SPAM_MAX_LINKS,
+            max_caps_percent: DEFAULT_SPAM_MAX_CAPS_PERCENT,
+            max_repeated_lines: DEFAULT_SPAM_MAX_REPEATED_LINES,
+            max_emoji: DEFAULT_SPAM_MAX_EMOJI,
+            limit_margin: DEFAULT_SPAM_LIMIT_MARGIN,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    #[default]
+    Iso,
+    Dmy,
+    Mdy,
+}
+
+/**
+ * How clients should render the wall's times and text by default. Dates stay unix seconds in
+ * the records, these are hints.
+ */
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default)]
+pub struct Locale {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    pub date_format: DateFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/**
+ * Whether a post failing a cosmetic rule is rejected, or stored without the offending fields
+ * and told so through warnings
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SpamAction {
+    #[default]
+    Reject,
+    Tag,
+}
+
+/**
+ * Reference to a post on the same wall, `content_hash` is the keccak256 of the quoted record's
+ * content so the card can't silently change under the quote
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Quote {
     pub cid: String,
-    pub version: String,
+    pub author: String,
+    pub content_hash: String,
+    /**
+     * Record version of the quoted post, when the quote pins one
+     */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct OpenSeaAttributes {
-    pub trait_type: String,
-    pub value: String,
+/**
+ * One section of a `type: "article"` post, `body` is markdown
+ */
+#[derive(Deserialize)]
+pub struct ArticleSection {
+    #[serde(default)]
+    pub heading: Option<String>,
+    pub body: String,
+}
+
+/**
+ * Sticker of a `type: "sticker"` post, `cid` must be in the wall's pack of that name
+ */
+#[derive(Deserialize)]
+pub struct Sticker {
+    pub pack: String,
+    pub cid: String,
+}
+
+/**
+ * NFT rendered as a card inside a post
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NftEmbed {
+    pub chain_id: String,
+    pub contract: String,
+    pub token_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<Attestation>,
+}
+
+/**
+ * Post written "as" an NFT through its ERC-6551 token-bound account. The holder signs the binding
+ * of `account` to the token, an attester vouches that the holder owns the token.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TokenBound {
+    #[serde(flatten)]
+    pub token: NftEmbed,
+    pub account: String,
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Attestation {
+    pub signer: String,
+    pub signature: String,
+}
+
+/**
+ * File attached to a post, `size` in bytes as declared by the client
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Attachment {
+    pub cid: String,
+    pub filename: String,
+    pub mime: String,
+    pub size: u64,
+}
+
+/**
+ * Accepted MIME types and their size caps. `mime` is exact ("application/pdf") or a `*` subtype
+ * wildcard accepting the whole family.
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AttachmentRules {
+    pub max_count: usize,
+    pub types: Vec<MimeLimit>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MimeLimit {
+    pub mime: String,
+    pub max_size: u64,
+}
+
+impl AttachmentRules {
+    pub fn max_size(&self, mime: &str) -> Option<u64> {
+        self.types
+            .iter()
+            .find(|t| match t.mime.strip_suffix("/*") {
+                Some(family) => mime.split('/').next() == Some(family),
+                None => t.mime.eq_ignore_ascii_case(mime),
+            })
+            .map(|t| t.max_size)
+    }
+}
+
+impl Default for AttachmentRules {
+    fn default() -> Self {
+        AttachmentRules {
+            max_count: DEFAULT_MAX_ATTACHMENTS,
+            types: DEFAULT_ATTACHMENT_TYPES
+                .iter()
+                .map(|(mime, max_size)| MimeLimit {
+                    mime: mime.to_string(),
+                    max_size: *max_size,
+                })
+                .collect(),
+        }
+    }
+}
+
+/**
+ * Pixel size a post declares for its image, so clients can lay it out before it loads
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Dimensions {
+    pub width: u64,
+    pub height: u64,
+}
+
+/**
+ * Caps on the `size_bytes` and `dimensions` a post declares for its image
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MediaRules {
+    pub max_size_bytes: u64,
+    pub max_width: u64,
+    pub max_height: u64,
+}
+
+impl Default for MediaRules {
+    fn default() -> Self {
+        MediaRules {
+            max_size_bytes: DEFAULT_MAX_MEDIA_SIZE_BYTES,
+            max_width: DEFAULT_MAX_MEDIA_DIMENSION,
+            max_height: DEFAULT_MAX_MEDIA_DIMENSION,
+        }
+    }
+}
+
+/**
+ * Off-chain classifiers the wall delegates content checks to, a post's `verdict` must be signed
+ * by one of them
+ */
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ClassificationRules {
+    pub classifiers: Vec<String>,
+    /**
+     * Posts without a verdict are rejected
+     */
+    pub required: bool,
+    pub blocked_labels: Vec<String>,
+}
+
+impl ClassificationRules {
+    pub fn is_classifier(&self, public_key: &str) -> bool {
+        self.classifiers
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(public_key))
+    }
+}
+
+impl Default for ClassificationRules {
+    fn default() -> Self {
+        ClassificationRules {
+            classifiers: Vec::new(),
+            required: DEFAULT_CLASSIFICATION_REQUIRED,
+            blocked_labels: DEFAULT_BLOCKED_LABELS
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+        }
+    }
+}
+
+/**
+ * How a private post's `content` was encrypted. Each recipient gets the content key wrapped for
+ * their public key.
+ */
+#[cfg(feature = "encryption")]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EncryptionEnvelope {
+    pub algorithm: String,
+    pub recipients: Vec<Recipient>,
+}
+
+#[cfg(feature = "encryption")]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Recipient {
+    pub public_key: String,
+    pub encrypted_key: String,
+}
+
+/**
+ * Banned words and phrases of a single wall, checked on top of the global list
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct WallFilter {
+    pub words: Vec<String>,
+}
+
+/**
+ * Keys a user doesn't want replies or quotes from
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BlockList {
+    pub blocked: Vec<String>,
+}
+
+/**
+ * Stickers a wall offers under one name, with the license they are shared under. A retired pack
+ * takes no new posts, clients keep rendering the posts that used it.
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StickerPack {
+    pub stickers: Vec<String>,
+    pub license: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retired_at: Option<u64>,
+}
+
+/**
+ * The wall's sticker packs by name, managed with `register_sticker_pack` and
+ * `retire_sticker_pack`
+ */
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StickerRegistry {
+    pub packs: BTreeMap<String, StickerPack>,
+}
+
+impl StickerRegistry {
+    /**
+     * Packs that still take new posts
+     */
+    pub fn active(&self) -> impl Iterator<Item = (&String, &StickerPack)> {
+        self.packs
+            .iter()
+            .filter(|(_, pack)| pack.retired_at.is_none())
+    }
+}
+
+/**
+ * Terms a key does not want to read in replies to their posts, matched on the normalized text
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MutedWords {
+    pub muted: Vec<String>,
+}
+
+impl MutedWords {
+    pub fn mutes(&self, text: &str) -> bool {
+        let text = normalize(text);
+        self.muted
+            .iter()
+            .any(|w| !w.is_empty() && text.contains(&normalize(w)))
+    }
+}
+
+impl BlockList {
+    pub fn is_blocked(&self, public_key: &str) -> bool {
+        self.blocked
+            .iter()
+            .any(|b| b.eq_ignore_ascii_case(public_key))
+    }
+}
+
+/**
+ * Authors (keys) and threads (data keys) a user follows, what a home feed is built from
+ */
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FollowList {
+    pub authors: Vec<String>,
+    pub threads: Vec<String>,
+}
+
+/**
+ * Destructive actions the owners can take on a wall
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    Remove,
+    Ban,
+    Lock,
+}
+
+impl ModerationAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationAction::Remove => "remove",
+            ModerationAction::Ban => "ban",
+            ModerationAction::Lock => "lock",
+        }
+    }
+}
+
+/**
+ * One co-owner's personal_sign of the moderation message
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Approval {
+    pub signer: String,
+    pub signature: String,
+}
+
+/**
+ * `moderate` payload, `target` is the post cid for remove and the key for ban
+ */
+#[derive(Deserialize, Debug)]
+pub struct ModerationRequest {
+    pub action: ModerationAction,
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub approvals: Vec<Approval>,
+    /**
+     * Reason code recorded in the audit log
+     */
+    #[serde(default)]
+    pub reason: String,
+}
+
+/**
+ * Outcome of every approved moderation action on the wall
+ */
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ModerationState {
+    pub locked: bool,
+    pub banned: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ModerationState {
+    pub fn is_banned(&self, public_key: &str) -> bool {
+        self.banned
+            .iter()
+            .any(|b| b.eq_ignore_ascii_case(public_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_activity_counts_posts_and_authors() {
+        let post = |key: &str, alias: &str| Metadata {
+            public_key: key.to_string(),
+            alias: alias.to_string(),
+            ..Default::default()
+        };
+        let metadatas = vec![
+            post("a", ""),
+            post("a", ""),
+            post("b", ""),
+            post("c", "config"),
+        ];
+
+        let activity = ThreadActivity::from_metadatas(&metadatas);
+        assert_eq!(activity.replies, 3);
+        assert_eq!(activity.participants, 2);
+    }
 }
@@ -1,3 +1,401 @@
+#[cfg(feature = "mint")]
+pub static DEFAULT_EXTERNAL_URL_TEMPLATE: &str = "https://w3wall.xyz/topic/{data_key}/{token_id}";
+pub static DEFAULT_SELLER_FEE_BASIS_POINTS: u64 = 0u64;
+pub static DEFAULT_FEE_RECIPIENT: &str = "0x0000000000000000000000000000000000000000";
+pub static DEFAULT_COLLECTION_NAME: &str = "w3wall";
+pub static DEFAULT_COLLECTION_DESCRIPTION: &str = "Topics of the w3wall decentralize forum";
+pub static DEFAULT_COLLECTION_IMAGE: &str = "";
+pub static DEFAULT_COLLECTION_BANNER: &str = "";
+pub static DEFAULT_COLLECTION_EXTERNAL_LINK: &str = "https://w3wall.xyz";
+pub static DEFAULT_MAX_MINT_NAME_LENGTH: usize = 200;
+pub static DEFAULT_MAX_MINT_BODY_LENGTH: usize = 10_000;
+#[cfg(feature = "mint")]
+pub static DEFAULT_MAX_BATCH_MINT: usize = 50;
+#[cfg(feature = "mint")]
+pub static DEFAULT_MAX_BADGE_DECIMALS: u8 = 18;
+/**
+ * Images a topic carries besides its cover
+ */
+#[cfg(feature = "mint")]
+pub static DEFAULT_MAX_MINT_IMAGES: usize = 8;
+#[cfg(feature = "mint")]
+pub static DEFAULT_THEME_BACKGROUND: &str = "#0f172a";
+#[cfg(feature = "mint")]
+pub static DEFAULT_THEME_FOREGROUND: &str = "#f8fafc";
+#[cfg(feature = "mint")]
+pub static DEFAULT_THEME_ACCENT: &str = "#38bdf8";
+pub static DEFAULT_CLONE_ALLOWED_CONTRACTS: &[&str] = &[];
+pub static DEFAULT_CLONE_ALLOWED_KEYS: &[&str] = &[];
+pub static DEFAULT_CLONE_REQUIRE_AUTHORIZATION: bool = false;
+/**
+ * How long a clone authorization stays usable after the owner signed it
+ */
+pub static DEFAULT_CLONE_AUTHORIZATION_TTL_SEC: u64 = 60 * 60;
 pub static DEFAULT_TIMEOUT_SEC: u64 = 1u64;
 pub static DEFAULT_IPFS_MULTIADDR: &str = "/ip4/127.0.0.1/tcp/5001";
-pub static DEFAULT_COLLABEAT_URL: &str = "https://app.collabeat.com/music/";
+pub static DEFAULT_MAX_TEXT_LENGTH: usize = 5000;
+pub static DEFAULT_OWNER_THRESHOLD: usize = 1;
+pub static DEFAULT_MAX_BLOCKED_KEYS: usize = 1000;
+pub static DEFAULT_MAX_MUTED_WORDS: usize = 100;
+pub static DEFAULT_MAX_MUTED_WORD_LENGTH: usize = 50;
+pub static DEFAULT_MAX_FOLLOWING: usize = 1000;
+pub static DEFAULT_MAX_MENTIONS: usize = 10;
+pub static DEFAULT_INBOX_SIZE: usize = 50;
+pub static DEFAULT_MAX_ANNOUNCEMENT_LENGTH: usize = 280;
+pub static DEFAULT_MAX_SUMMARY_LENGTH: usize = 500;
+pub static DEFAULT_MAX_ANNOUNCEMENT_SEC: u64 = 30 * 24 * 60 * 60;
+pub static DEFAULT_MAX_THEME_TITLE_LENGTH: usize = 80;
+pub static DEFAULT_MAX_THEME_DESCRIPTION_LENGTH: usize = 500;
+pub static DEFAULT_MAX_DISPLAY_NAME_LENGTH: usize = 50;
+/**
+ * Display names only owners and moderators may take, compared by skeleton word by word
+ */
+pub static DEFAULT_RESERVED_NAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "moderator",
+    "mod",
+    "owner",
+    "official",
+    "support",
+    "w3wall",
+];
+pub static DEFAULT_MAX_BIO_LENGTH: usize = 300;
+pub static DEFAULT_MAX_PROFILE_LINKS: usize = 5;
+pub static DEFAULT_MAX_PROFILE_LINK_LENGTH: usize = 200;
+pub static DEFAULT_REDACTION_MARKER: &str = "[redacted by author]";
+pub static DEFAULT_ACTIVITYSTREAMS_ENABLED: bool = false;
+pub static DEFAULT_JSONLD_ENABLED: bool = false;
+pub static DEFAULT_LENS_ENABLED: bool = false;
+pub static DEFAULT_FEED_SIZE: usize = 20;
+pub static MAX_FEED_SIZE: usize = 100;
+pub static DEFAULT_STATS_ACTIVE_DAYS: u64 = 30;
+pub static DEFAULT_STATS_TOP_TAGS: usize = 10;
+pub static DEFAULT_DAILY_DIGEST_SIZE: usize = 10;
+pub static MAX_STATS_TOP_TAGS: usize = 50;
+pub static DEFAULT_PAGE_SIZE: usize = 50;
+pub static DEFAULT_ACTOR_URL_TEMPLATE: &str = "https://w3wall.xyz/u/{public_key}";
+pub static DEFAULT_THREAD_URL_TEMPLATE: &str = "https://w3wall.xyz/topic/{data_key}";
+pub static DEFAULT_POST_URL_TEMPLATE: &str = "https://w3wall.xyz/topic/{data_key}/post/{id}";
+pub static DEFAULT_LICENSES: &[&str] = &["CC0", "CC-BY", "CC-BY-SA", "CC-BY-NC", "ARR"];
+pub static DEFAULT_GEO_DECIMALS: i32 = 2;
+pub static DEFAULT_MAX_PLACE_LENGTH: usize = 100;
+pub static SCHEMA_VERSION: u64 = 2u64;
+pub static DEFAULT_MAX_MIGRATIONS: usize = 50;
+pub static DEFAULT_METRICS_ENABLED: bool = false;
+pub static DEFAULT_ROLLUPS_ENABLED: bool = true;
+pub static DEFAULT_DIGESTS_ENABLED: bool = true;
+pub static DEFAULT_DIGEST_SIZE: usize = 20;
+pub static DEFAULT_DESCRIPTION_TEMPLATE: &str = "A subject in w3wall decentralize forum";
+pub static DEFAULT_MAX_DESCRIPTION_TEMPLATE_LENGTH: usize = 500;
+pub static DEFAULT_MAX_DESCRIPTION_TITLE_LENGTH: usize = 100;
+pub static DEFAULT_MAX_DESCRIPTION_LENGTH: usize = 1000;
+pub static DEFAULT_SEARCH_INDEX_ENABLED: bool = false;
+pub static DEFAULT_WORDS_PER_MINUTE: u64 = 200;
+pub static DEFAULT_TRENDING_HALF_LIFE_SEC: u64 = 24 * 60 * 60;
+pub static DEFAULT_TRENDING_REACTION_WEIGHT: u64 = 1;
+pub static DEFAULT_TRENDING_REPLY_WEIGHT: u64 = 2;
+pub static DEFAULT_MAX_SEARCH_TERMS: usize = 32;
+pub static DEFAULT_STOPWORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "in", "is",
+    "it", "its", "of", "on", "or", "so", "that", "the", "this", "to", "was", "were", "will",
+    "with",
+];
+pub static DEFAULT_OG_TOKEN_LIMIT: u64 = 100;
+pub static DEFAULT_KARMA_ENABLED: bool = false;
+pub static DEFAULT_KARMA_PER_POST: u64 = 1;
+pub static DEFAULT_KARMA_PER_REACTION: u64 = 2;
+pub static DEFAULT_KARMA_MIN_FOR_LINKS: u64 = 0;
+/**
+ * How old a key's first post must be before its reactions count, 0 counts every reaction
+ */
+pub static DEFAULT_KARMA_MIN_REACTOR_AGE_SEC: u64 = 0;
+pub static DEFAULT_MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+pub static DEFAULT_MAX_DECOMPRESSED_BYTES: usize = 4 * DEFAULT_MAX_PAYLOAD_BYTES;
+/**
+ * Bytes a transaction may scan or read from storage, a thread of a few thousand posts fits
+ */
+pub static DEFAULT_WORK_BUDGET: u64 = 32 * 1024 * 1024;
+/**
+ * Compiled size of a schema pattern, the embedded ones are far below it
+ */
+pub static DEFAULT_REGEX_SIZE_LIMIT: usize = 256 * 1024;
+pub static DEFAULT_MAX_CHUNKS: usize = 16;
+pub static DEFAULT_MAX_DRAFTS: usize = 32;
+/**
+ * Rejections a lenient wall turns into warnings, with the fields dropped from the stored post
+ */
+pub static DEFAULT_SOFT_RULES: &[(&str, &[&str])] = &[
+    ("blurhash", &["blurhash"]),
+    ("thumbnail", &["thumbnail"]),
+    ("media_hint", &["size_bytes", "dimensions"]),
+    ("media_dimensions", &["dimensions"]),
+];
+/**
+ * Share of `max_text_length` past which a post is warned it is close to the limit
+ */
+pub static DEFAULT_NEAR_LIMIT_PERCENT: usize = 90;
+pub static DEFAULT_MAX_ALT_LENGTH: usize = 1000;
+pub static DEFAULT_MAX_TEMPLATES: usize = 32;
+pub static DEFAULT_BOARDS: &[&str] = &["general"];
+pub static DEFAULT_MAX_STICKER_PACKS: usize = 16;
+pub static DEFAULT_MAX_STICKERS_PER_PACK: usize = 64;
+pub static DEFAULT_MAX_WEBHOOKS: usize = 8;
+pub static DEFAULT_MAX_PIN_PRIORITIES: usize = 100;
+pub static DEFAULT_VOTING_HOURS: u64 = 72;
+/**
+ * Optional subsystems and whether this build has them. Each comes with the Cargo feature of the
+ * same name, on by default, and a wall can still switch a built one off in `disabled_features`.
+ */
+pub static OPTIONAL_FEATURES: &[(&str, bool)] = &[
+    ("tipping", cfg!(feature = "tipping")),
+    ("encryption", cfg!(feature = "encryption")),
+    ("federation", cfg!(feature = "federation")),
+];
+/**
+ * Methods of an optional subsystem, refused wherever it is off. Private posts belong to
+ * `encryption`, builds without it don't accept them at all.
+ */
+pub static FEATURE_METHODS: &[(&str, &str)] = &[
+    ("tip", "tipping"),
+    ("relate_wall", "federation"),
+    ("unrelate_wall", "federation"),
+];
+/**
+ * Votes a proposal needs in all, as a decimal balance in the token's base units
+ */
+pub static DEFAULT_GOVERNANCE_QUORUM: &str = "0";
+pub static DEFAULT_MAX_PROPOSAL_TITLE_LENGTH: usize = 120;
+pub static DEFAULT_MAX_PROPOSAL_BODY_LENGTH: usize = 4000;
+pub static DEFAULT_MAX_WEBHOOK_URL_LENGTH: usize = 512;
+/**
+ * What a webhook may subscribe to, workers deliver one event per matching transaction
+ */
+pub static DEFAULT_WEBHOOK_EVENTS: &[&str] = &[
+    "post",
+    "reply",
+    "reaction",
+    "tip",
+    "moderation",
+    "announcement",
+];
+/**
+ * Filled in by the worker for each delivery
+ */
+pub static DEFAULT_WEBHOOK_PLACEHOLDERS: &[&str] = &["data_key", "event", "cid"];
+#[cfg(feature = "federation")]
+pub static DEFAULT_MAX_RELATED_WALLS: usize = 16;
+pub static DEFAULT_MAX_RELATED_LABEL_LENGTH: usize = 32;
+pub static DEFAULT_MAX_TEMPLATE_NAME_LENGTH: usize = 32;
+pub static DEFAULT_PROFANE_WORDS: &[&str] = &[];
+pub static DEFAULT_IMAGE_GATEWAYS: &[&str] = &["https://nftstorage.link/ipfs/"];
+/**
+ * Gateways clients try in order to fetch an `ipfs://` image, each ends with the path the CID
+ * is appended to
+ */
+pub static DEFAULT_MEDIA_GATEWAYS: &[&str] = &[
+    "https://nftstorage.link/ipfs/",
+    "https://dweb.link/ipfs/",
+    "https://ipfs.io/ipfs/",
+];
+/**
+ * Every image type a wall may accept and the extensions naming it, a wall's `image_types`
+ * picks from these
+ */
+pub static DEFAULT_IMAGE_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("image/png", &["png"]),
+    ("image/jpeg", &["jpg", "jpeg"]),
+    ("image/gif", &["gif"]),
+    ("image/webp", &["webp"]),
+    ("image/svg+xml", &["svg"]),
+];
+/**
+ * SVG elements that run code or embed another document, lowercase and without namespace prefix
+ */
+pub static DEFAULT_SVG_BLOCKED_ELEMENTS: &[&str] = &[
+    "script",
+    "foreignobject",
+    "iframe",
+    "embed",
+    "object",
+    "handler",
+    "listener",
+];
+/**
+ * Providers whose links a post describes for inline rendering, `image` covers direct links to
+ * an accepted image type
+ */
+pub static DEFAULT_EMBED_PROVIDERS: &[&str] = &["youtube", "twitter", "image"];
+pub static DEFAULT_MAX_EMBEDS: usize = 4;
+pub static DEFAULT_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+pub static DEFAULT_MAX_CODE_LENGTH: usize = 16_000;
+/**
+ * IANA names ("Europe/Berlin", "UTC") or a fixed offset ("+02:00")
+ */
+pub static TIMEZONE_PATTERN: &str =
+    r"^(?:[A-Za-z][A-Za-z_]*(?:/[A-Za-z0-9_+\-]+)*|[+-](?:0\d|1[0-4]):[0-5]\d)$";
+/**
+ * Limits of `type: "article"` posts, well past a post's text since writers need the room
+ */
+pub static DEFAULT_MAX_ARTICLE_TITLE_LENGTH: usize = 200;
+pub static DEFAULT_MAX_ARTICLE_SECTIONS: usize = 32;
+pub static DEFAULT_MAX_SECTION_LENGTH: usize = 20_000;
+pub static DEFAULT_MAX_ARTICLE_LENGTH: usize = 50_000;
+pub static DEFAULT_CODE_LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "cpp",
+    "csharp",
+    "css",
+    "diff",
+    "go",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "kotlin",
+    "lua",
+    "markdown",
+    "plaintext",
+    "python",
+    "ruby",
+    "rust",
+    "solidity",
+    "sql",
+    "swift",
+    "toml",
+    "typescript",
+    "vyper",
+    "yaml",
+];
+/**
+ * Tracking query parameters dropped from links in posts, besides every `utm_*`
+ */
+pub static DEFAULT_TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid",
+    "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "ref_src",
+];
+pub static DEFAULT_MAX_ATTACHMENTS: usize = 4;
+pub static DEFAULT_MAX_MEDIA_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+pub static DEFAULT_MAX_MEDIA_DIMENSION: u64 = 8192;
+pub static DEFAULT_ATTACHMENT_TYPES: &[(&str, u64)] = &[
+    ("image/png", 10 * 1024 * 1024),
+    ("image/jpeg", 10 * 1024 * 1024),
+    ("image/gif", 10 * 1024 * 1024),
+    ("image/webp", 10 * 1024 * 1024),
+];
+#[cfg(feature = "encryption")]
+pub static DEFAULT_ENCRYPTION_ALGORITHMS: &[&str] =
+    &["x25519-xsalsa20-poly1305", "ecies-secp256k1", "aes-256-gcm"];
+#[cfg(feature = "encryption")]
+pub static DEFAULT_MAX_RECIPIENTS: usize = 32;
+pub static DEFAULT_MAX_FILTER_WORDS: usize = 500;
+pub static DEFAULT_POW_DIFFICULTY: u32 = 0;
+pub static DEFAULT_REQUIRE_HUMANITY: bool = false;
+pub static DEFAULT_DAILY_POST_QUOTA: u64 = 20;
+pub static DEFAULT_MAX_CLOCK_SKEW_SEC: u64 = 5 * 60;
+pub static DEFAULT_MAX_POST_AGE_SEC: u64 = 24 * 60 * 60;
+pub static DEFAULT_TIMESTAMP_FIELDS: &[&str] = &["timestamp", "created_at"];
+pub static DEFAULT_SPAM_THRESHOLD: u32 = 2;
+pub static DEFAULT_SPAM_LIMIT_MARGIN: u32 = 0;
+pub static DEFAULT_CLASSIFICATION_REQUIRED: bool = false;
+/**
+ * Classifier labels a wall rejects, any other label lets the post through
+ */
+pub static DEFAULT_BLOCKED_LABELS: &[&str] = &["spam", "nsfw", "abuse"];
+pub static DEFAULT_SPAM_MAX_LINKS: usize = 3;
+pub static DEFAULT_MAX_LINKS: usize = 5;
+pub static DEFAULT_SPAM_MAX_CAPS_PERCENT: usize = 80;
+pub static DEFAULT_SPAM_MAX_REPEATED_LINES: usize = 2;
+pub static DEFAULT_SPAM_MAX_EMOJI: usize = 20;
+pub static DEFAULT_QUARANTINE_POSTS: usize = 0;
+pub static DEFAULT_QUARANTINE_HOURS: u64 = 24;
+pub static DEFAULT_DUPLICATE_WINDOW: usize = 0;
+pub static DEFAULT_DUPLICATE_MAX_DISTANCE: u32 = 3;
+/**
+ * Words a text needs before it is fingerprinted, shorter ones collide too easily
+ */
+pub static DEFAULT_FINGERPRINT_MIN_WORDS: usize = 4;
+pub static DEFAULT_LOOSE: i64 = 1;
+/**
+ * Loose flag per alias, entries ending in `.` match every alias with that prefix. Wall config,
+ * moderation and ownership records are strict so loose writes can't shadow them, aliases not
+ * listed are user content and stay loose. Handlers don't pick a flag, into_result sets it from
+ * this list on every record.
+ */
+pub static DEFAULT_LOOSE_POLICY: &[(&str, i64)] = &[
+    ("config", 0),
+    ("filter", 0),
+    ("w3wall.moderation", 0),
+    ("w3wall.ownership", 0),
+    ("w3wall.announcement", 0),
+    ("w3wall.theme", 0),
+    ("w3wall.related", 0),
+    ("w3wall.export", 0),
+    ("w3wall.stickers", 0),
+    ("w3wall.webhooks", 0),
+    ("w3wall.reveal", 0),
+    ("w3wall.summary", 0),
+    ("w3wall.approved_authors", 0),
+    ("w3wall.dispute.", 0),
+    ("w3wall.redaction.", 0),
+    ("w3wall.migrated.", 0),
+    ("w3wall.audit.", 0),
+    ("w3wall.appeal.", 0),
+    ("w3wall.proposal.", 0),
+    ("w3wall.vote.", 0),
+    ("w3wall.karma.", 0),
+    ("w3wall.pending.", 0),
+    ("w3wall.bounty.", 0),
+    ("w3wall.tip.", 0),
+    ("w3wall.tips.", 0),
+];
+pub static DEFAULT_MAX_REASON_CODE_LENGTH: usize = 32;
+pub static DEFAULT_MAX_APPEAL_LENGTH: usize = 1000;
+pub static DEFAULT_EXCERPT_LENGTH: usize = 280;
+pub static ENVELOPE_VERSION: u64 = 1u64;
+/**
+ * Record format of this contract, the major `version` of the transactions it takes and of the
+ * records it writes. Walls holding posts older than MIN_RECORD_VERSION run `migrate` first.
+ */
+pub static RECORD_VERSION: u64 = 1u64;
+pub static MIN_RECORD_VERSION: u64 = 1u64;
+/**
+ * Fields each post type needs, every group must have at least one of its fields present.
+ * Private posts are looked up as `private` whatever their type, types not listed need nothing
+ * beyond their schema. A wall's `required_fields` replaces the entry of a type it names.
+ */
+pub static DEFAULT_REQUIRED_FIELDS: &[(&str, &[&[&str]])] = &[
+    ("post", &[&["text", "image"]]),
+    ("private", &[&["content"], &["encryption"]]),
+    ("code", &[&["language"], &["code"]]),
+    ("bounty", &[&["reward"], &["deadline"]]),
+    ("sticker", &[&["sticker"]]),
+    ("article", &[&["title"], &["sections"]]),
+    (
+        "event",
+        &[&["start"], &["end"], &["timezone"], &["location", "url"]],
+    ),
+];
+/**
+ * Top-level fields holding the CID of another post, collected into the envelope's refs
+ */
+pub static DEFAULT_ENVELOPE_REF_FIELDS: &[&str] = &["reply_to", "post", "bounty", "reply", "event"];
+/**
+ * Cosmetic trait tables, (value, weight). Rarer values get smaller weights.
+ */
+pub static DEFAULT_COSMETIC_BACKGROUNDS: &[(&str, u64)] = &[
+    ("Slate", 40),
+    ("Ocean", 25),
+    ("Forest", 20),
+    ("Sunset", 10),
+    ("Aurora", 5),
+];
+pub static DEFAULT_COSMETIC_FRAMES: &[(&str, u64)] = &[
+    ("None", 50),
+    ("Silver", 30),
+    ("Gold", 15),
+    ("Holographic", 5),
+];
+pub static DEFAULT_COSMETIC_PALETTES: &[(&str, u64)] =
+    &[("Mono", 35), ("Earth", 30), ("Pastel", 25), ("Neon", 10)];
@@ -0,0 +1,290 @@
+//! ABI decoding that says where it failed. ethabi only reports "invalid data", so the layout is
+//! walked first, the way ethabi reads it, to name the first parameter that doesn't fit: its
+//! index (`1`, or `0[2].1` inside an array of tuples), its type and the byte it broke at.
+//! Mint data is also re-encoded, only the one canonical encoding of its values is accepted.
+
+use ethabi::{ParamType, Token};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AbiError {
+    #[error("param {path} ({expected}) at byte {offset}: {reason}")]
+    Param {
+        path: String,
+        expected: String,
+        offset: usize,
+        reason: String,
+    },
+    #[error("data is not the canonical encoding of its values, it differs from their re-encoding at byte {0}")]
+    NotCanonical(usize),
+    #[error("{0}")]
+    Other(String),
+}
+
+struct Walk<'a> {
+    data: &'a [u8],
+    path: String,
+    expected: &'a ParamType,
+}
+
+impl Walk<'_> {
+    fn fail<T>(&self, offset: usize, reason: String) -> Result<T, AbiError> {
+        Err(AbiError::Param {
+            path: self.path.clone(),
+            expected: self.expected.to_string(),
+            offset,
+            reason,
+        })
+    }
+
+    fn need(&self, offset: usize, len: usize) -> Result<(), AbiError> {
+        if offset.saturating_add(len) > self.data.len() {
+            return self.fail(
+                offset,
+                format!(
+                    "needs {} bytes, the data ends at byte {}",
+                    len,
+                    self.data.len()
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    fn word(&self, offset: usize) -> Result<&[u8], AbiError> {
+        self.need(offset, 32)?;
+        Ok(&self.data[offset..offset + 32])
+    }
+
+    /**
+     * An offset or a length, ethabi reads them as 32 bit numbers
+     */
+    fn number(&self, offset: usize, what: &str) -> Result<usize, AbiError> {
+        let word = self.word(offset)?;
+        if word[..28].iter().any(|b| *b != 0) {
+            return self.fail(offset, format!("{} does not fit in 32 bits", what));
+        }
+        Ok(u32::from_be_bytes(word[28..].try_into().unwrap()) as usize)
+    }
+
+    fn nested<'b>(&self, suffix: String, expected: &'b ParamType) -> Walk<'b>
+    where
+        Self: 'b,
+    {
+        Walk {
+            data: self.data,
+            path: format!("{}{}", self.path, suffix),
+            expected,
+        }
+    }
+
+    /**
+     * Checks the parameter whose head is at `base + at`, dynamic offsets count from `base`.
+     * Returns where the next head starts, relative to `base`.
+     */
+    fn check(&self, base: usize, at: usize) -> Result<usize, AbiError> {
+        let head = base + at;
+        match self.expected {
+            ParamType::Address | ParamType::Int(_) | ParamType::Uint(_) => {
+                self.word(head)?;
+            }
+            ParamType::Bool => {
+                let word = self.word(head)?;
+                if word[..31].iter().any(|b| *b != 0) {
+                    return self.fail(head, "bool is neither 0 nor 1".to_string());
+                }
+            }
+            ParamType::FixedBytes(len) => self.need(head, *len)?,
+            ParamType::Bytes | ParamType::String => {
+                let start = base + self.number(head, "offset")?;
+                let len = self.number(start, "length")?;
+                self.need(start + 32, len)?;
+            }
+            ParamType::Array(item) => {
+                let start = base + self.number(head, "offset")?;
+                let len = self.number(start, "length")?;
+                let tail = start + 32;
+                let mut next = 0;
+                for i in 0..len {
+                    next = self.nested(format!("[{}]", i), item).check(tail, next)?;
+                }
+            }
+            ParamType::FixedArray(_, _) | ParamType::Tuple(_) => {
+                let items: Vec<(String, &ParamType)> = match self.expected {
+                    ParamType::FixedArray(item, len) => {
+                        (0..*len).map(|i| (format!("[{}]", i), &**item)).collect()
+                    }
+                    ParamType::Tuple(fields) => fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| (format!(".{}", i), field))
+                        .collect(),
+                    _ => unreachable!(),
+                };
+                // a dynamic composite sits behind an offset, a static one is inline
+                let (tail, mut next) = if self.expected.is_dynamic() {
+                    let start = base + self.number(head, "offset")?;
+                    self.need(start, 0)?;
+                    (start, 0)
+                } else {
+                    (base, at)
+                };
+                for (suffix, item) in items {
+                    next = self.nested(suffix, item).check(tail, next)?;
+                }
+                if !self.expected.is_dynamic() {
+                    return Ok(next);
+                }
+            }
+        }
+        Ok(at + 32)
+    }
+}
+
+/**
+ * `ethabi::decode`, with the first misplaced parameter named when the data doesn't fit
+ */
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, AbiError> {
+    let mut next = 0;
+    for (i, expected) in types.iter().enumerate() {
+        let walk = Walk {
+            data,
+            path: i.to_string(),
+            expected,
+        };
+        next = walk.check(0, next)?;
+    }
+    ethabi::decode(types, data).map_err(|e| AbiError::Other(e.to_string()))
+}
+
+/**
+ * `decode`, refusing any data that isn't exactly what encoding the decoded values gives back:
+ * trailing bytes, dirty padding, reordered or shared tails, and text that isn't UTF-8 all
+ * decode fine but never come out of a standard encoder such as our frontend's.
+ */
+pub fn decode_canonical(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, AbiError> {
+    let tokens = decode(types, data)?;
+    let encoded = ethabi::encode(&tokens);
+    if encoded != data {
+        let offset = encoded
+            .iter()
+            .zip(data)
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| encoded.len().min(data.len()));
+        return Err(AbiError::NotCanonical(offset));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethabi::encode;
+
+    fn strings(values: &[&str]) -> Vec<u8> {
+        let tokens: Vec<Token> = values
+            .iter()
+            .map(|v| Token::String(v.to_string()))
+            .collect();
+        encode(&tokens)
+    }
+
+    fn reason(types: &[ParamType], data: &[u8]) -> String {
+        decode(types, data).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn decodes_what_ethabi_decodes() {
+        let data = strings(&["a", "b", "c"]);
+        let types = vec![ParamType::String; 3];
+        assert_eq!(
+            decode(&types, &data),
+            Ok(ethabi::decode(&types, &data).unwrap())
+        );
+    }
+
+    #[test]
+    fn names_the_parameter_and_byte() {
+        let types = vec![ParamType::String; 3];
+        // the heads alone
+        let data = strings(&["a", "b", "c"]);
+        assert_eq!(
+            reason(&types, &data[..96]),
+            "param 0 (string) at byte 96: needs 32 bytes, the data ends at byte 96"
+        );
+
+        // the second string's content cut short
+        let mut data = strings(&["a", "bb", "c"]);
+        data[6 * 32 - 1] = 200;
+        assert_eq!(
+            reason(&types, &data),
+            "param 1 (string) at byte 192: needs 200 bytes, the data ends at byte 288"
+        );
+
+        let mut data = strings(&["a", "b", "c"]);
+        data[32] = 1;
+        assert_eq!(
+            reason(&types, &data),
+            "param 1 (string) at byte 32: offset does not fit in 32 bits"
+        );
+        assert_eq!(
+            reason(&types, &[]),
+            "param 0 (string) at byte 0: needs 32 bytes, the data ends at byte 0"
+        );
+    }
+
+    #[test]
+    fn only_canonical_encodings_round_trip() {
+        let types = vec![ParamType::String; 3];
+        let data = strings(&["a", "b", "c"]);
+        assert!(decode_canonical(&types, &data).is_ok());
+
+        let padded = [data.clone(), vec![0; 32]].concat();
+        assert!(decode(&types, &padded).is_ok());
+        assert_eq!(
+            decode_canonical(&types, &padded),
+            Err(AbiError::NotCanonical(288))
+        );
+
+        // a byte after "a" inside its padding
+        let mut dirty = data.clone();
+        dirty[4 * 32 + 1] = b'!';
+        assert_eq!(
+            decode_canonical(&types, &dirty),
+            Err(AbiError::NotCanonical(129))
+        );
+
+        // the second and third params sharing one tail
+        let mut shared = data.clone();
+        shared[63] = 224;
+        assert_eq!(
+            decode_canonical(&types, &shared),
+            Err(AbiError::NotCanonical(63))
+        );
+
+        let mut latin1 = data;
+        latin1[4 * 32] = 0xe9;
+        assert!(decode(&types, &latin1).is_ok());
+        assert!(decode_canonical(&types, &latin1).is_err());
+    }
+
+    #[test]
+    fn paths_reach_into_arrays_of_tuples() {
+        let tuple = ParamType::Tuple(vec![ParamType::String; 2]);
+        let types = [ParamType::Array(Box::new(tuple))];
+        let item =
+            |a: &str, b: &str| Token::Tuple(vec![Token::String(a.into()), Token::String(b.into())]);
+        let data = encode(&[Token::Array(vec![item("a", "b"), item("c", "d")])]);
+        assert!(decode(&types, &data).is_ok());
+
+        let cut = &data[..data.len() - 32];
+        assert!(reason(&types, cut).starts_with("param 0[1].1 (string) at byte "));
+
+        let mut data = data.clone();
+        data[30] = 0xff;
+        assert_eq!(
+            reason(&types, &data),
+            "param 0 ((string,string)[]) at byte 65312: needs 32 bytes, the data ends at byte 512"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Bitcoin's base58 alphabet, the encoding of CIDv0 strings (`Qm…`)
+
+const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/**
+ * Leading `1`s are leading zero bytes, as in Bitcoin addresses
+ */
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    let zeros = input.bytes().take_while(|c| *c == b'1').count();
+    // little endian base 256 digits of the number the rest spells
+    let mut digits: Vec<u8> = vec![];
+    for c in input.bytes().skip(zeros) {
+        let mut carry = ALPHABET.iter().position(|a| *a == c)? as u32;
+        for digit in digits.iter_mut() {
+            carry += *digit as u32 * 58;
+            *digit = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            digits.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut out = vec![0u8; zeros];
+    out.extend(digits.iter().rev());
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_with_leading_zeroes() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("2g").unwrap(), b"a");
+        assert_eq!(decode("ZiCa").unwrap(), b"abc");
+        assert_eq!(decode("11").unwrap(), [0, 0]);
+        assert_eq!(decode("1112").unwrap(), [0, 0, 0, 1]);
+        assert_eq!(decode("15Q").unwrap(), [0, 255]);
+    }
+
+    #[test]
+    fn malformed_input_is_refused() {
+        for bad in ["0", "O", "I", "l", "2g ", "+", "é"] {
+            assert!(decode(bad).is_none(), "{:?}", bad);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+fn standard(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+fn url_safe(c: u8) -> Option<u8> {
+    match c {
+        b'-' => Some(62),
+        b'_' => Some(63),
+        b'+' | b'/' => None,
+        _ => standard(c),
+    }
+}
+
+fn decode_with(input: &str, value: fn(u8) -> Option<u8>) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=').as_bytes();
+    if input.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    for chunk in input.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            n |= (value(c)? as u32) << (18 - 6 * i);
+        }
+        out.push((n >> 16) as u8);
+        if chunk.len() > 2 {
+            out.push((n >> 8) as u8);
+        }
+        if chunk.len() > 3 {
+            out.push(n as u8);
+        }
+        // bits past the last byte are zero in anything an encoder wrote, so each value has
+        // exactly one encoding
+        let unused = match chunk.len() {
+            2 => 0xffff,
+            3 => 0xff,
+            _ => 0,
+        };
+        if n & unused != 0 {
+            return None;
+        }
+    }
+    Some(out)
+}
+
+/**
+ * Standard alphabet, padding optional, whitespace not allowed
+ */
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    decode_with(input, standard)
+}
+
+/**
+ * URL and filename safe alphabet (`-` and `_`), padding optional
+ */
+pub fn decode_url(input: &str) -> Option<Vec<u8>> {
+    decode_with(input, url_safe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_with_and_without_padding() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("Zg==").unwrap(), b"f");
+        assert_eq!(decode("Zm8").unwrap(), b"fo");
+        assert_eq!(decode("Zm9vYmFy").unwrap(), b"foobar");
+        assert!(decode("Zm9v!").is_none());
+        assert!(decode("Z").is_none());
+    }
+
+    #[test]
+    fn malformed_input_is_refused() {
+        for bad in [
+            "Zh==", "Zm9=", "Zg=Zg", "Zm 9v", "Zm9v\n", "Zm9vY", "-_8", "Ä",
+        ] {
+            assert!(decode(bad).is_none(), "{:?}", bad);
+        }
+        assert_eq!(decode_url("-_8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(decode("+/8").unwrap(), [0xfb, 0xff]);
+        assert!(decode_url("+/8").is_none());
+    }
+}
@@ -0,0 +1,37 @@
+//! RFC 7932 brotli streams, decoded by the pure Rust brotli-decompressor.
+
+use super::gzip::read_limited;
+use brotli_decompressor::Decompressor;
+
+/**
+ * Decompresses a brotli stream to at most `limit` bytes
+ */
+pub fn decompress(data: &[u8], limit: usize) -> Result<Vec<u8>, &'static str> {
+    read_limited(Decompressor::new(data, 4096), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::base64;
+
+    fn unbrotli(encoded: &str, limit: usize) -> Result<Vec<u8>, &'static str> {
+        decompress(&base64::decode(encoded).unwrap(), limit)
+    }
+
+    #[test]
+    fn decompresses_within_the_limit() {
+        assert_eq!(
+            unbrotli("GxkA+I3EOCbxQHciShGQvdY5a+tTksBTBIhgj1gY", 1024).unwrap(),
+            br#"{"text": "gm gm gm gm gm"}"#
+        );
+        // 3000 bytes from 16
+        let repetitive = "G7cL+CVBztrisyDgbQCgAQ==";
+        assert_eq!(unbrotli(repetitive, 4096).unwrap().len(), 3000);
+        assert_eq!(
+            unbrotli(repetitive, 1000).unwrap_err(),
+            "Decompressed data is too large."
+        );
+        assert!(decompress(b"not brotli at all", 1024).is_err());
+    }
+}
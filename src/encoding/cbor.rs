@@ -0,0 +1,107 @@
+//! RFC 8949 CBOR to JSON, parsed by ciborium and mapped onto what a post payload can be: text
+//! map keys, integers within i64/u64. Byte strings become 0x-prefixed hex and tags are dropped
+//! in favour of their content.
+
+use ciborium::value::Value as Cbor;
+use serde_json::{Map, Number, Value};
+
+/**
+ * Nesting limit, deeper payloads are rejected before they can exhaust the stack
+ */
+const MAX_DEPTH: usize = 32;
+
+fn to_json(item: Cbor) -> Result<Value, &'static str> {
+    Ok(match item {
+        Cbor::Integer(n) => {
+            let n = i128::from(n);
+            match (u64::try_from(n), i64::try_from(n)) {
+                (Ok(n), _) => n.into(),
+                (_, Ok(n)) => n.into(),
+                _ => return Err("CBOR integer is out of range."),
+            }
+        }
+        Cbor::Bytes(bytes) => format!("0x{}", hex::encode(bytes)).into(),
+        Cbor::Float(float) => Number::from_f64(float)
+            .map(Value::Number)
+            .ok_or("CBOR float is not a JSON number.")?,
+        Cbor::Text(text) => text.into(),
+        Cbor::Bool(b) => b.into(),
+        Cbor::Null => Value::Null,
+        Cbor::Tag(_, content) => to_json(*content)?,
+        Cbor::Array(items) => {
+            Value::Array(items.into_iter().map(to_json).collect::<Result<_, _>>()?)
+        }
+        Cbor::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let key = match key {
+                    Cbor::Text(key) => key,
+                    _ => return Err("CBOR map keys must be text."),
+                };
+                map.insert(key, to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        _ => return Err("CBOR simple value is not supported."),
+    })
+}
+
+/**
+ * One CBOR data item, trailing bytes are an error
+ */
+pub fn decode(bytes: &[u8]) -> Result<Value, &'static str> {
+    let mut rest = bytes;
+    let item: Cbor = ciborium::de::from_reader_with_recursion_limit(&mut rest, MAX_DEPTH).map_err(
+        |e| match e {
+            ciborium::de::Error::Io(_) => "CBOR data is truncated.",
+            ciborium::de::Error::RecursionLimitExceeded => "CBOR data is nested too deeply.",
+            _ => "CBOR data is malformed.",
+        },
+    )?;
+    if !rest.is_empty() {
+        return Err("CBOR data has trailing bytes.");
+    }
+    to_json(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn decodes_rfc_8949_examples() {
+        assert_eq!(decode(&[0x00]).unwrap(), json!(0));
+        assert_eq!(decode(&[0x18, 0x64]).unwrap(), json!(100));
+        assert_eq!(decode(&[0x38, 0x63]).unwrap(), json!(-100));
+        assert_eq!(decode(&[0xf9, 0x3c, 0x00]).unwrap(), json!(1.0));
+        assert_eq!(decode(&[0xf5]).unwrap(), json!(true));
+        assert_eq!(decode(&[0x43, 1, 2, 3]).unwrap(), json!("0x010203"));
+        // {"a": 1, "b": [2, 3]}
+        assert_eq!(
+            decode(&[0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x82, 0x02, 0x03]).unwrap(),
+            json!({ "a": 1, "b": [2, 3] })
+        );
+        // indefinite lengths are valid CBOR, [_ 1, 2]
+        assert_eq!(decode(&[0x9f, 0x01, 0x02, 0xff]).unwrap(), json!([1, 2]));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[0x62, 0x61]).is_err());
+        assert!(decode(&[0x9f]).is_err());
+        assert!(decode(&[0xa1, 0x01, 0x01]).is_err());
+        assert!(decode(&[0x00, 0x00]).is_err());
+        assert!(decode(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert_eq!(
+            decode(&[0x81; 64]).unwrap_err(),
+            "CBOR data is nested too deeply."
+        );
+        // -2^64, below i64
+        assert_eq!(
+            decode(&[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).unwrap_err(),
+            "CBOR integer is out of range."
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! RFC 1952 gzip members, inflated by flate2 on its pure Rust miniz_oxide backend, which also
+//! checks the CRC-32 and size trailer.
+
+use flate2::read::GzDecoder;
+use std::io::Read;
+
+/**
+ * Decompresses a single gzip member to at most `limit` bytes and checks its CRC-32
+ */
+pub fn decompress(data: &[u8], limit: usize) -> Result<Vec<u8>, &'static str> {
+    if data.len() < 18 || data[0] != 0x1f || data[1] != 0x8b || data[2] != 8 {
+        return Err("Data is not gzip compressed.");
+    }
+    read_limited(GzDecoder::new(data), limit)
+}
+
+/**
+ * Everything `reader` decompresses, refused as soon as it passes `limit` bytes so a small
+ * payload can't expand into a memory bomb
+ */
+pub(super) fn read_limited(reader: impl Read, limit: usize) -> Result<Vec<u8>, &'static str> {
+    let mut output = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut output)
+        .map_err(|_| "Compressed data is corrupt or fails its integrity check.")?;
+    if output.len() > limit {
+        return Err("Decompressed data is too large.");
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::base64;
+
+    fn gunzip(encoded: &str, limit: usize) -> Result<Vec<u8>, &'static str> {
+        decompress(&base64::decode(encoded).unwrap(), limit)
+    }
+
+    #[test]
+    fn decompresses_stored_fixed_and_dynamic_blocks() {
+        assert_eq!(
+            gunzip("H4sIAAAAAAAEAwEGAPn/c3RvcmVkC/lDVgYAAAA=", 1024).unwrap(),
+            b"stored"
+        );
+        assert_eq!(
+            gunzip("H4sIAAAAAAACA6tWKkmtKFGyUlBKz1VAQkq1AFlqpwsaAAAA", 1024).unwrap(),
+            br#"{"text": "gm gm gm gm gm"}"#
+        );
+        let dynamic = "H4sIAAAAAAACA41VW47CMBC7Sq8WltBFpKUSXe31QbbC2EmK+EnDxBl7XmFOy5Km5bru0ynvafpPpXB3KX95/cndt1zXnOY8nXN5oQyayvab3oD6hXMeVSBY6CCot/uj4nTd77e8Nq55tf5SHE9azXoGGm4dDFHOw3tQNyNRkR644bmj6RRALAjQhXzIjEdWvyQXSmwt951Up8IFr5P/Cq2xOL26pUze5J52PfW0QAC8mhTQEiHZ8KtKPCLwcIJIsZ4Nw1qKtJdEVi9c1JJBVeKQBlSEVmkwWLEc1kL6CEDxCSFxouWRfIoBiwXdTohoAdiS4mMXxEImw+xl0U7xfqKN+/F89AX0MRk9DVEp1wGtJFNJEUxEJFDzKvb+aQJCslCVzvHAHr9kMc6DPIpnq1Y9lyp4G8UkGFTzLvRtfx0VfTDWTq5Vk7bqWrV/T0DSzm8YY4lafZPx9t+lRmDhiKYn2xqh5xQHAAA=";
+        assert_eq!(gunzip(dynamic, 4096).unwrap().len(), 1812);
+    }
+
+    #[test]
+    fn bombs_and_corruption_are_rejected() {
+        // 1230 bytes inflated from a 100 byte member
+        let repetitive = "H4sIAAAAAAACAys3Lk/MyVEoH6VoQZVkpCoUlmYmZyskFeWX5ymk5VcoZJXmFhQr5JelFoGlcxKrKhVS8tNH1Y6qHVVLoVoAGinE+M4EAAA=";
+        assert_eq!(gunzip(repetitive, 2048).unwrap().len(), 1230);
+        assert_eq!(
+            gunzip(repetitive, 1000).unwrap_err(),
+            "Decompressed data is too large."
+        );
+
+        let mut corrupt =
+            base64::decode("H4sIAAAAAAACA6tWKkmtKFGyUlBKz1VAQkq1AFlqpwsaAAAA").unwrap();
+        let last = corrupt.len() - 5;
+        corrupt[last] ^= 0xff;
+        assert!(decompress(&corrupt, 1024).is_err());
+        assert!(decompress(b"not gzip at all!!!", 1024).is_err());
+    }
+}
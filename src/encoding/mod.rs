@@ -0,0 +1,109 @@
+//! Compact transaction encodings. Everything is decoded to the same JSON value the validators
+//! already work on, so a payload's rules don't depend on how it was sent. The base decoders are
+//! shared with CID and data URI checks, they refuse anything an encoder would not have written.
+
+#[cfg(feature = "mint")]
+pub mod abi;
+pub mod base32;
+pub mod base58;
+pub mod base64;
+pub mod brotli;
+pub mod cbor;
+pub mod gzip;
+pub mod multibase;
+
+use serde_json::Value;
+
+/**
+ * Prefix of base64 CBOR transaction data, `cbor:<base64>`
+ */
+pub static CBOR_PREFIX: &str = "cbor:";
+
+/**
+ * `encoding` of a compressed body, `{"encoding": "gzip+base64", "data": "<base64>"}`, or
+ * `br+base64` for brotli
+ */
+pub static GZIP_ENCODING: &str = "gzip+base64";
+pub static BROTLI_ENCODING: &str = "br+base64";
+
+/**
+ * Text of a base64 gzip body, inflated to at most `limit` bytes
+ */
+pub fn decode_gzip_base64(encoded: &str, limit: usize) -> Result<String, &'static str> {
+    let bytes = base64::decode(encoded.trim()).ok_or("Data is not valid base64.")?;
+    String::from_utf8(gzip::decompress(&bytes, limit)?).map_err(|_| "Data is not UTF-8.")
+}
+
+/**
+ * Text of a base64 brotli body, decompressed to at most `limit` bytes
+ */
+pub fn decode_brotli_base64(encoded: &str, limit: usize) -> Result<String, &'static str> {
+    let bytes = base64::decode(encoded.trim()).ok_or("Data is not valid base64.")?;
+    String::from_utf8(brotli::decompress(&bytes, limit)?).map_err(|_| "Data is not UTF-8.")
+}
+
+/**
+ * Hex as callers actually send it: an optional `0x`/`0X` prefix, surrounding whitespace and
+ * line breaks of a wrapped dump are all ignored. An invalid character is reported at its
+ * position in `input`.
+ */
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    let start = input.len() - input.trim_start().len();
+    let body = &input[start..];
+    let start = match body.get(..2) {
+        Some("0x" | "0X") => start + 2,
+        _ => start,
+    };
+
+    let mut nibbles = Vec::with_capacity(input.len());
+    for (index, c) in input[start..].char_indices() {
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+        let nibble = c
+            .to_digit(16)
+            .ok_or(hex::FromHexError::InvalidHexCharacter {
+                c,
+                index: start + index,
+            })?;
+        nibbles.push(nibble as u8);
+    }
+    if nibbles.len() % 2 == 1 {
+        return Err(hex::FromHexError::OddLength);
+    }
+    Ok(nibbles
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/**
+ * Decodes base64 CBOR into a JSON value
+ */
+pub fn decode_cbor_base64(encoded: &str) -> Result<Value, &'static str> {
+    let bytes = base64::decode(encoded.trim()).ok_or("Data is not valid base64.")?;
+    cbor::decode(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_tolerates_prefix_and_whitespace() {
+        assert_eq!(decode_hex("0xdeadBEEF").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            decode_hex(" 0Xde ad\r\nbe\tef \n").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(decode_hex("").unwrap(), b"");
+        assert_eq!(decode_hex("0x").unwrap(), b"");
+        assert_eq!(decode_hex("0xabc"), Err(hex::FromHexError::OddLength));
+        assert_eq!(
+            decode_hex("  0x12g4"),
+            Err(hex::FromHexError::InvalidHexCharacter { c: 'g', index: 6 })
+        );
+        // a prefix is only a prefix once
+        assert!(decode_hex("0x0x12").is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! Multibase strings, a one character prefix naming the base of the rest. Only the bases IPFS
+//! tooling prints are known.
+
+use super::{base32, base58, base64};
+
+/**
+ * Bytes of a multibase string: `b`/`B` base32, `z` base58btc, `m` base64, `u` base64url and
+ * `f`/`F` hex
+ */
+pub fn decode(input: &str) -> Result<Vec<u8>, &'static str> {
+    let mut chars = input.chars();
+    let prefix = chars.next().ok_or("Multibase string is empty.")?;
+    let rest = chars.as_str();
+    let bytes = match prefix {
+        'b' => base32::decode(rest),
+        'B' if !rest.contains(|c: char| c.is_ascii_lowercase()) => {
+            base32::decode(&rest.to_lowercase())
+        }
+        'z' => base58::decode(rest),
+        'm' if !rest.contains('=') => base64::decode(rest),
+        'u' if !rest.contains('=') => base64::decode_url(rest),
+        'f' if !rest.contains(|c: char| c.is_ascii_uppercase()) => hex::decode(rest).ok(),
+        'F' if !rest.contains(|c: char| c.is_ascii_lowercase()) => hex::decode(rest).ok(),
+        'B' | 'm' | 'u' | 'f' | 'F' => None,
+        _ => return Err("Unknown multibase prefix."),
+    };
+    bytes.ok_or("Multibase string does not decode in its base.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_base_decodes_the_same_bytes() {
+        for encoded in [
+            "bmzxw6", "BMZXW6", "zbQbp", "mZm9v", "uZm9v", "f666f6f", "F666F6F",
+        ] {
+            assert_eq!(decode(encoded).unwrap(), b"foo", "{}", encoded);
+        }
+    }
+
+    #[test]
+    fn malformed_input_is_refused() {
+        assert_eq!(decode(""), Err("Multibase string is empty."));
+        assert_eq!(decode("qfoo"), Err("Unknown multibase prefix."));
+        for bad in [
+            "BmzXW6", "bMZXW6", "mZm9v=", "f666F6f", "F666f6f", "z0", "b1", "f6",
+        ] {
+            assert_eq!(
+                decode(bad),
+                Err("Multibase string does not decode in its base."),
+                "{}",
+                bad
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::storage::loose_flag;
+use crate::types::{FinalMetadata, MetaContractResult};
+use crate::validation::ValidationError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum W3WallError {
+    #[error("Invalid data structure: {}", hex_reason(.0))]
+    Hex(#[from] hex::FromHexError),
+    #[cfg(feature = "mint")]
+    #[error("Invalid data structure: {0}")]
+    Abi(#[from] crate::encoding::abi::AbiError),
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Validation(#[from] ValidationError),
+    #[error("{0}")]
+    Rejected(String),
+}
+
+fn hex_reason(error: &hex::FromHexError) -> String {
+    match error {
+        hex::FromHexError::OddLength => {
+            "hex has an odd number of digits, every byte takes two".to_string()
+        }
+        hex::FromHexError::InvalidHexCharacter { c, index } => {
+            format!("{:?} at position {} is not a hex digit", c, index)
+        }
+        other => other.to_string(),
+    }
+}
+
+impl W3WallError {
+    pub fn rejected(reason: &str) -> Self {
+        W3WallError::Rejected(reason.to_string())
+    }
+}
+
+pub trait IntoResult {
+    fn into_result(self) -> MetaContractResult;
+}
+
+/**
+ * The one place a handler outcome is turned into what the lineage node expects, so the loose
+ * policy is applied to every record here rather than by each handler
+ */
+impl IntoResult for Result<Vec<FinalMetadata>, W3WallError> {
+    fn into_result(self) -> MetaContractResult {
+        match self {
+            Ok(mut metadatas) => {
+                for metadata in metadatas.iter_mut() {
+                    metadata.loose = loose_flag(&metadata.alias);
+                }
+                MetaContractResult {
+                    result: true,
+                    metadatas,
+                    error_string: "".to_string(),
+                }
+            }
+            Err(e) => MetaContractResult {
+                result: false,
+                metadatas: Vec::new(),
+                error_string: e.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_result_maps_both_outcomes() {
+        let ok: Result<Vec<FinalMetadata>, W3WallError> = Ok(Vec::new());
+        let result = ok.into_result();
+        assert!(result.result);
+        assert!(result.error_string.is_empty());
+
+        let record = |alias: &str| FinalMetadata {
+            public_key: "0xa".to_string(),
+            alias: alias.to_string(),
+            content: "{}".to_string(),
+            loose: 1,
+            version: "1".to_string(),
+        };
+        let ok: Result<Vec<FinalMetadata>, W3WallError> = Ok(vec![
+            record("config"),
+            record("w3wall.dispute.bafy"),
+            record(""),
+            record("configs"),
+            record("w3wall.karma.0xa"),
+            record("w3wall.vote.00000000000000aa"),
+            record("w3wall.tips.bafy"),
+        ]);
+        let loose: Vec<i64> = ok.into_result().metadatas.iter().map(|m| m.loose).collect();
+        assert_eq!(loose, [0, 0, 1, 1, 0, 0, 0]);
+
+        let err: Result<Vec<FinalMetadata>, W3WallError> =
+            Err(hex::decode("z").unwrap_err().into());
+        let result = err.into_result();
+        assert!(!result.result);
+        assert!(result.error_string.starts_with("Invalid data structure"));
+    }
+}
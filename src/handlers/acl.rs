@@ -0,0 +1,197 @@
+//! Who may write which alias, checked once over everything a transaction emits so a handler
+//! bug can't hand a wall record to the wrong key.
+
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::handlers::announce::ANNOUNCEMENT_ALIAS;
+use crate::handlers::approval::APPROVED_AUTHORS_ALIAS;
+use crate::handlers::export::EXPORT_ALIAS;
+#[cfg(feature = "federation")]
+use crate::handlers::federation::RELATED_ALIAS;
+use crate::handlers::ownership::{load_transfer, OWNERSHIP_ALIAS};
+use crate::handlers::profile::PROFILE_ALIAS;
+use crate::handlers::reveal::REVEAL_ALIAS;
+use crate::handlers::summary::SUMMARY_ALIAS;
+use crate::handlers::theme::THEME_ALIAS;
+use crate::handlers::webhook::WEBHOOKS_ALIAS;
+use crate::storage::alias::Alias;
+use crate::storage::blocklist::BLOCKLIST_ALIAS;
+use crate::storage::config::CONFIG_ALIAS;
+use crate::storage::filter::FILTER_ALIAS;
+use crate::storage::following::FOLLOWING_ALIAS;
+use crate::storage::moderation::MODERATION_ALIAS;
+use crate::storage::muted::MUTED_ALIAS;
+use crate::storage::stickers::STICKERS_ALIAS;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+
+#[derive(Debug, PartialEq)]
+enum Writer {
+    /**
+     * Wall record, sent by an owner, or by the key accepting a pending ownership transfer
+     */
+    Owner,
+    /**
+     * Wall record, sent by a moderator or owner
+     */
+    Moderator,
+    /**
+     * Record of the sender's own, posts included
+     */
+    Subject,
+}
+
+fn writer(alias: &str) -> Option<Writer> {
+    if [
+        CONFIG_ALIAS,
+        OWNERSHIP_ALIAS,
+        THEME_ALIAS,
+        REVEAL_ALIAS,
+        #[cfg(feature = "federation")]
+        RELATED_ALIAS,
+        EXPORT_ALIAS,
+        STICKERS_ALIAS,
+        WEBHOOKS_ALIAS,
+    ]
+    .contains(&alias)
+    {
+        Some(Writer::Owner)
+    } else if [
+        FILTER_ALIAS,
+        MODERATION_ALIAS,
+        APPROVED_AUTHORS_ALIAS,
+        ANNOUNCEMENT_ALIAS,
+        SUMMARY_ALIAS,
+    ]
+    .contains(&alias)
+    {
+        Some(Writer::Moderator)
+    } else if [
+        Alias::Post.as_str(),
+        PROFILE_ALIAS,
+        BLOCKLIST_ALIAS,
+        MUTED_ALIAS,
+        FOLLOWING_ALIAS,
+    ]
+    .contains(&alias)
+    {
+        Some(Writer::Subject)
+    } else {
+        None
+    }
+}
+
+/**
+ * Rejects the whole transaction when any emitted record breaks the alias ACL, aliases not in
+ * the ACL are left to their handler
+ */
+pub fn check_writes(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    finals: &[FinalMetadata],
+) -> Result<(), W3WallError> {
+    let sender = &transaction.public_key;
+    let is_wall =
+        |record: &FinalMetadata| record.public_key.eq_ignore_ascii_case(&contract.public_key);
+
+    for record in finals {
+        let allowed = match writer(&record.alias) {
+            // a passed governance proposal rewrites the config whoever sends it
+            Some(Writer::Owner) => {
+                is_wall(record)
+                    && (config.is_owner(sender)
+                        || (transaction.method == "apply_config" && record.alias == CONFIG_ALIAS)
+                        || {
+                            let transfer = load_transfer(contract, metadatas);
+                            transfer.is_pending() && transfer.to.eq_ignore_ascii_case(sender)
+                        })
+            }
+            Some(Writer::Moderator) => is_wall(record) && config.is_moderator(sender),
+            // an approved post is stored under its author by the reviewing moderator
+            Some(Writer::Subject) => {
+                record.public_key.eq_ignore_ascii_case(sender)
+                    || (transaction.method == "approve"
+                        && record.alias == Alias::Post
+                        && config.is_moderator(sender))
+            }
+            None => true,
+        };
+        if !allowed {
+            log::warn!(
+                "acl: {} may not write {:?} under {}",
+                sender,
+                record.alias,
+                record.public_key
+            );
+            return Err(W3WallError::Rejected(format!(
+                "Not allowed to write the {:?} record.",
+                record.alias
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(alias: &str, public_key: &str) -> FinalMetadata {
+        FinalMetadata {
+            public_key: public_key.to_string(),
+            alias: alias.to_string(),
+            content: "{}".to_string(),
+            loose: 1,
+            version: "1".to_string(),
+        }
+    }
+
+    #[test]
+    fn wall_records_need_the_role_and_user_records_the_subject() {
+        let contract = MetaContract {
+            public_key: "0xwall".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            moderators: vec!["0xmod".to_string()],
+            ..Default::default()
+        };
+        let check = |sender: &str, finals: &[FinalMetadata]| {
+            let transaction = Transaction {
+                public_key: sender.to_string(),
+                ..Default::default()
+            };
+            check_writes(&contract, &[], &transaction, &config, finals)
+        };
+
+        assert!(check("0xowner", &[record(CONFIG_ALIAS, "0xwall")]).is_ok());
+        assert!(check("0xmod", &[record(CONFIG_ALIAS, "0xwall")]).is_err());
+        assert!(check("0xowner", &[record(CONFIG_ALIAS, "0xowner")]).is_err());
+        assert!(check("0xmod", &[record(FILTER_ALIAS, "0xwall")]).is_ok());
+        assert!(check("0xa", &[record(MODERATION_ALIAS, "0xwall")]).is_err());
+
+        assert!(check(
+            "0xa",
+            &[record("", "0xa"), record("w3wall.rollup", "0xwall")]
+        )
+        .is_ok());
+        assert!(check("0xa", &[record("", "0xb")]).is_err());
+        let apply_config = Transaction {
+            method: "apply_config".to_string(),
+            public_key: "0xa".to_string(),
+            ..Default::default()
+        };
+        let finals = [record(CONFIG_ALIAS, "0xwall")];
+        assert!(check_writes(&contract, &[], &apply_config, &config, &finals).is_ok());
+        let finals = [record(THEME_ALIAS, "0xwall")];
+        assert!(check_writes(&contract, &[], &apply_config, &config, &finals).is_err());
+        assert_eq!(
+            check("0xa", &[record(PROFILE_ALIAS, "0xb")])
+                .unwrap_err()
+                .to_string(),
+            r#"Not allowed to write the "w3wall.profile" record."#
+        );
+    }
+}
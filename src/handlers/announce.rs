@@ -0,0 +1,120 @@
+use crate::data::WallConfig;
+use crate::defaults::{DEFAULT_MAX_ANNOUNCEMENT_LENGTH, DEFAULT_MAX_ANNOUNCEMENT_SEC};
+use crate::error::W3WallError;
+use crate::metadata::timestamp_sec;
+use crate::types::{FinalMetadata, MetaContract, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static ANNOUNCEMENT_ALIAS: &str = "w3wall.announcement";
+
+/**
+ * Banner shown by clients until `expires`, a new announcement replaces the previous one
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Announcement {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: String,
+    pub expires: u64,
+    pub announced_by: String,
+    pub announced_at: u64,
+}
+
+#[derive(Deserialize)]
+struct AnnounceRequest {
+    text: String,
+    expires: u64,
+}
+
+/**
+ * Owners and moderators only. The expiry is mandatory and at most DEFAULT_MAX_ANNOUNCEMENT_SEC
+ * ahead, so a banner can't be pinned forever.
+ */
+pub fn announce(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_moderator(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners and moderators can announce.",
+        ));
+    }
+
+    let request: AnnounceRequest = serde_json::from_str(&transaction.data)?;
+    let text = request.text.trim();
+    if text.is_empty() || text.chars().count() > DEFAULT_MAX_ANNOUNCEMENT_LENGTH {
+        return Err(W3WallError::Rejected(format!(
+            "Announcement must be 1 to {} characters.",
+            DEFAULT_MAX_ANNOUNCEMENT_LENGTH
+        )));
+    }
+
+    let now = timestamp_sec(transaction.timestamp);
+    if request.expires <= now || request.expires - now > DEFAULT_MAX_ANNOUNCEMENT_SEC {
+        return Err(W3WallError::rejected(
+            "Announcement must expire in the future, within 30 days.",
+        ));
+    }
+
+    let announcement = Announcement {
+        kind: "announcement".to_string(),
+        text: text.to_string(),
+        expires: request.expires,
+        announced_by: transaction.public_key.clone(),
+        announced_at: now,
+    };
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: ANNOUNCEMENT_ALIAS.to_string(),
+        content: serde_json::to_string(&announcement)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn transaction(public_key: &str, expires: u64) -> Transaction {
+        Transaction {
+            method: "announce".to_string(),
+            public_key: public_key.to_string(),
+            data: format!(
+                r#"{{"text": " Maintenance tonight ", "expires": {}}}"#,
+                expires
+            ),
+            timestamp: NOW * 1000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn moderators_announce_with_an_expiry() {
+        let contract = MetaContract::default();
+        let config = WallConfig {
+            moderators: vec!["0xmod".to_string()],
+            ..Default::default()
+        };
+
+        assert!(announce(&contract, &transaction("0xother", NOW + 60), &config).is_err());
+        assert!(announce(&contract, &transaction("0xmod", NOW), &config).is_err());
+        assert!(announce(
+            &contract,
+            &transaction("0xmod", NOW + DEFAULT_MAX_ANNOUNCEMENT_SEC + 1),
+            &config
+        )
+        .is_err());
+
+        let finals = announce(&contract, &transaction("0xmod", NOW + 60), &config).unwrap();
+        assert_eq!(finals[0].alias, ANNOUNCEMENT_ALIAS);
+        let announcement: Announcement = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(announcement.kind, "announcement");
+        assert_eq!(announcement.text, "Maintenance tonight");
+        assert_eq!(announcement.announced_at, NOW);
+    }
+}
@@ -0,0 +1,400 @@
+use crate::data::{Approval, WallConfig};
+use crate::defaults::DEFAULT_MAX_APPEAL_LENGTH;
+use crate::error::W3WallError;
+use crate::handlers::audit::{audit_record, reason_code};
+use crate::handlers::moderation::check_owner_threshold;
+use crate::metadata::timestamp_sec;
+use crate::storage::moderation::{load_moderation, moderation_cid, MODERATION_ALIAS};
+use crate::storage::{find_post, read_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static APPEAL_ALIAS_PREFIX: &str = "w3wall.appeal.";
+
+pub fn appeal_alias(cid: &str) -> String {
+    format!("{}{}", APPEAL_ALIAS_PREFIX, cid)
+}
+
+/**
+ * pending → granted | denied. Both outcomes are final, a post gets one appeal.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum AppealStatus {
+    Pending,
+    Granted,
+    Denied,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Appeal {
+    pub post: String,
+    pub status: AppealStatus,
+    pub by: String,
+    pub text: String,
+    pub at: u64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub resolved_by: Option<String>,
+    /**
+     * Reason code the moderator gave, also in the audit log
+     */
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub resolved_at: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct AppealRequest {
+    post: String,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct ResolveRequest {
+    post: String,
+    grant: bool,
+    reason: String,
+    #[serde(default)]
+    approvals: Vec<Approval>,
+}
+
+/**
+ * What co-owners sign to restore a removed post, the reverse of a `remove` so it takes the same
+ * `owner_threshold`, bound to the wall and the moderation record like moderation_message
+ */
+pub fn grant_message(data_key: &str, state_cid: &str, post: &str) -> String {
+    format!("w3wall:grant_appeal:{}:{}:{}", data_key, state_cid, post)
+}
+
+pub fn load_appeal(contract: &MetaContract, metadatas: &[Metadata], cid: &str) -> Option<Appeal> {
+    read_record(metadatas, &appeal_alias(cid), &contract.public_key)
+}
+
+fn appeal_record(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    appeal: &Appeal,
+) -> Result<FinalMetadata, W3WallError> {
+    Ok(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: appeal_alias(&appeal.post),
+        content: serde_json::to_string(appeal)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    })
+}
+
+/**
+ * The author of a removed post asks the moderators to restore it, once
+ */
+pub fn appeal(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: AppealRequest = serde_json::from_str(&transaction.data)?;
+
+    let post = find_post(metadatas, &request.post)
+        .ok_or_else(|| W3WallError::rejected("Post is not on this wall."))?;
+    if !post
+        .public_key
+        .eq_ignore_ascii_case(&transaction.public_key)
+    {
+        return Err(W3WallError::rejected("Only the author can appeal."));
+    }
+    if !load_moderation(contract, metadatas)?
+        .unwrap_or_default()
+        .removed
+        .contains(&request.post)
+    {
+        return Err(W3WallError::rejected("Post has not been removed."));
+    }
+    if load_appeal(contract, metadatas, &request.post).is_some() {
+        return Err(W3WallError::rejected("Post has already been appealed."));
+    }
+    let text = request.text.trim();
+    if text.chars().count() > DEFAULT_MAX_APPEAL_LENGTH {
+        return Err(W3WallError::Rejected(format!(
+            "Appeal is limited to {} characters.",
+            DEFAULT_MAX_APPEAL_LENGTH
+        )));
+    }
+
+    let appeal = Appeal {
+        post: request.post,
+        status: AppealStatus::Pending,
+        by: transaction.public_key.clone(),
+        text: text.to_string(),
+        at: timestamp_sec(transaction.timestamp),
+        resolved_by: None,
+        reason: None,
+        resolved_at: None,
+    };
+    Ok(vec![
+        appeal_record(contract, transaction, &appeal)?,
+        audit_record(contract, metadatas, transaction, "appeal", &appeal.post, "")?,
+    ])
+}
+
+/**
+ * Moderators grant or deny a pending appeal with a reason code. Granting restores the post, and
+ * like the removal it reverses it needs `owner_threshold` owner approvals.
+ */
+pub fn resolve_appeal(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_moderator(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall moderators can resolve appeals.",
+        ));
+    }
+
+    let request: ResolveRequest = serde_json::from_str(&transaction.data)?;
+    let mut appeal = match load_appeal(contract, metadatas, &request.post) {
+        Some(appeal) if appeal.status == AppealStatus::Pending => appeal,
+        _ => return Err(W3WallError::rejected("No pending appeal for this post.")),
+    };
+    if request.grant {
+        let message = grant_message(
+            &transaction.data_key,
+            &moderation_cid(contract, metadatas),
+            &appeal.post,
+        );
+        check_owner_threshold(&message, &request.approvals, transaction, config)?;
+    }
+    let (status, action) = match request.grant {
+        true => (AppealStatus::Granted, "grant_appeal"),
+        false => (AppealStatus::Denied, "deny_appeal"),
+    };
+    let audit = audit_record(
+        contract,
+        metadatas,
+        transaction,
+        action,
+        &appeal.post,
+        &request.reason,
+    )?;
+    appeal.status = status;
+    appeal.resolved_by = Some(transaction.public_key.clone());
+    appeal.reason = Some(reason_code(&request.reason)?);
+    appeal.resolved_at = Some(timestamp_sec(transaction.timestamp));
+
+    let mut finals = vec![appeal_record(contract, transaction, &appeal)?];
+    if request.grant {
+        let mut state = load_moderation(contract, metadatas)?.unwrap_or_default();
+        state.removed.retain(|cid| *cid != appeal.post);
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: MODERATION_ALIAS.to_string(),
+            content: serde_json::to_string(&state)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+    finals.push(audit);
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn wall(appeal: Option<(&str, &str)>) -> Vec<Metadata> {
+        test_store::put("appeal-moderation", r#"{"removed": ["taken-cid"]}"#);
+        let mut metadatas = vec![
+            Metadata {
+                public_key: "0xauthor".to_string(),
+                cid: "taken-cid".to_string(),
+                ..Default::default()
+            },
+            Metadata {
+                public_key: "0xauthor".to_string(),
+                cid: "live-cid".to_string(),
+                ..Default::default()
+            },
+            Metadata {
+                public_key: "0xcontract".to_string(),
+                alias: MODERATION_ALIAS.to_string(),
+                cid: "appeal-moderation".to_string(),
+                ..Default::default()
+            },
+        ];
+        if let Some((cid, content)) = appeal {
+            test_store::put(cid, content);
+            metadatas.push(Metadata {
+                public_key: "0xcontract".to_string(),
+                alias: appeal_alias("taken-cid"),
+                cid: cid.to_string(),
+                ..Default::default()
+            });
+        }
+        metadatas
+    }
+
+    #[test]
+    fn authors_appeal_removed_posts_once() {
+        let data = r#"{"post": "taken-cid", "text": "it was satire"}"#;
+        let err = |metadatas: &[Metadata], public_key: &str, data: &str| {
+            appeal(&contract(), metadatas, &transaction(public_key, data))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            err(&wall(None), "0xother", data),
+            "Only the author can appeal."
+        );
+        assert_eq!(
+            err(&wall(None), "0xauthor", r#"{"post": "live-cid"}"#),
+            "Post has not been removed."
+        );
+
+        let finals = appeal(&contract(), &wall(None), &transaction("0xAUTHOR", data)).unwrap();
+        assert_eq!(finals[0].alias, "w3wall.appeal.taken-cid");
+        let filed: Appeal = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(filed.status, AppealStatus::Pending);
+        assert!(finals[1].content.contains(r#""action":"appeal""#));
+
+        let denied =
+            r#"{"post": "taken-cid", "status": "denied", "by": "0xauthor", "text": "", "at": 0}"#;
+        assert_eq!(
+            err(&wall(Some(("appeal-denied", denied))), "0xauthor", data),
+            "Post has already been appealed."
+        );
+    }
+
+    #[test]
+    fn moderators_grant_appeals_and_restore_the_post() {
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let pending =
+            r#"{"post": "taken-cid", "status": "pending", "by": "0xauthor", "text": "", "at": 0}"#;
+        let metadatas = wall(Some(("appeal-pending", pending)));
+        let grant = r#"{"post": "taken-cid", "grant": true, "reason": "satire"}"#;
+        assert!(resolve_appeal(
+            &contract(),
+            &metadatas,
+            &transaction("0xauthor", grant),
+            &config
+        )
+        .is_err());
+
+        let finals = resolve_appeal(
+            &contract(),
+            &metadatas,
+            &transaction("0xowner", grant),
+            &config,
+        )
+        .unwrap();
+        let resolved: Appeal = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(resolved.status, AppealStatus::Granted);
+        assert_eq!(resolved.reason.as_deref(), Some("satire"));
+        assert_eq!(finals[1].alias, MODERATION_ALIAS);
+        assert!(!finals[1].content.contains("taken-cid"));
+        assert!(finals[2]
+            .content
+            .contains(r#""action":"grant_appeal","target":"taken-cid","reason":"satire""#));
+
+        let denied =
+            r#"{"post": "taken-cid", "status": "denied", "by": "0xauthor", "text": "", "at": 0}"#;
+        assert!(resolve_appeal(
+            &contract(),
+            &wall(Some(("appeal-resolved", denied))),
+            &transaction("0xowner", grant),
+            &config
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn granting_takes_the_owner_threshold() {
+        use crate::crypto::test_wallet;
+
+        let config = WallConfig {
+            owner: test_wallet::address(1),
+            owners: vec![test_wallet::address(2)],
+            owner_threshold: 2,
+            moderators: vec!["0xmod".to_string()],
+            ..Default::default()
+        };
+        let pending =
+            r#"{"post": "taken-cid", "status": "pending", "by": "0xauthor", "text": "", "at": 0}"#;
+        let metadatas = wall(Some(("appeal-pending-multisig", pending)));
+        let resolve = |public_key: &str, grant: bool, signers: &[u64]| {
+            let message = grant_message("", "appeal-moderation", "taken-cid");
+            let approvals: Vec<serde_json::Value> = signers
+                .iter()
+                .map(|signer| {
+                    serde_json::json!({
+                        "signer": test_wallet::address(*signer),
+                        "signature": test_wallet::sign(*signer, &message)
+                    })
+                })
+                .collect();
+            let data = serde_json::json!({
+                "post": "taken-cid", "grant": grant, "reason": "satire", "approvals": approvals
+            });
+            resolve_appeal(
+                &contract(),
+                &metadatas,
+                &transaction(public_key, &data.to_string()),
+                &config,
+            )
+        };
+
+        // a single moderator can deny, but not undo a removal on their own
+        assert!(resolve("0xmod", false, &[]).is_ok());
+        assert_eq!(
+            resolve("0xmod", true, &[1]).unwrap_err().to_string(),
+            "Action needs 2 owner approvals, got 1."
+        );
+        let finals = resolve("0xmod", true, &[1, 2]).unwrap();
+        assert_eq!(finals[1].alias, MODERATION_ALIAS);
+    }
+
+    #[test]
+    fn unreadable_moderation_state_is_not_overwritten() {
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let pending =
+            r#"{"post": "taken-cid", "status": "pending", "by": "0xauthor", "text": "", "at": 0}"#;
+        let mut metadatas = wall(Some(("appeal-pending-unread", pending)));
+        metadatas[2].cid = "appeal-moderation-not-stored".to_string();
+        let grant = r#"{"post": "taken-cid", "grant": true, "reason": "satire"}"#;
+        let err = resolve_appeal(
+            &contract(),
+            &metadatas,
+            &transaction("0xowner", grant),
+            &config,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The w3wall.moderation record can't be read, try again later."
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use crate::crypto::keccak_hex;
+use crate::data::{ValidationMode, WallConfig, WallMode};
+use crate::error::W3WallError;
+use crate::handlers::execute;
+use crate::metadata::quarantine::is_new_account;
+use crate::metadata::timestamp_sec;
+use crate::moderation::WordFilter;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static PENDING_ALIAS_PREFIX: &str = "w3wall.pending.";
+pub static APPROVED_AUTHORS_ALIAS: &str = "w3wall.approved_authors";
+
+pub fn pending_alias(id: &str) -> String {
+    format!("{}{}", PENDING_ALIAS_PREFIX, id)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Review {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/**
+ * A post held back until a moderator reviews it, under its author's key. `data` is the post as
+ * sent, it only becomes a post once approved.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PendingPost {
+    pub data: String,
+    pub submitted_at: u64,
+    pub review: Review,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+}
+
+/**
+ * Keys whose posts skip the queue of an approval wall
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct ApprovedAuthors {
+    pub authors: Vec<String>,
+}
+
+impl ApprovedAuthors {
+    pub fn contains(&self, public_key: &str) -> bool {
+        self.authors
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(public_key))
+    }
+}
+
+pub fn load_approved_authors(contract: &MetaContract, metadatas: &[Metadata]) -> ApprovedAuthors {
+    read_record(metadatas, APPROVED_AUTHORS_ALIAS, &contract.public_key).unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+struct ReviewRequest {
+    author: String,
+    id: String,
+    /**
+     * Approving also lets the author's later posts skip the queue
+     */
+    #[serde(default)]
+    approve_author: bool,
+    #[serde(default)]
+    reason: String,
+}
+
+/**
+ * Whether a post has to wait for a moderator: every post of an author not yet approved on an
+ * approval wall, and a new key's first posts on a strict wall with a quarantine
+ */
+pub fn is_held(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> bool {
+    let sender = &transaction.public_key;
+    match config.mode {
+        WallMode::Approval if !config.is_moderator(sender) => {
+            !load_approved_authors(contract, metadatas).contains(sender)
+        }
+        _ => {
+            config.rules.validation_mode == ValidationMode::Strict
+                && is_new_account(metadatas, transaction, config)
+        }
+    }
+}
+
+/**
+ * The pending record of a post that passed validation but waits for review
+ */
+pub fn hold(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let id = &keccak_hex(transaction.data.as_bytes())[2..18];
+    let alias = pending_alias(id);
+    if read_record::<PendingPost>(metadatas, &alias, &transaction.public_key)
+        .is_some_and(|pending| pending.review == Review::Pending)
+    {
+        return Err(W3WallError::rejected(
+            "This post is already waiting for review.",
+        ));
+    }
+    let pending = PendingPost {
+        data: transaction.data.clone(),
+        submitted_at: timestamp_sec(transaction.timestamp),
+        review: Review::Pending,
+        reviewed_by: None,
+        reason: String::new(),
+    };
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias,
+        content: serde_json::to_string(&pending)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+/**
+ * Moderator decision on a held post, `approve` stores it as the author's post through the
+ * regular pipeline, validated as of when it was sent
+ */
+pub fn review(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_moderator(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only moderators can review held posts.",
+        ));
+    }
+    let request: ReviewRequest = serde_json::from_str(&transaction.data)?;
+    let alias = pending_alias(&request.id);
+    let mut pending: PendingPost = read_record(metadatas, &alias, &request.author)
+        .ok_or_else(|| W3WallError::rejected("No such post is waiting for review."))?;
+    if pending.review != Review::Pending {
+        return Err(W3WallError::rejected("This post was already reviewed."));
+    }
+
+    let mut finals = vec![];
+    if transaction.method == "approve" {
+        let held = Transaction {
+            method: "post".to_string(),
+            public_key: request.author.clone(),
+            data: pending.data.clone(),
+            timestamp: pending.submitted_at,
+            ..transaction.clone()
+        };
+        finals = execute::approved_post(contract, metadatas, &held, config, filter)?;
+        pending.review = Review::Approved;
+
+        let mut approved = load_approved_authors(contract, metadatas);
+        if request.approve_author && !approved.contains(&request.author) {
+            approved.authors.push(request.author.clone());
+            finals.push(FinalMetadata {
+                public_key: contract.public_key.clone(),
+                alias: APPROVED_AUTHORS_ALIAS.to_string(),
+                content: serde_json::to_string(&approved)?,
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    } else {
+        pending.review = Review::Rejected;
+        pending.reason = request.reason;
+    }
+
+    pending.reviewed_by = Some(transaction.public_key.clone());
+    finals.push(FinalMetadata {
+        public_key: request.author,
+        alias,
+        content: serde_json::to_string(&pending)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    });
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::config::CONFIG_ALIAS;
+    use crate::storage::envelope;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xwall".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(method: &str, public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        }
+    }
+
+    fn stored(record: &FinalMetadata, cid: &str) -> Metadata {
+        crate::storage::ipfs::test_store::put(cid, &record.content);
+        Metadata {
+            alias: record.alias.clone(),
+            public_key: record.public_key.clone(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn held_posts_become_posts_once_a_moderator_approves() {
+        let config = FinalMetadata {
+            public_key: "0xwall".to_string(),
+            alias: CONFIG_ALIAS.to_string(),
+            content: r#"{"owner": "0xowner", "moderators": ["0xmod"], "mode": "approval"}"#
+                .to_string(),
+            loose: 0,
+            version: "".to_string(),
+        };
+        let mut metadatas = vec![stored(&config, "approval-config")];
+
+        let sent = transaction("post", "0xa", r#"{"text": "first!"}"#);
+        let held = execute::handle(&contract(), &metadatas, &sent).unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].public_key, "0xa");
+        let id = held[0]
+            .alias
+            .trim_start_matches(PENDING_ALIAS_PREFIX)
+            .to_string();
+        assert!(execute::handle(&contract(), &metadatas, &sent).is_ok());
+        metadatas.push(stored(&held[0], "approval-pending"));
+        assert!(execute::handle(&contract(), &metadatas, &sent).is_err());
+
+        let request = serde_json::json!({ "author": "0xa", "id": id, "approve_author": true });
+        let approve = transaction("approve", "0xb", &request.to_string());
+        assert!(execute::handle(&contract(), &metadatas, &approve).is_err());
+
+        let approve = Transaction {
+            public_key: "0xmod".to_string(),
+            ..approve
+        };
+        let finals = execute::handle(&contract(), &metadatas, &approve).unwrap();
+        let post = finals.iter().find(|f| f.alias.is_empty()).unwrap();
+        assert_eq!(post.public_key, "0xa");
+        assert!(envelope::open(post.content.clone()).contains("first!"));
+        let approved = finals
+            .iter()
+            .find(|f| f.alias == APPROVED_AUTHORS_ALIAS)
+            .unwrap();
+        assert_eq!(
+            envelope::open(approved.content.clone()),
+            r#"{"authors":["0xa"]}"#
+        );
+        let reviewed = finals.iter().find(|f| f.alias == held[0].alias).unwrap();
+        assert!(reviewed
+            .content
+            .contains(r#""review":"approved","reviewed_by":"0xmod""#));
+
+        metadatas.push(stored(approved, "approval-authors"));
+        metadatas[1] = stored(reviewed, "approval-reviewed");
+        assert!(execute::handle(&contract(), &metadatas, &approve).is_err());
+        let direct = transaction("post", "0xa", r#"{"text": "second"}"#);
+        let finals = execute::handle(&contract(), &metadatas, &direct).unwrap();
+        assert_eq!(finals[0].alias, "");
+    }
+
+    #[test]
+    fn rejected_posts_stay_off_the_wall() {
+        let mut config = WallConfig {
+            moderators: vec!["0xmod".to_string()],
+            mode: WallMode::Approval,
+            ..Default::default()
+        };
+        let sent = transaction("post", "0xa", r#"{"text": "buy now"}"#);
+        let filter = WordFilter::new(&[]);
+        let held = execute::post(&contract(), &[], &sent, &config, &filter).unwrap();
+        let metadatas = [stored(&held[0], "approval-rejected")];
+
+        let id = held[0].alias.trim_start_matches(PENDING_ALIAS_PREFIX);
+        let request = serde_json::json!({ "author": "0xa", "id": id, "reason": "spam" });
+        let reject = transaction("reject", "0xmod", &request.to_string());
+        let finals = review(&contract(), &metadatas, &reject, &config, &filter).unwrap();
+        assert_eq!(finals.len(), 1);
+        assert!(finals[0]
+            .content
+            .ends_with(r#""review":"rejected","reviewed_by":"0xmod","reason":"spam"}"#));
+
+        config.mode = WallMode::Open;
+        assert!(!is_held(&contract(), &[], &sent, &config));
+    }
+}
@@ -0,0 +1,132 @@
+//! Append-only log of moderation actions, so a community can see who took down, banned, locked
+//! or redacted what and why. Entries are numbered in order under `w3wall.audit.<n>` by the
+//! contract key and written immutable, a later action adds an entry instead of replacing one.
+
+use crate::defaults::DEFAULT_MAX_REASON_CODE_LENGTH;
+use crate::error::W3WallError;
+use crate::metadata::timestamp_sec;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static AUDIT_ALIAS_PREFIX: &str = "w3wall.audit.";
+
+pub fn audit_alias(seq: usize) -> String {
+    format!("{}{}", AUDIT_ALIAS_PREFIX, seq)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AuditEntry {
+    pub actor: String,
+    pub action: String,
+    /**
+     * Post CID or key acted on, empty for actions on the whole wall
+     */
+    pub target: String,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+/**
+ * Reason codes are short snake_case words such as `spam`, an action without one is `unspecified`
+ */
+pub fn reason_code(reason: &str) -> Result<String, W3WallError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Ok("unspecified".to_string());
+    }
+    let valid = reason.len() <= DEFAULT_MAX_REASON_CODE_LENGTH
+        && reason
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !valid {
+        return Err(W3WallError::Rejected(format!(
+            "Reason code must be up to {} lowercase letters, digits or underscores.",
+            DEFAULT_MAX_REASON_CODE_LENGTH
+        )));
+    }
+    Ok(reason.to_string())
+}
+
+/**
+ * Number the next entry takes, one past the entries already on the wall
+ */
+fn next_seq(contract: &MetaContract, metadatas: &[Metadata]) -> usize {
+    metadatas
+        .iter()
+        .filter(|m| {
+            m.alias.starts_with(AUDIT_ALIAS_PREFIX)
+                && m.public_key.eq_ignore_ascii_case(&contract.public_key)
+        })
+        .count()
+}
+
+/**
+ * Entry recording that the sender of `transaction` took `action` on `target`
+ */
+pub fn audit_record(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    action: &str,
+    target: &str,
+    reason: &str,
+) -> Result<FinalMetadata, W3WallError> {
+    let entry = AuditEntry {
+        actor: transaction.public_key.clone(),
+        action: action.to_string(),
+        target: target.to_string(),
+        reason: reason_code(reason)?,
+        timestamp: timestamp_sec(transaction.timestamp),
+    };
+    Ok(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: audit_alias(next_seq(contract, metadatas)),
+        content: serde_json::to_string(&entry)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_numbered_after_the_existing_ones() {
+        let contract = MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        };
+        let logged = |public_key: &str, seq: usize| Metadata {
+            public_key: public_key.to_string(),
+            alias: audit_alias(seq),
+            ..Default::default()
+        };
+        let metadatas = [logged("0xCONTRACT", 0), logged("0xmallory", 1)];
+        let transaction = Transaction {
+            public_key: "0xmod".to_string(),
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        };
+
+        let record = audit_record(
+            &contract,
+            &metadatas,
+            &transaction,
+            "ban",
+            "0xspammer",
+            "spam",
+        )
+        .unwrap();
+        assert_eq!(record.alias, "w3wall.audit.1");
+        assert_eq!(record.public_key, "0xcontract");
+        assert_eq!(
+            record.content,
+            r#"{"actor":"0xmod","action":"ban","target":"0xspammer","reason":"spam","timestamp":1700000000}"#
+        );
+
+        let unspecified = audit_record(&contract, &[], &transaction, "lock", "", " ").unwrap();
+        assert!(unspecified.content.contains(r#""reason":"unspecified""#));
+        assert!(audit_record(&contract, &[], &transaction, "lock", "", "Spam!").is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::data::BlockList;
+use crate::defaults::DEFAULT_MAX_BLOCKED_KEYS;
+use crate::error::W3WallError;
+use crate::storage::blocklist::BLOCKLIST_ALIAS;
+use crate::types::{FinalMetadata, Transaction};
+
+/**
+ * Replaces the sender's personal block list, the record is written by their own key
+ */
+pub fn block(transaction: &Transaction) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut list: BlockList = serde_json::from_str(&transaction.data)?;
+    list.blocked = list
+        .blocked
+        .iter()
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty() && !k.eq_ignore_ascii_case(&transaction.public_key))
+        .collect();
+    list.blocked.sort();
+    list.blocked.dedup();
+
+    if list.blocked.len() > DEFAULT_MAX_BLOCKED_KEYS {
+        return Err(W3WallError::Rejected(format!(
+            "Block list is limited to {} keys.",
+            DEFAULT_MAX_BLOCKED_KEYS
+        )));
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: BLOCKLIST_ALIAS.to_string(),
+        content: serde_json::to_string(&list)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_list_is_normalized_and_owned_by_the_sender() {
+        let transaction = Transaction {
+            method: "block".to_string(),
+            public_key: "0xMe".to_string(),
+            data: r#"{"blocked": ["0xTroll", "0xtroll ", "0xme", ""]}"#.to_string(),
+            ..Default::default()
+        };
+        let finals = block(&transaction).unwrap();
+        assert_eq!(finals[0].public_key, "0xMe");
+        assert_eq!(finals[0].alias, BLOCKLIST_ALIAS);
+        assert_eq!(finals[0].content, r#"{"blocked":["0xtroll"]}"#);
+    }
+}
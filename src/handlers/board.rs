@@ -0,0 +1,90 @@
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::handlers::template::is_template_name;
+use crate::storage::config::CONFIG_ALIAS;
+use crate::types::{FinalMetadata, MetaContract, Transaction};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct BoardRequest {
+    board: String,
+}
+
+/**
+ * Adds (`add_board`) or removes (`remove_board`) a board of the wall. Owners only. Posts
+ * already made on a removed board keep their category.
+ */
+pub fn set_board(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected("Only wall owners can change boards."));
+    }
+
+    let request: BoardRequest = serde_json::from_str(&transaction.data)?;
+    // boards go in aliases like template names do
+    if !is_template_name(&request.board) {
+        return Err(W3WallError::rejected(
+            "Board name must be 1 to 32 lowercase letters, digits, - or _.",
+        ));
+    }
+
+    let mut config = config.clone();
+    let exists = config.boards.contains(&request.board);
+    match (transaction.method.as_str(), exists) {
+        ("add_board", false) => config.boards.push(request.board),
+        ("add_board", true) => return Err(W3WallError::rejected("Board already exists.")),
+        (_, true) => config.boards.retain(|b| b != &request.board),
+        (_, false) => return Err(W3WallError::rejected("Board does not exist.")),
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: CONFIG_ALIAS.to_string(),
+        content: serde_json::to_string(&config)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(method: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: "0xowner".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn owners_manage_boards() {
+        let contract = MetaContract::default();
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let dev = r#"{"board": "dev"}"#;
+
+        let finals = set_board(&contract, &transaction("add_board", dev), &config).unwrap();
+        let config: WallConfig = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(config.boards, vec!["general", "dev"]);
+        assert!(set_board(&contract, &transaction("add_board", dev), &config).is_err());
+
+        let finals = set_board(&contract, &transaction("remove_board", dev), &config).unwrap();
+        let config: WallConfig = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(config.boards, vec!["general"]);
+        assert!(set_board(&contract, &transaction("remove_board", dev), &config).is_err());
+
+        let stranger = Transaction {
+            public_key: "0xother".to_string(),
+            ..transaction("add_board", dev)
+        };
+        assert!(set_board(&contract, &stranger, &config).is_err());
+    }
+}
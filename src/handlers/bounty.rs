@@ -0,0 +1,209 @@
+use crate::error::W3WallError;
+use crate::storage::{find_post, post_type, read_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static BOUNTY_ALIAS_PREFIX: &str = "w3wall.bounty.";
+
+pub fn bounty_alias(cid: &str) -> String {
+    format!("{}{}", BOUNTY_ALIAS_PREFIX, cid)
+}
+
+/**
+ * open → awarded → closed, or open → closed when the creator withdraws it. A bounty post without
+ * a state record is open.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BountyStatus {
+    #[default]
+    Open,
+    Awarded,
+    Closed,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct BountyState {
+    pub status: BountyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner: Option<Winner>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Winner {
+    pub reply: String,
+    pub author: String,
+}
+
+#[derive(Deserialize)]
+struct Award {
+    bounty: String,
+    #[serde(default)]
+    reply: String,
+}
+
+pub fn load_state(contract: &MetaContract, metadatas: &[Metadata], cid: &str) -> BountyState {
+    read_record(metadatas, &bounty_alias(cid), &contract.public_key).unwrap_or_default()
+}
+
+/**
+ * The bounty post itself, only its creator may change its state
+ */
+fn find_bounty<'a>(
+    metadatas: &'a [Metadata],
+    cid: &str,
+    transaction: &Transaction,
+) -> Result<&'a Metadata, W3WallError> {
+    let bounty = find_post(metadatas, cid)
+        .ok_or_else(|| W3WallError::rejected("Bounty is not on this wall."))?;
+
+    if post_type(bounty).as_deref() != Some("bounty") {
+        return Err(W3WallError::rejected("Post is not a bounty."));
+    }
+
+    if !bounty
+        .public_key
+        .eq_ignore_ascii_case(&transaction.public_key)
+    {
+        return Err(W3WallError::rejected(
+            "Only the bounty creator can change it.",
+        ));
+    }
+    Ok(bounty)
+}
+
+fn state_record(
+    contract: &MetaContract,
+    cid: &str,
+    state: &BountyState,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: bounty_alias(cid),
+        content: serde_json::to_string(state)?,
+        loose: 1,
+        version: "".to_string(),
+    }])
+}
+
+/**
+ * Records the winning reply of an open bounty
+ */
+pub fn award(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let award: Award = serde_json::from_str(&transaction.data)?;
+    let bounty = find_bounty(metadatas, &award.bounty, transaction)?;
+
+    let mut state = load_state(contract, metadatas, &award.bounty);
+    if state.status != BountyStatus::Open {
+        return Err(W3WallError::rejected("Bounty is no longer open."));
+    }
+
+    let reply = find_post(metadatas, &award.reply)
+        .ok_or_else(|| W3WallError::rejected("Winning reply is not on this wall."))?;
+    if reply.public_key.eq_ignore_ascii_case(&bounty.public_key) {
+        return Err(W3WallError::rejected(
+            "The creator can't award their own reply.",
+        ));
+    }
+
+    state.status = BountyStatus::Awarded;
+    state.winner = Some(Winner {
+        reply: reply.cid.clone(),
+        author: reply.public_key.clone(),
+    });
+    state_record(contract, &award.bounty, &state)
+}
+
+/**
+ * Closes an open (withdrawn) or awarded (paid out) bounty, closed is final
+ */
+pub fn close(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let award: Award = serde_json::from_str(&transaction.data)?;
+    find_bounty(metadatas, &award.bounty, transaction)?;
+
+    let mut state = load_state(contract, metadatas, &award.bounty);
+    if state.status == BountyStatus::Closed {
+        return Err(W3WallError::rejected("Bounty is already closed."));
+    }
+
+    state.status = BountyStatus::Closed;
+    state_record(contract, &award.bounty, &state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn post(key: &str, cid: &str, content: &str) -> Metadata {
+        test_store::put(cid, content);
+        Metadata {
+            public_key: key.to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(key: &str, data: &str) -> Transaction {
+        Transaction {
+            public_key: key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn wall() -> Vec<Metadata> {
+        vec![
+            post(
+                "0xcreator",
+                "bounty-cid",
+                r#"{"type": "bounty", "text": "fix it"}"#,
+            ),
+            post("0xhunter", "reply-cid", r#"{"text": "fixed"}"#),
+        ]
+    }
+
+    #[test]
+    fn creator_awards_once() {
+        let contract = MetaContract::default();
+        let data = r#"{"bounty": "bounty-cid", "reply": "reply-cid"}"#;
+
+        assert!(award(&contract, &wall(), &transaction("0xhunter", data)).is_err());
+
+        let finals = award(&contract, &wall(), &transaction("0xcreator", data)).unwrap();
+        assert_eq!(finals[0].alias, "w3wall.bounty.bounty-cid");
+        let state: serde_json::Value = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(state["status"], "awarded");
+        assert_eq!(state["winner"]["author"], "0xhunter");
+
+        // the node stores the state record, the next transaction sees it
+        let mut awarded = wall();
+        awarded.push(Metadata {
+            alias: finals[0].alias.clone(),
+            ..post(&contract.public_key, "bounty-state-cid", &finals[0].content)
+        });
+        assert!(award(&contract, &awarded, &transaction("0xcreator", data)).is_err());
+        assert!(close(&contract, &awarded, &transaction("0xcreator", data)).is_ok());
+    }
+
+    #[test]
+    fn only_bounties_can_be_awarded() {
+        let data = r#"{"bounty": "reply-cid", "reply": "bounty-cid"}"#;
+        let err = award(
+            &MetaContract::default(),
+            &wall(),
+            &transaction("0xhunter", data),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Post is not a bounty.");
+    }
+}
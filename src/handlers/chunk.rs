@@ -0,0 +1,191 @@
+use crate::crypto::hash::{keccak256, keccak256_concat};
+use crate::data::WallConfig;
+use crate::defaults::DEFAULT_MAX_CHUNKS;
+use crate::error::W3WallError;
+use crate::handlers::execute;
+use crate::moderation::WordFilter;
+use crate::storage::{find_record, read_content};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::Deserialize;
+
+pub static CHUNK_ALIAS_PREFIX: &str = "w3wall.chunk.";
+
+pub fn chunk_alias(root: &str, index: usize) -> String {
+    format!("{}{}.{}", CHUNK_ALIAS_PREFIX, root.to_lowercase(), index)
+}
+
+/**
+ * keccak256 over the keccak256 of every chunk, in order, 0x-prefixed
+ */
+pub fn chunk_root<S: AsRef<str>>(chunks: &[S]) -> String {
+    let hashes: Vec<[u8; 32]> = chunks.iter().map(|c| keccak256(c.as_ref())).collect();
+    let parts: Vec<&[u8]> = hashes.iter().map(|h| h.as_slice()).collect();
+    format!("0x{}", hex::encode(keccak256_concat(&parts)))
+}
+
+#[derive(Deserialize)]
+struct ChunkRequest {
+    root: String,
+    index: usize,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct AssembleRequest {
+    root: String,
+    chunks: usize,
+}
+
+/**
+ * Stores one slice of a long post under the sender's key, nothing is validated until assembly
+ */
+pub fn chunk(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: ChunkRequest = serde_json::from_str(&transaction.data)?;
+    if request.index >= DEFAULT_MAX_CHUNKS {
+        return Err(W3WallError::Rejected(format!(
+            "A post is limited to {} chunks.",
+            DEFAULT_MAX_CHUNKS
+        )));
+    }
+
+    let alias = chunk_alias(&request.root, request.index);
+    if find_record(metadatas, &alias, &transaction.public_key).is_some() {
+        return Err(W3WallError::rejected("Chunk is already stored."));
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias,
+        content: request.data,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+/**
+ * Joins the sender's chunks once they hash to the declared root, the result goes through the
+ * regular post pipeline as if it had been sent in one transaction
+ */
+pub fn assemble(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: AssembleRequest = serde_json::from_str(&transaction.data)?;
+    if request.chunks == 0 || request.chunks > DEFAULT_MAX_CHUNKS {
+        return Err(W3WallError::Rejected(format!(
+            "A post takes 1 to {} chunks.",
+            DEFAULT_MAX_CHUNKS
+        )));
+    }
+
+    let mut chunks = Vec::with_capacity(request.chunks);
+    for index in 0..request.chunks {
+        let content = find_record(
+            metadatas,
+            &chunk_alias(&request.root, index),
+            &transaction.public_key,
+        )
+        .and_then(read_content)
+        .ok_or_else(|| W3WallError::Rejected(format!("Chunk {} is missing.", index)))?;
+        chunks.push(content);
+    }
+
+    if !chunk_root(&chunks).eq_ignore_ascii_case(&request.root) {
+        return Err(W3WallError::rejected(
+            "Chunks do not hash to the declared root.",
+        ));
+    }
+
+    let assembled = Transaction {
+        method: "post".to_string(),
+        data: chunks.concat(),
+        ..transaction.clone()
+    };
+    execute::post(contract, metadatas, &assembled, config, filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(method: &str, data: String) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: "0xauthor".to_string(),
+            data,
+            ..Default::default()
+        }
+    }
+
+    fn stored(finals: Vec<FinalMetadata>, cid: &str) -> Metadata {
+        crate::storage::ipfs::test_store::put(cid, &finals[0].content);
+        Metadata {
+            alias: finals[0].alias.clone(),
+            public_key: finals[0].public_key.clone(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chunks_assemble_into_a_post_when_they_hash_to_the_root() {
+        let parts = [r#"{"text": "a long"#, r#" story"}"#];
+        let root = chunk_root(&parts);
+
+        let mut metadatas = vec![];
+        for (index, part) in parts.iter().enumerate() {
+            let data = serde_json::json!({ "root": root, "index": index, "data": part });
+            let finals = chunk(&metadatas, &transaction("chunk", data.to_string())).unwrap();
+            metadatas.push(stored(finals, &format!("chunk-{}", index)));
+        }
+        let again = serde_json::json!({ "root": root, "index": 0, "data": parts[0] });
+        assert!(chunk(&metadatas, &transaction("chunk", again.to_string())).is_err());
+
+        let contract = MetaContract::default();
+        let config = WallConfig::default();
+        let filter = WordFilter::new(&[]);
+
+        let request = serde_json::json!({ "root": root, "chunks": 2 }).to_string();
+        let finals = assemble(
+            &contract,
+            &metadatas,
+            &transaction("assemble", request),
+            &config,
+            &filter,
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, "");
+        assert_eq!(
+            finals[0].content,
+            r#"{"schema_version":2,"text":"a long story","type":"post"}"#
+        );
+
+        let missing = serde_json::json!({ "root": root, "chunks": 3 }).to_string();
+        let err = assemble(
+            &contract,
+            &metadatas,
+            &transaction("assemble", missing),
+            &config,
+            &filter,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Chunk 2 is missing.");
+
+        let forged = serde_json::json!({ "root": root, "chunks": 1 }).to_string();
+        let err = assemble(
+            &contract,
+            &metadatas,
+            &transaction("assemble", forged),
+            &config,
+            &filter,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Chunks do not hash to the declared root.");
+    }
+}
@@ -0,0 +1,258 @@
+use crate::crypto::verify_personal_signature;
+use crate::defaults::{
+    DEFAULT_CLONE_ALLOWED_CONTRACTS, DEFAULT_CLONE_ALLOWED_KEYS,
+    DEFAULT_CLONE_AUTHORIZATION_TTL_SEC, DEFAULT_CLONE_REQUIRE_AUTHORIZATION,
+    DEFAULT_MAX_CLOCK_SKEW_SEC,
+};
+use crate::error::W3WallError;
+use crate::metadata::timestamp_sec;
+use crate::types::{MetaContract, Transaction};
+
+/**
+ * Message the wall owner signs at `issued_at` (unix seconds) to authorize `public_key` to clone
+ * the contract. on_clone sees no storage to remember used authorizations in, so the time is what
+ * keeps one from being replayed past DEFAULT_CLONE_AUTHORIZATION_TTL_SEC.
+ */
+pub fn authorization_message(contract: &MetaContract, public_key: &str, issued_at: u64) -> String {
+    format!(
+        "clone:{}:{}:{}",
+        contract.meta_contract_id,
+        public_key.to_lowercase(),
+        issued_at
+    )
+}
+
+/**
+ * Who may clone, the defaults unless a test needs another one
+ */
+struct ClonePolicy<'a> {
+    allowed_keys: &'a [&'a str],
+    /**
+     * Meta contracts a clone request may come from, matched against the transaction's
+     * `meta_contract_id` rather than the wall's own
+     */
+    allowed_contracts: &'a [&'a str],
+    require_authorization: bool,
+}
+
+static CLONE_POLICY: ClonePolicy<'static> = ClonePolicy {
+    allowed_keys: DEFAULT_CLONE_ALLOWED_KEYS,
+    allowed_contracts: DEFAULT_CLONE_ALLOWED_CONTRACTS,
+    require_authorization: DEFAULT_CLONE_REQUIRE_AUTHORIZATION,
+};
+
+/**
+ * Clones are accepted from the wall owner, allowlisted keys and allowlisted origin contracts.
+ * When authorization is required, anyone but the owner also needs the owner's signature
+ * over `authorization_message`, sent as `{"authorization": "0x...", "issued_at": <sec>}` in the
+ * transaction data no later than DEFAULT_CLONE_AUTHORIZATION_TTL_SEC after it was issued.
+ */
+pub fn check_clone_policy(
+    contract: &MetaContract,
+    transaction: &Transaction,
+) -> Result<(), W3WallError> {
+    check_policy(&CLONE_POLICY, contract, transaction)
+}
+
+fn check_policy(
+    policy: &ClonePolicy,
+    contract: &MetaContract,
+    transaction: &Transaction,
+) -> Result<(), W3WallError> {
+    let requester = transaction.public_key.as_str();
+
+    if requester.eq_ignore_ascii_case(&contract.public_key) {
+        return Ok(());
+    }
+
+    let allowed_key = policy
+        .allowed_keys
+        .iter()
+        .any(|key| key.eq_ignore_ascii_case(requester));
+    let allowed_contract = !transaction.meta_contract_id.is_empty()
+        && policy
+            .allowed_contracts
+            .contains(&transaction.meta_contract_id.as_str());
+
+    if !allowed_key && !allowed_contract {
+        return Err(W3WallError::Rejected(format!(
+            "{} is not allowed to clone {}",
+            requester, contract.meta_contract_id
+        )));
+    }
+
+    if policy.require_authorization {
+        let data: serde_json::Value = serde_json::from_str(&transaction.data).unwrap_or_default();
+        let authorization = data["authorization"].as_str().unwrap_or_default();
+        let issued_at = data["issued_at"].as_u64().unwrap_or_default();
+
+        let now = timestamp_sec(transaction.timestamp);
+        if issued_at.saturating_add(DEFAULT_CLONE_AUTHORIZATION_TTL_SEC) < now
+            || issued_at > now.saturating_add(DEFAULT_MAX_CLOCK_SKEW_SEC)
+        {
+            return Err(W3WallError::Rejected(format!(
+                "Clone authorization for {} is expired",
+                requester
+            )));
+        }
+        if !verify_personal_signature(
+            &contract.public_key,
+            &authorization_message(contract, requester, issued_at),
+            authorization,
+        ) {
+            return Err(W3WallError::Rejected(format!(
+                "Clone authorization for {} is missing or invalid",
+                requester
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle(contract: &MetaContract, transaction: &Transaction) -> bool {
+    match check_clone_policy(contract, transaction) {
+        Ok(()) => {
+            log::info!(
+                "clone of {} accepted for {}",
+                contract.meta_contract_id,
+                transaction.public_key
+            );
+            true
+        }
+        Err(reason) => {
+            log::warn!("clone rejected: {}", reason);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_may_clone_strangers_may_not() {
+        let contract = MetaContract {
+            public_key: "0xOwner".to_string(),
+            meta_contract_id: "wall".to_string(),
+            ..Default::default()
+        };
+        let owner = Transaction {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let stranger = Transaction {
+            public_key: "0xstranger".to_string(),
+            ..Default::default()
+        };
+
+        assert!(handle(&contract, &owner));
+        assert!(!handle(&contract, &stranger));
+    }
+
+    #[test]
+    fn allowlisted_origins_need_the_owner_authorization() {
+        use crate::crypto::test_wallet;
+
+        let policy = ClonePolicy {
+            allowed_keys: &[],
+            allowed_contracts: &["origin"],
+            require_authorization: true,
+        };
+        let contract = MetaContract {
+            public_key: test_wallet::address(1),
+            meta_contract_id: "wall".to_string(),
+            ..Default::default()
+        };
+        let requester = test_wallet::address(2);
+        let issued_at = 1_700_000_000;
+        let request = |meta_contract_id: &str, data: serde_json::Value| Transaction {
+            public_key: requester.clone(),
+            meta_contract_id: meta_contract_id.to_string(),
+            data: data.to_string(),
+            timestamp: (issued_at + 60) * 1000,
+            ..Default::default()
+        };
+        let message = authorization_message(&contract, &requester, issued_at);
+        let signed = serde_json::json!({
+            "authorization": test_wallet::sign(1, &message),
+            "issued_at": issued_at,
+        });
+
+        // the wall's own id is not an origin, whoever asks
+        let err = check_policy(&policy, &contract, &request("wall", signed.clone())).unwrap_err();
+        assert!(err.to_string().ends_with("is not allowed to clone wall"));
+
+        let forged = serde_json::json!({
+            "authorization": test_wallet::sign(2, &message),
+            "issued_at": issued_at,
+        });
+        let err = check_policy(&policy, &contract, &request("origin", forged)).unwrap_err();
+        assert!(err.to_string().starts_with("Clone authorization for"));
+        assert!(check_policy(
+            &policy,
+            &contract,
+            &request("origin", serde_json::json!({}))
+        )
+        .is_err());
+
+        assert!(check_policy(&policy, &contract, &request("origin", signed.clone())).is_ok());
+
+        // a signature for one time is not one for a later time
+        let moved = serde_json::json!({
+            "authorization": signed["authorization"],
+            "issued_at": issued_at + 30,
+        });
+        assert!(check_policy(&policy, &contract, &request("origin", moved)).is_err());
+    }
+
+    #[test]
+    fn stale_authorizations_are_not_replayed() {
+        use crate::crypto::test_wallet;
+
+        let policy = ClonePolicy {
+            allowed_keys: &[],
+            allowed_contracts: &["origin"],
+            require_authorization: true,
+        };
+        let contract = MetaContract {
+            public_key: test_wallet::address(1),
+            meta_contract_id: "wall".to_string(),
+            ..Default::default()
+        };
+        let requester = test_wallet::address(2);
+        let issued_at = 1_700_000_000;
+        let data = serde_json::json!({
+            "authorization": test_wallet::sign(1, &authorization_message(&contract, &requester, issued_at)),
+            "issued_at": issued_at,
+        });
+        let at = |sec: u64| Transaction {
+            public_key: requester.clone(),
+            meta_contract_id: "origin".to_string(),
+            data: data.to_string(),
+            timestamp: sec * 1000,
+            ..Default::default()
+        };
+
+        let ttl = DEFAULT_CLONE_AUTHORIZATION_TTL_SEC;
+        assert!(check_policy(&policy, &contract, &at(issued_at + ttl)).is_ok());
+        let err = check_policy(&policy, &contract, &at(issued_at + ttl + 1)).unwrap_err();
+        assert!(err.to_string().ends_with("is expired"));
+        // issued in the future would outlive its ttl
+        let early = issued_at - DEFAULT_MAX_CLOCK_SKEW_SEC - 1;
+        assert!(check_policy(&policy, &contract, &at(early)).is_err());
+    }
+
+    #[test]
+    fn authorization_message_is_normalized() {
+        let contract = MetaContract {
+            meta_contract_id: "wall".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            authorization_message(&contract, "0xAB", 42),
+            "clone:wall:0xab:42"
+        );
+    }
+}
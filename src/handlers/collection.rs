@@ -0,0 +1,35 @@
+use crate::budget;
+use crate::data::{CollectionMetadata, WallConfig};
+use crate::defaults::{
+    DEFAULT_COLLECTION_BANNER, DEFAULT_COLLECTION_DESCRIPTION, DEFAULT_COLLECTION_EXTERNAL_LINK,
+    DEFAULT_COLLECTION_IMAGE, DEFAULT_COLLECTION_NAME,
+};
+use crate::error::W3WallError;
+use crate::storage::alias::Alias;
+use crate::types::{FinalMetadata, MetaContract};
+
+/**
+ * Collection-level (contractURI) metadata so marketplaces can name the w3wall collection
+ */
+pub fn contract_metadata(contract: &MetaContract) -> Result<Vec<FinalMetadata>, W3WallError> {
+    budget::start();
+    let config = WallConfig::default();
+
+    let collection = CollectionMetadata {
+        name: DEFAULT_COLLECTION_NAME.to_string(),
+        description: DEFAULT_COLLECTION_DESCRIPTION.to_string(),
+        image: DEFAULT_COLLECTION_IMAGE.to_string(),
+        banner_image: DEFAULT_COLLECTION_BANNER.to_string(),
+        external_link: DEFAULT_COLLECTION_EXTERNAL_LINK.to_string(),
+        seller_fee_basis_points: config.seller_fee_basis_points,
+        fee_recipient: config.fee_recipient,
+    };
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::ContractMetadata.to_string(),
+        content: serde_json::to_string(&collection)?,
+        loose: 1,
+        version: "".to_string(),
+    }])
+}
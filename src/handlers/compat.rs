@@ -0,0 +1,149 @@
+//! Which record versions this contract can mix. A transaction, or a wall, of a version it does
+//! not know is refused before any handler runs, with the upgrade that would fix it.
+
+use crate::defaults::{MIN_RECORD_VERSION, RECORD_VERSION};
+use crate::error::W3WallError;
+use crate::handlers::migrate::migrated_alias;
+use crate::storage::alias::Alias;
+use crate::types::{Metadata, Transaction};
+
+/**
+ * Major version of a `version` string such as "1", "1.2" or "v1.2.0", None when it has none.
+ * Records and transactions from before versioning carry an empty one.
+ */
+pub fn major_version(version: &str) -> Option<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/**
+ * Methods that still run on a wall with posts to migrate, they are how it gets upgraded
+ */
+fn is_upgrade(method: &str) -> bool {
+    ["migrate", "export"].contains(&method)
+}
+
+pub fn check_versions(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<(), W3WallError> {
+    let sent = transaction.version.trim();
+    if !sent.is_empty() {
+        match major_version(sent) {
+            None => {
+                return Err(W3WallError::Rejected(format!(
+                    "Transaction version {} is not a record version, send {}.",
+                    sent, RECORD_VERSION
+                )))
+            }
+            Some(major) if major > RECORD_VERSION => {
+                return Err(W3WallError::Rejected(format!(
+                    "Transaction version {} is newer than this contract's {}, the wall needs a meta contract upgrade.",
+                    sent, RECORD_VERSION
+                )))
+            }
+            Some(major) if major < MIN_RECORD_VERSION => {
+                return Err(W3WallError::Rejected(format!(
+                    "Transaction version {} is no longer supported, upgrade the client to version {}.",
+                    sent, RECORD_VERSION
+                )))
+            }
+            Some(_) => {}
+        }
+    }
+
+    let versions = || {
+        metadatas
+            .iter()
+            .filter_map(|m| major_version(&m.version).map(|major| (m, major)))
+    };
+    if let Some(newest) = versions().map(|(_, major)| major).max() {
+        if newest > RECORD_VERSION {
+            return Err(W3WallError::Rejected(format!(
+                "Wall holds version {} records, newer than this contract's {}, upgrade the meta contract.",
+                newest, RECORD_VERSION
+            )));
+        }
+    }
+    let unmigrated = versions().find(|(m, major)| {
+        *major < MIN_RECORD_VERSION
+            && m.alias == Alias::Post
+            && !metadatas.iter().any(|r| r.alias == migrated_alias(&m.cid))
+    });
+    if let Some((_, major)) = unmigrated {
+        if !is_upgrade(&transaction.method) {
+            return Err(W3WallError::Rejected(format!(
+                "Wall holds version {} posts, an owner has to run migrate before version {} transactions.",
+                major, RECORD_VERSION
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(method: &str, version: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn record(alias: &str, cid: &str, version: &str) -> Metadata {
+        Metadata {
+            alias: alias.to_string(),
+            cid: cid.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn transactions_of_an_unknown_version_are_refused() {
+        assert_eq!(major_version("v1.2.0"), Some(1));
+        assert_eq!(major_version(""), None);
+        assert!(check_versions(&[], &transaction("post", "")).is_ok());
+        assert!(check_versions(&[], &transaction("post", "1.4")).is_ok());
+        assert_eq!(
+            check_versions(&[], &transaction("post", "2"))
+                .unwrap_err()
+                .to_string(),
+            "Transaction version 2 is newer than this contract's 1, the wall needs a meta contract upgrade."
+        );
+        assert_eq!(
+            check_versions(&[], &transaction("post", "0.9"))
+                .unwrap_err()
+                .to_string(),
+            "Transaction version 0.9 is no longer supported, upgrade the client to version 1."
+        );
+        assert!(check_versions(&[], &transaction("post", "latest")).is_err());
+    }
+
+    #[test]
+    fn walls_of_another_version_say_how_to_upgrade() {
+        let newer = [record("config", "", "2.0.0")];
+        assert!(check_versions(&newer, &transaction("post", "1"))
+            .unwrap_err()
+            .to_string()
+            .ends_with("upgrade the meta contract."));
+
+        let older = [record("", "old", "0"), record("", "new", "1")];
+        assert!(check_versions(&older, &transaction("post", "1"))
+            .unwrap_err()
+            .to_string()
+            .contains("run migrate"));
+        assert!(check_versions(&older, &transaction("migrate", "1")).is_ok());
+
+        let migrated = [older[0].clone(), record(&migrated_alias("old"), "m", "1")];
+        assert!(check_versions(&migrated, &transaction("post", "1")).is_ok());
+    }
+}
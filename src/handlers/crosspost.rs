@@ -0,0 +1,158 @@
+use crate::crypto::eth_address::is_address;
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::moderation::WordFilter;
+use crate::storage::alias::Alias;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::media::is_cid;
+use crate::validation::reference::is_data_key;
+use crate::validation::rules::{LengthValidator, ProfanityValidator, SpamValidator};
+use crate::validation::{self, PostContext, ValidationError};
+use serde::{Deserialize, Serialize};
+
+/**
+ * Where a reposted post comes from: the origin wall (data_key), the post record, its author
+ * and when it was first posted
+ */
+#[derive(Serialize, Deserialize)]
+pub struct Provenance {
+    pub data_key: String,
+    pub cid: String,
+    pub author: String,
+    pub timestamp: u64,
+}
+
+#[derive(Deserialize)]
+struct Crosspost {
+    origin: Provenance,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/**
+ * Reposts a post from another wall. The copy is a regular post record marked with
+ * `"type": "crosspost"` and its provenance, optional commentary is checked like post text.
+ */
+pub fn crosspost(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let payload = validation::parse_payload(&transaction.data)?;
+    let crosspost: Crosspost = serde_json::from_value(payload.clone())?;
+    check_provenance(&crosspost.origin, transaction)?;
+
+    let ctx = PostContext {
+        transaction,
+        metadatas,
+        wall: &contract.public_key,
+        config,
+        filter,
+        payload: &payload,
+    };
+    validation::run_pipeline(
+        &[&LengthValidator, &ProfanityValidator, &SpamValidator],
+        &ctx,
+    )?;
+
+    let mut content = serde_json::json!({
+        "type": "crosspost",
+        "provenance": crosspost.origin,
+    });
+    if let Some(text) = crosspost.text {
+        content["text"] = text.into();
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: Alias::Post.to_string(),
+        content: content.to_string(),
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+fn check_provenance(origin: &Provenance, transaction: &Transaction) -> Result<(), ValidationError> {
+    let invalid = |message: &str| Err(ValidationError::new("crosspost", message));
+
+    if !is_data_key(&origin.data_key) {
+        return invalid("Origin data_key is not valid.");
+    }
+    if origin
+        .data_key
+        .trim_start_matches("0x")
+        .eq_ignore_ascii_case(transaction.data_key.trim_start_matches("0x"))
+    {
+        return invalid("Cannot crosspost within the same wall.");
+    }
+    if !is_cid(&origin.cid) {
+        return invalid("Origin post CID is not valid.");
+    }
+    if !is_address(&origin.author) {
+        return invalid("Origin author is not a valid address.");
+    }
+    if origin.timestamp == 0 {
+        return invalid("Origin timestamp is missing.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(data: &str) -> Transaction {
+        Transaction {
+            method: "crosspost".to_string(),
+            data_key: "cd".repeat(32),
+            public_key: "0xa".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn origin(data_key: &str) -> serde_json::Value {
+        serde_json::json!({
+            "data_key": data_key,
+            "cid": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            "author": "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+            "timestamp": 1700000000
+        })
+    }
+
+    fn run(data: &serde_json::Value) -> Result<Vec<FinalMetadata>, W3WallError> {
+        crosspost(
+            &MetaContract::default(),
+            &[],
+            &transaction(&data.to_string()),
+            &WallConfig::default(),
+            &WordFilter::Global,
+        )
+    }
+
+    #[test]
+    fn crosspost_carries_provenance() {
+        let data =
+            serde_json::json!({ "origin": origin(&"ab".repeat(32)), "text": "worth a read" });
+        let finals = run(&data).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(finals[0].alias, "");
+        assert_eq!(stored["type"], "crosspost");
+        assert_eq!(stored["provenance"]["timestamp"], 1700000000);
+        assert_eq!(stored["text"], "worth a read");
+    }
+
+    #[test]
+    fn bad_references_are_rejected() {
+        let same_wall = serde_json::json!({ "origin": origin(&"cd".repeat(32)) });
+        assert!(run(&same_wall).is_err());
+
+        let bad_key = serde_json::json!({ "origin": origin("nope") });
+        assert_eq!(
+            run(&bad_key).unwrap_err().to_string(),
+            "[crosspost] Origin data_key is not valid."
+        );
+    }
+}
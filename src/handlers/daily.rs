@@ -0,0 +1,238 @@
+use crate::data::{Locale, WallConfig};
+use crate::defaults::DEFAULT_DAILY_DIGEST_SIZE;
+use crate::error::W3WallError;
+use crate::metadata::karma::{Karma, ReactionFilter};
+use crate::metadata::{iso8601, parse_iso8601, timestamp_sec};
+use crate::storage::alias::Alias;
+use crate::storage::envelope;
+use crate::storage::moderation::load_moderation;
+use crate::storage::read_content;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+pub static DAILY_DIGEST_ALIAS_PREFIX: &str = "w3wall.daily.";
+
+const DAY: u64 = 24 * 60 * 60;
+
+pub fn daily_digest_alias(date: &str) -> String {
+    format!("{}{}", DAILY_DIGEST_ALIAS_PREFIX, date)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DigestRequest {
+    /**
+     * `YYYY-MM-DD`, the day before the transaction when left out
+     */
+    day: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DigestPost {
+    pub cid: String,
+    pub author: String,
+    pub reactions: u64,
+    pub replies: u64,
+}
+
+/**
+ * One UTC day of the wall for notification bridges. Reactions go to authors rather than posts,
+ * so a post ranks by the counted reactions of its author, then by its replies, then by cid.
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DailyDigest {
+    pub day: String,
+    pub posts: u64,
+    /**
+     * Keys whose first post was made that day
+     */
+    pub new_members: u64,
+    pub top: Vec<DigestPost>,
+    /**
+     * The wall's rendering hints, so bridges format the day like the wall does
+     */
+    pub locale: Locale,
+}
+
+fn day_start(request: &DigestRequest, now: u64) -> Result<u64, W3WallError> {
+    let start = match &request.day {
+        Some(day) => parse_iso8601(&format!("{}T00:00:00Z", day))
+            .ok_or_else(|| W3WallError::rejected("Digest day must be YYYY-MM-DD."))?,
+        None => (now / DAY).saturating_sub(1) * DAY,
+    };
+    // a day still running would give a different digest to every caller
+    if start + DAY > now {
+        return Err(W3WallError::rejected("Digest day is not over yet."));
+    }
+    Ok(start)
+}
+
+/**
+ * Writes the digest of a finished day under `w3wall.daily.<day>`. Anyone may call it, the
+ * record only depends on the metadata so every caller writes the same one. Removed posts and
+ * posts stored before envelopes, which carry no creation time, are left out.
+ */
+pub fn digest(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: DigestRequest = if transaction.data.trim().is_empty() {
+        DigestRequest::default()
+    } else {
+        serde_json::from_str(&transaction.data)?
+    };
+    let start = day_start(&request, timestamp_sec(transaction.timestamp))?;
+    let day = iso8601(start)[..10].to_string();
+    let state = load_moderation(contract, metadatas)?.unwrap_or_default();
+
+    let mut first_post: HashMap<String, u64> = HashMap::new();
+    let mut replies: HashMap<String, u64> = HashMap::new();
+    let mut posts = vec![];
+    for post in metadatas
+        .iter()
+        .filter(|m| m.alias == Alias::Post && !state.removed.contains(&m.cid))
+    {
+        let payload: Value = read_content(post)
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if let Some(parent) = payload["reply_to"].as_str() {
+            *replies.entry(parent.to_string()).or_default() += 1;
+        }
+        let Some(created_at) = envelope::created_at(post) else {
+            continue;
+        };
+        let author = post.public_key.to_lowercase();
+        let first = first_post.entry(author.clone()).or_insert(created_at);
+        *first = (*first).min(created_at);
+        let public = payload["visibility"].as_str() != Some("private");
+        if (start..start + DAY).contains(&created_at) {
+            posts.push((post.cid.clone(), author, public));
+        }
+    }
+
+    let reactions_filter = ReactionFilter::new(metadatas, &config.karma, start + DAY);
+    let mut reactions: BTreeMap<String, u64> = BTreeMap::new();
+    let mut top: Vec<DigestPost> = posts
+        .iter()
+        .filter(|(_, _, public)| *public)
+        .map(|(cid, author, _)| DigestPost {
+            cid: cid.clone(),
+            author: author.clone(),
+            reactions: *reactions
+                .entry(author.clone())
+                .or_insert_with(|| Karma::of(metadatas, author, &reactions_filter).reactions),
+            replies: replies.get(cid).copied().unwrap_or_default(),
+        })
+        .collect();
+    top.sort_by(|a, b| {
+        (b.reactions, b.replies)
+            .cmp(&(a.reactions, a.replies))
+            .then_with(|| a.cid.cmp(&b.cid))
+    });
+    top.truncate(DEFAULT_DAILY_DIGEST_SIZE);
+
+    let digest = DailyDigest {
+        day: day.clone(),
+        posts: posts.len() as u64,
+        new_members: first_post
+            .values()
+            .filter(|first| (start..start + DAY).contains(*first))
+            .count() as u64,
+        top,
+        locale: config.locale.clone(),
+    };
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: daily_digest_alias(&day),
+        content: serde_json::to_string(&digest)?,
+        loose: 1,
+        version: "".to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn post(cid: &str, author: &str, created_at: u64, body: &str) -> Metadata {
+        test_store::put(
+            cid,
+            &format!(
+                r#"{{"v":1,"type":"post","author":"{}","created_at":{},"body":{},"refs":[]}}"#,
+                author, created_at, body
+            ),
+        );
+        Metadata {
+            public_key: author.to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn reaction(from: &str, to: &str) -> Metadata {
+        Metadata {
+            alias: format!("w3wall.reaction.{}", to),
+            public_key: from.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn digests_rank_a_finished_day() {
+        // 2023-11-13, the day before NOW
+        let start = (NOW / DAY - 1) * DAY;
+        let metadatas = vec![
+            post("daily-1", "0xa", start - DAY, r#"{"text": "old timer"}"#),
+            post("daily-2", "0xa", start + 10, r#"{"text": "gm"}"#),
+            post("daily-3", "0xb", start + 20, r#"{"text": "first!"}"#),
+            post(
+                "daily-4",
+                "0xc",
+                start + 30,
+                r#"{"text": "re", "reply_to": "daily-3"}"#,
+            ),
+            post("daily-5", "0xd", NOW, r#"{"text": "today"}"#),
+            reaction("0xc", "0xa"),
+        ];
+        let transaction = Transaction {
+            method: "digest".to_string(),
+            timestamp: NOW,
+            ..Default::default()
+        };
+        let config = WallConfig::default();
+
+        let finals = digest(&MetaContract::default(), &metadatas, &transaction, &config).unwrap();
+        assert_eq!(finals[0].alias, "w3wall.daily.2023-11-13");
+        let daily: DailyDigest = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(daily.posts, 3);
+        assert_eq!(daily.new_members, 2);
+        let ranked: Vec<(&str, u64, u64)> = daily
+            .top
+            .iter()
+            .map(|p| (p.cid.as_str(), p.reactions, p.replies))
+            .collect();
+        assert_eq!(
+            ranked,
+            [("daily-2", 1, 0), ("daily-3", 0, 1), ("daily-4", 0, 0)]
+        );
+
+        let today = Transaction {
+            data: r#"{"day": "2023-11-14"}"#.to_string(),
+            ..transaction
+        };
+        assert_eq!(
+            digest(&MetaContract::default(), &metadatas, &today, &config)
+                .unwrap_err()
+                .to_string(),
+            "Digest day is not over yet."
+        );
+    }
+}
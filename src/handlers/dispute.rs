@@ -0,0 +1,345 @@
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::handlers::audit::audit_record;
+use crate::metadata::timestamp_sec;
+use crate::storage::moderation::{load_moderation, MODERATION_ALIAS};
+use crate::storage::{find_post, read_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::attestation::{check_credential, Credential};
+use serde::{Deserialize, Serialize};
+
+pub static DISPUTE_ALIAS_PREFIX: &str = "w3wall.dispute.";
+
+pub fn dispute_alias(cid: &str) -> String {
+    format!("{}{}", DISPUTE_ALIAS_PREFIX, cid)
+}
+
+/**
+ * claimed → countered → upheld | rejected, moderators may also resolve straight from claimed.
+ * A rejected claim can be filed again, an upheld one is final and removes the post.
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DisputeStatus {
+    Claimed,
+    Countered,
+    Upheld,
+    Rejected,
+}
+
+impl DisputeStatus {
+    pub fn is_open(&self) -> bool {
+        matches!(self, DisputeStatus::Claimed | DisputeStatus::Countered)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Statement {
+    pub by: String,
+    pub text: String,
+    pub at: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Dispute {
+    pub post: String,
+    pub status: DisputeStatus,
+    pub claim: Statement,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub counter: Option<Statement>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub resolved_by: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClaimRequest {
+    post: String,
+    #[serde(default)]
+    reason: String,
+    credential: Option<Credential>,
+}
+
+#[derive(Deserialize)]
+struct ResolveRequest {
+    post: String,
+    uphold: bool,
+    #[serde(default)]
+    reason: String,
+}
+
+pub fn load_dispute(contract: &MetaContract, metadatas: &[Metadata], cid: &str) -> Option<Dispute> {
+    read_record(metadatas, &dispute_alias(cid), &contract.public_key)
+}
+
+fn dispute_record(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    dispute: &Dispute,
+) -> Result<FinalMetadata, W3WallError> {
+    Ok(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: dispute_alias(&dispute.post),
+        content: serde_json::to_string(dispute)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    })
+}
+
+/**
+ * Claimants are listed in the wall config or carry a `claimant` credential from an attester
+ */
+fn check_claimant(
+    request: &ClaimRequest,
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<(), W3WallError> {
+    let key = &transaction.public_key;
+    if config.claimants.iter().any(|c| c.eq_ignore_ascii_case(key)) {
+        return Ok(());
+    }
+
+    match &request.credential {
+        Some(credential) => check_credential(
+            "claimant",
+            credential,
+            key,
+            timestamp_sec(transaction.timestamp),
+            config,
+        )
+        .map_err(W3WallError::rejected),
+        None => Err(W3WallError::rejected(
+            "Only verified rights holders can file a claim.",
+        )),
+    }
+}
+
+pub fn claim(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: ClaimRequest = serde_json::from_str(&transaction.data)?;
+    check_claimant(&request, transaction, config)?;
+
+    if find_post(metadatas, &request.post).is_none() {
+        return Err(W3WallError::rejected("Post is not on this wall."));
+    }
+    if let Some(existing) = load_dispute(contract, metadatas, &request.post) {
+        if existing.status != DisputeStatus::Rejected {
+            return Err(W3WallError::rejected("Post is already disputed."));
+        }
+    }
+
+    let dispute = Dispute {
+        post: request.post,
+        status: DisputeStatus::Claimed,
+        claim: Statement {
+            by: transaction.public_key.clone(),
+            text: request.reason,
+            at: timestamp_sec(transaction.timestamp),
+        },
+        counter: None,
+        resolved_by: None,
+    };
+    Ok(vec![dispute_record(contract, transaction, &dispute)?])
+}
+
+/**
+ * The post's author answers a pending claim, once
+ */
+pub fn counter_claim(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: ClaimRequest = serde_json::from_str(&transaction.data)?;
+
+    let post = find_post(metadatas, &request.post)
+        .ok_or_else(|| W3WallError::rejected("Post is not on this wall."))?;
+    if !post
+        .public_key
+        .eq_ignore_ascii_case(&transaction.public_key)
+    {
+        return Err(W3WallError::rejected("Only the author can answer a claim."));
+    }
+
+    let mut dispute = match load_dispute(contract, metadatas, &request.post) {
+        Some(dispute) if dispute.status == DisputeStatus::Claimed => dispute,
+        _ => return Err(W3WallError::rejected("No claim is awaiting an answer.")),
+    };
+    dispute.status = DisputeStatus::Countered;
+    dispute.counter = Some(Statement {
+        by: transaction.public_key.clone(),
+        text: request.reason,
+        at: timestamp_sec(transaction.timestamp),
+    });
+    Ok(vec![dispute_record(contract, transaction, &dispute)?])
+}
+
+/**
+ * Moderators close an open dispute, upholding it takes the post down
+ */
+pub fn resolve_claim(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_moderator(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall moderators can resolve claims.",
+        ));
+    }
+
+    let request: ResolveRequest = serde_json::from_str(&transaction.data)?;
+    let mut dispute = match load_dispute(contract, metadatas, &request.post) {
+        Some(dispute) if dispute.status.is_open() => dispute,
+        _ => return Err(W3WallError::rejected("No open dispute for this post.")),
+    };
+    dispute.status = if request.uphold {
+        DisputeStatus::Upheld
+    } else {
+        DisputeStatus::Rejected
+    };
+    dispute.resolved_by = Some(transaction.public_key.clone());
+
+    let action = match dispute.status {
+        DisputeStatus::Upheld => "uphold_claim",
+        _ => "reject_claim",
+    };
+    let audit = audit_record(
+        contract,
+        metadatas,
+        transaction,
+        action,
+        &dispute.post,
+        &request.reason,
+    )?;
+    let mut finals = vec![dispute_record(contract, transaction, &dispute)?];
+    if request.uphold {
+        let mut state = load_moderation(contract, metadatas)?.unwrap_or_default();
+        if !state.removed.contains(&dispute.post) {
+            state.removed.push(dispute.post.clone());
+        }
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: MODERATION_ALIAS.to_string(),
+            content: serde_json::to_string(&state)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+    finals.push(audit);
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn config() -> WallConfig {
+        WallConfig {
+            owner: "0xowner".to_string(),
+            claimants: vec!["0xlabel".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn transaction(public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn wall(dispute: Option<(&str, &str)>) -> Vec<Metadata> {
+        let mut metadatas = vec![Metadata {
+            public_key: "0xauthor".to_string(),
+            cid: "song-cid".to_string(),
+            ..Default::default()
+        }];
+        if let Some((cid, content)) = dispute {
+            test_store::put(cid, content);
+            metadatas.push(Metadata {
+                public_key: "0xcontract".to_string(),
+                alias: dispute_alias("song-cid"),
+                cid: cid.to_string(),
+                ..Default::default()
+            });
+        }
+        metadatas
+    }
+
+    #[test]
+    fn claims_need_a_verified_claimant() {
+        let data = r#"{"post": "song-cid", "reason": "my lyrics"}"#;
+        assert!(claim(
+            &contract(),
+            &wall(None),
+            &transaction("0xanon", data),
+            &config()
+        )
+        .is_err());
+
+        let finals = claim(
+            &contract(),
+            &wall(None),
+            &transaction("0xlabel", data),
+            &config(),
+        )
+        .unwrap();
+        let dispute: Dispute = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Claimed);
+        assert_eq!(dispute.claim.by, "0xlabel");
+    }
+
+    #[test]
+    fn author_counters_and_moderators_resolve() {
+        let claimed = r#"{"post": "song-cid", "status": "claimed", "claim": {"by": "0xlabel", "text": "", "at": 0}}"#;
+        let data = r#"{"post": "song-cid", "reason": "I wrote it"}"#;
+        assert!(counter_claim(
+            &contract(),
+            &wall(Some(("claimed-cid", claimed))),
+            &transaction("0xlabel", data)
+        )
+        .is_err());
+        let finals = counter_claim(
+            &contract(),
+            &wall(Some(("claimed-cid", claimed))),
+            &transaction("0xauthor", data),
+        )
+        .unwrap();
+        let dispute: Dispute = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Countered);
+
+        let uphold = r#"{"post": "song-cid", "uphold": true}"#;
+        assert!(resolve_claim(
+            &contract(),
+            &wall(Some(("claimed-cid", claimed))),
+            &transaction("0xauthor", uphold),
+            &config()
+        )
+        .is_err());
+        let finals = resolve_claim(
+            &contract(),
+            &wall(Some(("claimed-cid", claimed))),
+            &transaction("0xowner", uphold),
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(finals[1].alias, MODERATION_ALIAS);
+        assert!(finals[1].content.contains("song-cid"));
+        assert_eq!(finals[2].alias, "w3wall.audit.0");
+        assert!(finals[2].content.contains(r#""action":"uphold_claim""#));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::data::WallConfig;
+use crate::defaults::DEFAULT_MAX_DRAFTS;
+use crate::error::W3WallError;
+use crate::handlers::execute;
+use crate::handlers::template::is_template_name;
+use crate::metadata::timestamp_sec;
+use crate::moderation::WordFilter;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub static DRAFT_ALIAS_PREFIX: &str = "w3wall.draft.";
+
+pub fn draft_alias(id: &str) -> String {
+    format!("{}{}", DRAFT_ALIAS_PREFIX, id)
+}
+
+/**
+ * A post kept back by its author. Drafts are not posts, so feeds, stats and the search index
+ * never see them, `draft` says as much to anyone reading the record.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Draft {
+    pub draft: bool,
+    pub payload: Value,
+    pub saved_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct DraftRequest {
+    id: String,
+    #[serde(default)]
+    payload: Value,
+}
+
+fn check_draft_id(id: &str) -> Result<(), W3WallError> {
+    match is_template_name(id) {
+        true => Ok(()),
+        false => Err(W3WallError::rejected(
+            "Draft id must be 1 to 32 lowercase letters, digits, - or _.",
+        )),
+    }
+}
+
+fn draft_record(
+    transaction: &Transaction,
+    id: &str,
+    draft: &Draft,
+) -> Result<FinalMetadata, W3WallError> {
+    Ok(FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: draft_alias(id),
+        content: serde_json::to_string(draft)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    })
+}
+
+/**
+ * Saves or overwrites one of the sender's drafts. Only the size is checked, a draft may be
+ * unfinished.
+ */
+pub fn save_draft(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    validation::check_payload_size(&transaction.data)?;
+    let request: DraftRequest = serde_json::from_str(&transaction.data)?;
+    check_draft_id(&request.id)?;
+    let id = request.id.as_str();
+    if !request.payload.is_object() {
+        return Err(W3WallError::rejected(
+            "Draft payload must be a post object.",
+        ));
+    }
+
+    let alias = draft_alias(id);
+    let drafts = metadatas
+        .iter()
+        .filter(|m| m.alias.starts_with(DRAFT_ALIAS_PREFIX) && m.alias != alias)
+        .filter(|m| m.public_key.eq_ignore_ascii_case(&transaction.public_key))
+        .count();
+    if drafts >= DEFAULT_MAX_DRAFTS {
+        return Err(W3WallError::Rejected(format!(
+            "A key is limited to {} drafts.",
+            DEFAULT_MAX_DRAFTS
+        )));
+    }
+
+    let draft = Draft {
+        draft: true,
+        payload: request.payload,
+        saved_at: timestamp_sec(transaction.timestamp),
+        published_at: None,
+    };
+    Ok(vec![draft_record(transaction, id, &draft)?])
+}
+
+/**
+ * Turns one of the sender's drafts into a post. The payload goes through the regular post
+ * pipeline as if it had just been sent, the post names its draft in `from_draft` and the draft
+ * is kept with its publication time.
+ */
+pub fn publish(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: DraftRequest = serde_json::from_str(&transaction.data)?;
+    check_draft_id(&request.id)?;
+    let id = request.id.as_str();
+    let mut draft: Draft = read_record(metadatas, &draft_alias(id), &transaction.public_key)
+        .ok_or_else(|| W3WallError::rejected("Draft does not exist."))?;
+    if draft.published_at.is_some() {
+        return Err(W3WallError::rejected("Draft is already published."));
+    }
+
+    let mut payload = draft.payload.clone();
+    payload["from_draft"] = id.into();
+    let published = Transaction {
+        method: "post".to_string(),
+        data: payload.to_string(),
+        ..transaction.clone()
+    };
+    let mut finals = execute::post(contract, metadatas, &published, config, filter)?;
+
+    draft.published_at = Some(timestamp_sec(transaction.timestamp));
+    finals.push(draft_record(transaction, id, &draft)?);
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(method: &str, data: Value) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: "0xauthor".to_string(),
+            data: data.to_string(),
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        }
+    }
+
+    fn stored(finals: &[FinalMetadata], cid: &str) -> Metadata {
+        let record = finals.last().unwrap();
+        crate::storage::ipfs::test_store::put(cid, &record.content);
+        Metadata {
+            alias: record.alias.clone(),
+            public_key: record.public_key.clone(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn drafts_publish_once_after_full_validation() {
+        let contract = MetaContract::default();
+        let config = WallConfig::default();
+        let filter = WordFilter::Global;
+        let save = |id: &str, payload: Value| {
+            save_draft(
+                &[],
+                &transaction(
+                    "save_draft",
+                    serde_json::json!({ "id": id, "payload": payload }),
+                ),
+            )
+        };
+        let publish_draft = |metadatas: &[Metadata], id: &str| {
+            let tx = transaction("publish", serde_json::json!({ "id": id }));
+            publish(&contract, metadatas, &tx, &config, &filter)
+        };
+
+        let finals = save("essay", serde_json::json!({ "text": "half a thought" })).unwrap();
+        assert_eq!(finals[0].alias, "w3wall.draft.essay");
+        let draft: Draft = serde_json::from_str(&finals[0].content).unwrap();
+        assert!(draft.draft);
+        let metadatas = [stored(&finals, "draft-1")];
+
+        let finals = publish_draft(&metadatas, "essay").unwrap();
+        assert_eq!(finals[0].alias, "");
+        let post: Value = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(post["from_draft"], "essay");
+        let draft: Draft = serde_json::from_str(&finals.last().unwrap().content).unwrap();
+        assert_eq!(draft.published_at, Some(1_700_000_000));
+
+        let metadatas = [stored(&finals, "draft-2")];
+        assert_eq!(
+            publish_draft(&metadatas, "essay").unwrap_err().to_string(),
+            "Draft is already published."
+        );
+        assert!(publish_draft(&[], "essay").is_err());
+
+        let finals = save("empty", serde_json::json!({})).unwrap();
+        assert!(publish_draft(&[stored(&finals, "draft-3")], "empty").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::W3WallError;
+use crate::storage::{find_post, post_type};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+pub static RSVP_ALIAS_PREFIX: &str = "w3wall.rsvp.";
+pub static ATTENDEES_ALIAS_PREFIX: &str = "w3wall.attendees.";
+
+pub fn rsvp_alias(cid: &str) -> String {
+    format!("{}{}", RSVP_ALIAS_PREFIX, cid)
+}
+
+pub fn attendees_alias(cid: &str) -> String {
+    format!("{}{}", ATTENDEES_ALIAS_PREFIX, cid)
+}
+
+#[derive(Deserialize)]
+struct Rsvp {
+    event: String,
+}
+
+/**
+ * One RSVP per key and event, answered with the updated attendee count
+ */
+pub fn rsvp(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let rsvp: Rsvp = serde_json::from_str(&transaction.data)?;
+
+    let event = find_post(metadatas, &rsvp.event)
+        .ok_or_else(|| W3WallError::rejected("Event is not on this wall."))?;
+    if post_type(event).as_deref() != Some("event") {
+        return Err(W3WallError::rejected("Post is not an event."));
+    }
+
+    let alias = rsvp_alias(&rsvp.event);
+    let mut attendees: HashSet<String> = metadatas
+        .iter()
+        .filter(|m| m.alias == alias)
+        .map(|m| m.public_key.to_lowercase())
+        .collect();
+    if !attendees.insert(transaction.public_key.to_lowercase()) {
+        return Err(W3WallError::rejected("Already RSVPed to this event."));
+    }
+
+    Ok(vec![
+        FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias,
+            content: serde_json::json!({ "event": rsvp.event }).to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: attendees_alias(&rsvp.event),
+            content: serde_json::json!({ "attendees": attendees.len() }).to_string(),
+            loose: 1,
+            version: "".to_string(),
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn wall() -> Vec<Metadata> {
+        test_store::put("event-cid", r#"{"type": "event", "text": "raid night"}"#);
+        vec![
+            Metadata {
+                public_key: "0xhost".to_string(),
+                cid: "event-cid".to_string(),
+                ..Default::default()
+            },
+            Metadata {
+                public_key: "0xa".to_string(),
+                alias: rsvp_alias("event-cid"),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn transaction(key: &str) -> Transaction {
+        Transaction {
+            public_key: key.to_string(),
+            data: r#"{"event": "event-cid"}"#.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rsvps_are_counted_once_per_key() {
+        let contract = MetaContract::default();
+        let finals = rsvp(&contract, &wall(), &transaction("0xb")).unwrap();
+        assert_eq!(finals[1].alias, "w3wall.attendees.event-cid");
+        assert_eq!(finals[1].content, r#"{"attendees":2}"#);
+
+        let err = rsvp(&contract, &wall(), &transaction("0xA")).unwrap_err();
+        assert_eq!(err.to_string(), "Already RSVPed to this event.");
+    }
+}
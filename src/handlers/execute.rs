@@ -0,0 +1,1632 @@
+use crate::budget;
+use crate::crypto::eth_address::parse_address;
+use crate::crypto::keccak_hex;
+use crate::data::{
+    Attachment, CompressedBody, CompressedStorage, NftEmbed, ProfanityAction, Quote, SpamAction,
+    ThreadActivity, TokenBound, ValidationMode, WallConfig,
+};
+use crate::defaults::DEFAULT_MAX_DECOMPRESSED_BYTES;
+use crate::encoding::{decode_gzip_base64, GZIP_ENCODING};
+use crate::error::W3WallError;
+#[cfg(feature = "federation")]
+use crate::handlers::federation;
+#[cfg(feature = "tipping")]
+use crate::handlers::tip;
+use crate::handlers::{
+    acl, announce, appeal, approval, block, board, bounty, chunk, compat, crosspost, daily,
+    dispute, draft, event, export, feed, follow, governance, import, merkle, migrate, moderation,
+    mute, ownership, pages, pins, profile, reaction, redaction, reveal, stats, sticker, summary,
+    template, theme, webhook,
+};
+use crate::logging;
+use crate::metadata::attributes::topic_attributes;
+use crate::metadata::board as board_index;
+use crate::metadata::cosmetics::cosmetic_attributes;
+use crate::metadata::embeds::{embeds, EMBEDS_ALIAS};
+use crate::metadata::excerpt::{article_text, excerpt, EXCERPT_ALIAS};
+use crate::metadata::fingerprints::{fingerprints_record, load_fingerprints};
+use crate::metadata::inbox;
+use crate::metadata::karma::{self, Karma, ReactionFilter};
+use crate::metadata::metrics;
+use crate::metadata::quarantine::is_new_account;
+use crate::metadata::reading::{Reading, READING_ALIAS};
+use crate::metadata::search::{search_terms, SEARCH_INDEX_ALIAS};
+use crate::metadata::trending::{trending_score, Trending, TRENDING_ALIAS};
+use crate::metadata::{activitystreams, jsonld, lens};
+use crate::metadata::{description, timestamp_sec};
+use crate::metadata::{digest, rollup};
+use crate::moderation::simhash::simhash;
+use crate::moderation::spam::{is_single_emoji, post_link_count, spam_score};
+use crate::moderation::WordFilter;
+use crate::storage::alias::Alias;
+use crate::storage::moderation::load_moderation;
+use crate::storage::muted::load_muted_words;
+use crate::storage::{config, envelope, filter};
+use crate::storage::{find_post, find_record, read_content};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::embed::held_by_author;
+use crate::validation::geo::normalize_geo;
+use crate::validation::image::canonical_uri;
+use crate::validation::links::canonical_links;
+use crate::validation::migration;
+use crate::validation::rules::media_hints;
+use crate::validation::{self, PostContext};
+
+/**
+ * on_execute methods, anything else is handled as a post
+ */
+pub static METHODS: &[&str] = &[
+    "post",
+    "init",
+    "refresh_metadata",
+    "set_filter",
+    "react",
+    "crosspost",
+    "tip",
+    "award",
+    "close_bounty",
+    "rsvp",
+    "moderate",
+    "transfer_ownership",
+    "accept_ownership",
+    "block",
+    "mute",
+    "redact",
+    "claim",
+    "counter_claim",
+    "resolve_claim",
+    "appeal",
+    "resolve_appeal",
+    "refresh_feed",
+    "import",
+    "chunk",
+    "assemble",
+    "save_draft",
+    "publish",
+    "approve",
+    "reject",
+    "merkle_root",
+    "digest",
+    "export",
+    "refresh_pages",
+    "set_template",
+    "register_sticker_pack",
+    "retire_sticker_pack",
+    "register_webhook",
+    "remove_webhook",
+    "add_board",
+    "remove_board",
+    "relate_wall",
+    "unrelate_wall",
+    "follow",
+    "unfollow",
+    "announce",
+    "set_theme",
+    "set_profile",
+    "migrate",
+    "reveal",
+    "set_summary",
+    "refresh_stats",
+    "refresh_pins",
+    "proposal",
+    "governance_vote",
+    "apply_config",
+];
+
+pub fn handle(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    budget::start();
+    if let Err(e) = validation::check_payload_size(&transaction.data) {
+        let outcome = Err(e.into());
+        logging::log_outcome(transaction, &outcome);
+        return outcome;
+    }
+    if let Err(e) = compat::check_versions(metadatas, transaction) {
+        let outcome = Err(e);
+        logging::log_outcome(transaction, &outcome);
+        return outcome;
+    }
+
+    let config = match config::load_config(contract, metadatas) {
+        Ok(config) => config,
+        Err(e) => {
+            let outcome = Err(e);
+            logging::log_outcome(transaction, &outcome);
+            return outcome;
+        }
+    };
+    logging::apply_config_level(&config);
+
+    log::debug!(
+        "method={} tx={} data_key={} metadatas={} data_len={}",
+        transaction.method,
+        transaction.hash,
+        transaction.data_key,
+        metadatas.len(),
+        transaction.data.len()
+    );
+
+    let is_post = transaction.method == "post" || !METHODS.contains(&transaction.method.as_str());
+    if !is_post {
+        if let Err(e) = validation::check_method_schema(&transaction.method, &transaction.data) {
+            let outcome = Err(e.into());
+            logging::log_outcome(transaction, &outcome);
+            return outcome;
+        }
+    }
+
+    let mut outcome = match transaction.method.as_str() {
+        method if !config.has_method(method) => Err(W3WallError::Rejected(format!(
+            "The {} method is off on this wall.",
+            method
+        ))),
+        "init" => init(contract, metadatas, transaction),
+        "refresh_metadata" => refresh_metadata(contract, metadatas, transaction, &config),
+        "set_filter" => moderation::set_filter(contract, transaction, &config),
+        "moderate" => moderation::moderate(contract, metadatas, transaction, &config),
+        "transfer_ownership" => {
+            ownership::transfer_ownership(contract, metadatas, transaction, &config)
+        }
+        "accept_ownership" => {
+            ownership::accept_ownership(contract, metadatas, transaction, &config)
+        }
+        "block" => block::block(transaction),
+        "mute" => mute::mute(transaction),
+        "announce" => announce::announce(contract, transaction, &config),
+        "set_theme" => theme::set_theme(contract, transaction, &config),
+        "set_profile" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            profile::set_profile(metadatas, transaction, &config, &word_filter)
+        }
+        "follow" | "unfollow" => follow::follow(metadatas, transaction),
+        "set_template" => template::set_template(contract, transaction, &config),
+        "register_sticker_pack" | "retire_sticker_pack" => {
+            sticker::set_sticker_pack(contract, metadatas, transaction, &config)
+        }
+        "register_webhook" | "remove_webhook" => {
+            webhook::set_webhook(contract, metadatas, transaction, &config)
+        }
+        "add_board" | "remove_board" => board::set_board(contract, transaction, &config),
+        #[cfg(feature = "federation")]
+        "relate_wall" | "unrelate_wall" => {
+            federation::set_related(contract, metadatas, transaction, &config)
+        }
+        "redact" => redaction::redact(contract, metadatas, transaction),
+        "claim" => dispute::claim(contract, metadatas, transaction, &config),
+        "counter_claim" => dispute::counter_claim(contract, metadatas, transaction),
+        "refresh_feed" => feed::refresh_feed(contract, metadatas, transaction),
+        "refresh_stats" => stats::refresh_stats(contract, metadatas, transaction),
+        "refresh_pins" => pins::refresh_pins(contract, metadatas, transaction, &config),
+        "proposal" => governance::propose(contract, metadatas, transaction, &config),
+        "governance_vote" => governance::vote(contract, metadatas, transaction, &config),
+        "apply_config" => governance::apply_config(contract, metadatas, transaction, &config),
+        "merkle_root" => merkle::merkle(contract, metadatas, transaction),
+        "digest" => daily::digest(contract, metadatas, transaction, &config),
+        "export" => export::export(contract, metadatas, transaction, &config),
+        "refresh_pages" => pages::refresh_pages(contract, metadatas, transaction),
+        "migrate" => migrate::migrate(contract, metadatas, transaction, &config),
+        "reveal" => reveal::reveal(contract, metadatas, transaction, &config),
+        "set_summary" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            summary::set_summary(contract, transaction, &config, &word_filter)
+        }
+        "resolve_claim" => dispute::resolve_claim(contract, metadatas, transaction, &config),
+        "appeal" => appeal::appeal(contract, metadatas, transaction),
+        "resolve_appeal" => appeal::resolve_appeal(contract, metadatas, transaction, &config),
+        "react" => reaction::react(contract, metadatas, transaction, &config),
+        #[cfg(feature = "tipping")]
+        "tip" => tip::tip(contract, metadatas, transaction),
+        "award" => bounty::award(contract, metadatas, transaction),
+        "close_bounty" => bounty::close(contract, metadatas, transaction),
+        "rsvp" => event::rsvp(contract, metadatas, transaction),
+        "chunk" => chunk::chunk(metadatas, transaction),
+        "assemble" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            chunk::assemble(contract, metadatas, transaction, &config, &word_filter)
+        }
+        "save_draft" => draft::save_draft(metadatas, transaction),
+        "publish" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            draft::publish(contract, metadatas, transaction, &config, &word_filter)
+        }
+        "approve" | "reject" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            approval::review(contract, metadatas, transaction, &config, &word_filter)
+        }
+        "import" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            import::import(contract, metadatas, transaction, &config, &word_filter)
+        }
+        "crosspost" => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            crosspost::crosspost(contract, metadatas, transaction, &config, &word_filter)
+        }
+        _ => {
+            let word_filter = filter::load_word_filter(contract, metadatas);
+            post(contract, metadatas, transaction, &config, &word_filter)
+        }
+    };
+
+    if config.metrics {
+        if let Ok(finals) = outcome.as_mut() {
+            let spent = budget::spent();
+            let mut wall_metrics = metrics::load_metrics(contract, metadatas);
+            metrics::count_execution(&mut wall_metrics, metadatas, transaction, is_post, spent);
+            finals.push(metrics::metrics_record(contract, &wall_metrics));
+        }
+    }
+
+    if let Ok(finals) = &outcome {
+        if let Err(e) = acl::check_writes(contract, metadatas, transaction, &config, finals) {
+            outcome = Err(e);
+        }
+    }
+    if let Ok(finals) = outcome.as_mut() {
+        envelope::seal_all(finals, metadatas, transaction);
+    }
+    // whatever was cut short may have changed the outcome, so none of it is kept
+    if budget::overrun() {
+        outcome = Err(W3WallError::rejected(
+            "Transaction needs more work than a wall allows and was stopped.",
+        ));
+    }
+
+    logging::log_outcome(transaction, &outcome);
+    outcome
+}
+
+pub fn post(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    store_post(contract, metadatas, transaction, config, filter, false)
+}
+
+/**
+ * `post` for a held post a moderator approved, which is never held back again
+ */
+pub fn approved_post(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    store_post(contract, metadatas, transaction, config, filter, true)
+}
+
+fn store_post(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+    approved: bool,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let state = load_moderation(contract, metadatas)?.unwrap_or_default();
+    if state.locked {
+        return Err(W3WallError::rejected("This wall is locked."));
+    }
+    if state.is_banned(&transaction.public_key) {
+        return Err(W3WallError::rejected("This key is banned from the wall."));
+    }
+
+    let (payload, migrated) = migration::migrate(validation::parse_payload(&transaction.data)?)?;
+
+    let ctx = PostContext {
+        transaction,
+        metadatas,
+        wall: &contract.public_key,
+        config,
+        filter,
+        payload: &payload,
+    };
+
+    let validators = validation::post_validators();
+    let (cleaned, mut warnings) = match config.rules.validation_mode {
+        ValidationMode::Strict => {
+            validation::run_pipeline(&validators, &ctx).map(|_| (None, vec![]))?
+        }
+        ValidationMode::Lenient => validation::run_pipeline_lenient(&validators, &ctx)?,
+    };
+    // a lenient post is stored without the fields its warnings are about
+    let migrated = migrated || cleaned.is_some();
+    let payload = cleaned.unwrap_or(payload);
+    let ctx = PostContext {
+        transaction,
+        metadatas,
+        wall: &contract.public_key,
+        config,
+        filter,
+        payload: &payload,
+    };
+    if config.rules.validation_mode == ValidationMode::Lenient {
+        warnings.extend(validation::advisories(&ctx));
+    }
+    if !approved && approval::is_held(contract, metadatas, transaction, config) {
+        return approval::hold(metadatas, transaction);
+    }
+
+    let stored = stored_payload(&ctx);
+    // annotated and migrated payloads are always stored inflated, the compressed body would
+    // lose the edits
+    let compressed = match config.compressed_storage {
+        CompressedStorage::Compressed if !migrated => validation::gzip_body(&transaction.data),
+        _ => None,
+    };
+    let content = match (&stored, compressed) {
+        (Some(filtered), _) => serde_json::to_string(filtered)?,
+        (None, Some(data)) => {
+            let text = decode_gzip_base64(&data, DEFAULT_MAX_DECOMPRESSED_BYTES)
+                .map_err(W3WallError::rejected)?;
+            serde_json::to_string(&CompressedBody {
+                encoding: GZIP_ENCODING.to_string(),
+                data,
+                content_hash: keccak_hex(text.as_bytes()),
+            })?
+        }
+        (None, None) if migrated || validation::is_encoded(&transaction.data) => {
+            payload.to_string()
+        }
+        (None, None) => transaction.data.clone(),
+    };
+
+    let mut finals: Vec<FinalMetadata> = vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: Alias::Post.to_string(),
+        content,
+        loose: 1,
+        version: transaction.version.clone(),
+    }];
+
+    let text = article_text(ctx.payload).or_else(|| ctx.str_field("text").map(str::to_string));
+    if let Some(text) = text {
+        let reading = Reading::of(&text);
+        if reading.words > 0 {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: READING_ALIAS.to_string(),
+                content: serde_json::to_string(&reading)?,
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    if let Some(Ok(geo)) = ctx.payload.get("geo").map(normalize_geo) {
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Geo.to_string(),
+            content: geo.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    if let Some(template) = ctx
+        .str_field("template")
+        .and_then(|name| config.templates.get(name))
+        .filter(|template| !template.attributes.is_empty())
+    {
+        let pinned = serde_json::json!({
+            "template": ctx.str_field("template"),
+            "attributes": template.attributes,
+        });
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Template.to_string(),
+            content: pinned.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    if let Some(license) = ctx.str_field("license") {
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::License.to_string(),
+            content: license.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    // structured quote card, already checked against the quoted record by QuoteValidator
+    if let Some(quote) = ctx.payload.get("quote") {
+        let quote: Quote = serde_json::from_value(quote.clone())?;
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Quote.to_string(),
+            content: serde_json::to_string(&quote)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    if let Some(embed) = ctx.payload.get("embed_nft") {
+        let embed: NftEmbed = serde_json::from_value(embed.clone())?;
+        let card = serde_json::json!({
+            "chain_id": embed.chain_id,
+            "contract": embed.contract,
+            "token_id": embed.token_id,
+            "held_by_author": held_by_author(&embed, &transaction.public_key, config),
+        });
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::EmbedNft.to_string(),
+            content: card.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    // snippets get their own record, untouched by masking, for syntax highlighting
+    if ctx.str_field("type") == Some("code") {
+        let snippet = serde_json::json!({
+            "language": ctx.str_field("language"),
+            "code": ctx.str_field("code"),
+        });
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Code.to_string(),
+            content: snippet.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    if let Some(attachments) = ctx.payload.get("attachments") {
+        let attachments: Vec<Attachment> = serde_json::from_value(attachments.clone())?;
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Attachments.to_string(),
+            content: serde_json::to_string(&attachments)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    // authorship goes to the NFT, the signature chain was checked by TokenBoundValidator
+    if let Some(bound) = ctx.payload.get("token_bound") {
+        let bound: TokenBound = serde_json::from_value(bound.clone())?;
+        let token = serde_json::json!({
+            "chain_id": bound.token.chain_id,
+            "contract": bound.token.contract,
+            "token_id": bound.token.token_id,
+            "account": bound.account,
+        });
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::AuthoredByToken.to_string(),
+            content: token.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    for alias in [Alias::Blurhash, Alias::Thumbnail] {
+        if let Some(value) = ctx.str_field(alias.as_str()) {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: alias.to_string(),
+                content: value.to_string(),
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    // the image's ipfs:// URI with the wall's gateways to fail over between, and any declared
+    // size so clients can reserve the image's space
+    let uri = ctx.str_field("image").and_then(canonical_uri);
+    if let (Some(uri), Ok((size_bytes, dimensions))) = (uri, media_hints(ctx.payload)) {
+        let mut media = serde_json::Map::new();
+        media.insert("uri".to_string(), uri.into());
+        media.insert("gateways".to_string(), config.gateways.clone().into());
+        if let Some(size_bytes) = size_bytes {
+            media.insert("size_bytes".to_string(), size_bytes.into());
+        }
+        if let Some(dimensions) = dimensions {
+            media.insert("dimensions".to_string(), serde_json::to_value(dimensions)?);
+        }
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Media.to_string(),
+            content: serde_json::Value::Object(media).to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    if config.syndication.activitystreams {
+        if let Some(activity) =
+            activitystreams::create_note(transaction, stored.as_ref().unwrap_or(&payload))
+        {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: activitystreams::ACTIVITYSTREAMS_ALIAS.to_string(),
+                content: activity.to_string(),
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    if config.syndication.jsonld {
+        if let Some(posting) = jsonld::post_jsonld(transaction, stored.as_ref().unwrap_or(&payload))
+        {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: jsonld::JSONLD_ALIAS.to_string(),
+                content: posting.to_string(),
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    if config.syndication.lens {
+        if let Some(metadata) =
+            lens::publication_metadata(transaction, stored.as_ref().unwrap_or(&payload))
+        {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: lens::LENS_ALIAS.to_string(),
+                content: metadata.to_string(),
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    // indexed from the stored text so masked words stay out, private posts are never indexed
+    if config.search_index && ctx.str_field("visibility") != Some("private") {
+        let text = stored.as_ref().unwrap_or(&payload)["text"].as_str();
+        let terms = text.map(search_terms).unwrap_or_default();
+        if !terms.is_empty() {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: SEARCH_INDEX_ALIAS.to_string(),
+                content: serde_json::to_string(&terms)?,
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    // pre-rendered for clients without a markdown renderer, from the stored text like the index,
+    // articles from their sections
+    let body = stored.as_ref().unwrap_or(&payload);
+    if let Some(text) = article_text(body).or_else(|| body["text"].as_str().map(str::to_string)) {
+        let plain = excerpt(&text);
+        if !plain.is_empty() {
+            finals.push(FinalMetadata {
+                public_key: transaction.public_key.clone(),
+                alias: EXCERPT_ALIAS.to_string(),
+                content: plain,
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+
+    let text = stored.as_ref().unwrap_or(&payload)["text"].as_str();
+    let embeds = text.map(|text| embeds(text, config)).unwrap_or_default();
+    if !embeds.is_empty() {
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: EMBEDS_ALIAS.to_string(),
+            content: serde_json::to_string(&embeds)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    if config.karma.enabled {
+        let reactions = ReactionFilter::new(
+            metadatas,
+            &config.karma,
+            timestamp_sec(transaction.timestamp),
+        );
+        let karma = Karma::of(metadatas, &transaction.public_key, &reactions);
+        let karma = Karma::new(karma.posts + 1, karma.reactions);
+        finals.push(karma::karma_record(
+            contract,
+            &transaction.public_key,
+            &karma,
+        ));
+    }
+
+    finals.extend(inbox::notify(
+        contract,
+        metadatas,
+        transaction,
+        stored.as_ref().unwrap_or(&payload),
+    ));
+
+    if let Some(category) = ctx.str_field("category") {
+        let mut index = board_index::load_board(contract, metadatas, category);
+        board_index::count_post(&mut index, transaction);
+        finals.push(board_index::board_record(contract, &index));
+    }
+
+    if config.rollups {
+        let mut thread = rollup::load_rollup(contract, metadatas);
+        rollup::count_reply(&mut thread, metadatas, transaction);
+        finals.push(rollup::rollup_record(contract, &thread));
+    }
+
+    let duplicates = &config.rules.duplicates;
+    if let Some(hash) = ctx
+        .str_field("text")
+        .filter(|_| duplicates.window > 0 && !ctx.is_trusted())
+        .and_then(simhash)
+    {
+        let mut recent = load_fingerprints(metadatas, &contract.public_key);
+        recent.add(hash, transaction, duplicates);
+        finals.push(fingerprints_record(contract, &recent));
+    }
+
+    if config.digests {
+        let mut thread = digest::load_digest(contract, metadatas);
+        digest::add_reply(&mut thread, transaction, &finals[0].content);
+        finals.push(digest::digest_record(contract, &thread));
+    }
+
+    if !warnings.is_empty() {
+        let warnings = serde_json::json!({ "tx": transaction.hash, "warnings": warnings });
+        finals.push(FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: validation::WARNINGS_ALIAS.to_string(),
+            content: warnings.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    Ok(finals)
+}
+
+/**
+ * The payload as stored when the contract annotates it: profane words bleeped with
+ * `"filtered": true`, a `spam_score`, `link_count` or `near_duplicate` when the wall tags
+ * instead of rejecting, `visibility: "limited"` on borderline spam, `"new_account": true` on a
+ * new key's first posts, `"encrypted": true` on private posts, template tags, the wall's
+ * timezone on events that name none, and `geo` truncated. None keeps the raw transaction data.
+ */
+fn stored_payload(ctx: &PostContext) -> Option<serde_json::Value> {
+    let rules = &ctx.config.rules;
+    let mut payload: Option<serde_json::Value> = None;
+    // trusted authors skip the filter and the spam score
+    let screened = !ctx.is_trusted();
+
+    if let Some(text) = ctx.str_field("text") {
+        if screened && rules.profanity_action == ProfanityAction::Mask {
+            if let Some(masked) = ctx.filter.mask(text) {
+                let stored = payload.get_or_insert_with(|| ctx.payload.clone());
+                stored["text"] = masked.into();
+                stored["filtered"] = true.into();
+            }
+        }
+
+        // collapsed for the replied author when it uses one of their muted terms
+        if let Some(post) = ctx
+            .str_field("reply_to")
+            .and_then(|cid| find_post(ctx.metadatas, cid))
+        {
+            if load_muted_words(ctx.metadatas, &post.public_key).mutes(text) {
+                payload.get_or_insert_with(|| ctx.payload.clone())["muted_for"] =
+                    post.public_key.clone().into();
+            }
+        }
+
+        if screened && (rules.spam.action == SpamAction::Tag || rules.spam.limit_margin > 0) {
+            let score = spam_score(text, &rules.spam);
+            if rules.spam.is_borderline(score) {
+                let stored = payload.get_or_insert_with(|| ctx.payload.clone());
+                stored["visibility"] = "limited".into();
+                stored["spam_score"] = score.into();
+            } else if rules.spam.action == SpamAction::Tag && rules.spam.is_spam(score) {
+                payload.get_or_insert_with(|| ctx.payload.clone())["spam_score"] = score.into();
+            }
+        }
+
+        let duplicates = &rules.duplicates;
+        if screened && duplicates.window > 0 && duplicates.action == SpamAction::Tag {
+            if let Some(hash) = simhash(text) {
+                let recent = load_fingerprints(ctx.metadatas, ctx.wall);
+                if recent.is_near_duplicate(hash, duplicates) {
+                    payload.get_or_insert_with(|| ctx.payload.clone())["near_duplicate"] =
+                        true.into();
+                }
+            }
+        }
+    }
+
+    if screened
+        && rules.profanity_action == ProfanityAction::Mask
+        && ctx.str_field("type") == Some("article")
+    {
+        let mut article = payload.clone().unwrap_or_else(|| ctx.payload.clone());
+        let mut filtered = false;
+        let mut mask = |field: &mut serde_json::Value| {
+            if let Some(masked) = field.as_str().and_then(|text| ctx.filter.mask(text)) {
+                *field = masked.into();
+                filtered = true;
+            }
+        };
+        mask(&mut article["title"]);
+        for section in article["sections"].as_array_mut().into_iter().flatten() {
+            for field in ["heading", "body"] {
+                if let Some(text) = section.get_mut(field) {
+                    mask(text);
+                }
+            }
+        }
+        if filtered {
+            article["filtered"] = true.into();
+            payload = Some(article);
+        }
+    }
+
+    let links = post_link_count(ctx.payload);
+    if rules.link_action == SpamAction::Tag && rules.max_links > 0 && links > rules.max_links {
+        payload.get_or_insert_with(|| ctx.payload.clone())["link_count"] = links.into();
+    }
+
+    if ctx.str_field("visibility") == Some("private") {
+        payload.get_or_insert_with(|| ctx.payload.clone())["encrypted"] = true.into();
+    }
+
+    if is_new_account(ctx.metadatas, ctx.transaction, ctx.config) {
+        payload.get_or_insert_with(|| ctx.payload.clone())["new_account"] = true.into();
+    }
+
+    if let (Some("event"), None, Some(timezone)) = (
+        ctx.str_field("type"),
+        ctx.payload.get("timezone"),
+        &ctx.config.locale.timezone,
+    ) {
+        payload.get_or_insert_with(|| ctx.payload.clone())["timezone"] = timezone.clone().into();
+    }
+
+    // template tags are merged into the post's own
+    if let Some(template) = ctx
+        .str_field("template")
+        .and_then(|name| ctx.config.templates.get(name))
+    {
+        let mut tags: Vec<serde_json::Value> =
+            ctx.payload["tags"].as_array().cloned().unwrap_or_default();
+        for tag in &template.tags {
+            if !tags.iter().any(|t| t.as_str() == Some(tag)) {
+                tags.push(tag.clone().into());
+            }
+        }
+        if Some(&tags) != ctx.payload["tags"].as_array() && !tags.is_empty() {
+            payload.get_or_insert_with(|| ctx.payload.clone())["tags"] = tags.into();
+        }
+    }
+
+    // links are stored canonical, so the same page always reads the same
+    for field in ["text", "url"] {
+        // masking may already have rewritten the text
+        let current = payload.as_ref().unwrap_or(ctx.payload)[field].as_str();
+        if let Some(canonical) = current.and_then(canonical_links) {
+            payload.get_or_insert_with(|| ctx.payload.clone())[field] = canonical.into();
+        }
+    }
+
+    // the precise position never reaches storage
+    if let Some(Ok(geo)) = ctx.payload.get("geo").map(normalize_geo) {
+        if ctx.payload["geo"].is_object() && ctx.payload["geo"] != geo {
+            payload.get_or_insert_with(|| ctx.payload.clone())["geo"] = geo;
+        }
+    }
+
+    payload
+}
+
+/**
+ * Stores the initial configuration bundle of a freshly cloned wall. The transaction data is a
+ * (partial) WallConfig, anything left out falls back to the defaults.
+ */
+pub fn init(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !transaction
+        .public_key
+        .eq_ignore_ascii_case(&contract.public_key)
+    {
+        return Err(W3WallError::rejected(
+            "Only the contract owner can initialize the wall.",
+        ));
+    }
+
+    if config::find_config(contract, metadatas).is_some() {
+        return Err(W3WallError::rejected("Wall is already initialized."));
+    }
+
+    let mut config: WallConfig = if transaction.data.trim().is_empty() {
+        WallConfig::default()
+    } else {
+        serde_json::from_str(&transaction.data)?
+    };
+
+    if config.owner.is_empty() {
+        config.owner = transaction.public_key.clone();
+    }
+    check_config(&mut config)?;
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: config::CONFIG_ALIAS.to_string(),
+        content: serde_json::to_string(&config)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+/**
+ * Checks every config stored as the wall's, by `init` or by a passed proposal, and normalizes
+ * the moderator list. The schema covers the limits, the rest needs more than a schema can say.
+ */
+pub fn check_config(config: &mut WallConfig) -> Result<(), W3WallError> {
+    validation::check_schema("init", &serde_json::to_value(&*config)?)?;
+    if config.owner.is_empty() {
+        return Err(W3WallError::rejected("The wall needs an owner."));
+    }
+    let mut owners: Vec<String> = config
+        .owners
+        .iter()
+        .chain(std::iter::once(&config.owner))
+        .map(|o| o.to_lowercase())
+        .collect();
+    owners.sort();
+    owners.dedup();
+    if config.owner_threshold > owners.len() {
+        return Err(W3WallError::Rejected(format!(
+            "Owner threshold {} is more than the {} owners.",
+            config.owner_threshold,
+            owners.len()
+        )));
+    }
+    for moderator in config.moderators.iter_mut() {
+        *moderator = parse_address(moderator).map_err(|e| {
+            W3WallError::Rejected(format!("Moderator {} is refused: {}", moderator, e))
+        })?;
+    }
+    config.moderators.sort();
+    config.moderators.dedup();
+    if let Some(reaction) = config.reactions.iter().find(|r| !is_single_emoji(r)) {
+        return Err(W3WallError::Rejected(format!(
+            "Reaction {} is not a single emoji.",
+            reaction
+        )));
+    }
+    Ok(())
+}
+
+/**
+ * Recompute the topic attributes from the current thread so the NFT reflects live stats
+ */
+pub fn refresh_metadata(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut activity = ThreadActivity::from_metadatas(metadatas);
+    activity.token_id = transaction.token_id.parse().unwrap_or(0);
+    activity.last_activity = timestamp_sec(transaction.timestamp);
+    let thread = rollup::load_rollup(contract, metadatas);
+    let reactions = ReactionFilter::new(metadatas, &config.karma, activity.last_activity);
+    activity.trending = trending_score(metadatas, &thread, activity.last_activity, &reactions);
+
+    let mut attr = topic_attributes(&activity);
+    attr.extend(cosmetic_attributes(
+        &transaction.data_key,
+        &transaction.token_id,
+    ));
+
+    let mut finals = vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::Attributes.to_string(),
+        content: serde_json::to_string(&attr)?,
+        loose: 1,
+        version: "".to_string(),
+    }];
+
+    // a curated summary wins, otherwise re-rendered so {reply_count} follows the thread, the
+    // title is the one set at mint
+    let title = find_record(metadatas, Alias::Name.as_str(), &contract.public_key)
+        .and_then(read_content)
+        .unwrap_or_default();
+    let content = match summary::load_summary(contract, metadatas) {
+        Some(summary) => summary.text,
+        None => description(
+            &config.description_template,
+            &title,
+            &contract.public_key,
+            activity.replies,
+        ),
+    };
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::Description.to_string(),
+        content,
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    let trending = Trending {
+        score: activity.trending,
+        computed_at: activity.last_activity,
+    };
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: TRENDING_ALIAS.to_string(),
+        content: serde_json::to_string(&trending)?,
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    if config.syndication.jsonld {
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: jsonld::JSONLD_ALIAS.to_string(),
+            content: jsonld::thread_jsonld(&transaction.data_key, &activity).to_string(),
+            loose: 1,
+            version: "".to_string(),
+        });
+    }
+
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::fingerprints::FINGERPRINTS_ALIAS;
+    use crate::storage::muted::MUTED_ALIAS;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn body(record: &FinalMetadata) -> String {
+        envelope::open(record.content.clone())
+    }
+
+    fn transaction(method: &str, public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn articles_get_an_excerpt_and_reading_time_from_their_sections() {
+        let data = r#"{"type": "article", "title": "Notes", "sections": [{"heading": "One", "body": "*first* part"}, {"body": "second part"}]}"#;
+        let finals = handle(&contract(), &[], &transaction("metadata", "0xa", data)).unwrap();
+        let reading = finals.iter().find(|f| f.alias == READING_ALIAS).unwrap();
+        assert_eq!(body(reading), r#"{"words":5,"minutes":1}"#);
+        let excerpt = finals.iter().find(|f| f.alias == EXCERPT_ALIAS).unwrap();
+        assert_eq!(body(excerpt), "One first part second part");
+    }
+
+    #[test]
+    fn post_stores_payload_under_empty_alias() {
+        let data = r#"{"schema_version": 2, "type": "post", "text": "hello", "blurhash": "LEHV6nWB2yk8pyo0adR*.7kCMdnj"}"#;
+        let finals = handle(&contract(), &[], &transaction("metadata", "0xa", data)).unwrap();
+        assert_eq!(finals.len(), 6);
+        assert_eq!(finals[0].alias, "");
+        assert!(finals[0]
+            .content
+            .starts_with(r#"{"v":1,"type":"post","author":"0xa","created_at":0,"body":{"#));
+        assert_eq!(body(&finals[0]), data);
+        assert_eq!(finals[1].alias, READING_ALIAS);
+        assert_eq!(body(&finals[1]), r#"{"words":1,"minutes":1}"#);
+        assert_eq!(finals[2].alias, "blurhash");
+        assert_eq!(finals[3].alias, EXCERPT_ALIAS);
+        assert_eq!(body(&finals[3]), "hello");
+        assert_eq!(finals[4].alias, rollup::ROLLUP_ALIAS);
+        assert_eq!(finals[5].alias, digest::DIGEST_ALIAS);
+    }
+
+    #[test]
+    fn replies_using_muted_words_are_marked_for_the_author() {
+        let post = Metadata {
+            public_key: "0xauthor".to_string(),
+            ..record("", "exec-muted-post", r#"{"text": "gm"}"#)
+        };
+        let muted = Metadata {
+            public_key: "0xauthor".to_string(),
+            ..record(
+                MUTED_ALIAS,
+                "exec-muted-words",
+                r#"{"muted": ["wen moon"]}"#,
+            )
+        };
+        let wall = [post, muted];
+
+        let data = r#"{"text": "WEN M00N ser", "reply_to": "exec-muted-post"}"#;
+        let finals = handle(&contract(), &wall, &transaction("post", "0xb", data)).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored["muted_for"], "0xauthor");
+
+        let data = r#"{"text": "gm ser", "reply_to": "exec-muted-post"}"#;
+        let finals = handle(&contract(), &wall, &transaction("post", "0xb", data)).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert!(stored.get("muted_for").is_none());
+    }
+
+    #[test]
+    fn payloads_are_checked_against_the_method_schema() {
+        let err = handle(
+            &contract(),
+            &[],
+            &transaction("block", "0xa", r#"{"blocked": "0xb"}"#),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[schema] Expected array at /blocked (schema /properties/blocked/type)"
+        );
+
+        let data = r#"{"text": "gm", "schema_version": 99}"#;
+        let err = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap_err();
+        assert!(err.to_string().starts_with("[schema_version]"));
+    }
+
+    #[test]
+    fn legacy_posts_are_stored_at_the_current_schema_version() {
+        let data = r#"{"text": "gm", "image": ""}"#;
+        let finals = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap();
+        assert_eq!(
+            body(&finals[0]),
+            r#"{"image":"","schema_version":2,"text":"gm","type":"post"}"#
+        );
+    }
+
+    #[test]
+    fn refreshed_description_follows_the_wall_template() {
+        let wall = [
+            record(
+                config::CONFIG_ALIAS,
+                "exec-description-config",
+                r#"{"description_template": "{title}: {reply_count} replies"}"#,
+            ),
+            record("name", "exec-description-name", "gm thread"),
+            record("", "exec-description-post", r#"{"text": "gm"}"#),
+        ];
+        let finals = handle(
+            &contract(),
+            &wall,
+            &transaction("refresh_metadata", "0xa", ""),
+        )
+        .unwrap();
+        let description = finals.iter().find(|f| f.alias == "description").unwrap();
+        assert_eq!(body(description), "gm thread: 1 replies");
+
+        let summarized = [
+            wall.as_slice(),
+            &[record(
+                summary::SUMMARY_ALIAS,
+                "exec-description-summary",
+                r#"{"text": "tl;dr gm", "set_by": "0xowner", "set_at": 1}"#,
+            )],
+        ]
+        .concat();
+        let finals = handle(
+            &contract(),
+            &summarized,
+            &transaction("refresh_metadata", "0xa", ""),
+        )
+        .unwrap();
+        let description = finals.iter().find(|f| f.alias == "description").unwrap();
+        assert_eq!(body(description), "tl;dr gm");
+    }
+
+    #[test]
+    fn image_only_post_is_accepted() {
+        let data = r#"{"image": "https://nftstorage.link/ipfs/bafy", "image_mime": "image/png"}"#;
+        assert!(handle(&contract(), &[], &transaction("metadata", "0xa", data)).is_ok());
+    }
+
+    #[test]
+    fn borderline_spam_is_stored_with_limited_visibility() {
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-limit-config",
+            r#"{"rules": {"spam": {"limit_margin": 1}}}"#,
+        );
+        let wall = [config];
+        let post = |text: &str| {
+            let data = serde_json::json!({ "text": text }).to_string();
+            handle(&contract(), &wall, &transaction("metadata", "0xa", &data))
+        };
+
+        let spam = "CLICK HTTP://A HTTP://B HTTP://C HTTP://D";
+        let finals = post(spam).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored["visibility"], "limited");
+        assert_eq!(stored["spam_score"], 2);
+        assert!(post(&format!("{} {}", spam, "🚀".repeat(21))).is_err());
+    }
+
+    #[test]
+    fn near_duplicates_of_recent_posts_are_caught() {
+        let rules = |action: &str, cid: &str| {
+            let rules = format!(
+                r#"{{"rules": {{"duplicates": {{"window": 5, "action": "{}"}}}}}}"#,
+                action
+            );
+            record(config::CONFIG_ALIAS, cid, &rules)
+        };
+        let data = r#"{"text": "Claim your free airdrop at example dot com 🚀"}"#;
+        let finals = handle(
+            &contract(),
+            &[rules("reject", "exec-dup-reject")],
+            &transaction("post", "0xa", data),
+        )
+        .unwrap();
+        let recent = finals
+            .iter()
+            .find(|f| f.alias == FINGERPRINTS_ALIAS)
+            .unwrap();
+        let recent = record(FINGERPRINTS_ALIAS, "exec-dup-recent", &body(recent));
+
+        let again = r#"{"text": "claim your FREE airdrop at example dot com 💰"}"#;
+        let wall = [rules("reject", "exec-dup-reject"), recent.clone()];
+        let err = handle(&contract(), &wall, &transaction("post", "0xb", again)).unwrap_err();
+        assert!(err.to_string().starts_with("[duplicate]"));
+        let other = r#"{"text": "the meetup moves to thursday, same room"}"#;
+        assert!(handle(&contract(), &wall, &transaction("post", "0xb", other)).is_ok());
+
+        let wall = [rules("tag", "exec-dup-tag"), recent];
+        let finals = handle(&contract(), &wall, &transaction("post", "0xb", again)).unwrap();
+        assert!(body(&finals[0]).contains(r#""near_duplicate":true"#));
+    }
+
+    #[test]
+    fn link_floods_are_tagged_when_the_wall_says_so() {
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-link-config",
+            r#"{"rules": {"max_links": 1, "link_action": "tag"}}"#,
+        );
+        let data = r#"{"text": "https://a.example https://b.example"}"#;
+        let finals = handle(
+            &contract(),
+            &[config],
+            &transaction("metadata", "0xa", data),
+        )
+        .unwrap();
+        assert!(body(&finals[0]).contains(r#""link_count":2"#));
+    }
+
+    #[test]
+    fn new_keys_are_marked_or_held_back() {
+        let lenient = record(
+            config::CONFIG_ALIAS,
+            "exec-quarantine-lenient",
+            r#"{"rules": {"validation_mode": "lenient", "quarantine": {"posts": 1, "hours": 24}}}"#,
+        );
+        let data = r#"{"text": "gm"}"#;
+        let finals = handle(&contract(), &[lenient], &transaction("post", "0xa", data)).unwrap();
+        assert!(body(&finals[0]).contains(r#""new_account":true"#));
+
+        let strict = record(
+            config::CONFIG_ALIAS,
+            "exec-quarantine-strict",
+            r#"{"rules": {"quarantine": {"posts": 1, "hours": 24}}}"#,
+        );
+        let finals = handle(&contract(), &[strict], &transaction("post", "0xa", data)).unwrap();
+        assert_eq!(finals.len(), 1);
+        assert!(finals[0].alias.starts_with(approval::PENDING_ALIAS_PREFIX));
+    }
+
+    #[test]
+    fn lenient_walls_store_posts_without_their_faulty_hints() {
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-lenient-config",
+            r#"{"rules": {"validation_mode": "lenient"}}"#,
+        );
+        let data = r#"{"text": "gm", "thumbnail": "not-a-cid"}"#;
+        assert!(handle(&contract(), &[], &transaction("metadata", "0xa", data)).is_err());
+
+        let finals = handle(
+            &contract(),
+            &[config],
+            &transaction("metadata", "0xa", data),
+        )
+        .unwrap();
+        assert_eq!(
+            body(&finals[0]),
+            r#"{"schema_version":2,"text":"gm","type":"post"}"#
+        );
+        assert!(finals.iter().all(|record| record.alias != Alias::Thumbnail));
+        let warnings = finals
+            .iter()
+            .find(|record| record.alias == validation::WARNINGS_ALIAS)
+            .unwrap();
+        assert_eq!(
+            body(warnings),
+            r#"{"tx":"","warnings":[{"code":"thumbnail","message":"Invalid thumbnail CID."}]}"#
+        );
+    }
+
+    #[test]
+    fn proof_of_work_covers_the_payload_as_sent() {
+        // a v1 post is migrated and this one loses its thumbnail before it is stored
+        let sent = serde_json::json!({ "text": "gm", "thumbnail": "not-a-cid" });
+        let content = crate::crypto::pow::work_content(&sent);
+        let nonce = (0u64..)
+            .map(|n| n.to_string())
+            .find(|n| crate::crypto::pow::verify("", "0xa", &content, n, 8))
+            .unwrap();
+        let mut data = sent.clone();
+        data["nonce"] = nonce.into();
+        let data = data.to_string();
+
+        let lenient = record(
+            config::CONFIG_ALIAS,
+            "exec-pow-lenient",
+            r#"{"rules": {"validation_mode": "lenient", "pow_difficulty": 8}}"#,
+        );
+        let finals = handle(
+            &contract(),
+            &[lenient],
+            &transaction("metadata", "0xa", &data),
+        );
+        assert!(!body(&finals.unwrap()[0]).contains("thumbnail"));
+
+        let strict = record(
+            config::CONFIG_ALIAS,
+            "exec-pow-strict",
+            r#"{"rules": {"pow_difficulty": 8}}"#,
+        );
+        let mut v1 = serde_json::json!({ "text": "gm" });
+        let content = crate::crypto::pow::work_content(&v1);
+        v1["nonce"] = (0u64..)
+            .map(|n| n.to_string())
+            .find(|n| crate::crypto::pow::verify("", "0xa", &content, n, 8))
+            .unwrap()
+            .into();
+        let post = transaction("metadata", "0xa", &v1.to_string());
+        assert!(handle(&contract(), std::slice::from_ref(&strict), &post).is_ok());
+        let forged = transaction("metadata", "0xb", &v1.to_string());
+        assert!(handle(&contract(), &[strict], &forged).is_err());
+    }
+
+    #[test]
+    fn links_are_stored_canonical() {
+        let data = r#"{"text": "read HTTPS://News.Example/a?utm_source=x&id=2"}"#;
+        let finals = handle(&contract(), &[], &transaction("metadata", "0xa", data)).unwrap();
+        assert_eq!(
+            body(&finals[0]),
+            r#"{"schema_version":2,"text":"read https://news.example/a?id=2","type":"post"}"#
+        );
+    }
+
+    #[test]
+    fn declared_media_sizes_are_recorded() {
+        let data = r#"{"image": "https://nftstorage.link/ipfs/bafy/a.png", "size_bytes": 2048, "dimensions": {"width": 640, "height": 480}}"#;
+        let finals = handle(&contract(), &[], &transaction("metadata", "0xa", data)).unwrap();
+        let media = finals.iter().find(|f| f.alias == Alias::Media).unwrap();
+        assert_eq!(
+            body(media),
+            r#"{"dimensions":{"height":480,"width":640},"gateways":["https://nftstorage.link/ipfs/","https://dweb.link/ipfs/","https://ipfs.io/ipfs/"],"size_bytes":2048,"uri":"ipfs://bafy/a.png"}"#
+        );
+    }
+
+    #[test]
+    fn post_rejections_carry_codes() {
+        let err = handle(&contract(), &[], &transaction("metadata", "0xa", "nope")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[invalid_format] Data is not a valid format."
+        );
+    }
+
+    fn record(alias: &str, cid: &str, content: &str) -> Metadata {
+        crate::storage::ipfs::test_store::put(cid, content);
+        Metadata {
+            alias: alias.to_string(),
+            public_key: "0xowner".to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wall_filter_rejects_or_masks() {
+        let filter = record(
+            filter::FILTER_ALIAS,
+            "exec-filter",
+            r#"{"words": ["griefer"]}"#,
+        );
+        let data = r#"{"text": "what a GRIEFER"}"#;
+
+        let err = handle(
+            &contract(),
+            std::slice::from_ref(&filter),
+            &transaction("post", "0xa", data),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("[profanity]"));
+
+        let mask = record(
+            config::CONFIG_ALIAS,
+            "exec-mask",
+            r#"{"rules": {"profanity_action": "mask"}}"#,
+        );
+        let finals = handle(
+            &contract(),
+            &[filter, mask],
+            &transaction("post", "0xa", data),
+        )
+        .unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored["text"], "what a *******");
+        assert_eq!(stored["filtered"], true);
+    }
+
+    #[test]
+    fn walls_can_switch_off_optional_methods() {
+        let off = record(
+            config::CONFIG_ALIAS,
+            "exec-features",
+            r#"{"disabled_features": ["tipping"]}"#,
+        );
+        let data = format!(
+            r#"{{"post": "bafy", "tx_hash": "0x{}", "amount": "1", "token": "native"}}"#,
+            "ab".repeat(32)
+        );
+        let err = handle(&contract(), &[off], &transaction("tip", "0xa", &data)).unwrap_err();
+        assert_eq!(err.to_string(), "The tip method is off on this wall.");
+    }
+
+    #[test]
+    fn trusted_authors_skip_the_filter_but_not_the_schema() {
+        let filter = record(
+            filter::FILTER_ALIAS,
+            "exec-trusted-filter",
+            r#"{"words": ["griefer"]}"#,
+        );
+        let trusted = record(
+            config::CONFIG_ALIAS,
+            "exec-trusted",
+            r#"{"trusted": ["0xA"], "rules": {"profanity_action": "mask"}}"#,
+        );
+        let wall = [filter, trusted];
+
+        let data = r#"{"text": "what a griefer"}"#;
+        let finals = handle(&contract(), &wall, &transaction("post", "0xa", data)).unwrap();
+        assert_eq!(
+            body(&finals[0]),
+            r#"{"schema_version":2,"text":"what a griefer","type":"post"}"#
+        );
+        let finals = handle(&contract(), &wall, &transaction("post", "0xb", data)).unwrap();
+        assert!(body(&finals[0]).contains(r#""filtered":true"#));
+
+        let data = r#"{"text": "what a griefer", "geo": {"lat": 128, "lon": 0}}"#;
+        let err = handle(&contract(), &wall, &transaction("post", "0xa", data)).unwrap_err();
+        assert!(err.to_string().starts_with("[schema]"));
+    }
+
+    #[test]
+    fn compressed_walls_keep_gzip_bodies() {
+        let data = r#"{"encoding": "gzip+base64", "data": "H4sIAAAAAAACA6tWKk7OSM1NjC9LLSrOzM9TslIw0lFQKqksSAUylQryi0uUQPzUihIQPz1XAQkp1QIAdTXhxD8AAAA="}"#;
+        let finals = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap();
+        assert_eq!(
+            body(&finals[0]),
+            r#"{"schema_version":2,"text":"gm gm gm gm gm","type":"post"}"#
+        );
+
+        let mode = [record(
+            config::CONFIG_ALIAS,
+            "exec-compressed",
+            r#"{"compressed_storage": "compressed"}"#,
+        )];
+        let finals = handle(&contract(), &mode, &transaction("post", "0xa", data)).unwrap();
+        let stored: CompressedBody = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored.encoding, GZIP_ENCODING);
+        assert_eq!(
+            stored.content_hash,
+            keccak_hex(
+                r#"{"schema_version": 2, "type": "post", "text": "gm gm gm gm gm"}"#.as_bytes()
+            )
+        );
+
+        let post = record("", "exec-compressed-post", &finals[0].content);
+        assert_eq!(
+            crate::storage::read_content(&post).unwrap(),
+            r#"{"schema_version": 2, "type": "post", "text": "gm gm gm gm gm"}"#
+        );
+
+        // a legacy body has to be rewritten, so it is stored inflated
+        let legacy = r#"{"encoding": "gzip+base64", "data": "H4sIAAAAAAACA6tWKkmtKFGyUlBKz1VAQkq1AFlqpwsaAAAA"}"#;
+        let finals = handle(&contract(), &mode, &transaction("post", "0xa", legacy)).unwrap();
+        assert_eq!(
+            body(&finals[0]),
+            r#"{"schema_version":2,"text":"gm gm gm gm gm","type":"post"}"#
+        );
+    }
+
+    #[test]
+    fn search_index_uses_the_masked_text() {
+        let filter = record(
+            filter::FILTER_ALIAS,
+            "exec-search-filter",
+            r#"{"words": ["griefer"]}"#,
+        );
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-search-config",
+            r#"{"search_index": true, "rules": {"profanity_action": "mask"}}"#,
+        );
+        let data = r#"{"text": "The griefer raided the Wall"}"#;
+        let finals = handle(
+            &contract(),
+            &[filter, config],
+            &transaction("post", "0xa", data),
+        )
+        .unwrap();
+        let index = finals
+            .iter()
+            .find(|f| f.alias == SEARCH_INDEX_ALIAS)
+            .unwrap();
+        assert_eq!(body(index), r#"["raided","wall"]"#);
+    }
+
+    #[test]
+    fn geo_is_stored_truncated() {
+        let data = r#"{"text": "here", "geo": {"lat": 38.7223, "lon": -9.1393}}"#;
+        let finals = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(
+            stored["geo"],
+            serde_json::json!({ "lat": 38.72, "lon": -9.13 })
+        );
+        let geo = finals.iter().find(|f| f.alias == "geo").unwrap();
+        assert_eq!(body(geo), r#"{"lat":38.72,"lon":-9.13}"#);
+
+        let data = r#"{"text": "somewhere", "geo": {"lat": 128, "lon": 0}}"#;
+        let err = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap_err();
+        assert!(err.to_string().starts_with("[schema]"));
+    }
+
+    #[test]
+    fn templates_require_fields_and_add_tags() {
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-templates",
+            r#"{"templates": {"announcement": {"required": ["title"], "tags": ["news"], "attributes": {"kind": "announcement"}}}}"#,
+        );
+        let wall = std::slice::from_ref(&config);
+
+        let data = r#"{"text": "v2 is out", "template": "announcement"}"#;
+        let err = handle(&contract(), wall, &transaction("post", "0xa", data)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[template] Template announcement requires the title field."
+        );
+        let data = r#"{"text": "v2 is out", "template": "nope"}"#;
+        assert!(handle(&contract(), wall, &transaction("post", "0xa", data)).is_err());
+
+        let data = r#"{"text": "v2 is out", "title": "v2", "template": "announcement", "tags": ["release"]}"#;
+        let finals = handle(&contract(), wall, &transaction("post", "0xa", data)).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored["tags"], serde_json::json!(["release", "news"]));
+        let pinned = finals.iter().find(|f| f.alias == "template").unwrap();
+        assert_eq!(
+            body(pinned),
+            r#"{"attributes":{"kind":"announcement"},"template":"announcement"}"#
+        );
+    }
+
+    #[test]
+    fn events_are_stored_in_the_wall_timezone() {
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-locale",
+            r#"{"locale": {"timezone": "Europe/Lisbon", "date_format": "dmy"}}"#,
+        );
+        let wall = std::slice::from_ref(&config);
+
+        let data = r#"{"type": "event", "text": "meetup", "start": 100, "end": 200, "location": "Lisbon"}"#;
+        let finals = handle(&contract(), wall, &transaction("post", "0xa", data)).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored["timezone"], "Europe/Lisbon");
+
+        let data = r#"{"type": "event", "text": "call", "start": 100, "end": 200, "timezone": "UTC", "url": "https://meet.example"}"#;
+        let finals = handle(&contract(), wall, &transaction("post", "0xa", data)).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored["timezone"], "UTC");
+    }
+
+    #[test]
+    fn categories_must_be_boards() {
+        let data = r#"{"text": "wen", "category": "memes"}"#;
+        let err = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap_err();
+        assert!(err.to_string().starts_with("[category]"));
+
+        let data = r#"{"text": "gm", "category": "general"}"#;
+        let finals = handle(&contract(), &[], &transaction("post", "0xa", data)).unwrap();
+        let board = finals
+            .iter()
+            .find(|f| f.alias == "w3wall.board.general")
+            .unwrap();
+        assert_eq!(board.public_key, "0xowner");
+    }
+
+    #[test]
+    fn daily_quota_spares_moderators() {
+        let config = record(
+            config::CONFIG_ALIAS,
+            "exec-quota-config",
+            r#"{"moderators": ["0xmod"], "rules": {"daily_post_quota": 1}}"#,
+        );
+        // posts of today, the transactions below run at time 0
+        let posted = |author: &str, cid: &str| Metadata {
+            public_key: author.to_string(),
+            ..record(
+                "",
+                cid,
+                r#"{"v":1,"type":"post","author":"","created_at":5,"body":{"text":"gm"},"refs":[]}"#,
+            )
+        };
+        let wall = [
+            config,
+            posted("0xA", "exec-quota-a"),
+            posted("0xmod", "exec-quota-mod"),
+        ];
+        let data = r#"{"text": "again"}"#;
+
+        let err = handle(&contract(), &wall, &transaction("post", "0xa", data)).unwrap_err();
+        assert!(err.to_string().starts_with("[quota]"));
+        assert!(handle(&contract(), &wall, &transaction("post", "0xb", data)).is_ok());
+        assert!(handle(&contract(), &wall, &transaction("post", "0xmod", data)).is_ok());
+    }
+
+    #[test]
+    fn init_is_owner_only_and_fills_owner() {
+        assert!(handle(&contract(), &[], &transaction("init", "0xa", "")).is_err());
+
+        let finals = handle(&contract(), &[], &transaction("init", "0xowner", "")).unwrap();
+        assert_eq!(finals[0].alias, config::CONFIG_ALIAS);
+        let stored: WallConfig = serde_json::from_str(&body(&finals[0])).unwrap();
+        assert_eq!(stored.owner, "0xowner");
+
+        let data = r#"{"reactions": ["👍", "🔥🔥"]}"#;
+        let err = handle(&contract(), &[], &transaction("init", "0xowner", data)).unwrap_err();
+        assert_eq!(err.to_string(), "Reaction 🔥🔥 is not a single emoji.");
+    }
+
+    #[test]
+    fn init_only_once() {
+        let existing = record(config::CONFIG_ALIAS, "exec-initialized", "{}");
+        let err = handle(
+            &contract(),
+            &[existing],
+            &transaction("init", "0xowner", ""),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Wall is already initialized.");
+    }
+}
@@ -0,0 +1,349 @@
+use crate::crypto::hash::keccak256;
+use crate::crypto::merkle::merkle_root;
+use crate::crypto::verify_personal_signature;
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::handlers::import::import_alias;
+use crate::metadata::timestamp_sec;
+use crate::storage::{find_record, loose_flag, read_content};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static EXPORT_ALIAS: &str = "w3wall.export";
+
+/**
+ * One record of the wall as it is stored, `hash` is the keccak256 of its content without the
+ * envelope
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExportEntry {
+    pub alias: String,
+    pub public_key: String,
+    pub cid: String,
+    pub hash: String,
+}
+
+/**
+ * Everything a backup needs to find and check the wall's records. Entries are sorted by alias,
+ * key and cid so the same wall always gives the same manifest, `root` is the merkle root over
+ * their hashes in that order.
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub wall: String,
+    pub exported_at: u64,
+    pub config: WallConfig,
+    pub records: Vec<ExportEntry>,
+    pub root: String,
+}
+
+/**
+ * What the exporting wall's owner signs so another wall can restore the bundle
+ */
+pub fn bundle_message(manifest: &ExportManifest) -> String {
+    format!("w3wall:export:{}:{}", manifest.wall, manifest.root)
+}
+
+/**
+ * A record of the bundle as the importer hands it over, it must hash to its manifest entry
+ */
+#[derive(Deserialize)]
+struct BundleRecord {
+    alias: String,
+    public_key: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct Bundle {
+    manifest: ExportManifest,
+    signature: String,
+    records: Vec<BundleRecord>,
+}
+
+/**
+ * Builds the manifest of every record on the wall but earlier exports. Owners only, and a
+ * record that can't be read fails the export rather than giving an incomplete backup.
+ */
+pub fn export(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can export the wall.",
+        ));
+    }
+
+    let mut records = vec![];
+    let mut leaves = vec![];
+    let mut stored: Vec<&Metadata> = metadatas
+        .iter()
+        .filter(|m| m.alias != EXPORT_ALIAS)
+        .collect();
+    stored.sort_by(|a, b| {
+        (&a.alias, a.public_key.to_lowercase(), &a.cid).cmp(&(
+            &b.alias,
+            b.public_key.to_lowercase(),
+            &b.cid,
+        ))
+    });
+    for m in stored {
+        let content = read_content(m)
+            .ok_or_else(|| W3WallError::Rejected(format!("Record {} can't be read.", m.cid)))?;
+        let hash = keccak256(content);
+        leaves.push(hash);
+        records.push(ExportEntry {
+            alias: m.alias.clone(),
+            public_key: m.public_key.to_lowercase(),
+            cid: m.cid.clone(),
+            hash: format!("0x{}", hex::encode(hash)),
+        });
+    }
+
+    let manifest = ExportManifest {
+        wall: transaction.data_key.to_lowercase(),
+        exported_at: timestamp_sec(transaction.timestamp),
+        config: config.clone(),
+        records,
+        root: format!("0x{}", hex::encode(merkle_root(&leaves))),
+    };
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: EXPORT_ALIAS.to_string(),
+        content: serde_json::to_string(&manifest)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+/**
+ * Re-emits a whole exported wall on this one, for the `w3wall` source of `import`. The bundle
+ * must be signed by the owner of the exported wall, its root must cover its entries and every
+ * record must match an entry, so the importer can neither forge nor leave out records. Wall
+ * records move to this wall's key.
+ */
+pub fn restore(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    bundle: serde_json::Value,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can restore a wall.",
+        ));
+    }
+    let bundle: Bundle = serde_json::from_value(bundle)?;
+    let manifest = &bundle.manifest;
+    if manifest.wall.eq_ignore_ascii_case(&transaction.data_key) {
+        return Err(W3WallError::rejected(
+            "A wall cannot be restored onto itself.",
+        ));
+    }
+
+    let mut leaves = Vec::with_capacity(manifest.records.len());
+    for entry in &manifest.records {
+        let hash = hex::decode(entry.hash.trim_start_matches("0x"))?;
+        let leaf: [u8; 32] = hash
+            .try_into()
+            .map_err(|_| W3WallError::rejected("Bundle entry hash is not valid."))?;
+        leaves.push(leaf);
+    }
+    if format!("0x{}", hex::encode(merkle_root(&leaves))) != manifest.root {
+        return Err(W3WallError::rejected(
+            "Bundle root does not cover its entries.",
+        ));
+    }
+    if !verify_personal_signature(
+        &manifest.config.owner,
+        &bundle_message(manifest),
+        &bundle.signature,
+    ) {
+        return Err(W3WallError::rejected(
+            "Bundle is not signed by the owner of the exported wall.",
+        ));
+    }
+
+    let marker = import_alias("w3wall", &manifest.root);
+    if find_record(metadatas, &marker, &contract.public_key).is_some() {
+        return Err(W3WallError::rejected("This bundle was already restored."));
+    }
+    if bundle.records.len() != manifest.records.len() {
+        return Err(W3WallError::rejected(
+            "Bundle records do not match its manifest.",
+        ));
+    }
+
+    let mut finals = vec![];
+    for (record, entry) in bundle.records.into_iter().zip(&manifest.records) {
+        let hash = format!("0x{}", hex::encode(keccak256(&record.content)));
+        if record.alias != entry.alias
+            || !record.public_key.eq_ignore_ascii_case(&entry.public_key)
+            || hash != entry.hash
+        {
+            return Err(W3WallError::Rejected(format!(
+                "Bundle record {} does not match its manifest.",
+                entry.cid
+            )));
+        }
+        let loose = loose_flag(&record.alias);
+        finals.push(FinalMetadata {
+            public_key: match loose {
+                0 => contract.public_key.clone(),
+                _ => entry.public_key.clone(),
+            },
+            alias: record.alias,
+            content: record.content,
+            loose,
+            version: transaction.version.clone(),
+        });
+    }
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: marker,
+        content: serde_json::json!({ "importer": transaction.public_key, "wall": manifest.wall })
+            .to_string(),
+        loose: 1,
+        version: "".to_string(),
+    });
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{keccak_hex, test_wallet};
+    use crate::storage::ipfs::test_store;
+
+    fn stored(alias: &str, public_key: &str, cid: &str, content: &str) -> Metadata {
+        test_store::put(cid, content);
+        Metadata {
+            alias: alias.to_string(),
+            public_key: public_key.to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn manifests_are_sorted_and_hash_every_record() {
+        let contract = MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let transaction = Transaction {
+            public_key: "0xowner".to_string(),
+            data_key: "0xWALL".to_string(),
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        };
+        let mut metadatas = vec![
+            stored("w3wall.profile", "0xB", "export-2", r#"{"name":"b"}"#),
+            stored("", "0xa", "export-1", r#"{"text":"gm"}"#),
+            stored(EXPORT_ALIAS, "0xowner", "export-0", "{}"),
+        ];
+
+        let finals = export(&contract, &metadatas, &transaction, &config).unwrap();
+        assert_eq!(finals[0].alias, EXPORT_ALIAS);
+        let manifest: ExportManifest = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(manifest.wall, "0xwall");
+        assert_eq!(manifest.config.owner, "0xowner");
+        let aliases: Vec<&str> = manifest.records.iter().map(|r| r.alias.as_str()).collect();
+        assert_eq!(aliases, ["", "w3wall.profile"]);
+        assert_eq!(manifest.records[0].hash, keccak_hex(br#"{"text":"gm"}"#));
+        assert_eq!(manifest.records[1].public_key, "0xb");
+
+        metadatas.reverse();
+        let again = export(&contract, &metadatas, &transaction, &config).unwrap();
+        assert_eq!(again[0].content, finals[0].content);
+
+        let stranger = Transaction {
+            public_key: "0xother".to_string(),
+            ..transaction.clone()
+        };
+        assert!(export(&contract, &metadatas, &stranger, &config).is_err());
+        metadatas.push(Metadata {
+            cid: "export-missing".to_string(),
+            ..Default::default()
+        });
+        assert!(export(&contract, &metadatas, &transaction, &config).is_err());
+    }
+
+    #[test]
+    fn bundles_restore_only_when_signed_and_complete() {
+        let exported = WallConfig {
+            owner: test_wallet::address(3),
+            ..Default::default()
+        };
+        let transaction = Transaction {
+            public_key: exported.owner.clone(),
+            data_key: "0xold".to_string(),
+            ..Default::default()
+        };
+        let metadatas = [
+            stored("", "0xa", "restore-1", r#"{"text":"gm"}"#),
+            stored("config", "0xoldcontract", "restore-2", r#"{"title":"w"}"#),
+        ];
+        let finals = export(
+            &MetaContract::default(),
+            &metadatas,
+            &transaction,
+            &exported,
+        )
+        .unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&finals[0].content).unwrap();
+        let records = serde_json::json!([
+            { "alias": "", "public_key": "0xa", "content": r#"{"text":"gm"}"# },
+            { "alias": "config", "public_key": "0xoldcontract", "content": r#"{"title":"w"}"# }
+        ]);
+        let message = format!("w3wall:export:0xold:{}", manifest["root"].as_str().unwrap());
+
+        let contract = MetaContract {
+            public_key: "0xnew".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xnew".to_string(),
+            ..Default::default()
+        };
+        let importer = Transaction {
+            public_key: "0xnew".to_string(),
+            data_key: "0xfresh".to_string(),
+            ..Default::default()
+        };
+        let bundle = |signer: u64, records: &serde_json::Value| {
+            serde_json::json!({
+                "manifest": manifest,
+                "signature": test_wallet::sign(signer, &message),
+                "records": records
+            })
+        };
+
+        let restored = restore(&contract, &[], &importer, &config, bundle(3, &records)).unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored[0].public_key, "0xa");
+        assert_eq!(restored[1].public_key, "0xnew");
+        assert_eq!(restored[1].loose, 0);
+
+        assert_eq!(
+            restore(&contract, &[], &importer, &config, bundle(4, &records))
+                .unwrap_err()
+                .to_string(),
+            "Bundle is not signed by the owner of the exported wall."
+        );
+        let mut tampered = records.clone();
+        tampered[0]["content"] = r#"{"text":"gn"}"#.into();
+        assert!(restore(&contract, &[], &importer, &config, bundle(3, &tampered)).is_err());
+        let partial = serde_json::json!([records[0]]);
+        assert!(restore(&contract, &[], &importer, &config, bundle(3, &partial)).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::crypto::eth_address::parse_address;
+use crate::crypto::verify_personal_signature;
+use crate::data::WallConfig;
+use crate::defaults::{DEFAULT_MAX_RELATED_LABEL_LENGTH, DEFAULT_MAX_RELATED_WALLS};
+use crate::error::W3WallError;
+use crate::metadata::timestamp_sec;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::reference::is_data_key;
+use serde::{Deserialize, Serialize};
+
+pub static RELATED_ALIAS: &str = "w3wall.related";
+
+/**
+ * Another wall this one links to, with its owner's signed confirmation that the link is mutual
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RelatedWall {
+    pub data_key: String,
+    pub label: String,
+    pub owner: String,
+    pub signature: String,
+    pub related_at: u64,
+}
+
+/**
+ * Walls a client shows in the wall's navigation bar, in the order they were added
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct RelatedWalls {
+    pub walls: Vec<RelatedWall>,
+}
+
+#[derive(Deserialize)]
+struct RelateRequest {
+    data_key: String,
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    owner: String,
+    #[serde(default)]
+    signature: String,
+}
+
+fn normalize_data_key(data_key: &str) -> String {
+    data_key.trim_start_matches("0x").to_lowercase()
+}
+
+/**
+ * What the other wall's owner signs to confirm a link from `from` to their wall `to`
+ */
+pub fn relation_message(from: &str, to: &str) -> String {
+    format!(
+        "w3wall:relate:{}:{}",
+        normalize_data_key(from),
+        normalize_data_key(to)
+    )
+}
+
+pub fn load_related(contract: &MetaContract, metadatas: &[Metadata]) -> RelatedWalls {
+    read_record(metadatas, RELATED_ALIAS, &contract.public_key).unwrap_or_default()
+}
+
+/**
+ * Links (`relate_wall`) or unlinks (`unrelate_wall`) another wall. Owners only, and a link needs
+ * the other wall's owner to have signed relation_message(their wall, this wall). Clients check
+ * that `owner` does own the other wall before showing it.
+ */
+pub fn set_related(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected("Only wall owners can relate walls."));
+    }
+
+    let request: RelateRequest = serde_json::from_str(&transaction.data)?;
+    if !is_data_key(&request.data_key) {
+        return Err(W3WallError::rejected("Related data_key is not valid."));
+    }
+    let data_key = normalize_data_key(&request.data_key);
+    if data_key == normalize_data_key(&transaction.data_key) {
+        return Err(W3WallError::rejected("A wall cannot relate to itself."));
+    }
+
+    let mut related = load_related(contract, metadatas);
+    let exists = related.walls.iter().any(|w| w.data_key == data_key);
+    match (transaction.method.as_str(), exists) {
+        ("relate_wall", true) => return Err(W3WallError::rejected("Wall is already related.")),
+        ("relate_wall", false) => {
+            if related.walls.len() >= DEFAULT_MAX_RELATED_WALLS {
+                return Err(W3WallError::Rejected(format!(
+                    "A wall relates to at most {} walls.",
+                    DEFAULT_MAX_RELATED_WALLS
+                )));
+            }
+            let label = request.label.trim();
+            if label.is_empty() || label.chars().count() > DEFAULT_MAX_RELATED_LABEL_LENGTH {
+                return Err(W3WallError::Rejected(format!(
+                    "Related wall label must be 1 to {} characters.",
+                    DEFAULT_MAX_RELATED_LABEL_LENGTH
+                )));
+            }
+            let owner = parse_address(&request.owner).map_err(|e| {
+                W3WallError::Rejected(format!("Related wall owner is refused: {}", e))
+            })?;
+            let message = relation_message(&data_key, &transaction.data_key);
+            if !verify_personal_signature(&owner, &message, &request.signature) {
+                return Err(W3WallError::rejected(
+                    "The related wall's owner has not confirmed the relation.",
+                ));
+            }
+            related.walls.push(RelatedWall {
+                data_key,
+                label: label.to_string(),
+                owner,
+                signature: request.signature,
+                related_at: timestamp_sec(transaction.timestamp),
+            });
+        }
+        (_, true) => related.walls.retain(|w| w.data_key != data_key),
+        (_, false) => return Err(W3WallError::rejected("Wall is not related.")),
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: RELATED_ALIAS.to_string(),
+        content: serde_json::to_string(&related)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::test_wallet;
+
+    fn transaction(method: &str, data: serde_json::Value) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: "0xowner".to_string(),
+            data_key: "aa".repeat(32),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn relations_need_the_other_owner_to_confirm() {
+        let contract = MetaContract::default();
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let other = format!("0x{}", "BB".repeat(32));
+        let relate = |signer: u64| {
+            let signature = test_wallet::sign(signer, &relation_message(&other, &"aa".repeat(32)));
+            serde_json::json!({
+                "data_key": other,
+                "label": "Sister wall",
+                "owner": test_wallet::address(6),
+                "signature": signature
+            })
+        };
+
+        assert_eq!(
+            set_related(
+                &contract,
+                &[],
+                &transaction("relate_wall", relate(7)),
+                &config
+            )
+            .unwrap_err()
+            .to_string(),
+            "The related wall's owner has not confirmed the relation."
+        );
+        let finals = set_related(
+            &contract,
+            &[],
+            &transaction("relate_wall", relate(6)),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, RELATED_ALIAS);
+        let related: RelatedWalls = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(related.walls[0].data_key, "bb".repeat(32));
+        assert_eq!(
+            related.walls[0].owner,
+            test_wallet::address(6).to_lowercase()
+        );
+
+        let own = serde_json::json!({ "data_key": "aa".repeat(32) });
+        assert!(set_related(&contract, &[], &transaction("unrelate_wall", own), &config).is_err());
+        let stranger = Transaction {
+            public_key: "0xother".to_string(),
+            ..transaction("relate_wall", relate(6))
+        };
+        assert!(set_related(&contract, &[], &stranger, &config).is_err());
+    }
+}
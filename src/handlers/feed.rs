@@ -0,0 +1,109 @@
+use crate::defaults::{DEFAULT_FEED_SIZE, MAX_FEED_SIZE};
+use crate::error::W3WallError;
+use crate::handlers::redaction::redaction_alias;
+use crate::metadata::feed::{render_rss, FeedItem, FEED_ALIAS};
+use crate::storage::alias::Alias;
+use crate::storage::moderation::load_moderation;
+use crate::storage::{find_record, read_content};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::Deserialize;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FeedRequest {
+    limit: Option<usize>,
+}
+
+/**
+ * Renders the latest posts into the `feed` alias. Private, removed and redacted posts are left
+ * out, metadatas are taken to be in the order they were written.
+ */
+pub fn refresh_feed(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: FeedRequest = if transaction.data.trim().is_empty() {
+        FeedRequest::default()
+    } else {
+        serde_json::from_str(&transaction.data)?
+    };
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_FEED_SIZE)
+        .min(MAX_FEED_SIZE);
+    let state = load_moderation(contract, metadatas)?.unwrap_or_default();
+
+    let items: Vec<FeedItem> = metadatas
+        .iter()
+        .rev()
+        .filter(|m| m.alias == Alias::Post && !state.removed.contains(&m.cid))
+        .filter(|m| {
+            find_record(metadatas, &redaction_alias(&m.cid), &contract.public_key).is_none()
+        })
+        .filter_map(|m| {
+            let payload: serde_json::Value = serde_json::from_str(&read_content(m)?).ok()?;
+            if payload["visibility"].as_str() == Some("private") {
+                return None;
+            }
+            Some(FeedItem {
+                cid: m.cid.clone(),
+                author: m.public_key.clone(),
+                text: payload["text"].as_str()?.to_string(),
+            })
+        })
+        .take(limit)
+        .collect();
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: FEED_ALIAS.to_string(),
+        content: render_rss(&transaction.data_key, &items),
+        loose: 1,
+        version: "".to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn post(cid: &str, content: &str) -> Metadata {
+        test_store::put(cid, content);
+        Metadata {
+            public_key: "0xabc".to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn feed_lists_latest_public_posts() {
+        let metadatas = vec![
+            post("feed-old", r#"{"text": "first"}"#),
+            post(
+                "feed-secret",
+                r#"{"visibility": "private", "content": "x"}"#,
+            ),
+            post("feed-new", r#"{"text": "second"}"#),
+        ];
+        let transaction = Transaction {
+            method: "refresh_feed".to_string(),
+            data_key: "wall".to_string(),
+            data: r#"{"limit": 1}"#.to_string(),
+            ..Default::default()
+        };
+        let finals = refresh_feed(&MetaContract::default(), &metadatas, &transaction).unwrap();
+        assert_eq!(finals[0].alias, FEED_ALIAS);
+        assert!(finals[0].content.contains("<title>second</title>"));
+        assert!(!finals[0].content.contains("first"));
+
+        let transaction = Transaction {
+            data: "".to_string(),
+            ..transaction
+        };
+        let finals = refresh_feed(&MetaContract::default(), &metadatas, &transaction).unwrap();
+        assert_eq!(finals[0].content.matches("<item>").count(), 2);
+    }
+}
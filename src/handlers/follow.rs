@@ -0,0 +1,104 @@
+use crate::defaults::DEFAULT_MAX_FOLLOWING;
+use crate::error::W3WallError;
+use crate::storage::following::{load_following, FOLLOWING_ALIAS};
+use crate::types::{FinalMetadata, Metadata, Transaction};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct FollowRequest {
+    author: Option<String>,
+    thread: Option<String>,
+}
+
+/**
+ * `follow` and `unfollow` of one author key or thread data_key. The list is the sender's own
+ * record, kept sorted and without repeats.
+ */
+pub fn follow(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: FollowRequest = serde_json::from_str(&transaction.data)?;
+    let mut list = load_following(metadatas, &transaction.public_key);
+
+    let (entries, target) = match (request.author, request.thread) {
+        (Some(author), None) => (&mut list.authors, author.trim().to_lowercase()),
+        (None, Some(thread)) => (&mut list.threads, thread.trim().to_string()),
+        _ => {
+            return Err(W3WallError::rejected(
+                "Follow either an author or a thread.",
+            ))
+        }
+    };
+    if target.is_empty() || target.eq_ignore_ascii_case(&transaction.public_key) {
+        return Err(W3WallError::rejected("Nothing to follow."));
+    }
+
+    if transaction.method == "unfollow" {
+        entries.retain(|e| e != &target);
+    } else if !entries.contains(&target) {
+        entries.push(target);
+        entries.sort();
+    }
+
+    if list.authors.len() + list.threads.len() > DEFAULT_MAX_FOLLOWING {
+        return Err(W3WallError::Rejected(format!(
+            "Following is limited to {} authors and threads.",
+            DEFAULT_MAX_FOLLOWING
+        )));
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: FOLLOWING_ALIAS.to_string(),
+        content: serde_json::to_string(&list)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn transaction(method: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: "0xme".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn follow_list_is_deduplicated() {
+        let finals = follow(&[], &transaction("follow", r#"{"author": "0xB"}"#)).unwrap();
+        assert_eq!(finals[0].public_key, "0xme");
+        assert_eq!(finals[0].content, r#"{"authors":["0xb"],"threads":[]}"#);
+
+        test_store::put("following-me", &finals[0].content);
+        let metadatas = vec![Metadata {
+            alias: FOLLOWING_ALIAS.to_string(),
+            public_key: "0xme".to_string(),
+            cid: "following-me".to_string(),
+            ..Default::default()
+        }];
+        let finals = follow(&metadatas, &transaction("follow", r#"{"author": "0xb"}"#)).unwrap();
+        assert_eq!(finals[0].content, r#"{"authors":["0xb"],"threads":[]}"#);
+        let finals = follow(
+            &metadatas,
+            &transaction("follow", r#"{"thread": "wall-1"}"#),
+        )
+        .unwrap();
+        assert_eq!(
+            finals[0].content,
+            r#"{"authors":["0xb"],"threads":["wall-1"]}"#
+        );
+        let finals = follow(&metadatas, &transaction("unfollow", r#"{"author": "0xB"}"#)).unwrap();
+        assert_eq!(finals[0].content, r#"{"authors":[],"threads":[]}"#);
+
+        assert!(follow(&[], &transaction("follow", r#"{"author": "0xme"}"#)).is_err());
+        assert!(follow(&[], &transaction("follow", "{}")).is_err());
+    }
+}
@@ -0,0 +1,747 @@
+use crate::crypto::{keccak_hex, verify_personal_signature};
+use crate::data::{Governance, WallConfig};
+use crate::error::W3WallError;
+use crate::handlers::execute::check_config;
+use crate::metadata::timestamp_sec;
+use crate::storage::config::CONFIG_ALIAS;
+use crate::storage::{load_record, read_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub static PROPOSAL_ALIAS_PREFIX: &str = "w3wall.proposal.";
+pub static VOTE_ALIAS_PREFIX: &str = "w3wall.vote.";
+
+pub fn proposal_alias(id: &str) -> String {
+    format!("{}{}", PROPOSAL_ALIAS_PREFIX, id)
+}
+
+/**
+ * A voter's record of their vote on the proposal `id`, voting again replaces it
+ */
+pub fn vote_alias(id: &str) -> String {
+    format!("{}{}", VOTE_ALIAS_PREFIX, id)
+}
+
+/**
+ * The governance attester's word that `voter` held `balance` (decimal, base units) at `block`
+ */
+#[derive(Deserialize, Debug)]
+pub struct Snapshot {
+    pub block: u64,
+    pub balance: String,
+    pub signature: String,
+}
+
+/**
+ * Bound to the wall and the governance token, so a snapshot signature can't be replayed on
+ * another wall voting on the same block
+ */
+pub fn snapshot_message(
+    data_key: &str,
+    token: &str,
+    voter: &str,
+    block: u64,
+    balance: &str,
+) -> String {
+    format!(
+        "w3wall:snapshot:{}:{}:{}:{}:{}",
+        data_key,
+        token.to_lowercase(),
+        voter.to_lowercase(),
+        block,
+        balance
+    )
+}
+
+/**
+ * Voting power the snapshot gives `voter` on the wall `data_key`
+ */
+pub fn check_snapshot(
+    snapshot: &Snapshot,
+    data_key: &str,
+    voter: &str,
+    governance: &Governance,
+) -> Result<U256, &'static str> {
+    if governance.attester.is_empty() {
+        return Err("This wall has no governance attester.");
+    }
+    let balance = U256::from_dec_str(&snapshot.balance)
+        .map_err(|_| "Snapshot balance must be an integer in base units.")?;
+    if !verify_personal_signature(
+        &governance.attester,
+        &snapshot_message(
+            data_key,
+            &governance.token,
+            voter,
+            snapshot.block,
+            &snapshot.balance,
+        ),
+        &snapshot.signature,
+    ) {
+        return Err("Snapshot is not signed by the governance attester.");
+    }
+    Ok(balance)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Choice {
+    For,
+    Against,
+    Abstain,
+}
+
+/**
+ * Voting power per choice as decimal strings, the sum of the latest vote of every voter
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct Tally {
+    #[serde(rename = "for")]
+    pub for_: String,
+    pub against: String,
+    pub abstain: String,
+    pub voters: u64,
+}
+
+impl Tally {
+    fn entry(&mut self, choice: Choice) -> &mut String {
+        match choice {
+            Choice::For => &mut self.for_,
+            Choice::Against => &mut self.against,
+            Choice::Abstain => &mut self.abstain,
+        }
+    }
+
+    pub fn power(&self, choice: Choice) -> U256 {
+        let total = match choice {
+            Choice::For => &self.for_,
+            Choice::Against => &self.against,
+            Choice::Abstain => &self.abstain,
+        };
+        U256::from_dec_str(total).unwrap_or_default()
+    }
+
+    fn add(&mut self, choice: Choice, power: U256) {
+        let total = self.power(choice).saturating_add(power);
+        *self.entry(choice) = total.to_string();
+    }
+
+    fn remove(&mut self, choice: Choice, power: U256) {
+        let total = self.power(choice).saturating_sub(power);
+        *self.entry(choice) = total.to_string();
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Open,
+    Passed,
+    Failed,
+}
+
+/**
+ * Stored by the contract, voting power is read at `snapshot_block` and taken until `closes_at`.
+ * The quorum is the wall's as of when the proposal opened. A rule change carries `changes`, a
+ * JSON merge patch of the wall config applied once the proposal passes.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Proposal {
+    pub id: String,
+    pub proposer: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+    pub snapshot_block: u64,
+    pub opens_at: u64,
+    pub closes_at: u64,
+    pub quorum: String,
+    pub tally: Tally,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied_at: Option<u64>,
+}
+
+impl Proposal {
+    /**
+     * Passed once voting closed with the quorum met, counting abstentions, and more power for
+     * than against
+     */
+    pub fn outcome(&self, now: u64) -> Outcome {
+        if now < self.closes_at {
+            return Outcome::Open;
+        }
+        let tally = &self.tally;
+        let total = tally
+            .power(Choice::For)
+            .saturating_add(tally.power(Choice::Against))
+            .saturating_add(tally.power(Choice::Abstain));
+        let quorum = U256::from_dec_str(&self.quorum).unwrap_or_default();
+        if total >= quorum && tally.power(Choice::For) > tally.power(Choice::Against) {
+            Outcome::Passed
+        } else {
+            Outcome::Failed
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Vote {
+    pub proposal: String,
+    pub choice: Choice,
+    pub balance: String,
+    pub at: u64,
+}
+
+#[derive(Deserialize)]
+struct ProposalRequest {
+    title: String,
+    #[serde(default)]
+    body: String,
+    snapshot: Snapshot,
+    changes: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct VoteRequest {
+    proposal: String,
+    choice: Choice,
+    snapshot: Snapshot,
+}
+
+#[derive(Deserialize)]
+struct ApplyRequest {
+    proposal: String,
+}
+
+pub fn load_proposal(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    id: &str,
+) -> Option<Proposal> {
+    read_record(metadatas, &proposal_alias(id), &contract.public_key)
+}
+
+fn proposal_record(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    proposal: &Proposal,
+) -> Result<FinalMetadata, W3WallError> {
+    Ok(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: proposal_alias(&proposal.id),
+        content: serde_json::to_string(proposal)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    })
+}
+
+/**
+ * RFC 7396 merge: objects merge field by field, null takes a field out, anything else replaces
+ */
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(fields) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (name, value) in fields {
+            if value.is_null() {
+                target.remove(name);
+            } else {
+                merge_patch(target.entry(name.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/**
+ * The wall config with a proposal's changes merged in, fields taken out fall back to their
+ * defaults. The result goes through the same checks as the config of `init`.
+ */
+fn patched_config(config: &WallConfig, changes: &Value) -> Result<WallConfig, W3WallError> {
+    if !changes.is_object() {
+        return Err(W3WallError::rejected(
+            "Proposal changes must be an object of config fields.",
+        ));
+    }
+    let mut patched = serde_json::to_value(config)?;
+    merge_patch(&mut patched, changes);
+    let invalid = |e: String| {
+        W3WallError::Rejected(format!(
+            "Proposal changes do not make a valid config: {}",
+            e
+        ))
+    };
+    let mut patched: WallConfig =
+        serde_json::from_value(patched).map_err(|e| invalid(e.to_string()))?;
+    check_config(&mut patched).map_err(|e| invalid(e.to_string()))?;
+    Ok(patched)
+}
+
+/**
+ * Any key holding tokens at the snapshot block can open a proposal, its block is the one every
+ * vote is counted at
+ */
+pub fn propose(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: ProposalRequest = serde_json::from_str(&transaction.data)?;
+    let balance = check_snapshot(
+        &request.snapshot,
+        &transaction.data_key,
+        &transaction.public_key,
+        &config.governance,
+    )
+    .map_err(W3WallError::rejected)?;
+    if balance.is_zero() {
+        return Err(W3WallError::rejected(
+            "Only token holders can open a proposal.",
+        ));
+    }
+
+    if let Some(changes) = &request.changes {
+        patched_config(config, changes)?;
+    }
+
+    let key = format!(
+        "{}:{}",
+        transaction.public_key.to_lowercase(),
+        transaction.data
+    );
+    let id = keccak_hex(key.as_bytes())[2..18].to_string();
+    if load_proposal(contract, metadatas, &id).is_some() {
+        return Err(W3WallError::rejected("This proposal is already open."));
+    }
+
+    let now = timestamp_sec(transaction.timestamp);
+    let proposal = Proposal {
+        id,
+        proposer: transaction.public_key.clone(),
+        title: request.title,
+        body: request.body,
+        snapshot_block: request.snapshot.block,
+        opens_at: now,
+        closes_at: now + config.governance.voting_hours * 60 * 60,
+        quorum: config.governance.quorum.clone(),
+        tally: Tally::default(),
+        changes: request.changes,
+        applied_at: None,
+    };
+    Ok(vec![proposal_record(contract, transaction, &proposal)?])
+}
+
+/**
+ * A vote weighted by the voter's balance at the proposal's snapshot block. The tally is updated
+ * in the same transaction, a voter changing their mind has their earlier vote taken out first.
+ */
+pub fn vote(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: VoteRequest = serde_json::from_str(&transaction.data)?;
+    let mut proposal = load_proposal(contract, metadatas, &request.proposal)
+        .ok_or_else(|| W3WallError::rejected("No such proposal on this wall."))?;
+    let now = timestamp_sec(transaction.timestamp);
+    if now >= proposal.closes_at {
+        return Err(W3WallError::rejected("Voting on this proposal is closed."));
+    }
+    if request.snapshot.block != proposal.snapshot_block {
+        return Err(W3WallError::Rejected(format!(
+            "Votes on this proposal need a snapshot of block {}.",
+            proposal.snapshot_block
+        )));
+    }
+    let power = check_snapshot(
+        &request.snapshot,
+        &transaction.data_key,
+        &transaction.public_key,
+        &config.governance,
+    )
+    .map_err(W3WallError::rejected)?;
+    if power.is_zero() {
+        return Err(W3WallError::rejected(
+            "No voting power at the snapshot block.",
+        ));
+    }
+
+    let alias = vote_alias(&proposal.id);
+    // a vote that can't be read must not count as a first one, the voter would be counted twice
+    match load_record::<Vote>(metadatas, &alias, &transaction.public_key)? {
+        Some(earlier) => proposal.tally.remove(
+            earlier.choice,
+            U256::from_dec_str(&earlier.balance).unwrap_or_default(),
+        ),
+        None => proposal.tally.voters += 1,
+    }
+    proposal.tally.add(request.choice, power);
+
+    let vote = Vote {
+        proposal: proposal.id.clone(),
+        choice: request.choice,
+        balance: power.to_string(),
+        at: now,
+    };
+    Ok(vec![
+        FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias,
+            content: serde_json::to_string(&vote)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        proposal_record(contract, transaction, &proposal)?,
+    ])
+}
+
+/**
+ * Rewrites the wall config with the changes of a passed proposal, once. Anyone may send it, the
+ * outcome of the vote is what authorizes the change.
+ */
+pub fn apply_config(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: ApplyRequest = serde_json::from_str(&transaction.data)?;
+    let mut proposal = load_proposal(contract, metadatas, &request.proposal)
+        .ok_or_else(|| W3WallError::rejected("No such proposal on this wall."))?;
+    let changes = proposal
+        .changes
+        .clone()
+        .ok_or_else(|| W3WallError::rejected("This proposal changes no rules."))?;
+    if proposal.applied_at.is_some() {
+        return Err(W3WallError::rejected("This proposal was already applied."));
+    }
+    let now = timestamp_sec(transaction.timestamp);
+    match proposal.outcome(now) {
+        Outcome::Open => {
+            return Err(W3WallError::rejected(
+                "Voting on this proposal is still open.",
+            ))
+        }
+        Outcome::Failed => return Err(W3WallError::rejected("This proposal did not pass.")),
+        Outcome::Passed => {}
+    }
+
+    let patched = patched_config(config, &changes)?;
+    proposal.applied_at = Some(now);
+    Ok(vec![
+        FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: CONFIG_ALIAS.to_string(),
+            content: serde_json::to_string(&patched)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        proposal_record(contract, transaction, &proposal)?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::test_wallet;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xwall".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn config() -> WallConfig {
+        WallConfig {
+            owner: "0xwall".to_string(),
+            governance: Governance {
+                attester: test_wallet::address(9),
+                token: "0xToken".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(voter: u64, block: u64, balance: &str) -> serde_json::Value {
+        let message = snapshot_message(
+            "wall",
+            "0xtoken",
+            &test_wallet::address(voter),
+            block,
+            balance,
+        );
+        serde_json::json!({
+            "block": block,
+            "balance": balance,
+            "signature": test_wallet::sign(9, &message)
+        })
+    }
+
+    fn transaction(method: &str, voter: u64, data: serde_json::Value) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: test_wallet::address(voter),
+            data_key: "wall".to_string(),
+            data: data.to_string(),
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        }
+    }
+
+    fn stored(record: &FinalMetadata, cid: &str) -> Metadata {
+        crate::storage::ipfs::test_store::put(cid, &record.content);
+        Metadata {
+            alias: record.alias.clone(),
+            public_key: record.public_key.clone(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn votes_are_weighted_by_the_attested_balance() {
+        let config = config();
+        let open = serde_json::json!({ "title": "Allow gifs", "snapshot": snapshot(1, 100, "5") });
+        let finals = propose(&contract(), &[], &transaction("proposal", 1, open), &config).unwrap();
+        let mut metadatas = vec![stored(&finals[0], "governance-proposal")];
+        let id = finals[0].alias.trim_start_matches(PROPOSAL_ALIAS_PREFIX);
+
+        let cast = |voter: u64, choice: &str, snapshot: serde_json::Value| {
+            let data =
+                serde_json::json!({ "proposal": id, "choice": choice, "snapshot": snapshot });
+            transaction("governance_vote", voter, data)
+        };
+        let finals = vote(
+            &contract(),
+            &metadatas,
+            &cast(2, "for", snapshot(2, 100, "30")),
+            &config,
+        )
+        .unwrap();
+        metadatas[0] = stored(&finals[1], "governance-first-vote");
+        metadatas.push(stored(&finals[0], "governance-vote"));
+
+        let finals = vote(
+            &contract(),
+            &metadatas,
+            &cast(3, "against", snapshot(3, 100, "20")),
+            &config,
+        )
+        .unwrap();
+        let proposal: Proposal = serde_json::from_str(&finals[1].content).unwrap();
+        assert_eq!(proposal.tally.for_, "30");
+        assert_eq!(proposal.tally.against, "20");
+        assert_eq!(proposal.tally.voters, 2);
+
+        let finals = vote(
+            &contract(),
+            &metadatas,
+            &cast(2, "abstain", snapshot(2, 100, "30")),
+            &config,
+        )
+        .unwrap();
+        let proposal: Proposal = serde_json::from_str(&finals[1].content).unwrap();
+        assert_eq!(proposal.tally.power(Choice::For), U256::zero());
+        assert_eq!(proposal.tally.abstain, "30");
+        assert_eq!(proposal.tally.voters, 1);
+
+        let error = |transaction: Transaction| {
+            vote(&contract(), &metadatas, &transaction, &config)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error(cast(3, "for", snapshot(3, 101, "20"))),
+            "Votes on this proposal need a snapshot of block 100."
+        );
+        assert_eq!(
+            error(cast(3, "for", snapshot(4, 100, "20"))),
+            "Snapshot is not signed by the governance attester."
+        );
+        let late = Transaction {
+            timestamp: 1_700_000_000 + 72 * 60 * 60,
+            ..cast(3, "for", snapshot(3, 100, "20"))
+        };
+        assert_eq!(error(late), "Voting on this proposal is closed.");
+
+        // the same signature on another wall
+        let elsewhere = Transaction {
+            data_key: "other-wall".to_string(),
+            ..cast(3, "for", snapshot(3, 100, "20"))
+        };
+        assert_eq!(
+            error(elsewhere),
+            "Snapshot is not signed by the governance attester."
+        );
+
+        // an earlier vote that can't be read is not a first vote
+        metadatas[1].cid = "governance-vote-not-stored".to_string();
+        let err = vote(
+            &contract(),
+            &metadatas,
+            &cast(2, "for", snapshot(2, 100, "30")),
+            &config,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "The {} record can't be read, try again later.",
+                vote_alias(id)
+            )
+        );
+    }
+
+    #[test]
+    fn passed_rule_changes_rewrite_the_config_once() {
+        let config = WallConfig {
+            moderators: vec!["0xmod".to_string()],
+            ..config()
+        };
+        let apply_at = |timestamp: u64, for_: &str, applied_at: Option<u64>| {
+            let proposal = Proposal {
+                id: "00000000000000aa".to_string(),
+                proposer: "0xa".to_string(),
+                title: "Shorter posts".to_string(),
+                body: "".to_string(),
+                snapshot_block: 1,
+                opens_at: 0,
+                closes_at: 1_700_000_000,
+                quorum: "10".to_string(),
+                tally: Tally {
+                    for_: for_.to_string(),
+                    against: "4".to_string(),
+                    abstain: "0".to_string(),
+                    voters: 2,
+                },
+                changes: Some(serde_json::json!({
+                    "moderators": null,
+                    "rules": { "max_text_length": 10 }
+                })),
+                applied_at,
+            };
+            let data = serde_json::json!({ "proposal": proposal.id });
+            let transaction = Transaction {
+                timestamp,
+                ..transaction("apply_config", 1, data)
+            };
+            let record = proposal_record(&contract(), &transaction, &proposal).unwrap();
+            let metadatas = [stored(&record, &format!("governance-apply-{}", for_))];
+            apply_config(&contract(), &metadatas, &transaction, &config)
+        };
+
+        let finals = apply_at(1_700_000_000, "8", None).unwrap();
+        assert_eq!(finals[0].alias, CONFIG_ALIAS);
+        let patched: WallConfig = serde_json::from_str(&finals[0].content).unwrap();
+        assert!(patched.moderators.is_empty());
+        assert_eq!(patched.rules.max_text_length, 10);
+        assert_eq!(
+            patched.rules.daily_post_quota,
+            config.rules.daily_post_quota
+        );
+        assert_eq!(patched.governance.attester, config.governance.attester);
+        let proposal: Proposal = serde_json::from_str(&finals[1].content).unwrap();
+        assert_eq!(proposal.applied_at, Some(1_700_000_000));
+
+        let error =
+            |outcome: Result<Vec<FinalMetadata>, W3WallError>| outcome.unwrap_err().to_string();
+        assert_eq!(
+            error(apply_at(1_700_000_000, "8", Some(1))),
+            "This proposal was already applied."
+        );
+        assert_eq!(
+            error(apply_at(1_699_999_999, "8", None)),
+            "Voting on this proposal is still open."
+        );
+        assert_eq!(
+            error(apply_at(1_700_000_000, "5", None)),
+            "This proposal did not pass."
+        );
+        assert_eq!(
+            error(apply_at(1_700_000_000, "4", None)),
+            "This proposal did not pass."
+        );
+
+        let data = serde_json::json!({
+            "title": "t",
+            "snapshot": snapshot(1, 7, "1"),
+            "changes": { "rules": { "max_text_length": "long" } }
+        });
+        assert!(error(propose(
+            &contract(),
+            &[],
+            &transaction("proposal", 1, data),
+            &config
+        ))
+        .starts_with("Proposal changes do not make a valid config:"));
+
+        // changes are held to the same checks as the config of init
+        for (changes, reason) in [
+            (
+                serde_json::json!({ "owner_threshold": 2 }),
+                "Owner threshold 2 is more than the 1 owners.",
+            ),
+            (
+                serde_json::json!({ "owner": "" }),
+                "The wall needs an owner.",
+            ),
+            (
+                serde_json::json!({ "moderators": ["0xmod"] }),
+                "Moderator 0xmod is refused:",
+            ),
+        ] {
+            let data = serde_json::json!({
+                "title": "t",
+                "snapshot": snapshot(1, 7, "1"),
+                "changes": changes
+            });
+            let err = error(propose(
+                &contract(),
+                &[],
+                &transaction("proposal", 1, data),
+                &config,
+            ));
+            assert!(
+                err.starts_with(&format!(
+                    "Proposal changes do not make a valid config: {}",
+                    reason
+                )),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn proposals_need_an_attester_and_a_holder() {
+        let open = |balance: &str| {
+            let data = serde_json::json!({ "title": "t", "snapshot": snapshot(1, 7, balance) });
+            transaction("proposal", 1, data)
+        };
+        assert_eq!(
+            propose(&contract(), &[], &open("1"), &WallConfig::default())
+                .unwrap_err()
+                .to_string(),
+            "This wall has no governance attester."
+        );
+        assert_eq!(
+            propose(&contract(), &[], &open("0"), &config())
+                .unwrap_err()
+                .to_string(),
+            "Only token holders can open a proposal."
+        );
+    }
+}
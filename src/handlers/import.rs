@@ -0,0 +1,406 @@
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::handlers::export;
+use crate::metadata::parse_iso8601;
+use crate::moderation::WordFilter;
+use crate::storage::alias::Alias;
+use crate::storage::find_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::rules::{LengthValidator, ProfanityValidator, SpamValidator};
+use crate::validation::{self, PostContext, ValidationError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub static IMPORT_ALIAS_PREFIX: &str = "w3wall.import.";
+
+/**
+ * Farcaster timestamps count seconds from 2021-01-01T00:00:00Z
+ */
+const FARCASTER_EPOCH: u64 = 1_609_459_200;
+
+pub fn import_alias(source: &str, id: &str) -> String {
+    format!("{}{}.{}", IMPORT_ALIAS_PREFIX, source, id.to_lowercase())
+}
+
+/**
+ * Where an imported post was first published. Signatures are kept as published for off-chain
+ * verification, the contract has no ed25519 to check them with.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportProvenance {
+    pub source: String,
+    pub author: String,
+    pub id: String,
+    pub timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub parent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub signer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub signature: Option<String>,
+}
+
+/**
+ * A Farcaster hub message, as served by the hub HTTP API
+ */
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cast {
+    data: CastData,
+    hash: String,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    signer: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CastData {
+    #[serde(rename = "type")]
+    kind: String,
+    fid: u64,
+    timestamp: u64,
+    cast_add_body: CastBody,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CastBody {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    embeds: Vec<Value>,
+    #[serde(default)]
+    parent_cast_id: Option<CastId>,
+}
+
+#[derive(Deserialize)]
+struct CastId {
+    fid: u64,
+    hash: String,
+}
+
+/**
+ * A Lens publication with its v2 metadata, as returned by the Lens API
+ */
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LensPublication {
+    id: String,
+    profile: LensProfile,
+    created_at: String,
+    metadata: LensMetadata,
+    #[serde(default)]
+    comment_on: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LensProfile {
+    id: String,
+    #[serde(default)]
+    owned_by: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LensMetadata {
+    version: String,
+    #[serde(default)]
+    content: String,
+    main_content_focus: String,
+    #[serde(default)]
+    media: Vec<LensMedia>,
+}
+
+#[derive(Deserialize)]
+struct LensMedia {
+    item: String,
+}
+
+#[derive(Deserialize)]
+struct ImportRequest {
+    source: String,
+    message: Value,
+}
+
+fn is_cast_hash(hash: &str) -> bool {
+    hash.strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/**
+ * The w3wall post payload of a cast and where it came from
+ */
+fn from_farcaster(message: Value) -> Result<(Value, ImportProvenance), ValidationError> {
+    let invalid = |message: &str| ValidationError::new("import", message);
+
+    let cast: Cast =
+        serde_json::from_value(message).map_err(|_| invalid("Farcaster message is not a cast."))?;
+    if cast.data.kind != "MESSAGE_TYPE_CAST_ADD" {
+        return Err(invalid("Only CAST_ADD messages can be imported."));
+    }
+    if cast.data.fid == 0 {
+        return Err(invalid("Cast author fid is missing."));
+    }
+    if !is_cast_hash(&cast.hash) {
+        return Err(invalid("Cast hash is not valid."));
+    }
+
+    let body = cast.data.cast_add_body;
+    let mut payload = json!({ "text": body.text });
+    let urls: Vec<&str> = body
+        .embeds
+        .iter()
+        .filter_map(|embed| embed["url"].as_str())
+        .collect();
+    if !urls.is_empty() {
+        payload["embeds"] = json!(urls);
+    }
+
+    let provenance = ImportProvenance {
+        source: "farcaster".to_string(),
+        author: format!("fid:{}", cast.data.fid),
+        id: cast.hash.to_lowercase(),
+        timestamp: FARCASTER_EPOCH + cast.data.timestamp,
+        parent: body
+            .parent_cast_id
+            .map(|parent| format!("fid:{}/{}", parent.fid, parent.hash.to_lowercase())),
+        signer: cast.signer,
+        signature: cast.signature,
+    };
+    Ok((payload, provenance))
+}
+
+/**
+ * Lens publication ids are `<profile id>-<publication id>`, both hex
+ */
+fn is_publication_id(id: &str) -> bool {
+    let is_hex_id = |part: &str| {
+        part.strip_prefix("0x")
+            .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+    };
+    id.split_once('-')
+        .is_some_and(|(profile, publication)| is_hex_id(profile) && is_hex_id(publication))
+}
+
+fn from_lens(message: Value) -> Result<(Value, ImportProvenance), ValidationError> {
+    let invalid = |message: &str| ValidationError::new("import", message);
+
+    let publication: LensPublication = serde_json::from_value(message)
+        .map_err(|_| invalid("Lens message is not a publication."))?;
+    let metadata = publication.metadata;
+    if !metadata.version.starts_with("2.") {
+        return Err(invalid("Only Lens metadata v2 can be imported."));
+    }
+    if !["TEXT_ONLY", "ARTICLE", "LINK", "IMAGE"].contains(&metadata.main_content_focus.as_str()) {
+        return Err(invalid("Lens publication focus is not supported."));
+    }
+    if !is_publication_id(&publication.id) {
+        return Err(invalid("Lens publication id is not valid."));
+    }
+    let timestamp = parse_iso8601(&publication.created_at)
+        .ok_or_else(|| invalid("Lens publication date is not valid."))?;
+
+    let mut payload = json!({ "text": metadata.content });
+    if let Some(media) = metadata.media.first() {
+        payload["embeds"] = json!([media.item]);
+    }
+
+    let provenance = ImportProvenance {
+        source: "lens".to_string(),
+        author: format!("lens:{}", publication.profile.id.to_lowercase()),
+        id: publication.id.to_lowercase(),
+        timestamp,
+        parent: publication.comment_on.map(|id| id.to_lowercase()),
+        signer: publication.profile.owned_by,
+        signature: None,
+    };
+    Ok((payload, provenance))
+}
+
+/**
+ * Brings a post published elsewhere onto the wall, once per original. The text goes through the
+ * same checks as a regular post. A `w3wall` export bundle restores a whole wall instead, see
+ * export::restore.
+ */
+pub fn import(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: ImportRequest = serde_json::from_str(&transaction.data)?;
+    if request.source == "w3wall" {
+        return export::restore(contract, metadatas, transaction, config, request.message);
+    }
+    let (mut payload, provenance) = match request.source.as_str() {
+        "farcaster" => from_farcaster(request.message)?,
+        "lens" => from_lens(request.message)?,
+        _ => return Err(W3WallError::rejected("Import source is not supported.")),
+    };
+
+    let marker = import_alias(&provenance.source, &provenance.id);
+    if find_record(metadatas, &marker, &contract.public_key).is_some() {
+        return Err(W3WallError::rejected("This post was already imported."));
+    }
+
+    let ctx = PostContext {
+        transaction,
+        metadatas,
+        wall: &contract.public_key,
+        config,
+        filter,
+        payload: &payload,
+    };
+    validation::run_pipeline(
+        &[&LengthValidator, &ProfanityValidator, &SpamValidator],
+        &ctx,
+    )?;
+
+    let attributes = json!([
+        { "trait_type": "source", "value": provenance.source },
+        { "trait_type": "original_author", "value": provenance.author },
+        { "display_type": "date", "trait_type": "originally_posted", "value": provenance.timestamp },
+    ]);
+    payload["type"] = "import".into();
+    payload["provenance"] = serde_json::to_value(&provenance)?;
+
+    Ok(vec![
+        FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Post.to_string(),
+            content: payload.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: Alias::Provenance.to_string(),
+            content: attributes.to_string(),
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: marker,
+            content: json!({ "importer": transaction.public_key }).to_string(),
+            loose: 1,
+            version: "".to_string(),
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cast(kind: &str) -> Value {
+        json!({
+            "data": {
+                "type": kind,
+                "fid": 3,
+                "timestamp": 90_540_800,
+                "network": "FARCASTER_NETWORK_MAINNET",
+                "castAddBody": {
+                    "text": "gm from warpcast",
+                    "embeds": [{ "url": "https://i.imgur.com/gm.png" }],
+                    "parentCastId": { "fid": 2, "hash": "0xAB00000000000000000000000000000000000000" }
+                }
+            },
+            "hash": "0x0b4d1e5a3c2f9e8d7c6b5a4f3e2d1c0b9a887766",
+            "signature": "c2lnbmF0dXJl",
+            "signer": "0x6a7b2c"
+        })
+    }
+
+    fn run(metadatas: &[Metadata], message: Value) -> Result<Vec<FinalMetadata>, W3WallError> {
+        run_source("farcaster", metadatas, message)
+    }
+
+    fn run_source(
+        source: &str,
+        metadatas: &[Metadata],
+        message: Value,
+    ) -> Result<Vec<FinalMetadata>, W3WallError> {
+        let transaction = Transaction {
+            method: "import".to_string(),
+            public_key: "0xmigrant".to_string(),
+            data: json!({ "source": source, "message": message }).to_string(),
+            ..Default::default()
+        };
+        import(
+            &MetaContract::default(),
+            metadatas,
+            &transaction,
+            &WallConfig::default(),
+            &WordFilter::Global,
+        )
+    }
+
+    #[test]
+    fn casts_become_posts_with_provenance() {
+        let finals = run(&[], cast("MESSAGE_TYPE_CAST_ADD")).unwrap();
+        let stored: Value = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(stored["type"], "import");
+        assert_eq!(stored["text"], "gm from warpcast");
+        assert_eq!(stored["provenance"]["author"], "fid:3");
+        assert_eq!(stored["provenance"]["timestamp"], 1_700_000_000);
+        assert_eq!(
+            stored["provenance"]["parent"],
+            "fid:2/0xab00000000000000000000000000000000000000"
+        );
+        assert_eq!(finals[1].alias, "provenance");
+        assert_eq!(
+            finals[2].alias,
+            "w3wall.import.farcaster.0x0b4d1e5a3c2f9e8d7c6b5a4f3e2d1c0b9a887766"
+        );
+    }
+
+    #[test]
+    fn lens_publications_become_posts() {
+        let publication = |version: &str| {
+            json!({
+                "id": "0x01-0x2a",
+                "profile": { "id": "0x01", "ownedBy": "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf" },
+                "createdAt": "2023-11-14T22:13:20.000Z",
+                "commentOn": "0x05-0x01",
+                "metadata": {
+                    "version": version,
+                    "metadata_id": "5b8f6c54-1b3c-4a8e-9d62-8f1f0a1b2c3d",
+                    "content": "gm from lens",
+                    "mainContentFocus": "TEXT_ONLY",
+                    "locale": "en"
+                }
+            })
+        };
+
+        let finals = run_source("lens", &[], publication("2.0.0")).unwrap();
+        let stored: Value = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(stored["text"], "gm from lens");
+        assert_eq!(stored["provenance"]["author"], "lens:0x01");
+        assert_eq!(stored["provenance"]["timestamp"], 1_700_000_000);
+        assert_eq!(stored["provenance"]["parent"], "0x05-0x01");
+        assert_eq!(finals[2].alias, "w3wall.import.lens.0x01-0x2a");
+
+        assert!(run_source("lens", &[], publication("1.0.0")).is_err());
+    }
+
+    #[test]
+    fn duplicates_and_other_messages_are_rejected() {
+        assert!(run(&[], cast("MESSAGE_TYPE_REACTION_ADD")).is_err());
+
+        let imported = Metadata {
+            alias: import_alias("farcaster", "0x0b4d1e5a3c2f9e8d7c6b5a4f3e2d1c0b9a887766"),
+            ..Default::default()
+        };
+        assert_eq!(
+            run(&[imported], cast("MESSAGE_TYPE_CAST_ADD"))
+                .unwrap_err()
+                .to_string(),
+            "This post was already imported."
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::data::WallConfig;
+use crate::defaults::{
+    DEFAULT_CLONE_REQUIRE_AUTHORIZATION, DEFAULT_MAX_PAYLOAD_BYTES, DEFAULT_WORK_BUDGET,
+    OPTIONAL_FEATURES, RECORD_VERSION, SCHEMA_VERSION,
+};
+use crate::handlers::execute::METHODS;
+use crate::types::{ContractInfo, ContractLimit};
+
+/**
+ * What this build of the contract supports, for the lineage node and dashboards
+ */
+pub fn info() -> ContractInfo {
+    let config = WallConfig::default();
+
+    let mut features = vec![
+        "royalties".to_string(),
+        "generated_images".to_string(),
+        "preflight".to_string(),
+        "cbor_payloads".to_string(),
+        "gzip_payloads".to_string(),
+    ];
+    if cfg!(feature = "mint") {
+        features.push("mint".to_string());
+    }
+    features.extend(
+        OPTIONAL_FEATURES
+            .iter()
+            .filter(|(_, built)| *built)
+            .map(|(name, _)| name.to_string()),
+    );
+    if DEFAULT_CLONE_REQUIRE_AUTHORIZATION {
+        features.push("clone_authorization".to_string());
+    }
+
+    ContractInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: SCHEMA_VERSION,
+        record_version: RECORD_VERSION,
+        methods: METHODS
+            .iter()
+            .filter(|m| config.has_method(m))
+            .map(|m| m.to_string())
+            .collect(),
+        features,
+        limits: vec![
+            limit("max_payload_bytes", DEFAULT_MAX_PAYLOAD_BYTES as u64),
+            limit("work_budget_bytes", DEFAULT_WORK_BUDGET),
+            limit("max_text_length", config.rules.max_text_length as u64),
+            limit("daily_post_quota", config.rules.daily_post_quota),
+        ],
+    }
+}
+
+fn limit(name: &str, value: u64) -> ContractLimit {
+    ContractLimit {
+        name: name.to_string(),
+        value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_package_version_and_methods() {
+        let info = info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.record_version, RECORD_VERSION);
+        assert!(info.methods.contains(&"init".to_string()));
+        assert_eq!(
+            info.methods.contains(&"tip".to_string()),
+            cfg!(feature = "tipping")
+        );
+        assert!(info.limits.iter().any(|l| l.name == "max_text_length"));
+    }
+}
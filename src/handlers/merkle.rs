@@ -0,0 +1,93 @@
+use crate::crypto::hash::keccak256;
+use crate::crypto::merkle::merkle_root;
+use crate::error::W3WallError;
+use crate::metadata::timestamp_sec;
+use crate::storage::alias::Alias;
+use crate::storage::read_content;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static MERKLE_ALIAS: &str = "w3wall.merkle";
+
+/**
+ * Merkle root over the keccak256 of every post in the thread, in the order they were written
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ThreadRoot {
+    pub root: String,
+    pub leaves: usize,
+    pub computed_at: u64,
+}
+
+/**
+ * Recomputes the thread root. A post that can't be read fails the whole computation rather than
+ * giving a root verifiers can't reproduce.
+ */
+pub fn merkle(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut leaves = vec![];
+    for post in metadatas.iter().filter(|m| m.alias == Alias::Post) {
+        let content = read_content(post)
+            .ok_or_else(|| W3WallError::Rejected(format!("Post {} can't be read.", post.cid)))?;
+        leaves.push(keccak256(content));
+    }
+
+    let root = ThreadRoot {
+        root: format!("0x{}", hex::encode(merkle_root(&leaves))),
+        leaves: leaves.len(),
+        computed_at: timestamp_sec(transaction.timestamp),
+    };
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: MERKLE_ALIAS.to_string(),
+        content: serde_json::to_string(&root)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keccak_hex;
+    use crate::storage::ipfs::test_store;
+
+    fn post(cid: &str, content: &str) -> Metadata {
+        test_store::put(cid, content);
+        Metadata {
+            public_key: "0xabc".to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn root_covers_every_post() {
+        let contract = MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        };
+        let mut metadatas = vec![post("merkle-1", r#"{"text": "gm"}"#)];
+        metadatas.push(Metadata {
+            alias: "blurhash".to_string(),
+            ..Default::default()
+        });
+
+        let finals = merkle(&contract, &metadatas, &Transaction::default()).unwrap();
+        assert_eq!(finals[0].alias, MERKLE_ALIAS);
+        assert_eq!(finals[0].public_key, "0xcontract");
+        let root: ThreadRoot = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(root.leaves, 1);
+        assert_eq!(root.root, keccak_hex(br#"{"text": "gm"}"#));
+
+        metadatas.push(Metadata {
+            cid: "merkle-missing".to_string(),
+            ..Default::default()
+        });
+        assert!(merkle(&contract, &metadatas, &Transaction::default()).is_err());
+    }
+}
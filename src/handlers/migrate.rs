@@ -0,0 +1,213 @@
+use crate::crypto::keccak_hex;
+use crate::data::WallConfig;
+use crate::defaults::DEFAULT_MAX_MIGRATIONS;
+use crate::error::W3WallError;
+use crate::moderation::WordFilter;
+use crate::storage::alias::Alias;
+use crate::storage::{find_record, read_content};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::migration::migrate as migrate_payload;
+use crate::validation::rules::{
+    CodeValidator, GeoValidator, LengthValidator, LicenseValidator, LinkValidator,
+    MediaHintValidator, SchemaValidator,
+};
+use crate::validation::{self, PostContext, Validator};
+use serde::{Deserialize, Serialize};
+
+pub static MIGRATED_ALIAS_PREFIX: &str = "w3wall.migrated.";
+
+pub fn migrated_alias(cid: &str) -> String {
+    format!("{}{}", MIGRATED_ALIAS_PREFIX, cid)
+}
+
+/**
+ * Canonical form of a post written by an older contract version. The post record is left as it
+ * was, clients read `content` in its place when this record exists. Posts that no longer
+ * validate get `error` instead so they are not retried.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MigratedPost {
+    pub post: String,
+    pub from_version: u64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub content_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
+}
+
+/**
+ * Rules that only look at the content, rate limits, quotas and clocks judged the post when it
+ * was sent
+ */
+fn content_validators() -> Vec<&'static dyn Validator> {
+    vec![
+        &SchemaValidator,
+        &LinkValidator,
+        &LengthValidator,
+        &MediaHintValidator,
+        &LicenseValidator,
+        &GeoValidator,
+        &CodeValidator,
+    ]
+}
+
+fn migrate_post(
+    contract: &MetaContract,
+    post: &Metadata,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Option<MigratedPost> {
+    let payload = validation::parse_payload(&read_content(post)?).ok()?;
+    let from_version = validation::migration::payload_version(&payload);
+    let outcome = migrate_payload(payload).and_then(|(payload, migrated)| {
+        let author = Transaction {
+            public_key: post.public_key.clone(),
+            ..transaction.clone()
+        };
+        let ctx = PostContext {
+            transaction: &author,
+            metadatas,
+            wall: &contract.public_key,
+            config,
+            filter: &WordFilter::Global,
+            payload: &payload,
+        };
+        validation::run_pipeline(&content_validators(), &ctx)?;
+        Ok(migrated.then(|| payload.to_string()))
+    });
+
+    match outcome {
+        Ok(None) => None,
+        Ok(Some(content)) => Some(MigratedPost {
+            post: post.cid.clone(),
+            from_version,
+            content_hash: Some(keccak_hex(content.as_bytes())),
+            content: Some(content),
+            error: None,
+        }),
+        Err(e) => Some(MigratedPost {
+            post: post.cid.clone(),
+            from_version,
+            content: None,
+            content_hash: None,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/**
+ * Rewrites up to DEFAULT_MAX_MIGRATIONS legacy posts into the current schema, owners only.
+ * Walls deployed on an older contract version call it until it returns nothing.
+ */
+pub fn migrate(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can migrate the wall.",
+        ));
+    }
+
+    let mut finals = vec![];
+    for post in metadatas.iter().filter(|m| m.alias == Alias::Post) {
+        if finals.len() == DEFAULT_MAX_MIGRATIONS {
+            break;
+        }
+        let alias = migrated_alias(&post.cid);
+        if find_record(metadatas, &alias, &contract.public_key).is_some() {
+            continue;
+        }
+        if let Some(migrated) = migrate_post(contract, post, metadatas, transaction, config) {
+            finals.push(FinalMetadata {
+                public_key: contract.public_key.clone(),
+                alias,
+                content: serde_json::to_string(&migrated)?,
+                loose: 1,
+                version: transaction.version.clone(),
+            });
+        }
+    }
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn post(cid: &str, content: &str) -> Metadata {
+        test_store::put(cid, content);
+        Metadata {
+            public_key: "0xauthor".to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn legacy_posts_get_a_canonical_record_once() {
+        let contract = MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let transaction = Transaction {
+            method: "migrate".to_string(),
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let mut metadatas = vec![
+            post("migrate-legacy", r#"{"text": "gm"}"#),
+            post(
+                "migrate-current",
+                r#"{"schema_version": 2, "type": "post", "text": "gm"}"#,
+            ),
+            post(
+                "migrate-invalid",
+                r#"{"image": "https://evil.example/x.png"}"#,
+            ),
+        ];
+
+        let finals = migrate(&contract, &metadatas, &transaction, &config).unwrap();
+        assert_eq!(finals.len(), 2);
+        assert_eq!(finals[0].alias, migrated_alias("migrate-legacy"));
+        let migrated: MigratedPost = serde_json::from_str(&finals[0].content).unwrap();
+        let canonical = r#"{"schema_version":2,"text":"gm","type":"post"}"#;
+        assert_eq!(migrated.content.as_deref(), Some(canonical));
+        assert_eq!(
+            migrated.content_hash,
+            Some(keccak_hex(canonical.as_bytes()))
+        );
+        assert_eq!(migrated.from_version, 1);
+        let invalid: MigratedPost = serde_json::from_str(&finals[1].content).unwrap();
+        assert!(invalid.error.unwrap().starts_with("[schema]"));
+
+        for (i, record) in finals.iter().enumerate() {
+            test_store::put(&format!("migrate-record-{}", i), &record.content);
+            metadatas.push(Metadata {
+                alias: record.alias.clone(),
+                public_key: record.public_key.clone(),
+                cid: format!("migrate-record-{}", i),
+                ..Default::default()
+            });
+        }
+        assert!(migrate(&contract, &metadatas, &transaction, &config)
+            .unwrap()
+            .is_empty());
+
+        let stranger = Transaction {
+            public_key: "0xauthor".to_string(),
+            ..transaction
+        };
+        assert!(migrate(&contract, &metadatas, &stranger, &config).is_err());
+    }
+}
@@ -0,0 +1,908 @@
+use crate::budget;
+use crate::crypto::eth_address::parse_address;
+use crate::crypto::hash::keccak256;
+use crate::data::{OpenSeaAttributes, ThreadActivity, WallConfig};
+use crate::defaults::{
+    DEFAULT_MAX_BADGE_DECIMALS, DEFAULT_MAX_BATCH_MINT, DEFAULT_MAX_DESCRIPTION_LENGTH,
+    DEFAULT_MAX_MINT_BODY_LENGTH, DEFAULT_MAX_MINT_IMAGES, DEFAULT_MAX_MINT_NAME_LENGTH,
+};
+use crate::encoding::{abi, decode_hex};
+use crate::error::{IntoResult, W3WallError};
+use crate::handlers::reveal::{RevealState, REVEAL_ALIAS};
+use crate::handlers::summary::load_summary;
+use crate::metadata::attributes::{merge_attributes, topic_attributes};
+use crate::metadata::cosmetics::cosmetic_attributes;
+use crate::metadata::reading::Reading;
+use crate::metadata::{description, external_url, sanitize, svg};
+use crate::storage::alias::Alias;
+use crate::storage::{envelope, load_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, MintItemResult};
+use crate::validation::image::check_image;
+use crate::validation::license::is_license;
+use ethabi::{ParamType, Token};
+use primitive_types::U256;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/**
+ * The user supplied part of a topic, as decoded from the mint data
+ */
+#[derive(Default, Deserialize)]
+struct Topic {
+    name: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    license: String,
+    /**
+     * Unix seconds from which the owner may reveal the real topic, 0 mints it revealed
+     */
+    #[serde(default)]
+    reveal_at: u64,
+    /**
+     * Category id of the minting frontend, 0 for none
+     */
+    #[serde(default)]
+    category: u64,
+    /**
+     * Address credited as the topic's creator, empty for none
+     */
+    #[serde(default)]
+    creator: String,
+    /**
+     * Images besides the cover
+     */
+    #[serde(default)]
+    images: Vec<String>,
+}
+
+/**
+ * ERC-1155 style fungible token, such as a "Top Contributor" badge or a reaction medallion
+ */
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Badge {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    decimals: u8,
+    /**
+     * Supply hint for marketplaces, 0 leaves it open
+     */
+    #[serde(default)]
+    max_supply: u64,
+}
+
+/**
+ * Mint data given as a JSON object, `type` picks the metadata standard
+ */
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MintRequest {
+    Topic(Topic),
+    Badge(Badge),
+}
+
+#[derive(Deserialize)]
+struct BatchItem {
+    token_id: String,
+    #[serde(flatten)]
+    topic: Topic,
+}
+
+/**
+ * Every layout the ABI mint data comes in, told apart by the size of its head: 3 to 5 strings,
+ * or the 5 strings followed by (uint256 category, address creator, string[] images, bytes
+ * body_hash)
+ */
+fn topic_params(head: Option<U256>) -> Vec<ParamType> {
+    let strings = |n| vec![ParamType::String; n];
+    match head {
+        Some(head) if head == U256::from(4 * 32) => strings(4),
+        Some(head) if head == U256::from(5 * 32) => strings(5),
+        Some(head) if head == U256::from(9 * 32) => [
+            strings(5),
+            vec![
+                ParamType::Uint(256),
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::String)),
+                ParamType::Bytes,
+            ],
+        ]
+        .concat(),
+        _ => strings(3),
+    }
+}
+
+fn topic_of(tokens: &[Token]) -> Result<Topic, W3WallError> {
+    let field = |i: usize| tokens.get(i).map(Token::to_string).unwrap_or_default();
+    let reveal_at = match field(4).trim() {
+        "" => 0,
+        at => at
+            .parse()
+            .map_err(|_| W3WallError::rejected("Reveal time must be unix seconds."))?,
+    };
+    let category = match tokens.get(5) {
+        Some(Token::Uint(id)) if id.bits() > 64 => {
+            return Err(W3WallError::rejected("Category id must fit in 64 bits."))
+        }
+        Some(Token::Uint(id)) => id.low_u64(),
+        _ => 0,
+    };
+    let creator = match tokens.get(6) {
+        Some(Token::Address(address)) if !address.is_zero() => {
+            format!("0x{}", hex::encode(address.as_bytes()))
+        }
+        _ => String::new(),
+    };
+    let images = match tokens.get(7) {
+        Some(Token::Array(images)) => images.iter().map(Token::to_string).collect(),
+        _ => vec![],
+    };
+    let body = field(2);
+    // a frontend may pin the body it showed the minter, an empty hash pins nothing
+    if let Some(Token::Bytes(hash)) = tokens.get(8) {
+        if !hash.is_empty() && hash[..] != keccak256(&body) {
+            return Err(W3WallError::rejected(
+                "Body hash is not the keccak256 of the body.",
+            ));
+        }
+    }
+    Ok(Topic {
+        name: field(0),
+        image: field(1),
+        body,
+        license: field(3),
+        reveal_at,
+        category,
+        creator,
+        images,
+    })
+}
+
+/**
+ * Topic metadata from the ABI encoded (name, image, body[, license[, reveal_at]]), or those five
+ * followed by the category, creator, images and body hash of `topic_params`. `metadatas`
+ * is what the lineage already holds for the token, a repeated mint merges into its attributes.
+ * With a reveal time the fields are the placeholder shown until the owner reveals the topic.
+ * Data that is a JSON object is a `MintRequest`, the only way to mint a badge.
+ */
+pub fn mint(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    data_key: &str,
+    token_id: &str,
+    data: &str,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    budget::start();
+    let mut topic = None;
+
+    if data.trim_start().starts_with('{') {
+        return match serde_json::from_str(data)? {
+            MintRequest::Topic(topic) => {
+                mint_topic(contract, metadatas, data_key, token_id, Some(topic))
+            }
+            MintRequest::Badge(badge) => mint_badge(contract, data_key, badge),
+        };
+    }
+
+    // extract out data
+    let decoded = decode_hex(data)?;
+    if !decoded.is_empty() {
+        // optional fields follow the first three strings, the first offset gives the head size
+        let param_types = topic_params(decoded.get(..32).map(U256::from_big_endian));
+        topic = Some(topic_of(&abi::decode_canonical(&param_types, &decoded)?)?);
+    }
+
+    mint_topic(contract, metadatas, data_key, token_id, topic)
+}
+
+/**
+ * Several topics in one call, from a JSON array of {token_id, name, image, body, license,
+ * reveal_at} or
+ * an ABI encoded (string token_id, string name, string image, string body, string license)[].
+ * Every item succeeds or fails on its own, only an unreadable batch fails as a whole.
+ */
+pub fn mint_batch(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    data_key: &str,
+    data: &str,
+) -> Result<Vec<MintItemResult>, W3WallError> {
+    budget::start();
+    let items: Vec<Result<BatchItem, W3WallError>> = if data.trim_start().starts_with('[') {
+        let values: Vec<Value> = serde_json::from_str(data)?;
+        values
+            .into_iter()
+            .map(|value| serde_json::from_value(value).map_err(W3WallError::from))
+            .collect()
+    } else {
+        let tuple = ParamType::Tuple(vec![ParamType::String; 5]);
+        let decoded =
+            abi::decode_canonical(&[ParamType::Array(Box::new(tuple))], &decode_hex(data)?)?;
+        decoded
+            .into_iter()
+            .next()
+            .and_then(Token::into_array)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| {
+                let fields = item.into_tuple().unwrap_or_default();
+                Ok(BatchItem {
+                    token_id: fields.first().map(Token::to_string).unwrap_or_default(),
+                    topic: topic_of(fields.get(1..).unwrap_or_default())?,
+                })
+            })
+            .collect()
+    };
+
+    if items.len() > DEFAULT_MAX_BATCH_MINT {
+        return Err(W3WallError::Rejected(format!(
+            "A batch mints at most {} tokens.",
+            DEFAULT_MAX_BATCH_MINT
+        )));
+    }
+
+    Ok(items
+        .into_iter()
+        .map(|item| match item {
+            Ok(item) => {
+                let token: Vec<Metadata> = metadatas
+                    .iter()
+                    .filter(|m| m.token_id == item.token_id)
+                    .cloned()
+                    .collect();
+                let result =
+                    mint_topic(contract, &token, data_key, &item.token_id, Some(item.topic))
+                        .into_result();
+                MintItemResult {
+                    token_id: item.token_id,
+                    result: result.result,
+                    metadatas: result.metadatas,
+                    error_string: result.error_string,
+                }
+            }
+            Err(e) => MintItemResult {
+                token_id: "".to_string(),
+                result: false,
+                metadatas: vec![],
+                error_string: e.to_string(),
+            },
+        })
+        .collect())
+}
+
+fn mint_badge(
+    contract: &MetaContract,
+    data_key: &str,
+    badge: Badge,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if badge.decimals > DEFAULT_MAX_BADGE_DECIMALS {
+        return Err(W3WallError::Rejected(format!(
+            "Badge decimals are limited to {}.",
+            DEFAULT_MAX_BADGE_DECIMALS
+        )));
+    }
+    let name = sanitize(&badge.name, DEFAULT_MAX_MINT_NAME_LENGTH, false);
+    if name.is_empty() {
+        return Err(W3WallError::rejected("Badge name is required."));
+    }
+
+    let mut image = badge.image.trim().to_string();
+    if image.is_empty() {
+        image = svg::topic_image(&name, data_key);
+    } else {
+        let accepted = WallConfig::default().image_types;
+        check_image(&image, None, &accepted).map_err(W3WallError::rejected)?;
+    }
+
+    let mut attributes = vec![
+        OpenSeaAttributes::text("origin", "w3wall"),
+        OpenSeaAttributes::text("type", "badge"),
+    ];
+    if badge.max_supply > 0 {
+        attributes.push(OpenSeaAttributes::number("max_supply", badge.max_supply));
+    }
+    let properties = json!({
+        "token_standard": "ERC-1155",
+        "fungible": true,
+        "max_supply": badge.max_supply,
+    });
+
+    let record = |alias: Alias, content: String| FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: alias.to_string(),
+        content,
+        loose: 1,
+        version: "".to_string(),
+    };
+    Ok(vec![
+        record(Alias::Name, name),
+        record(
+            Alias::Description,
+            sanitize(&badge.description, DEFAULT_MAX_DESCRIPTION_LENGTH, true),
+        ),
+        record(Alias::Image, image),
+        record(Alias::Decimals, badge.decimals.to_string()),
+        record(Alias::Properties, properties.to_string()),
+        record(Alias::Attributes, serde_json::to_string(&attributes)?),
+    ])
+}
+
+/**
+ * When the earliest post the lineage holds for the token was written, 0 when there is none.
+ * Every peer minting the token has to write the same record, so the date comes from the inputs
+ * and never from a clock.
+ */
+fn first_post_at(metadatas: &[Metadata]) -> u64 {
+    metadatas
+        .iter()
+        .filter(|m| m.alias.is_empty())
+        .filter_map(envelope::created_at)
+        .min()
+        .unwrap_or(0)
+}
+
+fn mint_topic(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    data_key: &str,
+    token_id: &str,
+    topic: Option<Topic>,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    // mint has no view of the wall metadata, so royalties come from the default config
+    let config = WallConfig::default();
+    let mut finals: Vec<FinalMetadata> = vec![];
+    let mut license = None;
+    let mut words = 0;
+    let mut title = String::new();
+    let mut credits = vec![];
+
+    if let Some(topic) = topic {
+        if !topic.license.is_empty() {
+            if !is_license(&topic.license) {
+                return Err(W3WallError::rejected("Topic license is not recognized."));
+            }
+            license = Some(topic.license);
+        }
+
+        if topic.reveal_at > 0 {
+            finals.push(FinalMetadata {
+                public_key: contract.public_key.clone(),
+                alias: REVEAL_ALIAS.to_string(),
+                content: serde_json::to_string(&RevealState {
+                    reveal_at: topic.reveal_at,
+                    revealed_at: 0,
+                })?,
+                loose: 1,
+                version: "".to_string(),
+            });
+        }
+
+        title = sanitize(&topic.name, DEFAULT_MAX_MINT_NAME_LENGTH, false);
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: Alias::Name.to_string(),
+            content: title.clone(),
+            loose: 1,
+            version: "".to_string(),
+        });
+
+        let mut image = topic.image.trim().to_string();
+        if image.is_empty() {
+            image = svg::topic_image(&title, data_key);
+        } else {
+            check_image(&image, None, &config.image_types).map_err(W3WallError::rejected)?;
+        }
+
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: Alias::Image.to_string(),
+            content: image,
+            loose: 1,
+            version: "".to_string(),
+        });
+
+        if topic.images.len() > DEFAULT_MAX_MINT_IMAGES {
+            return Err(W3WallError::Rejected(format!(
+                "A topic carries at most {} images besides its cover.",
+                DEFAULT_MAX_MINT_IMAGES
+            )));
+        }
+        if !topic.images.is_empty() {
+            let images: Vec<&str> = topic.images.iter().map(|image| image.trim()).collect();
+            for image in &images {
+                check_image(image, None, &config.image_types).map_err(W3WallError::rejected)?;
+            }
+            finals.push(FinalMetadata {
+                public_key: contract.public_key.clone(),
+                alias: Alias::Properties.to_string(),
+                content: json!({ "images": images }).to_string(),
+                loose: 1,
+                version: "".to_string(),
+            });
+        }
+
+        if topic.category > 0 {
+            credits.push(OpenSeaAttributes::number("category", topic.category));
+        }
+        if !topic.creator.is_empty() {
+            let creator = parse_address(&topic.creator).map_err(W3WallError::rejected)?;
+            credits.push(OpenSeaAttributes::text("creator", &creator));
+        }
+
+        let body = sanitize(&topic.body, DEFAULT_MAX_MINT_BODY_LENGTH, true);
+        words = Reading::of(&body).words;
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: Alias::Body.to_string(),
+            content: body,
+            loose: 1,
+            version: "".to_string(),
+        });
+    }
+
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::Description.to_string(),
+        content: sanitize(
+            &load_summary(contract, metadatas)
+                .map(|summary| summary.text)
+                .unwrap_or_else(|| {
+                    description(
+                        &config.description_template,
+                        &title,
+                        &contract.public_key,
+                        0,
+                    )
+                }),
+            DEFAULT_MAX_DESCRIPTION_LENGTH,
+            true,
+        ),
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::ExternalUrl.to_string(),
+        content: external_url(data_key, token_id),
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    // royalties (EIP-2981 style) declared by the wall
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::SellerFeeBasisPoints.to_string(),
+        content: config.seller_fee_basis_points.to_string(),
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::FeeRecipient.to_string(),
+        content: config.fee_recipient.clone(),
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    // adds attributes, a freshly minted topic has no activity yet
+    let mut attr = topic_attributes(&ThreadActivity {
+        token_id: token_id.parse().unwrap_or(0),
+        created: first_post_at(metadatas),
+        words,
+        license,
+        ..Default::default()
+    });
+    attr.extend(credits);
+    attr.extend(cosmetic_attributes(data_key, token_id));
+    let existing = load_record(metadatas, Alias::Attributes.as_str(), &contract.public_key)?
+        .unwrap_or_default();
+    let attr = merge_attributes(existing, attr);
+
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: Alias::Attributes.to_string(),
+        content: serde_json::to_string(&attr)?,
+        loose: 1,
+        version: "".to_string(),
+    });
+
+    // a record skipped for want of budget would silently drop what the token already holds
+    if budget::overrun() {
+        return Err(W3WallError::rejected(
+            "Mint needs more work than a wall allows and was stopped.",
+        ));
+    }
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethabi::{encode, Token};
+
+    fn mint_data(name: &str, image: &str, body: &str) -> String {
+        hex::encode(encode(&[
+            Token::String(name.to_string()),
+            Token::String(image.to_string()),
+            Token::String(body.to_string()),
+        ]))
+    }
+
+    fn content<'a>(finals: &'a [FinalMetadata], alias: &str) -> &'a str {
+        &finals.iter().find(|m| m.alias == alias).unwrap().content
+    }
+
+    #[test]
+    fn valid_abi_emits_topic_fields() {
+        let data = mint_data("title", "https://nftstorage.link/ipfs/x/cover.png", "body");
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        assert_eq!(content(&finals, "name"), "title");
+        assert_eq!(
+            content(&finals, "image"),
+            "https://nftstorage.link/ipfs/x/cover.png"
+        );
+        assert_eq!(content(&finals, "body"), "body");
+        assert_eq!(
+            content(&finals, "description"),
+            "A subject in w3wall decentralize forum"
+        );
+        assert_eq!(
+            content(&finals, "external_url"),
+            "https://w3wall.xyz/topic/key/1"
+        );
+    }
+
+    fn extended_data(category: u64, images: &[&str], body_hash: Vec<u8>) -> String {
+        let creator: [u8; 20] = hex::decode("7e5f4552091a69125d5dfcb7b8c2659029395bdf")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        hex::encode(encode(&[
+            Token::String("title".to_string()),
+            Token::String("".to_string()),
+            Token::String("body".to_string()),
+            Token::String("CC0".to_string()),
+            Token::String("".to_string()),
+            Token::Uint(category.into()),
+            Token::Address(creator.into()),
+            Token::Array(
+                images
+                    .iter()
+                    .map(|i| Token::String(i.to_string()))
+                    .collect(),
+            ),
+            Token::Bytes(body_hash),
+        ]))
+    }
+
+    #[test]
+    fn extended_abi_carries_category_creator_and_images() {
+        let gallery = [
+            "https://nftstorage.link/ipfs/x/1.png",
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        ];
+        let data = extended_data(7, &gallery, keccak256("body").to_vec());
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        assert_eq!(content(&finals, "body"), "body");
+        assert_eq!(
+            content(&finals, "properties"),
+            format!(r#"{{"images":["{}","{}"]}}"#, gallery[0], gallery[1])
+        );
+        let attributes = content(&finals, "attributes");
+        assert!(
+            attributes.contains(r#"{"display_type":"number","trait_type":"category","value":7}"#)
+        );
+        assert!(attributes.contains(
+            r#"{"trait_type":"creator","value":"0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"}"#
+        ));
+
+        // no hash pins nothing, a wrong one is refused
+        let data = extended_data(0, &[], vec![]);
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        assert!(finals.iter().all(|m| m.alias != "properties"));
+        assert!(!content(&finals, "attributes").contains("category"));
+        let data = extended_data(0, &[], keccak256("other").to_vec());
+        assert!(mint(&MetaContract::default(), &[], "key", "1", &data).is_err());
+        let data = extended_data(0, &["https://example.com/x.png"], vec![]);
+        assert!(mint(&MetaContract::default(), &[], "key", "1", &data).is_err());
+    }
+
+    #[test]
+    fn summary_becomes_the_description() {
+        crate::storage::ipfs::test_store::put(
+            "mint-summary",
+            r#"{"text": "tl;dr", "set_by": "0xmod", "set_at": 1}"#,
+        );
+        let metadatas = [Metadata {
+            alias: crate::handlers::summary::SUMMARY_ALIAS.to_string(),
+            cid: "mint-summary".to_string(),
+            ..Default::default()
+        }];
+        let data = mint_data("title", "", "body");
+        let finals = mint(&MetaContract::default(), &metadatas, "key", "1", &data).unwrap();
+        assert_eq!(content(&finals, "description"), "tl;dr");
+    }
+
+    #[test]
+    fn license_becomes_an_attribute() {
+        let data = hex::encode(encode(&[
+            Token::String("title".to_string()),
+            Token::String("".to_string()),
+            Token::String("body".to_string()),
+            Token::String("CC0".to_string()),
+        ]));
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        assert_eq!(content(&finals, "body"), "body");
+        assert!(
+            content(&finals, "attributes").contains(r#"{"trait_type":"license","value":"CC0"}"#)
+        );
+
+        let data = hex::encode(encode(&[
+            Token::String("title".to_string()),
+            Token::String("".to_string()),
+            Token::String("body".to_string()),
+            Token::String("mine".to_string()),
+        ]));
+        assert!(mint(&MetaContract::default(), &[], "key", "1", &data).is_err());
+    }
+
+    #[test]
+    fn missing_image_gets_generated_svg() {
+        let finals = mint(
+            &MetaContract::default(),
+            &[],
+            "key",
+            "1",
+            &mint_data("t", "", "b"),
+        )
+        .unwrap();
+        assert!(content(&finals, "image").starts_with("data:image/svg+xml;base64,"));
+    }
+
+    #[test]
+    fn fields_are_sanitized_and_images_checked() {
+        let name = format!("gm\u{202e}\u{0007} fr\u{2028}ens{}", "!".repeat(300));
+        let data = mint_data(&name, "", "line one\n\u{0000}line\ttwo\u{2029}");
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        let name = content(&finals, "name");
+        assert!(name.starts_with("gm fr ens!"));
+        assert_eq!(name.chars().count(), DEFAULT_MAX_MINT_NAME_LENGTH);
+        assert_eq!(content(&finals, "body"), "line one\nline\ttwo");
+
+        let data = mint_data("t", "https://evil.example/x.png", "b");
+        assert!(mint(&MetaContract::default(), &[], "key", "1", &data).is_err());
+    }
+
+    #[test]
+    fn minting_again_keeps_one_set_of_attributes() {
+        crate::storage::ipfs::test_store::put(
+            "mint-attributes",
+            r#"[{"trait_type":"origin","value":"w3wall"},{"display_type":"date","trait_type":"created","value":10}]"#,
+        );
+        let existing = Metadata {
+            alias: "attributes".to_string(),
+            cid: "mint-attributes".to_string(),
+            ..Default::default()
+        };
+        let data = mint_data("title", "", "body");
+        let finals = mint(&MetaContract::default(), &[existing], "key", "1", &data).unwrap();
+        let attributes = content(&finals, "attributes");
+        assert_eq!(attributes.matches(r#""trait_type":"origin""#).count(), 1);
+        assert_eq!(attributes.matches(r#""trait_type":"frame""#).count(), 1);
+        assert!(attributes.contains(r#"{"display_type":"date","trait_type":"created","value":10}"#));
+    }
+
+    #[test]
+    fn created_is_the_earliest_post() {
+        let post = |cid: &str, created_at: u64| {
+            crate::storage::ipfs::test_store::put(
+                cid,
+                &format!(
+                    r#"{{"v":1,"type":"post","author":"0xa","created_at":{},"body":{{"text":"gm"}},"refs":[]}}"#,
+                    created_at
+                ),
+            );
+            Metadata {
+                cid: cid.to_string(),
+                ..Default::default()
+            }
+        };
+        let metadatas = [post("mint-post-late", 300), post("mint-post-early", 200)];
+        let data = mint_data("title", "", "body");
+        let finals = mint(&MetaContract::default(), &metadatas, "key", "1", &data).unwrap();
+        assert!(content(&finals, "attributes")
+            .contains(r#"{"display_type":"date","trait_type":"created","value":200}"#));
+
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        assert!(!content(&finals, "attributes").contains(r#""created""#));
+    }
+
+    #[test]
+    fn minting_after_an_overrun_keeps_the_attributes() {
+        crate::storage::ipfs::test_store::put(
+            "mint-overrun-attributes",
+            r#"[{"trait_type":"origin","value":"w3wall"},{"trait_type":"pinned","value":"yes"}]"#,
+        );
+        let existing = Metadata {
+            alias: "attributes".to_string(),
+            cid: "mint-overrun-attributes".to_string(),
+            ..Default::default()
+        };
+        // an earlier call on this thread spent its budget
+        budget::start_with(0);
+        assert!(!budget::charge(1));
+
+        let data = mint_data("title", "", "body");
+        let finals = mint(&MetaContract::default(), &[existing], "key", "1", &data).unwrap();
+        assert!(content(&finals, "attributes").contains(r#"{"trait_type":"pinned","value":"yes"}"#));
+
+        let unreadable = Metadata {
+            alias: "attributes".to_string(),
+            cid: "mint-missing-attributes".to_string(),
+            ..Default::default()
+        };
+        assert!(mint(&MetaContract::default(), &[unreadable], "key", "1", &data).is_err());
+    }
+
+    #[test]
+    fn batch_reports_every_item_on_its_own() {
+        let data = r#"[
+            {"token_id": "1", "name": "one"},
+            {"token_id": "2", "name": "two", "license": "mine"},
+            {"name": "no token"}
+        ]"#;
+        let items = mint_batch(&MetaContract::default(), &[], "key", data).unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items[0].result);
+        assert_eq!(content(&items[0].metadatas, "name"), "one");
+        assert_eq!(
+            content(&items[0].metadatas, "external_url"),
+            "https://w3wall.xyz/topic/key/1"
+        );
+        assert_eq!(items[1].token_id, "2");
+        assert_eq!(items[1].error_string, "Topic license is not recognized.");
+        assert!(items[1].metadatas.is_empty());
+        assert!(!items[2].result);
+
+        let topic = |id: &str, name: &str| {
+            Token::Tuple(
+                [id, name, "", "body", ""]
+                    .iter()
+                    .map(|s| Token::String(s.to_string()))
+                    .collect(),
+            )
+        };
+        let data = hex::encode(encode(&[Token::Array(vec![
+            topic("7", "seven"),
+            topic("8", "eight"),
+        ])]));
+        let items = mint_batch(&MetaContract::default(), &[], "key", &data).unwrap();
+        assert_eq!(items[1].token_id, "8");
+        assert_eq!(content(&items[1].metadatas, "name"), "eight");
+
+        assert!(mint_batch(&MetaContract::default(), &[], "key", "zz").is_err());
+        let many = serde_json::to_string(&vec![serde_json::json!({}); 51]).unwrap();
+        assert!(mint_batch(&MetaContract::default(), &[], "key", &many).is_err());
+    }
+
+    #[test]
+    fn reveal_time_mints_a_placeholder() {
+        let data = hex::encode(encode(&[
+            Token::String("soon".to_string()),
+            Token::String("".to_string()),
+            Token::String("".to_string()),
+            Token::String("".to_string()),
+            Token::String("1700000000".to_string()),
+        ]));
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap();
+        assert_eq!(content(&finals, "name"), "soon");
+        assert_eq!(
+            content(&finals, REVEAL_ALIAS),
+            r#"{"reveal_at":1700000000,"revealed_at":0}"#
+        );
+
+        let data = hex::encode(encode(&[
+            Token::String("soon".to_string()),
+            Token::String("".to_string()),
+            Token::String("".to_string()),
+            Token::String("".to_string()),
+            Token::String("tomorrow".to_string()),
+        ]));
+        assert!(mint(&MetaContract::default(), &[], "key", "1", &data).is_err());
+    }
+
+    #[test]
+    fn badge_type_mints_erc1155_metadata() {
+        let data = r#"{"type": "badge", "name": "Top Contributor", "max_supply": 100}"#;
+        let finals = mint(&MetaContract::default(), &[], "key", "9", data).unwrap();
+        let aliases: Vec<&str> = finals.iter().map(|m| m.alias.as_str()).collect();
+        assert_eq!(
+            aliases,
+            [
+                "name",
+                "description",
+                "image",
+                "decimals",
+                "properties",
+                "attributes"
+            ]
+        );
+        assert_eq!(content(&finals, "decimals"), "0");
+        assert_eq!(
+            content(&finals, "properties"),
+            r#"{"fungible":true,"max_supply":100,"token_standard":"ERC-1155"}"#
+        );
+        assert!(content(&finals, "attributes").contains(r#"{"trait_type":"type","value":"badge"}"#));
+
+        let data = r#"{"type": "topic", "name": "a topic"}"#;
+        let finals = mint(&MetaContract::default(), &[], "key", "9", data).unwrap();
+        assert!(content(&finals, "attributes").contains(r#"{"trait_type":"type","value":"topic"}"#));
+
+        let data = r#"{"type": "badge", "name": "x", "decimals": 30}"#;
+        assert!(mint(&MetaContract::default(), &[], "key", "9", data).is_err());
+        let data = r#"{"type": "medal", "name": "x"}"#;
+        assert!(mint(&MetaContract::default(), &[], "key", "9", data).is_err());
+    }
+
+    #[test]
+    fn malformed_hex_is_rejected() {
+        let err = mint(&MetaContract::default(), &[], "key", "1", "zz").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid data structure: 'z' at position 0 is not a hex digit"
+        );
+        let err = mint(&MetaContract::default(), &[], "key", "1", "0xabc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid data structure: hex has an odd number of digits, every byte takes two"
+        );
+
+        let data = mint_data("title", "", "body");
+        let err = mint(
+            &MetaContract::default(),
+            &[],
+            "key",
+            "1",
+            &data[..data.len() - 64],
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Invalid data structure: param 2 (string) at byte 224: needs 4 bytes, the data ends at byte 224"
+        );
+    }
+
+    #[test]
+    fn padded_encodings_are_rejected() {
+        let data = format!("{}{}", mint_data("title", "", "body"), "00".repeat(32));
+        let err = mint(&MetaContract::default(), &[], "key", "1", &data).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid data structure: data is not the canonical encoding"));
+    }
+
+    #[test]
+    fn prefixed_and_wrapped_hex_is_accepted() {
+        let data = mint_data("title", "", "body");
+        let wrapped = format!(" 0x{}\n{}\n", &data[..64], &data[64..]);
+        let finals = mint(&MetaContract::default(), &[], "key", "1", &wrapped).unwrap();
+        assert_eq!(content(&finals, "name"), "title");
+        let finals = mint(&MetaContract::default(), &[], "key", "1", "0x").unwrap();
+        assert!(finals.iter().all(|m| m.alias != "name"));
+    }
+
+    #[test]
+    fn empty_data_still_emits_defaults() {
+        let finals = mint(&MetaContract::default(), &[], "key", "1", "").unwrap();
+        assert!(finals.iter().all(|m| m.alias != "name"));
+        assert!(finals.iter().any(|m| m.alias == "attributes"));
+    }
+}
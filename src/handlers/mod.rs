@@ -0,0 +1,48 @@
+pub mod acl;
+pub mod announce;
+pub mod appeal;
+pub mod approval;
+pub mod audit;
+pub mod block;
+pub mod board;
+pub mod bounty;
+pub mod chunk;
+pub mod clone;
+pub mod collection;
+pub mod compat;
+pub mod crosspost;
+pub mod daily;
+pub mod dispute;
+pub mod draft;
+pub mod event;
+pub mod execute;
+pub mod export;
+#[cfg(feature = "federation")]
+pub mod federation;
+pub mod feed;
+pub mod follow;
+pub mod governance;
+pub mod import;
+pub mod info;
+pub mod merkle;
+pub mod migrate;
+#[cfg(feature = "mint")]
+pub mod mint;
+pub mod moderation;
+pub mod mute;
+pub mod ownership;
+pub mod pages;
+pub mod pins;
+pub mod preflight;
+pub mod profile;
+pub mod reaction;
+pub mod redaction;
+pub mod reveal;
+pub mod stats;
+pub mod sticker;
+pub mod summary;
+pub mod template;
+pub mod theme;
+#[cfg(feature = "tipping")]
+pub mod tip;
+pub mod webhook;
@@ -0,0 +1,355 @@
+use crate::crypto::verify_personal_signature;
+use crate::data::{Approval, ModerationAction, ModerationRequest, WallConfig, WallFilter};
+use crate::defaults::DEFAULT_MAX_FILTER_WORDS;
+use crate::error::W3WallError;
+use crate::handlers::audit::audit_record;
+use crate::storage::filter::FILTER_ALIAS;
+use crate::storage::moderation::{load_moderation, moderation_cid, MODERATION_ALIAS};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use std::collections::HashSet;
+
+/**
+ * Replaces the wall's own banned word list. Moderators only, the record is kept under the
+ * contract key so nobody else can shadow it.
+ */
+pub fn set_filter(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_moderator(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall moderators can change the word filter.",
+        ));
+    }
+
+    let mut filter: WallFilter = serde_json::from_str(&transaction.data)?;
+    filter.words = filter
+        .words
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    filter.words.sort();
+    filter.words.dedup();
+
+    if filter.words.len() > DEFAULT_MAX_FILTER_WORDS {
+        return Err(W3WallError::Rejected(format!(
+            "Word filter is limited to {} entries.",
+            DEFAULT_MAX_FILTER_WORDS
+        )));
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: FILTER_ALIAS.to_string(),
+        content: serde_json::to_string(&filter)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+/**
+ * What co-owners sign to approve an action, bound to the wall so approvals can't be replayed
+ * on another one, and to the CID of the moderation record they saw (empty before the first
+ * action) so they can't be replayed once the state moved on, say after an appeal reversed them
+ */
+pub fn moderation_message(
+    data_key: &str,
+    state_cid: &str,
+    action: ModerationAction,
+    target: &str,
+) -> String {
+    format!(
+        "w3wall:moderate:{}:{}:{}:{}",
+        data_key,
+        state_cid,
+        action.as_str(),
+        target
+    )
+}
+
+/**
+ * Owners sending the transaction approve implicitly, every other approval must be a valid
+ * signature of `message` from a distinct owner
+ */
+fn approvers(
+    message: &str,
+    approvals: &[Approval],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> usize {
+    let mut approvers: HashSet<String> = HashSet::new();
+
+    if config.is_owner(&transaction.public_key) {
+        approvers.insert(transaction.public_key.to_lowercase());
+    }
+    for approval in approvals {
+        if config.is_owner(&approval.signer)
+            && verify_personal_signature(&approval.signer, message, &approval.signature)
+        {
+            approvers.insert(approval.signer.to_lowercase());
+        }
+    }
+
+    approvers.len()
+}
+
+/**
+ * Rejects unless `owner_threshold` owners approved `message`, for every action that takes the
+ * co-owners' consent
+ */
+pub fn check_owner_threshold(
+    message: &str,
+    approvals: &[Approval],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<(), W3WallError> {
+    let threshold = config.owner_threshold.max(1);
+    let approved = approvers(message, approvals, transaction, config);
+    if approved < threshold {
+        return Err(W3WallError::Rejected(format!(
+            "Action needs {} owner approvals, got {}.",
+            threshold, approved
+        )));
+    }
+    Ok(())
+}
+
+/**
+ * Removes a post, bans a key or locks the wall once `owner_threshold` owners approved
+ */
+pub fn moderate(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can take moderation actions.",
+        ));
+    }
+
+    let request: ModerationRequest = serde_json::from_str(&transaction.data)?;
+    if request.action != ModerationAction::Lock && request.target.is_empty() {
+        return Err(W3WallError::rejected("Moderation target is missing."));
+    }
+
+    let message = moderation_message(
+        &transaction.data_key,
+        &moderation_cid(contract, metadatas),
+        request.action,
+        &request.target,
+    );
+    check_owner_threshold(&message, &request.approvals, transaction, config)?;
+
+    let audit = audit_record(
+        contract,
+        metadatas,
+        transaction,
+        request.action.as_str(),
+        &request.target,
+        &request.reason,
+    )?;
+    let mut state = load_moderation(contract, metadatas)?.unwrap_or_default();
+    match request.action {
+        ModerationAction::Lock => state.locked = true,
+        ModerationAction::Ban => {
+            if !state.is_banned(&request.target) {
+                state.banned.push(request.target.to_lowercase());
+            }
+        }
+        ModerationAction::Remove => {
+            if !state.removed.contains(&request.target) {
+                state.removed.push(request.target);
+            }
+        }
+    }
+
+    Ok(vec![
+        FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: MODERATION_ALIAS.to_string(),
+            content: serde_json::to_string(&state)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        audit,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::test_wallet;
+    use crate::data::ModerationState;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn config() -> WallConfig {
+        WallConfig {
+            owner: "0xowner".to_string(),
+            moderators: vec!["0xmod".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn transaction(public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: "set_filter".to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn moderators_set_a_normalized_list() {
+        let data = r#"{"words": [" Griefer", "griefer", "", "noob"]}"#;
+        let finals = set_filter(&contract(), &transaction("0xMOD", data), &config()).unwrap();
+        assert_eq!(finals[0].alias, FILTER_ALIAS);
+        assert_eq!(finals[0].public_key, "0xowner");
+        assert_eq!(finals[0].content, r#"{"words":["griefer","noob"]}"#);
+    }
+
+    #[test]
+    fn strangers_and_oversized_lists_are_rejected() {
+        assert!(set_filter(
+            &contract(),
+            &transaction("0xa", r#"{"words": []}"#),
+            &config()
+        )
+        .is_err());
+
+        let words: Vec<String> = (0..=DEFAULT_MAX_FILTER_WORDS)
+            .map(|i| i.to_string())
+            .collect();
+        let data = serde_json::json!({ "words": words }).to_string();
+        assert!(set_filter(&contract(), &transaction("0xowner", &data), &config()).is_err());
+    }
+
+    fn multisig_config() -> WallConfig {
+        WallConfig {
+            owner: test_wallet::address(1),
+            owners: vec![test_wallet::address(2), test_wallet::address(3)],
+            owner_threshold: 2,
+            ..Default::default()
+        }
+    }
+
+    fn ban(approvals: serde_json::Value) -> Transaction {
+        Transaction {
+            method: "moderate".to_string(),
+            public_key: test_wallet::address(1),
+            data_key: "wall".to_string(),
+            data:
+                serde_json::json!({ "action": "ban", "target": "0xTroll", "approvals": approvals })
+                    .to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn destructive_actions_need_the_owner_threshold() {
+        let message = moderation_message("wall", "", ModerationAction::Ban, "0xTroll");
+        let finals = moderate(
+            &contract(),
+            &[],
+            &ban(serde_json::json!([{
+                "signer": test_wallet::address(2),
+                "signature": test_wallet::sign(2, &message)
+            }])),
+            &multisig_config(),
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, MODERATION_ALIAS);
+        let state: ModerationState = serde_json::from_str(&finals[0].content).unwrap();
+        assert!(state.is_banned("0xtroll"));
+        assert_eq!(finals[1].alias, "w3wall.audit.0");
+        assert!(finals[1]
+            .content
+            .contains(r#""action":"ban","target":"0xTroll""#));
+
+        // the sender's own approval and a stranger's signature don't add up to two
+        let replayed = serde_json::json!([
+            { "signer": test_wallet::address(1), "signature": test_wallet::sign(1, &message) },
+            { "signer": test_wallet::address(9), "signature": test_wallet::sign(9, &message) }
+        ]);
+        assert!(moderate(&contract(), &[], &ban(replayed), &multisig_config()).is_err());
+    }
+
+    #[test]
+    fn approvals_are_bound_to_the_moderation_state() {
+        let state = Metadata {
+            alias: MODERATION_ALIAS.to_string(),
+            public_key: "0xowner".to_string(),
+            cid: "moderation-state-1".to_string(),
+            ..Default::default()
+        };
+        crate::storage::ipfs::test_store::put("moderation-state-1", r#"{"banned": ["0xa"]}"#);
+
+        // signed before the first action, no longer valid once there is a moderation record
+        let stale = moderation_message("wall", "", ModerationAction::Ban, "0xTroll");
+        let approvals = |message: &str| {
+            serde_json::json!([{
+                "signer": test_wallet::address(2),
+                "signature": test_wallet::sign(2, message)
+            }])
+        };
+        let metadatas = std::slice::from_ref(&state);
+        let err = moderate(
+            &contract(),
+            metadatas,
+            &ban(approvals(&stale)),
+            &multisig_config(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Action needs 2 owner approvals, got 1.");
+
+        let fresh = moderation_message(
+            "wall",
+            "moderation-state-1",
+            ModerationAction::Ban,
+            "0xTroll",
+        );
+        let finals = moderate(
+            &contract(),
+            metadatas,
+            &ban(approvals(&fresh)),
+            &multisig_config(),
+        )
+        .unwrap();
+        let state: ModerationState = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(state.banned, vec!["0xa", "0xtroll"]);
+    }
+
+    #[test]
+    fn unreadable_moderation_state_is_not_overwritten() {
+        let unreadable = Metadata {
+            alias: MODERATION_ALIAS.to_string(),
+            public_key: "0xowner".to_string(),
+            cid: "moderation-not-stored".to_string(),
+            ..Default::default()
+        };
+        let mut transaction = ban(serde_json::json!([]));
+        transaction.public_key = "0xowner".to_string();
+        let err = moderate(&contract(), &[unreadable], &transaction, &config()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The w3wall.moderation record can't be read, try again later."
+        );
+    }
+
+    #[test]
+    fn strangers_cannot_moderate() {
+        let mut transaction = ban(serde_json::json!([]));
+        transaction.public_key = "0xstranger".to_string();
+        assert!(moderate(&contract(), &[], &transaction, &WallConfig::default()).is_err());
+    }
+}
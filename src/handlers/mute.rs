@@ -0,0 +1,64 @@
+use crate::data::MutedWords;
+use crate::defaults::{DEFAULT_MAX_MUTED_WORDS, DEFAULT_MAX_MUTED_WORD_LENGTH};
+use crate::error::W3WallError;
+use crate::storage::muted::MUTED_ALIAS;
+use crate::types::{FinalMetadata, Transaction};
+
+/**
+ * Replaces the sender's muted terms, replies to them using one get a `muted_for` marker
+ */
+pub fn mute(transaction: &Transaction) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut list: MutedWords = serde_json::from_str(&transaction.data)?;
+    list.muted = list
+        .muted
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    list.muted.sort();
+    list.muted.dedup();
+
+    if list.muted.len() > DEFAULT_MAX_MUTED_WORDS {
+        return Err(W3WallError::Rejected(format!(
+            "Muted words are limited to {}.",
+            DEFAULT_MAX_MUTED_WORDS
+        )));
+    }
+    if list
+        .muted
+        .iter()
+        .any(|w| w.chars().count() > DEFAULT_MAX_MUTED_WORD_LENGTH)
+    {
+        return Err(W3WallError::Rejected(format!(
+            "A muted word is limited to {} characters.",
+            DEFAULT_MAX_MUTED_WORD_LENGTH
+        )));
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: MUTED_ALIAS.to_string(),
+        content: serde_json::to_string(&list)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn muted_words_are_normalized_and_owned_by_the_sender() {
+        let transaction = Transaction {
+            method: "mute".to_string(),
+            public_key: "0xMe".to_string(),
+            data: r#"{"muted": ["Wen Moon", "wen moon ", " "]}"#.to_string(),
+            ..Default::default()
+        };
+        let finals = mute(&transaction).unwrap();
+        assert_eq!(finals[0].public_key, "0xMe");
+        assert_eq!(finals[0].alias, MUTED_ALIAS);
+        assert_eq!(finals[0].content, r#"{"muted":["wen moon"]}"#);
+    }
+}
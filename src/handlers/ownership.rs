@@ -0,0 +1,278 @@
+use crate::crypto::eth_address::is_address;
+use crate::data::{Approval, WallConfig};
+use crate::error::W3WallError;
+use crate::handlers::moderation::check_owner_threshold;
+use crate::metadata::timestamp_sec;
+use crate::storage::config::CONFIG_ALIAS;
+use crate::storage::{find_record, read_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static OWNERSHIP_ALIAS: &str = "w3wall.ownership";
+
+/**
+ * Two-step handover: the owner offers the wall to `to`, who becomes owner only once they accept.
+ * A new offer replaces a pending one.
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct OwnershipTransfer {
+    pub from: String,
+    pub to: String,
+    pub offered: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted: Option<u64>,
+}
+
+impl OwnershipTransfer {
+    pub fn is_pending(&self) -> bool {
+        !self.to.is_empty() && self.accepted.is_none()
+    }
+}
+
+#[derive(Deserialize)]
+struct Offer {
+    new_owner: String,
+    #[serde(default)]
+    approvals: Vec<Approval>,
+}
+
+/**
+ * What co-owners sign to approve handing the wall to `new_owner`, bound to the wall and to the
+ * ownership record they saw so an approval is good for one offer
+ */
+pub fn transfer_message(data_key: &str, transfer_cid: &str, new_owner: &str) -> String {
+    format!(
+        "w3wall:transfer_ownership:{}:{}:{}",
+        data_key,
+        transfer_cid,
+        new_owner.to_lowercase()
+    )
+}
+
+pub fn load_transfer(contract: &MetaContract, metadatas: &[Metadata]) -> OwnershipTransfer {
+    read_record(metadatas, OWNERSHIP_ALIAS, &contract.public_key).unwrap_or_default()
+}
+
+fn transfer_record(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    transfer: &OwnershipTransfer,
+) -> Result<FinalMetadata, W3WallError> {
+    Ok(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: OWNERSHIP_ALIAS.to_string(),
+        content: serde_json::to_string(transfer)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    })
+}
+
+/**
+ * First step, only the current owner can offer the wall, and on a multi-owner wall only with
+ * `owner_threshold` owner approvals like any destructive action
+ */
+pub fn transfer_ownership(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.owner.eq_ignore_ascii_case(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only the wall owner can transfer ownership.",
+        ));
+    }
+
+    let offer: Offer = serde_json::from_str(&transaction.data)?;
+    if !is_address(&offer.new_owner) {
+        return Err(W3WallError::rejected("New owner is not a valid address."));
+    }
+    if offer.new_owner.eq_ignore_ascii_case(&config.owner) {
+        return Err(W3WallError::rejected("New owner is already the owner."));
+    }
+    let transfer_cid = find_record(metadatas, OWNERSHIP_ALIAS, &contract.public_key)
+        .map(|record| record.cid.as_str())
+        .unwrap_or_default();
+    let message = transfer_message(&transaction.data_key, transfer_cid, &offer.new_owner);
+    check_owner_threshold(&message, &offer.approvals, transaction, config)?;
+
+    let transfer = OwnershipTransfer {
+        from: config.owner.clone(),
+        to: offer.new_owner,
+        offered: timestamp_sec(transaction.timestamp),
+        accepted: None,
+    };
+
+    Ok(vec![transfer_record(contract, transaction, &transfer)?])
+}
+
+/**
+ * Second step, the offered key takes over the config. The previous owner stays a co-owner only
+ * if the config says so.
+ */
+pub fn accept_ownership(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut transfer = load_transfer(contract, metadatas);
+    if !transfer.is_pending() || !transfer.to.eq_ignore_ascii_case(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "No ownership transfer is pending for this key.",
+        ));
+    }
+    if !transfer.from.eq_ignore_ascii_case(&config.owner) {
+        return Err(W3WallError::rejected(
+            "Ownership changed since the transfer was offered.",
+        ));
+    }
+
+    let mut config = config.clone();
+    config.owner = transaction.public_key.clone();
+    transfer.accepted = Some(timestamp_sec(transaction.timestamp));
+
+    Ok(vec![
+        FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: CONFIG_ALIAS.to_string(),
+            content: serde_json::to_string(&config)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        transfer_record(contract, transaction, &transfer)?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    const NEW_OWNER: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn config() -> WallConfig {
+        WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(method: &str, public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn owner_offers_and_new_owner_accepts() {
+        let offer = serde_json::json!({ "new_owner": NEW_OWNER }).to_string();
+        assert!(
+            transfer_ownership(&contract(), &[], &transaction("", "0xa", &offer), &config())
+                .is_err()
+        );
+
+        let finals = transfer_ownership(
+            &contract(),
+            &[],
+            &transaction("", "0xowner", &offer),
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, OWNERSHIP_ALIAS);
+        test_store::put("ownership-cid", &finals[0].content);
+        let pending = Metadata {
+            alias: OWNERSHIP_ALIAS.to_string(),
+            public_key: "0xcontract".to_string(),
+            cid: "ownership-cid".to_string(),
+            ..Default::default()
+        };
+
+        // nobody but the offered key can accept
+        assert!(accept_ownership(
+            &contract(),
+            std::slice::from_ref(&pending),
+            &transaction("", "0xowner", ""),
+            &config()
+        )
+        .is_err());
+
+        let finals = accept_ownership(
+            &contract(),
+            &[pending],
+            &transaction("", NEW_OWNER, ""),
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, CONFIG_ALIAS);
+        let stored: WallConfig = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(stored.owner, NEW_OWNER);
+        let transfer: OwnershipTransfer = serde_json::from_str(&finals[1].content).unwrap();
+        assert_eq!(transfer.accepted, Some(1_700_000_000));
+        assert!(!transfer.is_pending());
+    }
+
+    #[test]
+    fn multi_owner_walls_need_the_threshold_to_transfer() {
+        use crate::crypto::test_wallet;
+
+        let config = WallConfig {
+            owner: test_wallet::address(1),
+            owners: vec![test_wallet::address(2), test_wallet::address(3)],
+            owner_threshold: 2,
+            ..Default::default()
+        };
+        let new_owner = test_wallet::address(4);
+        let offer = |signer: u64| {
+            let message = transfer_message("", "", &new_owner);
+            serde_json::json!({
+                "new_owner": new_owner,
+                "approvals": [{
+                    "signer": test_wallet::address(signer),
+                    "signature": test_wallet::sign(signer, &message)
+                }]
+            })
+            .to_string()
+        };
+        let owner = test_wallet::address(1);
+
+        // the owner's own signature doesn't count twice, a stranger's doesn't count at all
+        for signer in [1, 9] {
+            let err = transfer_ownership(
+                &contract(),
+                &[],
+                &transaction("", &owner, &offer(signer)),
+                &config,
+            )
+            .unwrap_err();
+            assert_eq!(err.to_string(), "Action needs 2 owner approvals, got 1.");
+        }
+        let finals = transfer_ownership(
+            &contract(),
+            &[],
+            &transaction("", &owner, &offer(3)),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, OWNERSHIP_ALIAS);
+    }
+
+    #[test]
+    fn accepting_without_an_offer_is_rejected() {
+        assert!(
+            accept_ownership(&contract(), &[], &transaction("", NEW_OWNER, ""), &config()).is_err()
+        );
+    }
+}
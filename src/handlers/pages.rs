@@ -0,0 +1,138 @@
+use crate::defaults::DEFAULT_PAGE_SIZE;
+use crate::error::W3WallError;
+use crate::storage::alias::Alias;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static PAGES_ALIAS: &str = "w3wall.pages";
+pub static PAGE_ALIAS_PREFIX: &str = "w3wall.page.";
+
+pub fn page_alias(page: usize) -> String {
+    format!("{}{}", PAGE_ALIAS_PREFIX, page)
+}
+
+/**
+ * How many posts and pages the page records cover, page N is at `w3wall.page.N`
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct PageIndex {
+    pub page_size: usize,
+    pub pages: usize,
+    pub posts: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PageEntry {
+    pub cid: String,
+    pub author: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Page {
+    pub page: usize,
+    pub posts: Vec<PageEntry>,
+}
+
+/**
+ * Groups the posts into pages in the order they were written. Posts are only ever appended, so
+ * pages before the last one already indexed are left as they are.
+ */
+pub fn refresh_pages(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let indexed: PageIndex =
+        read_record(metadatas, PAGES_ALIAS, &contract.public_key).unwrap_or_default();
+    let posts: Vec<&Metadata> = metadatas
+        .iter()
+        .filter(|m| m.alias == Alias::Post)
+        .collect();
+
+    // a page size change invalidates every page
+    let first = if indexed.page_size == DEFAULT_PAGE_SIZE {
+        indexed.posts / DEFAULT_PAGE_SIZE
+    } else {
+        0
+    };
+
+    let mut finals = vec![];
+    for (page, chunk) in posts.chunks(DEFAULT_PAGE_SIZE).enumerate().skip(first) {
+        let record = Page {
+            page,
+            posts: chunk
+                .iter()
+                .map(|m| PageEntry {
+                    cid: m.cid.clone(),
+                    author: m.public_key.clone(),
+                })
+                .collect(),
+        };
+        finals.push(FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias: page_alias(page),
+            content: serde_json::to_string(&record)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        });
+    }
+
+    let index = PageIndex {
+        page_size: DEFAULT_PAGE_SIZE,
+        pages: posts.len().div_ceil(DEFAULT_PAGE_SIZE),
+        posts: posts.len(),
+    };
+    finals.push(FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: PAGES_ALIAS.to_string(),
+        content: serde_json::to_string(&index)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    });
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn posts(count: usize) -> Vec<Metadata> {
+        (0..count)
+            .map(|i| Metadata {
+                public_key: "0xabc".to_string(),
+                cid: format!("page-post-{}", i),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_pages_past_the_index_are_rewritten() {
+        let contract = MetaContract::default();
+        let mut metadatas = posts(DEFAULT_PAGE_SIZE * 2 + 1);
+
+        let finals = refresh_pages(&contract, &metadatas, &Transaction::default()).unwrap();
+        assert_eq!(finals.len(), 4);
+        assert_eq!(finals[2].alias, "w3wall.page.2");
+        let last: Page = serde_json::from_str(&finals[2].content).unwrap();
+        assert_eq!(
+            last.posts[0].cid,
+            format!("page-post-{}", DEFAULT_PAGE_SIZE * 2)
+        );
+        let index: PageIndex = serde_json::from_str(&finals[3].content).unwrap();
+        assert_eq!((index.pages, index.posts), (3, DEFAULT_PAGE_SIZE * 2 + 1));
+
+        test_store::put("page-index", &finals[3].content);
+        metadatas.push(Metadata {
+            alias: PAGES_ALIAS.to_string(),
+            cid: "page-index".to_string(),
+            ..Default::default()
+        });
+        let finals = refresh_pages(&contract, &metadatas, &Transaction::default()).unwrap();
+        assert_eq!(finals.len(), 2);
+        assert_eq!(finals[0].alias, "w3wall.page.2");
+    }
+}
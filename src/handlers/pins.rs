@@ -0,0 +1,213 @@
+use crate::data::WallConfig;
+use crate::defaults::DEFAULT_MAX_PIN_PRIORITIES;
+use crate::error::W3WallError;
+use crate::metadata::karma::{Karma, ReactionFilter};
+use crate::metadata::timestamp_sec;
+use crate::storage::alias::Alias;
+use crate::storage::moderation::load_moderation;
+use crate::storage::read_content;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::image::canonical_uri;
+use crate::validation::media::is_cid;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+pub static PIN_PRIORITY_ALIAS: &str = "w3wall.pin_priority";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PinPriority {
+    pub cid: String,
+    /**
+     * Sum over the posts using the CID of one, plus their author's counted reactions, plus
+     * their replies
+     */
+    pub score: u64,
+    pub posts: u64,
+}
+
+/**
+ * Media CIDs worth keeping hot, highest score first, for pinning services to follow
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct PinPriorities {
+    pub pins: Vec<PinPriority>,
+    pub computed_at: u64,
+}
+
+/**
+ * The CID behind an ipfs:// or gateway link or a bare CID, its path left off
+ */
+fn media_cid(link: &str) -> Option<String> {
+    let uri = match link.strip_prefix("ipfs://") {
+        Some(_) => link.to_string(),
+        None => canonical_uri(link)?,
+    };
+    let cid = uri.strip_prefix("ipfs://")?.split('/').next()?;
+    is_cid(cid).then(|| cid.to_string())
+}
+
+/**
+ * Every media CID a post references: its image, thumbnail, article cover, sticker and
+ * attachments
+ */
+fn post_media(payload: &Value) -> Vec<String> {
+    let mut links: Vec<&str> = ["image", "thumbnail", "cover"]
+        .iter()
+        .filter_map(|field| payload[*field].as_str())
+        .collect();
+    links.extend(payload["sticker"]["cid"].as_str());
+    links.extend(
+        payload["attachments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|attachment| attachment["cid"].as_str()),
+    );
+    let mut cids: Vec<String> = links.into_iter().filter_map(media_cid).collect();
+    cids.sort();
+    cids.dedup();
+    cids
+}
+
+/**
+ * Ranks the media of the wall's posts into `w3wall.pin_priority`. Reactions go to authors
+ * rather than posts, so a post weighs its author's counted reactions. Removed and private
+ * posts are left out.
+ */
+pub fn refresh_pins(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let now = timestamp_sec(transaction.timestamp);
+    let state = load_moderation(contract, metadatas)?.unwrap_or_default();
+
+    let mut replies: HashMap<String, u64> = HashMap::new();
+    let mut posts = vec![];
+    for post in metadatas
+        .iter()
+        .filter(|m| m.alias == Alias::Post && !state.removed.contains(&m.cid))
+    {
+        let payload: Value = read_content(post)
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if let Some(parent) = payload["reply_to"].as_str() {
+            *replies.entry(parent.to_string()).or_default() += 1;
+        }
+        let media = post_media(&payload);
+        if payload["visibility"].as_str() != Some("private") && !media.is_empty() {
+            posts.push((post, media));
+        }
+    }
+
+    let filter = ReactionFilter::new(metadatas, &config.karma, now);
+    let mut reactions: HashMap<String, u64> = HashMap::new();
+    let mut scores: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for (post, media) in posts {
+        let author = post.public_key.to_lowercase();
+        let reactions = *reactions
+            .entry(author.clone())
+            .or_insert_with(|| Karma::of(metadatas, &author, &filter).reactions);
+        let weight = 1 + reactions + replies.get(&post.cid).copied().unwrap_or_default();
+        for cid in media {
+            let (score, count) = scores.entry(cid).or_default();
+            *score += weight;
+            *count += 1;
+        }
+    }
+
+    let mut pins: Vec<PinPriority> = scores
+        .into_iter()
+        .map(|(cid, (score, posts))| PinPriority { cid, score, posts })
+        .collect();
+    // the sort is stable and the map sorted by cid, so ties stay in cid order
+    pins.sort_by_key(|pin| std::cmp::Reverse(pin.score));
+    pins.truncate(DEFAULT_MAX_PIN_PRIORITIES);
+
+    let priorities = PinPriorities {
+        pins,
+        computed_at: now,
+    };
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: PIN_PRIORITY_ALIAS.to_string(),
+        content: serde_json::to_string(&priorities)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    const IMAGE: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+    const COVER: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    fn post(cid: &str, author: &str, body: &str) -> Metadata {
+        test_store::put(cid, body);
+        Metadata {
+            public_key: author.to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn media_of_popular_posts_ranks_first() {
+        let metadatas = vec![
+            post(
+                "pins-1",
+                "0xa",
+                &format!(r#"{{"image": "ipfs://{}/full.png"}}"#, IMAGE),
+            ),
+            post(
+                "pins-2",
+                "0xb",
+                &format!(
+                    r#"{{"type": "article", "cover": "{}", "image": "{}"}}"#,
+                    COVER, IMAGE
+                ),
+            ),
+            post("pins-3", "0xc", r#"{"text": "nice", "reply_to": "pins-2"}"#),
+            post(
+                "pins-4",
+                "0xc",
+                &format!(r#"{{"visibility": "private", "image": "{}"}}"#, COVER),
+            ),
+            Metadata {
+                alias: "w3wall.reaction.0xb".to_string(),
+                public_key: "0xa".to_string(),
+                ..Default::default()
+            },
+        ];
+        let finals = refresh_pins(
+            &MetaContract::default(),
+            &metadatas,
+            &Transaction::default(),
+            &WallConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, PIN_PRIORITY_ALIAS);
+        let priorities: PinPriorities = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(
+            priorities.pins,
+            [
+                PinPriority {
+                    cid: IMAGE.to_string(),
+                    score: 4,
+                    posts: 2,
+                },
+                PinPriority {
+                    cid: COVER.to_string(),
+                    score: 3,
+                    posts: 1,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::budget;
+use crate::data::WallConfig;
+use crate::moderation::WordFilter;
+use crate::types::{Transaction, ValidationReport};
+use crate::validation::migration::migrate;
+use crate::validation::{self, PostContext, ValidationError};
+
+/**
+ * Same checks on_execute runs on a post, without emitting any metadata, so clients can
+ * surface errors before the user signs. Wall specific config is not known here, the defaults apply.
+ */
+pub fn validate(data: &str) -> ValidationReport {
+    let transaction = Transaction {
+        data: data.to_string(),
+        ..Default::default()
+    };
+    let config = WallConfig::default();
+
+    budget::start();
+    let mut warnings = vec![];
+    let outcome = validation::check_payload_size(data)
+        .and_then(|_| validation::parse_payload(data))
+        .and_then(migrate)
+        .and_then(|(payload, _)| {
+            let ctx = PostContext {
+                transaction: &transaction,
+                metadatas: &[],
+                wall: "",
+                config: &config,
+                filter: &WordFilter::Global,
+                payload: &payload,
+            };
+            validation::run_pipeline(&validation::post_validators(), &ctx)?;
+            warnings = validation::advisories(&ctx);
+            Ok(())
+        })
+        .and_then(|_| {
+            if budget::overrun() {
+                return Err(ValidationError {
+                    code: "work_budget",
+                    message: "Post needs more work than a wall allows.".to_string(),
+                });
+            }
+            Ok(())
+        });
+
+    report(outcome, warnings)
+}
+
+fn report(
+    outcome: Result<(), ValidationError>,
+    warnings: Vec<ValidationError>,
+) -> ValidationReport {
+    match outcome {
+        Ok(()) => ValidationReport {
+            valid: true,
+            warnings: warnings.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        },
+        Err(e) => ValidationReport {
+            valid: false,
+            code: e.code.to_string(),
+            error_string: e.message,
+            warnings: vec![],
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_code_and_message() {
+        assert!(validate(r#"{"text": "gm"}"#).valid);
+
+        let report = validate(r#"{"image": "https://example.com/x.png"}"#);
+        assert!(!report.valid);
+        assert_eq!(report.code, "schema");
+        assert_eq!(
+            report.error_string,
+            "Matches none of the allowed forms at /image (schema /properties/image/anyOf)"
+        );
+    }
+
+    #[test]
+    fn valid_posts_still_carry_warnings() {
+        let image = "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e";
+        let report = validate(&format!(r#"{{"text": "gm", "image": "{}"}}"#, image));
+        assert!(report.valid);
+        assert_eq!(
+            report.warnings,
+            ["[missing_alt] The image has no alt text for readers who cannot see it."]
+        );
+        let described = format!(r#"{{"text": "gm", "image": "{}", "alt": "a cat"}}"#, image);
+        assert!(validate(&described).warnings.is_empty());
+    }
+}
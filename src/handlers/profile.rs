@@ -0,0 +1,240 @@
+use crate::crypto::eth_address::{is_address, short_address};
+use crate::data::{ProfanityAction, WallConfig};
+use crate::defaults::{
+    DEFAULT_MAX_BIO_LENGTH, DEFAULT_MAX_DISPLAY_NAME_LENGTH, DEFAULT_MAX_PROFILE_LINKS,
+    DEFAULT_MAX_PROFILE_LINK_LENGTH, DEFAULT_RESERVED_NAMES,
+};
+use crate::error::W3WallError;
+use crate::moderation::confusables::skeleton;
+use crate::moderation::WordFilter;
+use crate::storage::read_content;
+use crate::types::{FinalMetadata, Metadata, Transaction};
+use crate::validation::image::check_image;
+use serde::{Deserialize, Serialize};
+
+pub static PROFILE_ALIAS: &str = "w3wall.profile";
+
+/**
+ * What clients show for a key, written by the key itself
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    pub bio: String,
+    pub links: Vec<String>,
+}
+
+fn is_profile_link(link: &str) -> bool {
+    link.len() <= DEFAULT_MAX_PROFILE_LINK_LENGTH
+        && link
+            .strip_prefix("https://")
+            .is_some_and(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+}
+
+/**
+ * Whether `name` has a reserved word in it, `Team Аdmin` and `ad.min` as much as `admin`
+ */
+fn is_reserved(name: &str, config: &WallConfig) -> bool {
+    let reserved: Vec<String> = DEFAULT_RESERVED_NAMES
+        .iter()
+        .copied()
+        .chain(config.reserved_names.iter().map(String::as_str))
+        .map(skeleton)
+        .filter(|word| !word.is_empty())
+        .collect();
+    name.split(|c: char| c.is_whitespace() || c == '_' || c == '-' || c == '.')
+        .map(skeleton)
+        .chain([skeleton(name)])
+        .any(|word| reserved.contains(&word))
+}
+
+/**
+ * Display names must not pass for someone else: reserved names are kept for owners and
+ * moderators, an address for its own key, and a name reading like another profile's on the wall
+ * for that profile's key
+ */
+fn check_display_name(
+    name: &str,
+    sender: &str,
+    metadatas: &[Metadata],
+    config: &WallConfig,
+) -> Result<(), W3WallError> {
+    if name.is_empty() {
+        return Ok(());
+    }
+    if is_address(name) && !name.eq_ignore_ascii_case(sender) {
+        return Err(W3WallError::rejected(
+            "Display name is the address of another key.",
+        ));
+    }
+    if !config.is_moderator(sender) && is_reserved(name, config) {
+        return Err(W3WallError::Rejected(format!(
+            "Display name {} is reserved on this wall.",
+            name
+        )));
+    }
+
+    let name = skeleton(name);
+    let taken_by = metadatas
+        .iter()
+        .filter(|m| m.alias == PROFILE_ALIAS && !m.public_key.eq_ignore_ascii_case(sender))
+        .find(|m| {
+            read_content(m)
+                .and_then(|content| serde_json::from_str::<Profile>(&content).ok())
+                .is_some_and(|profile| skeleton(&profile.display_name) == name)
+        });
+    match taken_by {
+        Some(profile) => Err(W3WallError::Rejected(format!(
+            "Display name reads like the one of {}.",
+            short_address(&profile.public_key)
+        ))),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Publishes the sender's profile. Name and bio go through the wall's word filter, rejected or
+ * masked like post text.
+ */
+pub fn set_profile(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut profile: Profile = serde_json::from_str(&transaction.data)?;
+    profile.display_name = profile.display_name.trim().to_string();
+    profile.bio = profile.bio.trim().to_string();
+
+    if profile.display_name.chars().count() > DEFAULT_MAX_DISPLAY_NAME_LENGTH {
+        return Err(W3WallError::rejected(
+            "Display name is limited to 50 characters.",
+        ));
+    }
+    check_display_name(
+        &profile.display_name,
+        &transaction.public_key,
+        metadatas,
+        config,
+    )?;
+    if profile.bio.chars().count() > DEFAULT_MAX_BIO_LENGTH {
+        return Err(W3WallError::rejected("Bio is limited to 300 characters."));
+    }
+    if let Some(avatar) = profile.avatar.as_deref() {
+        check_image(avatar, None, &config.image_types).map_err(W3WallError::rejected)?;
+    }
+    if profile.links.len() > DEFAULT_MAX_PROFILE_LINKS
+        || !profile.links.iter().all(|l| is_profile_link(l))
+    {
+        return Err(W3WallError::rejected(
+            "A profile has at most 5 https links.",
+        ));
+    }
+
+    for text in [&mut profile.display_name, &mut profile.bio] {
+        if !filter.is_profane(text) {
+            continue;
+        }
+        match config.rules.profanity_action {
+            ProfanityAction::Mask => *text = filter.mask(text).unwrap_or_default(),
+            _ => return Err(W3WallError::rejected("Profanity found in the profile.")),
+        }
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: PROFILE_ALIAS.to_string(),
+        content: serde_json::to_string(&profile)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(data: &str, config: &WallConfig) -> Result<Vec<FinalMetadata>, W3WallError> {
+        let transaction = Transaction {
+            method: "set_profile".to_string(),
+            public_key: "0xme".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        };
+        set_profile(
+            &[],
+            &transaction,
+            config,
+            &WordFilter::new(&["griefer".to_string()]),
+        )
+    }
+
+    #[test]
+    fn profiles_are_validated_and_filtered() {
+        let config = WallConfig::default();
+        let finals = profile(
+            r#"{"display_name": " gm ", "avatar": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "links": ["https://w3wall.xyz"]}"#,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(finals[0].public_key, "0xme");
+        assert_eq!(finals[0].alias, PROFILE_ALIAS);
+        assert_eq!(
+            finals[0].content,
+            r#"{"display_name":"gm","avatar":"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG","bio":"","links":["https://w3wall.xyz"]}"#
+        );
+
+        assert!(profile(r#"{"avatar": "https://evil.example/a.png"}"#, &config).is_err());
+        assert!(profile(r#"{"links": ["javascript:alert(1)"]}"#, &config).is_err());
+        assert!(profile(r#"{"bio": "proud griefer"}"#, &config).is_err());
+        assert!(profile(r#"{"banner": "x"}"#, &config).is_err());
+
+        let mut masking = WallConfig::default();
+        masking.rules.profanity_action = ProfanityAction::Mask;
+        let finals = profile(r#"{"bio": "proud griefer"}"#, &masking).unwrap();
+        assert!(finals[0].content.contains(r#""bio":"proud *******""#));
+    }
+
+    #[test]
+    fn display_names_cannot_impersonate() {
+        crate::storage::ipfs::test_store::put("profile-alice", r#"{"display_name": "alice.eth"}"#);
+        let metadatas = [Metadata {
+            public_key: "0xalice".to_string(),
+            alias: PROFILE_ALIAS.to_string(),
+            cid: "profile-alice".to_string(),
+            ..Default::default()
+        }];
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            reserved_names: vec!["Lineage".to_string()],
+            ..Default::default()
+        };
+        let name = |public_key: &str, display_name: &str| {
+            let transaction = Transaction {
+                public_key: public_key.to_string(),
+                data: serde_json::json!({ "display_name": display_name }).to_string(),
+                ..Default::default()
+            };
+            set_profile(&metadatas, &transaction, &config, &WordFilter::Global)
+                .map_err(|e| e.to_string())
+                .map(|_| ())
+        };
+
+        assert_eq!(
+            name("0xme", "Team Аdmin"),
+            Err("Display name Team Аdmin is reserved on this wall.".to_string())
+        );
+        assert!(name("0xme", "lineage_dev").is_err());
+        assert!(name("0xme", "badminton fan").is_ok());
+        assert!(name("0xowner", "admin").is_ok());
+        assert_eq!(
+            name("0xme", "A1ice.eth"),
+            Err("Display name reads like the one of 0xalice.".to_string())
+        );
+        assert!(name("0xalice", "Alice.eth").is_ok());
+        assert!(name("0xme", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+    }
+}
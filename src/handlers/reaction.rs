@@ -0,0 +1,137 @@
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::metadata::karma::{karma_record, reaction_alias, Karma, ReactionFilter};
+use crate::metadata::timestamp_sec;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Reaction {
+    target: String,
+    #[serde(default)]
+    reaction: String,
+}
+
+/**
+ * A reaction to the posts of `target`. Each key gets one reaction record per target, reacting
+ * again replaces it.
+ */
+pub fn react(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let reaction: Reaction = serde_json::from_str(&transaction.data)?;
+    let target = reaction.target.trim();
+
+    if target.is_empty() {
+        return Err(W3WallError::rejected("Reaction target is missing."));
+    }
+    if target.eq_ignore_ascii_case(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Reacting to yourself is not allowed.",
+        ));
+    }
+    if !config.reactions.is_empty() && !config.reactions.contains(&reaction.reaction) {
+        return Err(W3WallError::Rejected(format!(
+            "Reactions on this wall are one of {}.",
+            config.reactions.join(" ")
+        )));
+    }
+
+    let alias = reaction_alias(target);
+    let mut finals = vec![FinalMetadata {
+        public_key: transaction.public_key.clone(),
+        alias: alias.clone(),
+        content: serde_json::json!({ "target": target, "reaction": reaction.reaction }).to_string(),
+        loose: 1,
+        version: transaction.version.clone(),
+    }];
+
+    if config.karma.enabled {
+        let reactions = ReactionFilter::new(
+            metadatas,
+            &config.karma,
+            timestamp_sec(transaction.timestamp),
+        );
+        let mut karma = Karma::of(metadatas, target, &reactions);
+        let reacted_before = metadatas.iter().any(|m| {
+            m.alias == alias && m.public_key.eq_ignore_ascii_case(&transaction.public_key)
+        });
+        if !reacted_before && reactions.counts(&transaction.public_key) {
+            karma = Karma::new(karma.posts, karma.reactions + 1);
+        }
+        finals.push(karma_record(contract, target, &karma));
+    }
+
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: "react".to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reactions_update_the_target_karma() {
+        let mut config = WallConfig::default();
+        config.karma.enabled = true;
+        let data = r#"{"target": "0xB", "reaction": "+1"}"#;
+
+        let finals = react(
+            &MetaContract::default(),
+            &[],
+            &transaction("0xa", data),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, "w3wall.reaction.0xb");
+        assert_eq!(finals[1].alias, "w3wall.karma.0xb");
+        let karma: serde_json::Value = serde_json::from_str(&finals[1].content).unwrap();
+        assert_eq!(karma["reactions"], 1);
+    }
+
+    #[test]
+    fn walls_with_an_allowlist_only_take_its_emoji() {
+        let config = WallConfig {
+            reactions: vec!["👍".to_string(), "👩‍💻".to_string()],
+            ..Default::default()
+        };
+        let react_with = |reaction: &str| {
+            let data = serde_json::json!({ "target": "0xb", "reaction": reaction }).to_string();
+            react(
+                &MetaContract::default(),
+                &[],
+                &transaction("0xa", &data),
+                &config,
+            )
+        };
+        assert!(react_with("👩‍💻").is_ok());
+        assert_eq!(
+            react_with("👩").unwrap_err().to_string(),
+            "Reactions on this wall are one of 👍 👩‍💻."
+        );
+        assert!(react_with("+1").is_err());
+    }
+
+    #[test]
+    fn self_reactions_are_rejected() {
+        let data = r#"{"target": "0xA"}"#;
+        assert!(react(
+            &MetaContract::default(),
+            &[],
+            &transaction("0xa", data),
+            &WallConfig::default()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::defaults::DEFAULT_REDACTION_MARKER;
+use crate::error::W3WallError;
+use crate::handlers::audit::audit_record;
+use crate::metadata::timestamp_sec;
+use crate::storage::{find_post, find_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static REDACTION_ALIAS_PREFIX: &str = "w3wall.redaction.";
+
+pub fn redaction_alias(cid: &str) -> String {
+    format!("{}{}", REDACTION_ALIAS_PREFIX, cid)
+}
+
+/**
+ * Stands in for a redacted post's content. The post record keeps its place so replies and
+ * quotes still resolve, readers show `content` instead of the original
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Redaction {
+    pub post: String,
+    pub content: String,
+    pub redacted_by: String,
+    pub redacted_at: u64,
+}
+
+#[derive(Deserialize)]
+struct RedactRequest {
+    post: String,
+    #[serde(default)]
+    reason: String,
+}
+
+/**
+ * Only the author of a post can redact it, once
+ */
+pub fn redact(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: RedactRequest = serde_json::from_str(&transaction.data)?;
+
+    let post = find_post(metadatas, &request.post)
+        .ok_or_else(|| W3WallError::rejected("Post is not on this wall."))?;
+    if !post
+        .public_key
+        .eq_ignore_ascii_case(&transaction.public_key)
+    {
+        return Err(W3WallError::rejected("Only the author can redact a post."));
+    }
+
+    let alias = redaction_alias(&request.post);
+    if find_record(metadatas, &alias, &contract.public_key).is_some() {
+        return Err(W3WallError::rejected("Post is already redacted."));
+    }
+
+    let audit = audit_record(
+        contract,
+        metadatas,
+        transaction,
+        "redact",
+        &request.post,
+        &request.reason,
+    )?;
+    let redaction = Redaction {
+        post: request.post,
+        content: DEFAULT_REDACTION_MARKER.to_string(),
+        redacted_by: transaction.public_key.clone(),
+        redacted_at: timestamp_sec(transaction.timestamp),
+    };
+
+    Ok(vec![
+        FinalMetadata {
+            public_key: contract.public_key.clone(),
+            alias,
+            content: serde_json::to_string(&redaction)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        audit,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract() -> MetaContract {
+        MetaContract {
+            public_key: "0xcontract".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn wall() -> Vec<Metadata> {
+        vec![Metadata {
+            public_key: "0xauthor".to_string(),
+            cid: "post-cid".to_string(),
+            ..Default::default()
+        }]
+    }
+
+    fn transaction(public_key: &str) -> Transaction {
+        Transaction {
+            method: "redact".to_string(),
+            public_key: public_key.to_string(),
+            data: r#"{"post": "post-cid"}"#.to_string(),
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn authors_redact_their_own_posts_once() {
+        assert!(redact(&contract(), &wall(), &transaction("0xother")).is_err());
+
+        let finals = redact(&contract(), &wall(), &transaction("0xAUTHOR")).unwrap();
+        assert_eq!(finals[0].alias, "w3wall.redaction.post-cid");
+        assert_eq!(finals[0].public_key, "0xcontract");
+        let redaction: Redaction = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(redaction.content, DEFAULT_REDACTION_MARKER);
+        assert_eq!(redaction.redacted_at, 1_700_000_000);
+
+        let mut metadatas = wall();
+        metadatas.push(Metadata {
+            public_key: "0xcontract".to_string(),
+            alias: finals[0].alias.clone(),
+            ..Default::default()
+        });
+        assert_eq!(
+            redact(&contract(), &metadatas, &transaction("0xauthor"))
+                .unwrap_err()
+                .to_string(),
+            "Post is already redacted."
+        );
+    }
+}
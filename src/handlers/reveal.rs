@@ -0,0 +1,196 @@
+use crate::data::WallConfig;
+use crate::defaults::{
+    DEFAULT_MAX_DESCRIPTION_LENGTH, DEFAULT_MAX_MINT_BODY_LENGTH, DEFAULT_MAX_MINT_NAME_LENGTH,
+};
+use crate::error::W3WallError;
+use crate::metadata::{description, sanitize, timestamp_sec};
+use crate::storage::alias::Alias;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::image::check_image;
+use serde::{Deserialize, Serialize};
+
+pub static REVEAL_ALIAS: &str = "w3wall.reveal";
+
+/**
+ * Written when a topic is minted behind a placeholder, `revealed_at` stays 0 until the reveal
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct RevealState {
+    pub reveal_at: u64,
+    pub revealed_at: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RevealRequest {
+    name: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    body: String,
+}
+
+/**
+ * Owner swaps the placeholder name, image and body of a topic for the real ones, once its
+ * reveal time has passed. An empty image keeps the placeholder's.
+ */
+pub fn reveal(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only the wall owner can reveal a topic.",
+        ));
+    }
+
+    let state: RevealState = read_record(metadatas, REVEAL_ALIAS, &contract.public_key)
+        .ok_or_else(|| W3WallError::rejected("Topic is not waiting for a reveal."))?;
+    if state.revealed_at > 0 {
+        return Err(W3WallError::rejected("Topic is already revealed."));
+    }
+    let now = timestamp_sec(transaction.timestamp);
+    if now < state.reveal_at {
+        return Err(W3WallError::Rejected(format!(
+            "Topic can be revealed from {}.",
+            state.reveal_at
+        )));
+    }
+
+    let request: RevealRequest = serde_json::from_str(&transaction.data)?;
+    let title = sanitize(&request.name, DEFAULT_MAX_MINT_NAME_LENGTH, false);
+    let record = |alias: &str, content: String| FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: alias.to_string(),
+        content,
+        loose: 1,
+        version: transaction.version.clone(),
+    };
+
+    let mut finals = vec![record(Alias::Name.as_str(), title.clone())];
+    let image = request.image.trim();
+    if !image.is_empty() {
+        check_image(image, None, &config.image_types).map_err(W3WallError::rejected)?;
+        finals.push(record(Alias::Image.as_str(), image.to_string()));
+    }
+    finals.push(record(
+        Alias::Body.as_str(),
+        sanitize(&request.body, DEFAULT_MAX_MINT_BODY_LENGTH, true),
+    ));
+    finals.push(record(
+        Alias::Description.as_str(),
+        sanitize(
+            &description(
+                &config.description_template,
+                &title,
+                &contract.public_key,
+                0,
+            ),
+            DEFAULT_MAX_DESCRIPTION_LENGTH,
+            true,
+        ),
+    ));
+    finals.push(record(
+        REVEAL_ALIAS,
+        serde_json::to_string(&RevealState {
+            reveal_at: state.reveal_at,
+            revealed_at: now,
+        })?,
+    ));
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(public_key: &str, timestamp: u64, data: &str) -> Transaction {
+        Transaction {
+            method: "reveal".to_string(),
+            public_key: public_key.to_string(),
+            timestamp,
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn owner_reveals_once_the_time_has_come() {
+        let contract = MetaContract {
+            public_key: "0xwall".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        crate::storage::ipfs::test_store::put(
+            "reveal-pending",
+            r#"{"reveal_at":1000,"revealed_at":0}"#,
+        );
+        let pending = [Metadata {
+            alias: REVEAL_ALIAS.to_string(),
+            public_key: "0xwall".to_string(),
+            cid: "reveal-pending".to_string(),
+            ..Default::default()
+        }];
+        let data = r#"{"name": "The real\u202e topic", "body": "at last"}"#;
+
+        let err = reveal(
+            &contract,
+            &pending,
+            &transaction("0xother", 2000, data),
+            &config,
+        );
+        assert!(err.is_err());
+        let err = reveal(
+            &contract,
+            &pending,
+            &transaction("0xowner", 999, data),
+            &config,
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Topic can be revealed from 1000."
+        );
+        let err = reveal(&contract, &[], &transaction("0xowner", 2000, data), &config);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Topic is not waiting for a reveal."
+        );
+
+        let finals = reveal(
+            &contract,
+            &pending,
+            &transaction("0xowner", 2000, data),
+            &config,
+        )
+        .unwrap();
+        let aliases: Vec<&str> = finals.iter().map(|m| m.alias.as_str()).collect();
+        assert_eq!(aliases, ["name", "body", "description", REVEAL_ALIAS]);
+        assert_eq!(finals[0].content, "The real topic");
+        assert_eq!(
+            finals[3].content,
+            r#"{"reveal_at":1000,"revealed_at":2000}"#
+        );
+
+        crate::storage::ipfs::test_store::put(
+            "reveal-done",
+            r#"{"reveal_at":1000,"revealed_at":2000}"#,
+        );
+        let done = [Metadata {
+            cid: "reveal-done".to_string(),
+            ..pending[0].clone()
+        }];
+        let err = reveal(
+            &contract,
+            &done,
+            &transaction("0xowner", 3000, data),
+            &config,
+        );
+        assert_eq!(err.unwrap_err().to_string(), "Topic is already revealed.");
+    }
+}
@@ -0,0 +1,202 @@
+use crate::crypto::rng::Roll;
+use crate::defaults::{DEFAULT_STATS_ACTIVE_DAYS, DEFAULT_STATS_TOP_TAGS, MAX_STATS_TOP_TAGS};
+use crate::error::W3WallError;
+use crate::metadata::timestamp_sec;
+use crate::storage::alias::Alias;
+use crate::storage::envelope;
+use crate::storage::moderation::load_moderation;
+use crate::storage::read_content;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+pub static STATS_ALIAS: &str = "w3wall.stats";
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StatsRequest {
+    days: Option<u64>,
+    top: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u64,
+}
+
+/**
+ * Wall-level figures for dashboards, as of `computed_at`
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct WallStats {
+    pub posts: u64,
+    pub authors: u64,
+    /**
+     * Authors with a post created in the last `active_days`
+     */
+    pub active_authors: u64,
+    pub active_days: u64,
+    pub top_tags: Vec<TagCount>,
+    /**
+     * CID of a post picked by the refreshing transaction, so the featured post rotates
+     */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub featured: Option<String>,
+    pub computed_at: u64,
+}
+
+/**
+ * Counts the wall's posts into the `w3wall.stats` alias, removed posts left out. Posts stored
+ * before envelopes carry no creation time and never count as active.
+ */
+pub fn refresh_stats(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let request: StatsRequest = if transaction.data.trim().is_empty() {
+        StatsRequest::default()
+    } else {
+        serde_json::from_str(&transaction.data)?
+    };
+    let active_days = request.days.unwrap_or(DEFAULT_STATS_ACTIVE_DAYS);
+    let top = request
+        .top
+        .unwrap_or(DEFAULT_STATS_TOP_TAGS)
+        .min(MAX_STATS_TOP_TAGS);
+    let now = timestamp_sec(transaction.timestamp);
+    let since = now.saturating_sub(active_days.saturating_mul(24 * 60 * 60));
+    let state = load_moderation(contract, metadatas)?.unwrap_or_default();
+
+    let mut stats = WallStats {
+        active_days,
+        computed_at: now,
+        ..Default::default()
+    };
+    let mut authors = HashSet::new();
+    let mut active = HashSet::new();
+    let mut tags: BTreeMap<String, u64> = BTreeMap::new();
+    let mut public = vec![];
+    for post in metadatas
+        .iter()
+        .filter(|m| m.alias == Alias::Post && !state.removed.contains(&m.cid))
+    {
+        stats.posts += 1;
+        let author = post.public_key.to_lowercase();
+        if envelope::created_at(post).is_some_and(|created_at| created_at >= since) {
+            active.insert(author.clone());
+        }
+        authors.insert(author);
+
+        let payload: serde_json::Value = read_content(post)
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if payload["visibility"].as_str() != Some("private") {
+            public.push(post.cid.clone());
+        }
+        let post_tags: HashSet<String> = payload["tags"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.as_str())
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        for tag in post_tags {
+            *tags.entry(tag).or_default() += 1;
+        }
+    }
+    stats.authors = authors.len() as u64;
+    stats.active_authors = active.len() as u64;
+
+    let mut tags: Vec<TagCount> = tags
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    // the map is in tag order, a stable sort keeps it within equal counts
+    tags.sort_by_key(|tag| std::cmp::Reverse(tag.count));
+    tags.truncate(top);
+    stats.top_tags = tags;
+    stats.featured = Roll::from_transaction(transaction, "featured")
+        .pick(&public)
+        .cloned();
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: STATS_ALIAS.to_string(),
+        content: serde_json::to_string(&stats)?,
+        loose: 1,
+        version: "".to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    const DAY: u64 = 24 * 60 * 60;
+    const NOW: u64 = 1_700_000_000;
+
+    fn post(cid: &str, author: &str, created_at: u64, tags: &str) -> Metadata {
+        let body = format!(r#"{{"text": "gm", "tags": {}}}"#, tags);
+        test_store::put(
+            cid,
+            &format!(
+                r#"{{"v":1,"type":"post","author":"{}","created_at":{},"body":{},"refs":[]}}"#,
+                author, created_at, body
+            ),
+        );
+        Metadata {
+            public_key: author.to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stats_count_posts_active_authors_and_tags() {
+        let metadatas = vec![
+            post("stats-1", "0xa", NOW - DAY, r#"["Art", "gm"]"#),
+            post("stats-2", "0xb", NOW - 40 * DAY, r#"["art", "art"]"#),
+            post("stats-3", "0xc", NOW - 2 * DAY, r#"["dev"]"#),
+        ];
+        let transaction = Transaction {
+            method: "refresh_stats".to_string(),
+            data: r#"{"top": 2}"#.to_string(),
+            timestamp: NOW * 1000,
+            ..Default::default()
+        };
+        let finals = refresh_stats(&MetaContract::default(), &metadatas, &transaction).unwrap();
+        assert_eq!(finals[0].alias, STATS_ALIAS);
+        let mut stats: WallStats = serde_json::from_str(&finals[0].content).unwrap();
+
+        let featured = stats.featured.take().unwrap();
+        assert!(metadatas.iter().any(|m| m.cid == featured));
+        let again = refresh_stats(&MetaContract::default(), &metadatas, &transaction).unwrap();
+        assert_eq!(again[0].content, finals[0].content);
+        assert_eq!(
+            stats,
+            WallStats {
+                posts: 3,
+                authors: 3,
+                active_authors: 2,
+                active_days: DEFAULT_STATS_ACTIVE_DAYS,
+                top_tags: vec![
+                    TagCount {
+                        tag: "art".to_string(),
+                        count: 2
+                    },
+                    TagCount {
+                        tag: "dev".to_string(),
+                        count: 1
+                    },
+                ],
+                featured: None,
+                computed_at: NOW,
+            }
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::data::{StickerPack, WallConfig};
+use crate::defaults::{DEFAULT_MAX_STICKERS_PER_PACK, DEFAULT_MAX_STICKER_PACKS};
+use crate::error::W3WallError;
+use crate::handlers::template::is_template_name;
+use crate::metadata::timestamp_sec;
+use crate::storage::stickers::{load_sticker_registry, STICKERS_ALIAS};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::license::is_license;
+use crate::validation::media::is_cid;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct StickerPackRequest {
+    pack: String,
+    #[serde(default)]
+    stickers: Vec<String>,
+    #[serde(default)]
+    license: String,
+}
+
+fn check_pack(stickers: &[String], license: &str) -> Result<(), W3WallError> {
+    if stickers.is_empty() || stickers.len() > DEFAULT_MAX_STICKERS_PER_PACK {
+        return Err(W3WallError::Rejected(format!(
+            "A sticker pack holds 1 to {} stickers.",
+            DEFAULT_MAX_STICKERS_PER_PACK
+        )));
+    }
+    if let Some(sticker) = stickers.iter().find(|sticker| !is_cid(sticker)) {
+        return Err(W3WallError::Rejected(format!(
+            "Sticker {} is not a CID.",
+            sticker
+        )));
+    }
+    if !is_license(license) {
+        return Err(W3WallError::rejected(
+            "Sticker pack license is not supported.",
+        ));
+    }
+    Ok(())
+}
+
+/**
+ * Registers (`register_sticker_pack`) or retires (`retire_sticker_pack`) one of the wall's
+ * sticker packs. Owners only. Registering a name again replaces the pack, and brings it back
+ * if it was retired.
+ */
+pub fn set_sticker_pack(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can change sticker packs.",
+        ));
+    }
+
+    let request: StickerPackRequest = serde_json::from_str(&transaction.data)?;
+    if !is_template_name(&request.pack) {
+        return Err(W3WallError::rejected(
+            "Sticker pack name must be 1 to 32 lowercase letters, digits, - or _.",
+        ));
+    }
+
+    let mut registry = load_sticker_registry(metadatas, &contract.public_key);
+    if transaction.method == "retire_sticker_pack" {
+        match registry.packs.get_mut(&request.pack) {
+            Some(pack) if pack.retired_at.is_none() => {
+                pack.retired_at = Some(timestamp_sec(transaction.timestamp))
+            }
+            _ => return Err(W3WallError::rejected("Sticker pack is not registered.")),
+        }
+    } else {
+        let mut stickers = request.stickers;
+        stickers.dedup();
+        check_pack(&stickers, &request.license)?;
+        let pack = StickerPack {
+            stickers,
+            license: request.license,
+            retired_at: None,
+        };
+        registry.packs.insert(request.pack, pack);
+        if registry.active().count() > DEFAULT_MAX_STICKER_PACKS {
+            return Err(W3WallError::Rejected(format!(
+                "A wall is limited to {} sticker packs.",
+                DEFAULT_MAX_STICKER_PACKS
+            )));
+        }
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: STICKERS_ALIAS.to_string(),
+        content: serde_json::to_string(&registry)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::StickerRegistry;
+    use crate::storage::ipfs::test_store;
+
+    fn transaction(method: &str, public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn stored(finals: &[FinalMetadata], cid: &str) -> Vec<Metadata> {
+        test_store::put(cid, &finals[0].content);
+        vec![Metadata {
+            alias: finals[0].alias.clone(),
+            public_key: finals[0].public_key.clone(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn owners_register_and_retire_sticker_packs() {
+        let contract = MetaContract {
+            public_key: "0xwall".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let cid = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        let pack = format!(
+            r#"{{"pack": "frogs", "stickers": ["{}"], "license": "CC0"}}"#,
+            cid
+        );
+        let register =
+            |public_key: &str, data: &str| transaction("register_sticker_pack", public_key, data);
+
+        let finals =
+            set_sticker_pack(&contract, &[], &register("0xowner", &pack), &config).unwrap();
+        assert_eq!(finals[0].public_key, "0xwall");
+        let registry: StickerRegistry = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(registry.packs["frogs"].stickers, [cid]);
+        assert!(set_sticker_pack(&contract, &[], &register("0xother", &pack), &config).is_err());
+
+        let unlicensed = format!(r#"{{"pack": "frogs", "stickers": ["{}"]}}"#, cid);
+        assert!(
+            set_sticker_pack(&contract, &[], &register("0xowner", &unlicensed), &config).is_err()
+        );
+        let bad = r#"{"pack": "frogs", "stickers": ["https://x.y/frog.png"], "license": "CC0"}"#;
+        assert!(set_sticker_pack(&contract, &[], &register("0xowner", bad), &config).is_err());
+
+        let metadatas = stored(&finals, "stickers-1");
+        let retire = transaction("retire_sticker_pack", "0xowner", r#"{"pack": "frogs"}"#);
+        let finals = set_sticker_pack(&contract, &metadatas, &retire, &config).unwrap();
+        let registry: StickerRegistry = serde_json::from_str(&finals[0].content).unwrap();
+        assert!(registry.packs["frogs"].retired_at.is_some());
+        assert_eq!(registry.active().count(), 0);
+
+        let metadatas = stored(&finals, "stickers-2");
+        assert!(set_sticker_pack(&contract, &metadatas, &retire, &config).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::data::WallConfig;
+use crate::defaults::DEFAULT_MAX_SUMMARY_LENGTH;
+use crate::error::W3WallError;
+use crate::metadata::{sanitize, timestamp_sec};
+use crate::moderation::WordFilter;
+use crate::storage::alias::Alias;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static SUMMARY_ALIAS: &str = "w3wall.summary";
+
+/**
+ * Curated TL;DR of the thread, the topic's NFT description while it is set
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Summary {
+    pub text: String,
+    pub set_by: String,
+    pub set_at: u64,
+}
+
+#[derive(Deserialize)]
+struct SummaryRequest {
+    text: String,
+}
+
+pub fn load_summary(contract: &MetaContract, metadatas: &[Metadata]) -> Option<Summary> {
+    read_record::<Summary>(metadatas, SUMMARY_ALIAS, &contract.public_key)
+        .filter(|summary| !summary.text.is_empty())
+}
+
+/**
+ * Owners and moderators only. The summary replaces the topic description right away, and on
+ * every later refresh_metadata.
+ */
+pub fn set_summary(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    config: &WallConfig,
+    filter: &WordFilter,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_moderator(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners and moderators can summarize.",
+        ));
+    }
+
+    let request: SummaryRequest = serde_json::from_str(&transaction.data)?;
+    let text = sanitize(request.text.trim(), usize::MAX, true);
+    if text.is_empty() || text.chars().count() > DEFAULT_MAX_SUMMARY_LENGTH {
+        return Err(W3WallError::Rejected(format!(
+            "Summary must be 1 to {} characters.",
+            DEFAULT_MAX_SUMMARY_LENGTH
+        )));
+    }
+    // marketplaces show the description as is, so there is nothing to mask it with
+    if filter.is_profane(&text) {
+        return Err(W3WallError::rejected("Profanity found in the summary."));
+    }
+
+    let summary = Summary {
+        text: text.clone(),
+        set_by: transaction.public_key.clone(),
+        set_at: timestamp_sec(transaction.timestamp),
+    };
+    let record = |alias: &str, content: String| FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: alias.to_string(),
+        content,
+        loose: 1,
+        version: transaction.version.clone(),
+    };
+    Ok(vec![
+        record(SUMMARY_ALIAS, serde_json::to_string(&summary)?),
+        record(Alias::Description.as_str(), text),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(public_key: &str, text: &str) -> Transaction {
+        Transaction {
+            method: "set_summary".to_string(),
+            public_key: public_key.to_string(),
+            data: serde_json::json!({ "text": text }).to_string(),
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn moderators_summarize_into_the_description() {
+        let contract = MetaContract::default();
+        let config = WallConfig {
+            moderators: vec!["0xmod".to_string()],
+            ..Default::default()
+        };
+        let filter = WordFilter::new(&["griefer".to_string()]);
+        let summarize = |key: &str, text: &str| {
+            set_summary(&contract, &transaction(key, text), &config, &filter)
+        };
+
+        assert!(summarize("0xother", "tl;dr").is_err());
+        assert!(summarize("0xmod", "  ").is_err());
+        assert!(summarize("0xmod", &"a".repeat(DEFAULT_MAX_SUMMARY_LENGTH + 1)).is_err());
+        assert!(summarize("0xmod", "a griefer thread").is_err());
+
+        let finals = summarize("0xmod", " Gas fees explained \u{202e}").unwrap();
+        let summary: Summary = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(summary.text, "Gas fees explained");
+        assert_eq!(summary.set_at, 1_700_000_000);
+        assert_eq!(finals[1].alias, Alias::Description);
+        assert_eq!(finals[1].content, "Gas fees explained");
+    }
+}
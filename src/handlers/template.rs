@@ -0,0 +1,117 @@
+use crate::data::{PostTemplate, WallConfig};
+use crate::defaults::{DEFAULT_MAX_TEMPLATES, DEFAULT_MAX_TEMPLATE_NAME_LENGTH};
+use crate::error::W3WallError;
+use crate::storage::config::CONFIG_ALIAS;
+use crate::types::{FinalMetadata, MetaContract, Transaction};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct TemplateRequest {
+    name: String,
+    /**
+     * Drops the template instead of setting it
+     */
+    #[serde(default)]
+    remove: bool,
+    #[serde(flatten)]
+    template: PostTemplate,
+}
+
+/**
+ * Lowercase letters, digits, `-` and `_`, so names can go in aliases and URLs as they are
+ */
+pub fn is_template_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= DEFAULT_MAX_TEMPLATE_NAME_LENGTH
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/**
+ * Sets or removes one of the wall's post templates. Owners only, templates live in the config
+ * so every rule sees them without another lookup.
+ */
+pub fn set_template(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can change post templates.",
+        ));
+    }
+
+    let request: TemplateRequest = serde_json::from_str(&transaction.data)?;
+    if !is_template_name(&request.name) {
+        return Err(W3WallError::rejected(
+            "Template name must be 1 to 32 lowercase letters, digits, - or _.",
+        ));
+    }
+
+    let mut config = config.clone();
+    if request.remove {
+        if config.templates.remove(&request.name).is_none() {
+            return Err(W3WallError::rejected("Template does not exist."));
+        }
+    } else {
+        config.templates.insert(request.name, request.template);
+        if config.templates.len() > DEFAULT_MAX_TEMPLATES {
+            return Err(W3WallError::Rejected(format!(
+                "A wall is limited to {} templates.",
+                DEFAULT_MAX_TEMPLATES
+            )));
+        }
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: CONFIG_ALIAS.to_string(),
+        content: serde_json::to_string(&config)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: "set_template".to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn owners_set_and_remove_templates() {
+        let contract = MetaContract::default();
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let data = r#"{"name": "announcement", "required": ["title"], "tags": ["news"]}"#;
+        assert!(set_template(&contract, &transaction("0xother", data), &config).is_err());
+        assert!(set_template(
+            &contract,
+            &transaction("0xowner", r#"{"name": "Big News"}"#),
+            &config
+        )
+        .is_err());
+
+        let finals = set_template(&contract, &transaction("0xowner", data), &config).unwrap();
+        let config: WallConfig = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(config.templates["announcement"].required, vec!["title"]);
+        assert_eq!(config.templates["announcement"].tags, vec!["news"]);
+
+        let remove = r#"{"name": "announcement", "remove": true}"#;
+        let finals = set_template(&contract, &transaction("0xowner", remove), &config).unwrap();
+        let config: WallConfig = serde_json::from_str(&finals[0].content).unwrap();
+        assert!(config.templates.is_empty());
+        assert!(set_template(&contract, &transaction("0xowner", remove), &config).is_err());
+    }
+}
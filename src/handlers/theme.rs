@@ -0,0 +1,140 @@
+use crate::data::WallConfig;
+use crate::defaults::{DEFAULT_MAX_THEME_DESCRIPTION_LENGTH, DEFAULT_MAX_THEME_TITLE_LENGTH};
+use crate::error::W3WallError;
+use crate::types::{FinalMetadata, MetaContract, Transaction};
+use crate::validation::image::check_image;
+use serde::{Deserialize, Serialize};
+
+pub static THEME_ALIAS: &str = "w3wall.theme";
+
+/**
+ * Colors are `#rgb` or `#rrggbb`, anything not listed is refused so the record can't carry
+ * arbitrary data
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeColors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct WallTheme {
+    pub colors: ThemeColors,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn check_theme(theme: &WallTheme, image_types: &[String]) -> Result<(), &'static str> {
+    let colors = &theme.colors;
+    if [
+        &colors.primary,
+        &colors.accent,
+        &colors.background,
+        &colors.text,
+    ]
+    .iter()
+    .filter_map(|c| c.as_deref())
+    .any(|c| !is_hex_color(c))
+    {
+        return Err("Theme colors must be #rgb or #rrggbb.");
+    }
+    if let Some(banner) = theme.banner.as_deref() {
+        check_image(banner, None, image_types)?;
+    }
+    if theme
+        .title
+        .as_deref()
+        .is_some_and(|t| t.chars().count() > DEFAULT_MAX_THEME_TITLE_LENGTH)
+    {
+        return Err("Theme title is limited to 80 characters.");
+    }
+    if theme
+        .description
+        .as_deref()
+        .is_some_and(|d| d.chars().count() > DEFAULT_MAX_THEME_DESCRIPTION_LENGTH)
+    {
+        return Err("Theme description is limited to 500 characters.");
+    }
+    Ok(())
+}
+
+/**
+ * Replaces the wall theme, owners only
+ */
+pub fn set_theme(
+    contract: &MetaContract,
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can change the theme.",
+        ));
+    }
+
+    let theme: WallTheme = serde_json::from_str(&transaction.data)?;
+    check_theme(&theme, &config.image_types).map_err(W3WallError::rejected)?;
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: THEME_ALIAS.to_string(),
+        content: serde_json::to_string(&theme)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(data: &str) -> Result<Vec<FinalMetadata>, W3WallError> {
+        let transaction = Transaction {
+            method: "set_theme".to_string(),
+            public_key: "0xowner".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        set_theme(&MetaContract::default(), &transaction, &config)
+    }
+
+    #[test]
+    fn theme_is_constrained() {
+        let finals = theme(
+            r##"{"colors": {"primary": "#0af", "background": "#101010"}, "banner": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "title": "gm"}"##,
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, THEME_ALIAS);
+        assert_eq!(
+            finals[0].content,
+            r##"{"colors":{"primary":"#0af","background":"#101010"},"banner":"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG","title":"gm"}"##
+        );
+
+        assert!(theme(r#"{"colors": {"primary": "red"}}"#).is_err());
+        assert!(theme(r#"{"banner": "https://evil.example/x.png"}"#).is_err());
+        assert!(theme(r#"{"css": "body { display: none }"}"#).is_err());
+        assert!(theme(&format!(r#"{{"title": "{}"}}"#, "x".repeat(81))).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::crypto::eth_address::parse_address;
+use crate::error::W3WallError;
+use crate::metadata::tips::{load_totals, tip_alias, totals_record};
+use crate::storage::alias::Alias;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use crate::validation::media::is_cid;
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+
+/**
+ * Reference to an on-chain tip, the contract can't see the chain so only the format is checked
+ */
+#[derive(Serialize, Deserialize)]
+struct Tip {
+    post: String,
+    tx_hash: String,
+    amount: String,
+    token: String,
+}
+
+fn is_tx_hash(hash: &str) -> bool {
+    match hash.strip_prefix("0x") {
+        Some(hex) => hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+/**
+ * Records a tip against a post on this wall and updates the post's running totals
+ */
+pub fn tip(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    let mut tip: Tip = serde_json::from_str(&transaction.data)?;
+    tip.tx_hash = tip.tx_hash.to_lowercase();
+
+    if !is_cid(&tip.post)
+        || !metadatas
+            .iter()
+            .any(|m| m.alias == Alias::Post && m.cid == tip.post)
+    {
+        return Err(W3WallError::rejected("Tipped post is not on this wall."));
+    }
+    if !is_tx_hash(&tip.tx_hash) {
+        return Err(W3WallError::rejected(
+            "Tip tx_hash is not a transaction hash.",
+        ));
+    }
+    if tip.token != "native" {
+        tip.token = parse_address(&tip.token).map_err(|_| {
+            W3WallError::rejected("Tip token must be \"native\" or a token contract address.")
+        })?;
+    }
+    let amount = match U256::from_dec_str(&tip.amount) {
+        Ok(amount) if !amount.is_zero() => amount,
+        _ => {
+            return Err(W3WallError::rejected(
+                "Tip amount must be a positive integer in base units.",
+            ))
+        }
+    };
+
+    let mut totals = load_totals(contract, metadatas, &tip.post);
+    if totals.tx_hashes.contains(&tip.tx_hash) {
+        return Err(W3WallError::rejected("Tip was already recorded."));
+    }
+    totals.add(&tip.tx_hash, &tip.token, amount);
+
+    Ok(vec![
+        FinalMetadata {
+            public_key: transaction.public_key.clone(),
+            alias: tip_alias(&tip.post),
+            content: serde_json::to_string(&tip)?,
+            loose: 1,
+            version: transaction.version.clone(),
+        },
+        totals_record(contract, &tip.post, &totals),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CID: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+    fn post() -> Metadata {
+        Metadata {
+            public_key: "0xauthor".to_string(),
+            cid: CID.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn transaction(data: serde_json::Value) -> Transaction {
+        Transaction {
+            method: "tip".to_string(),
+            public_key: "0xtipper".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn data(amount: &str) -> serde_json::Value {
+        serde_json::json!({
+            "post": CID,
+            "tx_hash": format!("0x{}", "ab".repeat(32)),
+            "amount": amount,
+            "token": "native"
+        })
+    }
+
+    #[test]
+    fn tips_are_recorded_and_totalled() {
+        let finals = tip(
+            &MetaContract::default(),
+            &[post()],
+            &transaction(data("1000")),
+        )
+        .unwrap();
+        assert_eq!(finals[0].alias, tip_alias(CID));
+        assert_eq!(finals[0].public_key, "0xtipper");
+        let totals: serde_json::Value = serde_json::from_str(&finals[1].content).unwrap();
+        assert_eq!(totals["count"], 1);
+        assert_eq!(totals["totals"]["native"], "1000");
+    }
+
+    #[test]
+    fn malformed_tips_are_rejected() {
+        let contract = MetaContract::default();
+        assert!(tip(&contract, &[], &transaction(data("1000"))).is_err());
+        assert!(tip(&contract, &[post()], &transaction(data("0"))).is_err());
+        assert!(tip(&contract, &[post()], &transaction(data("1.5"))).is_err());
+    }
+}
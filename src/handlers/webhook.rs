@@ -0,0 +1,244 @@
+use crate::data::WallConfig;
+use crate::defaults::{
+    DEFAULT_MAX_WEBHOOKS, DEFAULT_MAX_WEBHOOK_URL_LENGTH, DEFAULT_WEBHOOK_EVENTS,
+    DEFAULT_WEBHOOK_PLACEHOLDERS,
+};
+use crate::error::W3WallError;
+use crate::handlers::template::is_template_name;
+use crate::metadata::timestamp_sec;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub static WEBHOOKS_ALIAS: &str = "w3wall.webhooks";
+
+static URL_TEMPLATE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^https://[a-z0-9-]+(\.[a-z0-9-]+)+(:[0-9]{1,5})?(/\S*)?$").unwrap());
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{([^{}]*)\}").unwrap());
+static SECRET_HASH: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0x[0-9a-f]{64}$").unwrap());
+
+/**
+ * Where off-chain workers deliver a wall's events. Workers sign deliveries with a secret only
+ * they and the receiver know, the record keeps its keccak256.
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Webhook {
+    pub url: String,
+    pub secret_hash: String,
+    pub events: Vec<String>,
+    pub registered_at: u64,
+}
+
+/**
+ * The wall's webhooks by id, what workers deliver is whatever this record subscribes
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Webhooks {
+    pub hooks: BTreeMap<String, Webhook>,
+}
+
+pub fn load_webhooks(contract: &MetaContract, metadatas: &[Metadata]) -> Webhooks {
+    read_record(metadatas, WEBHOOKS_ALIAS, &contract.public_key).unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+struct WebhookRequest {
+    id: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    secret_hash: String,
+    #[serde(default)]
+    events: Vec<String>,
+}
+
+/**
+ * An https URL on a named host, `{placeholders}` only from DEFAULT_WEBHOOK_PLACEHOLDERS and
+ * only past the host, no credentials
+ */
+fn check_url_template(url: &str) -> Result<(), W3WallError> {
+    if url.len() > DEFAULT_MAX_WEBHOOK_URL_LENGTH {
+        return Err(W3WallError::Rejected(format!(
+            "Webhook URL is limited to {} characters.",
+            DEFAULT_MAX_WEBHOOK_URL_LENGTH
+        )));
+    }
+    if let Some(name) = PLACEHOLDER
+        .captures_iter(url)
+        .map(|c| c.get(1).map_or("", |m| m.as_str()))
+        .find(|name| !DEFAULT_WEBHOOK_PLACEHOLDERS.contains(name))
+    {
+        return Err(W3WallError::Rejected(format!(
+            "Unknown webhook URL placeholder {{{}}}.",
+            name
+        )));
+    }
+    // `_` is no host character, so a placeholder in the host fails the match
+    let filled = PLACEHOLDER.replace_all(url, "_");
+    if !URL_TEMPLATE.is_match(&filled) || filled.contains(['{', '}']) {
+        return Err(W3WallError::rejected(
+            "Webhook URL must be an https URL on a named host.",
+        ));
+    }
+    Ok(())
+}
+
+fn check_events(events: &[String]) -> Result<(), W3WallError> {
+    if events.is_empty() {
+        return Err(W3WallError::rejected(
+            "A webhook subscribes to at least one event.",
+        ));
+    }
+    match events
+        .iter()
+        .find(|event| !DEFAULT_WEBHOOK_EVENTS.contains(&event.as_str()))
+    {
+        Some(event) => Err(W3WallError::Rejected(format!(
+            "Unknown webhook event {}.",
+            event
+        ))),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Registers (`register_webhook`) or removes (`remove_webhook`) one of the wall's webhooks.
+ * Owners only. Registering an id again replaces its webhook.
+ */
+pub fn set_webhook(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> Result<Vec<FinalMetadata>, W3WallError> {
+    if !config.is_owner(&transaction.public_key) {
+        return Err(W3WallError::rejected(
+            "Only wall owners can change webhooks.",
+        ));
+    }
+
+    let request: WebhookRequest = serde_json::from_str(&transaction.data)?;
+    if !is_template_name(&request.id) {
+        return Err(W3WallError::rejected(
+            "Webhook id must be 1 to 32 lowercase letters, digits, - or _.",
+        ));
+    }
+
+    let mut webhooks = load_webhooks(contract, metadatas);
+    if transaction.method == "remove_webhook" {
+        if webhooks.hooks.remove(&request.id).is_none() {
+            return Err(W3WallError::rejected("Webhook is not registered."));
+        }
+    } else {
+        check_url_template(&request.url)?;
+        if !SECRET_HASH.is_match(&request.secret_hash) {
+            return Err(W3WallError::rejected(
+                "Webhook secret_hash must be a 0x-prefixed lowercase keccak256.",
+            ));
+        }
+        let mut events = request.events;
+        events.sort();
+        events.dedup();
+        check_events(&events)?;
+        let webhook = Webhook {
+            url: request.url,
+            secret_hash: request.secret_hash,
+            events,
+            registered_at: timestamp_sec(transaction.timestamp),
+        };
+        webhooks.hooks.insert(request.id, webhook);
+        if webhooks.hooks.len() > DEFAULT_MAX_WEBHOOKS {
+            return Err(W3WallError::Rejected(format!(
+                "A wall is limited to {} webhooks.",
+                DEFAULT_MAX_WEBHOOKS
+            )));
+        }
+    }
+
+    Ok(vec![FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: WEBHOOKS_ALIAS.to_string(),
+        content: serde_json::to_string(&webhooks)?,
+        loose: 1,
+        version: transaction.version.clone(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn transaction(method: &str, public_key: &str, data: &str) -> Transaction {
+        Transaction {
+            method: method.to_string(),
+            public_key: public_key.to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn url_templates_are_https_with_known_placeholders() {
+        assert!(
+            check_url_template("https://hooks.example.com/w3wall/{data_key}?e={event}").is_ok()
+        );
+        assert!(check_url_template("https://hooks.example.com:8443").is_ok());
+        for url in [
+            "http://hooks.example.com/",
+            "https://localhost/hook",
+            "https://user:pw@hooks.example.com/",
+            "https://{data_key}.example.com/",
+            "https://hooks.example.com/{secret}",
+            "https://hooks.example.com/{event",
+        ] {
+            assert!(check_url_template(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn owners_register_and_remove_webhooks() {
+        let contract = MetaContract {
+            public_key: "0xwall".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig {
+            owner: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let hook = format!(
+            r#"{{"id": "indexer", "url": "https://hooks.example.com/{{cid}}", "secret_hash": "0x{}", "events": ["reply", "post", "post"]}}"#,
+            "ab".repeat(32)
+        );
+        let register =
+            |public_key: &str, data: &str| transaction("register_webhook", public_key, data);
+
+        let finals = set_webhook(&contract, &[], &register("0xowner", &hook), &config).unwrap();
+        assert_eq!(finals[0].public_key, "0xwall");
+        let webhooks: Webhooks = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(webhooks.hooks["indexer"].events, ["post", "reply"]);
+        assert!(set_webhook(&contract, &[], &register("0xother", &hook), &config).is_err());
+
+        let plain = hook.replace(&format!("0x{}", "ab".repeat(32)), "hunter2");
+        assert!(set_webhook(&contract, &[], &register("0xowner", &plain), &config).is_err());
+        let unknown = hook.replace(r#""reply""#, r#""login""#);
+        let err = set_webhook(&contract, &[], &register("0xowner", &unknown), &config).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown webhook event login.");
+
+        test_store::put("webhooks-1", &finals[0].content);
+        let metadatas = [Metadata {
+            alias: WEBHOOKS_ALIAS.to_string(),
+            public_key: "0xwall".to_string(),
+            cid: "webhooks-1".to_string(),
+            ..Default::default()
+        }];
+        let remove = transaction("remove_webhook", "0xowner", r#"{"id": "indexer"}"#);
+        let finals = set_webhook(&contract, &metadatas, &remove, &config).unwrap();
+        assert_eq!(finals[0].content, r#"{"hooks":{}}"#);
+        assert!(set_webhook(&contract, &[], &remove, &config).is_err());
+    }
+}
@@ -0,0 +1,40 @@
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::types::{FinalMetadata, Transaction};
+use log::LevelFilter;
+
+/**
+ * Walls may raise or lower verbosity through `log_level` in their config
+ */
+pub fn apply_config_level(config: &WallConfig) {
+    if let Some(level) = &config.log_level {
+        match level.parse::<LevelFilter>() {
+            Ok(level) => log::set_max_level(level),
+            Err(_) => log::warn!("ignoring unknown log_level {:?} in wall config", level),
+        }
+    }
+}
+
+/**
+ * One line per transaction with enough context to find a rejected post on a peer
+ */
+pub fn log_outcome(transaction: &Transaction, outcome: &Result<Vec<FinalMetadata>, W3WallError>) {
+    match outcome {
+        Ok(finals) => log::info!(
+            "method={} tx={} data_key={} public_key={} outcome=accepted records={}",
+            transaction.method,
+            transaction.hash,
+            transaction.data_key,
+            transaction.public_key,
+            finals.len()
+        ),
+        Err(e) => log::info!(
+            "method={} tx={} data_key={} public_key={} outcome=rejected reason={:?}",
+            transaction.method,
+            transaction.hash,
+            transaction.data_key,
+            transaction.public_key,
+            e.to_string()
+        ),
+    }
+}
@@ -1,18 +1,37 @@
 #![allow(improper_ctypes)]
+// the `json!` schema of every method outgrows the default macro recursion limit
+#![recursion_limit = "256"]
 
+mod budget;
+mod crypto;
 mod data;
 mod defaults;
+mod encoding;
+mod error;
+mod handlers;
+mod logging;
+mod metadata;
+mod moderation;
+mod storage;
 mod types;
+mod validation;
 
-use data::OpenSeaAttributes;
-use ethabi::{decode, ParamType};
+#[cfg(test)]
+mod tests;
+
+use error::IntoResult;
+use handlers::{clone, collection, execute, info, preflight};
 use marine_rs_sdk::marine;
 use marine_rs_sdk::module_manifest;
 use marine_rs_sdk::WasmLoggerBuilder;
+#[cfg(feature = "mint")]
+use types::BatchMintResult;
+use types::ContractInfo;
 use types::MetaContract;
+use types::MetaContractResult;
 use types::Metadata;
 use types::Transaction;
-use types::{FinalMetadata, MetaContractResult};
+use types::ValidationReport;
 
 module_manifest!();
 
@@ -29,204 +48,79 @@ pub fn on_execute(
     metadatas: Vec<Metadata>,
     transaction: Transaction,
 ) -> MetaContractResult {
-    let mut finals: Vec<FinalMetadata> = vec![];
-
-    let data: serde_json::Result<serde_json::Value> = serde_json::from_str(&transaction.data);
-
-    match data {
-        Ok(json_data) => {
-            if json_data.is_object() {
-                let image: Option<&str> = json_data["image"].as_str();
-                let text: Option<&str> = json_data["text"].as_str();
-
-                if image.is_none() && text.is_none() {
-                    return MetaContractResult {
-                        result: false,
-                        metadatas: Vec::new(),
-                        error_string: "No data inputted".to_string(),
-                    };
-                }
-
-                if let Some(image) = image {
-                    if !is_nft_storage_link(image) {
-                        return MetaContractResult {
-                            result: false,
-                            metadatas: Vec::new(),
-                            error_string: "Invalid image link is been used".to_string(),
-                        };
-                    }
-                }
-
-                if let Some(text) = text {
-                    if is_profane(&text) {
-                        // Text is profane, handle accordingly
-                        return MetaContractResult {
-                            result: false,
-                            metadatas: Vec::new(),
-                            error_string: "Profanity found in the text.".to_string(),
-                        };
-                    }
-                }
-
-                let text = json_data["text"].as_str().unwrap();
-                if is_profane(text) {
-                    // Text is profane, handle accordingly
-                    return MetaContractResult {
-                        result: false,
-                        metadatas: Vec::new(),
-                        error_string: "Profanity found in the text.".to_string(),
-                    };
-                }
-            } else {
-                // JSON schema is not valid
-                return MetaContractResult {
-                    result: false,
-                    metadatas: Vec::new(),
-                    error_string: "Data does not follow the required JSON schema.".to_string(),
-                };
-            }
-        }
-        Err(_) => {
-            return MetaContractResult {
-                result: false,
-                metadatas: Vec::new(),
-                error_string: "Data is not a valid format.".to_string(),
-            };
-        }
-    }
-
-    finals.push(FinalMetadata {
-        public_key: transaction.public_key.clone(),
-        alias: "".to_string(),
-        content: transaction.data,
-        loose: 1,
-        version: transaction.version,
-    });
-
-    MetaContractResult {
-        result: true,
-        metadatas: finals,
-        error_string: "".to_string(),
-    }
+    execute::handle(&contract, &metadatas, &transaction).into_result()
 }
 
 #[marine]
-pub fn on_clone() -> bool {
-    return true;
+pub fn on_clone(contract: MetaContract, transaction: Transaction) -> bool {
+    clone::handle(&contract, &transaction)
 }
 
+#[cfg(feature = "mint")]
 #[marine]
 pub fn on_mint(
     contract: MetaContract,
+    metadatas: Vec<Metadata>,
     data_key: String,
     token_id: String,
     data: String,
 ) -> MetaContractResult {
-    let mut error: Option<String> = None;
-    let mut finals: Vec<FinalMetadata> = vec![];
-
-    // extract out data
-    if data.len() > 0 {
-        let data_bytes = &hex::decode(&data);
-
-        match data_bytes {
-            Ok(decoded) => {
-                let param_types = vec![ParamType::String, ParamType::String, ParamType::String];
-
-                let results = decode(&param_types, decoded);
-
-                match results {
-                    Ok(result) => {
-                        if result.len() == 3 {
-                            finals.push(FinalMetadata {
-                                public_key: contract.public_key.clone(),
-                                alias: "name".to_string(),
-                                content: result[0].clone().to_string(),
-                                loose: 1,
-                                version: "".to_string(),
-                            });
-
-                            finals.push(FinalMetadata {
-                                public_key: contract.public_key.clone(),
-                                alias: "image".to_string(),
-                                content: result[1].clone().to_string(),
-                                loose: 1,
-                                version: "".to_string(),
-                            });
-
-                            finals.push(FinalMetadata {
-                                public_key: contract.public_key.clone(),
-                                alias: "body".to_string(),
-                                content: result[2].clone().to_string(),
-                                loose: 1,
-                                version: "".to_string(),
-                            });
-                        }
-                    }
-                    Err(e) => error = Some(format!("Invalid data structure: {}", e.to_string())),
-                }
-            }
-            Err(e) => error = Some(format!("Invalid data structure: {}", e.to_string())),
-        }
-    }
+    handlers::mint::mint(&contract, &metadatas, &data_key, &token_id, &data).into_result()
+}
 
-    if !error.is_none() {
-        return MetaContractResult {
+/**
+ * Mints several tokens of `data_key` at once, with an outcome per token
+ */
+#[cfg(feature = "mint")]
+#[marine]
+pub fn on_mint_batch(
+    contract: MetaContract,
+    metadatas: Vec<Metadata>,
+    data_key: String,
+    data: String,
+) -> BatchMintResult {
+    match handlers::mint::mint_batch(&contract, &metadatas, &data_key, &data) {
+        Ok(items) => BatchMintResult {
+            result: true,
+            items,
+            error_string: "".to_string(),
+        },
+        Err(e) => BatchMintResult {
             result: false,
-            metadatas: Vec::new(),
-            error_string: error.unwrap().to_string(),
-        };
+            items: vec![],
+            error_string: e.to_string(),
+        },
     }
+}
 
-    finals.push(FinalMetadata {
-        public_key: contract.public_key.clone(),
-        alias: "description".to_string(),
-        content: "A subject in w3wall decentralize forum"
-            .to_string(),
-        loose: 1,
-        version: "".to_string(),
-    });
-
-    // adds attributes
-    let attr = vec![
-        OpenSeaAttributes {
-            trait_type: "origin".to_string(),
-            value: "w3wall".to_string()
-          },
-          OpenSeaAttributes {
-            trait_type: "type".to_string(),
-            value: "topic".to_string()
-          },
-    ];
-
-    finals.push(FinalMetadata {
-        public_key: contract.public_key.clone(),
-        alias: "attributes".to_string(),
-        content: serde_json::to_string(&attr).unwrap(),
-        loose: 1,
-        version: "".to_string(),
-    });
+/**
+ * Collection-level (contractURI) metadata so marketplaces can name the w3wall collection
+ */
+#[marine]
+pub fn on_contract_metadata(contract: MetaContract) -> MetaContractResult {
+    collection::contract_metadata(&contract).into_result()
+}
 
-    MetaContractResult {
-        result: true,
-        metadatas: finals,
-        error_string: "".to_string(),
-    }
+/**
+ * Preflight check of a post payload, nothing is stored
+ */
+#[marine]
+pub fn validate(data: String) -> ValidationReport {
+    preflight::validate(&data)
 }
 
 /**
- * For now leaving it empty. Freedom of speech
+ * JSON Schema of the accepted payloads, per method
  */
-fn is_profane(text: &str) -> bool {
-    let profane_words = vec!["", ""];
-    profane_words.iter().any(|&word| {
-      if word != "" {
-        return text.contains(word)
-      }
-      false
-    })
+#[marine]
+pub fn get_schema() -> String {
+    validation::schema::schema().to_string()
 }
 
-fn is_nft_storage_link(link: &str) -> bool {
-    link == "" || link.starts_with("https://nftstorage.link/ipfs/")
+/**
+ * Version, methods, features and limits of this deployment
+ */
+#[marine]
+pub fn get_info() -> ContractInfo {
+    info::info()
 }
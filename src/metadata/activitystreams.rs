@@ -0,0 +1,119 @@
+use crate::defaults::{
+    DEFAULT_ACTOR_URL_TEMPLATE, DEFAULT_POST_URL_TEMPLATE, DEFAULT_THREAD_URL_TEMPLATE,
+};
+use crate::metadata::{escape_markup, iso8601, timestamp_sec};
+use crate::types::Transaction;
+use serde_json::{json, Value};
+
+pub static ACTIVITYSTREAMS_ALIAS: &str = "activitystreams";
+
+pub fn actor_url(public_key: &str) -> String {
+    DEFAULT_ACTOR_URL_TEMPLATE.replace("{public_key}", &public_key.to_lowercase())
+}
+
+pub fn thread_url(data_key: &str) -> String {
+    DEFAULT_THREAD_URL_TEMPLATE.replace("{data_key}", data_key)
+}
+
+/**
+ * The post cid is only known once stored, so notes are addressed by the transaction hash
+ */
+pub fn post_url(data_key: &str, id: &str) -> String {
+    DEFAULT_POST_URL_TEMPLATE
+        .replace("{data_key}", data_key)
+        .replace("{id}", id)
+}
+
+/**
+ * Plain post text as the HTML an AS2 `content` carries
+ */
+fn html_content(text: &str) -> String {
+    format!("<p>{}</p>", escape_markup(text).replace('\n', "<br>"))
+}
+
+/**
+ * AS2 Create wrapping a Note, from the payload as stored (masked text included). Private posts
+ * are never federated.
+ */
+pub fn create_note(transaction: &Transaction, stored: &Value) -> Option<Value> {
+    if stored["visibility"].as_str() == Some("private") {
+        return None;
+    }
+
+    let actor = actor_url(&transaction.public_key);
+    let published = iso8601(timestamp_sec(transaction.timestamp));
+    let in_reply_to = match stored["reply_to"].as_str() {
+        Some(cid) => post_url(&transaction.data_key, cid),
+        None => thread_url(&transaction.data_key),
+    };
+
+    let mut note = json!({
+        "id": post_url(&transaction.data_key, &transaction.hash),
+        "type": "Note",
+        "attributedTo": actor,
+        "content": html_content(stored["text"].as_str().unwrap_or_default()),
+        "published": published,
+        "inReplyTo": in_reply_to,
+        "context": thread_url(&transaction.data_key),
+        "to": ["https://www.w3.org/ns/activitystreams#Public"],
+    });
+    if let Some(image) = stored["image"].as_str().filter(|image| !image.is_empty()) {
+        note["attachment"] = json!([{ "type": "Image", "url": image }]);
+    }
+    if stored["filtered"].as_bool() == Some(true) {
+        note["sensitive"] = true.into();
+    }
+
+    Some(json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": format!("{}/activity", post_url(&transaction.data_key, &transaction.hash)),
+        "type": "Create",
+        "actor": actor,
+        "published": published,
+        "to": ["https://www.w3.org/ns/activitystreams#Public"],
+        "object": note,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction() -> Transaction {
+        Transaction {
+            public_key: "0xABC".to_string(),
+            data_key: "wall".to_string(),
+            hash: "0xtx".to_string(),
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn posts_map_to_a_create_note() {
+        let stored = json!({ "text": "gm <frens>\nwagmi", "reply_to": "parent-cid" });
+        let activity = create_note(&transaction(), &stored).unwrap();
+        assert_eq!(activity["type"], "Create");
+        assert_eq!(activity["actor"], "https://w3wall.xyz/u/0xabc");
+        assert_eq!(activity["published"], "2023-11-14T22:13:20Z");
+
+        let note = &activity["object"];
+        assert_eq!(note["type"], "Note");
+        assert_eq!(note["content"], "<p>gm &lt;frens&gt;<br>wagmi</p>");
+        assert_eq!(
+            note["inReplyTo"],
+            "https://w3wall.xyz/topic/wall/post/parent-cid"
+        );
+        assert_eq!(note["id"], "https://w3wall.xyz/topic/wall/post/0xtx");
+    }
+
+    #[test]
+    fn top_level_posts_reply_to_the_thread_and_private_ones_stay_home() {
+        let activity = create_note(&transaction(), &json!({ "text": "gm" })).unwrap();
+        assert_eq!(
+            activity["object"]["inReplyTo"],
+            "https://w3wall.xyz/topic/wall"
+        );
+        assert!(create_note(&transaction(), &json!({ "visibility": "private" })).is_none());
+    }
+}
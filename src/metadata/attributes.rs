@@ -0,0 +1,139 @@
+use super::badges::badge_attributes;
+use crate::data::{OpenSeaAttributes, ThreadActivity};
+use crate::defaults::DEFAULT_WORDS_PER_MINUTE;
+
+pub fn topic_attributes(activity: &ThreadActivity) -> Vec<OpenSeaAttributes> {
+    let mut attr = vec![
+        OpenSeaAttributes::text("origin", "w3wall"),
+        OpenSeaAttributes::text("type", "topic"),
+        OpenSeaAttributes::number("replies", activity.replies),
+        OpenSeaAttributes::number("participants", activity.participants),
+        OpenSeaAttributes::number("tips", activity.tips),
+        OpenSeaAttributes::number("trending", activity.trending),
+        OpenSeaAttributes::boost_percentage("heat", 0),
+    ];
+
+    if activity.created > 0 {
+        attr.push(OpenSeaAttributes::date("created", activity.created));
+    }
+
+    if activity.last_activity > 0 {
+        attr.push(OpenSeaAttributes::date(
+            "last_activity",
+            activity.last_activity,
+        ));
+    }
+
+    if activity.words > 0 {
+        attr.push(OpenSeaAttributes::number("word_count", activity.words));
+        attr.push(OpenSeaAttributes::number(
+            "reading_time",
+            activity.words.div_ceil(DEFAULT_WORDS_PER_MINUTE),
+        ));
+    }
+
+    if let Some(license) = &activity.license {
+        attr.push(OpenSeaAttributes::text("license", license));
+    }
+
+    attr.extend(badge_attributes(activity));
+    attr
+}
+
+/**
+ * Attributes for a token minted again. Fresh values replace every attribute of the same
+ * trait_type, traits only the earlier mint set are kept after them, and the first mint's
+ * `created` date stands. Exact duplicates are dropped, so the result only depends on the inputs.
+ */
+#[cfg(feature = "mint")]
+pub fn merge_attributes(
+    existing: Vec<OpenSeaAttributes>,
+    fresh: Vec<OpenSeaAttributes>,
+) -> Vec<OpenSeaAttributes> {
+    let created = existing.iter().find(|a| a.trait_type == "created").cloned();
+    let mut merged: Vec<OpenSeaAttributes> = vec![];
+    for attribute in fresh {
+        let attribute = match (&created, attribute.trait_type.as_str()) {
+            (Some(created), "created") => created.clone(),
+            _ => attribute,
+        };
+        if !merged.contains(&attribute) {
+            merged.push(attribute);
+        }
+    }
+
+    let fresh_traits: Vec<String> = merged.iter().map(|a| a.trait_type.clone()).collect();
+    for attribute in existing {
+        if !fresh_traits.contains(&attribute.trait_type) && !merged.contains(&attribute) {
+            merged.push(attribute);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn repeated_mints_merge_instead_of_appending() {
+        let first = vec![
+            OpenSeaAttributes::text("origin", "w3wall"),
+            OpenSeaAttributes::text("type", "topic"),
+            OpenSeaAttributes::date("created", 10),
+            OpenSeaAttributes::text("license", "CC0"),
+            OpenSeaAttributes::text("license", "CC0"),
+        ];
+        let fresh = vec![
+            OpenSeaAttributes::text("origin", "w3wall"),
+            OpenSeaAttributes::text("type", "topic"),
+            OpenSeaAttributes::date("created", 20),
+            OpenSeaAttributes::number("replies", 0),
+        ];
+        let merged = serde_json::to_string(&merge_attributes(first, fresh)).unwrap();
+        assert_eq!(
+            merged,
+            r#"[{"trait_type":"origin","value":"w3wall"},{"trait_type":"type","value":"topic"},{"display_type":"date","trait_type":"created","value":10},{"display_type":"number","trait_type":"replies","value":0},{"trait_type":"license","value":"CC0"}]"#
+        );
+    }
+
+    #[test]
+    fn dates_only_when_known() {
+        let attr = topic_attributes(&ThreadActivity::default());
+        let json = serde_json::to_string(&attr).unwrap();
+        assert!(!json.contains("\"created\""));
+        assert!(json.contains(r#"{"display_type":"number","trait_type":"replies","value":0}"#));
+        assert!(json.contains(r#"{"trait_type":"origin","value":"w3wall"}"#));
+
+        let attr = topic_attributes(&ThreadActivity {
+            created: 10,
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&attr).unwrap();
+        assert!(json.contains(r#"{"display_type":"date","trait_type":"created","value":10}"#));
+
+        let attr = topic_attributes(&ThreadActivity {
+            replies: 1,
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&attr).unwrap();
+        assert!(json.ends_with(r#"{"trait_type":"badge","value":"First Post"}]"#));
+
+        let attr = topic_attributes(&ThreadActivity {
+            license: Some("CC-BY".to_string()),
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&attr).unwrap();
+        assert!(json.contains(r#"{"trait_type":"license","value":"CC-BY"}"#));
+        assert!(!json.contains("word_count"));
+
+        let attr = topic_attributes(&ThreadActivity {
+            words: 450,
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&attr).unwrap();
+        assert!(json.contains(r#"{"display_type":"number","trait_type":"word_count","value":450}"#));
+        assert!(json.contains(r#"{"display_type":"number","trait_type":"reading_time","value":3}"#));
+    }
+}
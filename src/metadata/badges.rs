@@ -0,0 +1,60 @@
+use crate::data::{OpenSeaAttributes, ThreadActivity};
+use crate::defaults::DEFAULT_OG_TOKEN_LIMIT;
+
+type Milestone = (&'static str, fn(&ThreadActivity) -> bool);
+
+/**
+ * Milestone badges, in the order they are emitted. Everything is derived from the thread
+ * history, so the same lineage always yields the same badges.
+ */
+static MILESTONES: &[Milestone] = &[
+    ("OG Wall", |a| {
+        a.token_id > 0 && a.token_id <= DEFAULT_OG_TOKEN_LIMIT
+    }),
+    ("First Post", |a| a.replies >= 1),
+    ("10 Replies", |a| a.replies >= 10),
+    ("100 Replies", |a| a.replies >= 100),
+    ("Crowd", |a| a.participants >= 25),
+];
+
+pub fn badges(activity: &ThreadActivity) -> Vec<&'static str> {
+    MILESTONES
+        .iter()
+        .filter(|(_, earned)| earned(activity))
+        .map(|(badge, _)| *badge)
+        .collect()
+}
+
+pub fn badge_attributes(activity: &ThreadActivity) -> Vec<OpenSeaAttributes> {
+    badges(activity)
+        .into_iter()
+        .map(|badge| OpenSeaAttributes::text("badge", badge))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn badges_follow_milestones() {
+        assert!(badges(&ThreadActivity::default()).is_empty());
+
+        let activity = ThreadActivity {
+            token_id: 7,
+            replies: 12,
+            participants: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            badges(&activity),
+            vec!["OG Wall", "First Post", "10 Replies"]
+        );
+
+        let late = ThreadActivity {
+            token_id: DEFAULT_OG_TOKEN_LIMIT + 1,
+            ..Default::default()
+        };
+        assert!(badges(&late).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::metadata::timestamp_sec;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static BOARD_ALIAS_PREFIX: &str = "w3wall.board.";
+
+pub fn board_alias(board: &str) -> String {
+    format!("{}{}", BOARD_ALIAS_PREFIX, board)
+}
+
+/**
+ * Per-board totals, so a board listing doesn't need the whole wall
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct BoardIndex {
+    pub board: String,
+    pub posts: u64,
+    pub last_activity: u64,
+}
+
+pub fn load_board(contract: &MetaContract, metadatas: &[Metadata], board: &str) -> BoardIndex {
+    read_record(metadatas, &board_alias(board), &contract.public_key).unwrap_or(BoardIndex {
+        board: board.to_string(),
+        ..Default::default()
+    })
+}
+
+pub fn count_post(index: &mut BoardIndex, transaction: &Transaction) {
+    index.posts += 1;
+    index.last_activity = index
+        .last_activity
+        .max(timestamp_sec(transaction.timestamp));
+}
+
+pub fn board_record(contract: &MetaContract, index: &BoardIndex) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: board_alias(&index.board),
+        content: serde_json::to_string(index).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    #[test]
+    fn board_counts_continue_from_the_stored_index() {
+        let contract = MetaContract::default();
+        let mut index = load_board(&contract, &[], "dev");
+        assert_eq!(index.posts, 0);
+
+        test_store::put(
+            "board-dev",
+            r#"{"board": "dev", "posts": 4, "last_activity": 9}"#,
+        );
+        let metadatas = vec![Metadata {
+            alias: board_alias("dev"),
+            cid: "board-dev".to_string(),
+            ..Default::default()
+        }];
+        index = load_board(&contract, &metadatas, "dev");
+        count_post(
+            &mut index,
+            &Transaction {
+                timestamp: 1_700_000_000_000,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            board_record(&contract, &index).content,
+            r#"{"board":"dev","posts":5,"last_activity":1700000000}"#
+        );
+    }
+}
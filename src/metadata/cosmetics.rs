@@ -0,0 +1,35 @@
+use crate::crypto::rng::Roll;
+use crate::data::OpenSeaAttributes;
+use crate::defaults::{
+    DEFAULT_COSMETIC_BACKGROUNDS, DEFAULT_COSMETIC_FRAMES, DEFAULT_COSMETIC_PALETTES,
+};
+
+/**
+ * Background, frame and palette of a topic, rolled from "data_key:token_id" so every node
+ * renders the same token the same way
+ */
+pub fn cosmetic_attributes(data_key: &str, token_id: &str) -> Vec<OpenSeaAttributes> {
+    let mut roll = Roll::new(&[data_key, token_id]);
+    let mut pick = |table| roll.weighted(table).copied().unwrap_or_default();
+    vec![
+        OpenSeaAttributes::text("background", pick(DEFAULT_COSMETIC_BACKGROUNDS)),
+        OpenSeaAttributes::text("frame", pick(DEFAULT_COSMETIC_FRAMES)),
+        OpenSeaAttributes::text("palette", pick(DEFAULT_COSMETIC_PALETTES)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traits_are_stable_and_follow_the_weights() {
+        assert!(cosmetic_attributes("key", "1") == cosmetic_attributes("key", "1"));
+
+        let gold = (0..400)
+            .map(|id| cosmetic_attributes("key", &id.to_string()))
+            .filter(|attr| attr[1] == OpenSeaAttributes::text("frame", "Gold"))
+            .count();
+        assert!((30..90).contains(&gold), "{} gold frames", gold);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::crypto::keccak_hex;
+use crate::data::ThreadActivity;
+use crate::defaults::DEFAULT_DIGEST_SIZE;
+use crate::metadata::timestamp_sec;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static DIGEST_ALIAS: &str = "w3wall.digest";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DigestEntry {
+    /**
+     * keccak256 of the reply as stored, before its envelope
+     */
+    pub hash: String,
+    pub author: String,
+    pub at: u64,
+}
+
+/**
+ * Latest replies of a thread, newest first, so a client that saw `replies` earlier can tell how
+ * many are new without refetching the thread
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct ThreadDigest {
+    pub replies: u64,
+    pub latest: Vec<DigestEntry>,
+}
+
+/**
+ * The stored digest, or one counted from the posts when the thread predates digests
+ */
+pub fn load_digest(contract: &MetaContract, metadatas: &[Metadata]) -> ThreadDigest {
+    read_record(metadatas, DIGEST_ALIAS, &contract.public_key).unwrap_or_else(|| ThreadDigest {
+        replies: ThreadActivity::from_metadatas(metadatas).replies,
+        latest: vec![],
+    })
+}
+
+/**
+ * Adds an accepted reply, only the newest DEFAULT_DIGEST_SIZE are kept
+ */
+pub fn add_reply(digest: &mut ThreadDigest, transaction: &Transaction, content: &str) {
+    digest.replies += 1;
+    digest.latest.insert(
+        0,
+        DigestEntry {
+            hash: keccak_hex(content.as_bytes()),
+            author: transaction.public_key.clone(),
+            at: timestamp_sec(transaction.timestamp),
+        },
+    );
+    digest.latest.truncate(DEFAULT_DIGEST_SIZE);
+}
+
+pub fn digest_record(contract: &MetaContract, digest: &ThreadDigest) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: DIGEST_ALIAS.to_string(),
+        content: serde_json::to_string(digest).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_keeps_the_newest_replies_and_the_total() {
+        let contract = MetaContract::default();
+        let metadatas = vec![Metadata::default(), Metadata::default()];
+        let mut digest = load_digest(&contract, &metadatas);
+        assert_eq!(digest.replies, 2);
+
+        for i in 0..DEFAULT_DIGEST_SIZE + 2 {
+            let transaction = Transaction {
+                public_key: format!("0x{}", i),
+                timestamp: 1_700_000_000_000 + i as u64 * 1000,
+                ..Default::default()
+            };
+            add_reply(&mut digest, &transaction, "gm");
+        }
+
+        assert_eq!(digest.replies, DEFAULT_DIGEST_SIZE as u64 + 4);
+        assert_eq!(digest.latest.len(), DEFAULT_DIGEST_SIZE);
+        let newest = &digest.latest[0];
+        assert_eq!(newest.author, format!("0x{}", DEFAULT_DIGEST_SIZE + 1));
+        assert_eq!(newest.hash, keccak_hex(b"gm"));
+        assert_eq!(newest.at, 1_700_000_000 + DEFAULT_DIGEST_SIZE as u64 + 1);
+    }
+}
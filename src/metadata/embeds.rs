@@ -0,0 +1,171 @@
+//! Links in a post the frontend may render inline, classified against the wall's allowlist of
+//! providers so oEmbed lookups only ever see URLs the contract has vetted.
+
+use crate::data::WallConfig;
+use crate::defaults::{DEFAULT_IMAGE_EXTENSIONS, DEFAULT_MAX_EMBEDS};
+use crate::validation::links::{canonical_url, links};
+use serde::Serialize;
+
+pub static EMBEDS_ALIAS: &str = "embeds";
+
+static YOUTUBE_HOSTS: &[&str] = &["youtube.com", "www.youtube.com", "m.youtube.com"];
+static TWITTER_HOSTS: &[&str] = &[
+    "twitter.com",
+    "www.twitter.com",
+    "mobile.twitter.com",
+    "x.com",
+    "www.x.com",
+];
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Embed {
+    pub provider: &'static str,
+    /**
+     * oEmbed type the frontend renders it as: video, rich or photo
+     */
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub url: String,
+    /**
+     * Video or status id, for providers addressing content by one
+     */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime: Option<&'static str>,
+}
+
+fn is_video_id(id: &str) -> bool {
+    id.len() == 11
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+fn youtube_id(host: &str, path: &str, query: &str) -> Option<String> {
+    let id = if host == "youtu.be" {
+        path.split('/').nth(1)?
+    } else if YOUTUBE_HOSTS.contains(&host) {
+        match path.strip_prefix('/')?.split_once('/') {
+            Some(("shorts" | "embed", rest)) => rest.split('/').next()?,
+            None if path == "/watch" => query.split('&').find_map(|p| p.strip_prefix("v="))?,
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    is_video_id(id).then(|| id.to_string())
+}
+
+fn status_id(host: &str, path: &str) -> Option<String> {
+    if !TWITTER_HOSTS.contains(&host) {
+        return None;
+    }
+    let mut segments = path.split('/').skip(1);
+    let (_user, "status", id) = (segments.next()?, segments.next()?, segments.next()?) else {
+        return None;
+    };
+    let is_id = !id.is_empty() && id.len() <= 20 && id.bytes().all(|b| b.is_ascii_digit());
+    is_id.then(|| id.to_string())
+}
+
+fn image_mime(path: &str, config: &WallConfig) -> Option<&'static str> {
+    let (_, extension) = path.rsplit('/').next()?.rsplit_once('.')?;
+    let extension = extension.to_lowercase();
+    DEFAULT_IMAGE_EXTENSIONS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map(|(mime, _)| *mime)
+        .filter(|mime| config.image_types.iter().any(|t| t == mime))
+}
+
+/**
+ * What a canonical https link embeds as, None for links rendered as plain links
+ */
+fn classify(url: String, config: &WallConfig) -> Option<Embed> {
+    let rest = url.strip_prefix("https://")?;
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = host.to_string();
+
+    let (provider, kind, id, mime) = if let Some(id) = youtube_id(&host, path, query) {
+        ("youtube", "video", Some(id), None)
+    } else if let Some(id) = status_id(&host, path) {
+        ("twitter", "rich", Some(id), None)
+    } else {
+        ("image", "photo", None, Some(image_mime(path, config)?))
+    };
+    config
+        .embed_providers
+        .iter()
+        .any(|p| p == provider)
+        .then_some(Embed {
+            provider,
+            kind,
+            url,
+            id,
+            mime,
+        })
+}
+
+/**
+ * Embeddable links of `text` in order of appearance, each once, at most DEFAULT_MAX_EMBEDS
+ */
+pub fn embeds(text: &str, config: &WallConfig) -> Vec<Embed> {
+    let mut found: Vec<Embed> = vec![];
+    for embed in links(text).filter_map(|link| classify(canonical_url(link), config)) {
+        if found.len() == DEFAULT_MAX_EMBEDS {
+            break;
+        }
+        if !found.iter().any(|e| e.url == embed.url) {
+            found.push(embed);
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str, config: &WallConfig) -> Vec<(&'static str, Option<String>)> {
+        embeds(text, config)
+            .into_iter()
+            .map(|embed| (embed.provider, embed.id))
+            .collect()
+    }
+
+    #[test]
+    fn links_are_classified_by_provider() {
+        let config = WallConfig::default();
+        let text = "watch https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=4, \
+            https://youtu.be/dQw4w9WgXcQ and https://x.com/w3wall/status/1790000000000000000 \
+            then https://cdn.example/cat.PNG?w=2 or https://example.com/page";
+        assert_eq!(
+            kinds(text, &config),
+            [
+                ("youtube", Some("dQw4w9WgXcQ".to_string())),
+                ("youtube", Some("dQw4w9WgXcQ".to_string())),
+                ("twitter", Some("1790000000000000000".to_string())),
+                ("image", None),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&embeds("https://a.example/b.gif.", &config)).unwrap(),
+            r#"[{"provider":"image","type":"photo","url":"https://a.example/b.gif","mime":"image/gif"}]"#
+        );
+    }
+
+    #[test]
+    fn only_allowed_providers_and_https_links_embed() {
+        let config = WallConfig {
+            embed_providers: vec!["twitter".to_string()],
+            ..Default::default()
+        };
+        let text = "https://youtu.be/dQw4w9WgXcQ https://twitter.com/a/status/12 \
+            http://x.com/a/status/13 https://x.com/a/likes https://youtu.be/short";
+        assert_eq!(kinds(text, &config), [("twitter", Some("12".to_string()))]);
+        assert!(kinds("https://a.example/x.svg", &WallConfig::default()).is_empty());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::defaults::DEFAULT_EXCERPT_LENGTH;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+pub static EXCERPT_ALIAS: &str = "excerpt";
+
+static IMAGE_OR_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap());
+static LINE_MARKER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^\s*(#{1,6}\s+|>\s?|[-*+]\s+|\d+[.)]\s+|```.*$)").unwrap());
+static EMPHASIS: Lazy<Regex> = Lazy::new(|| Regex::new(r"[*_~`]+").unwrap());
+
+/**
+ * Markdown reduced to its text: links and images keep their label, headings, quotes, list
+ * markers, fences and emphasis go, whitespace collapses to single spaces
+ */
+pub fn strip_markdown(text: &str) -> String {
+    let text = IMAGE_OR_LINK.replace_all(text, "$1");
+    let text = LINE_MARKER.replace_all(&text, "");
+    let text = EMPHASIS.replace_all(&text, "");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/**
+ * Markdown of an article's sections as one text, headings included, None for other posts
+ */
+pub fn article_text(payload: &Value) -> Option<String> {
+    let sections = payload["sections"].as_array()?;
+    let parts: Vec<&str> = sections
+        .iter()
+        .flat_map(|section| [section["heading"].as_str(), section["body"].as_str()])
+        .flatten()
+        .collect();
+    Some(parts.join("\n\n"))
+}
+
+/**
+ * Plain text of a post clamped to DEFAULT_EXCERPT_LENGTH characters, an ellipsis marks the cut
+ */
+pub fn excerpt(text: &str) -> String {
+    let plain = strip_markdown(text);
+    if plain.chars().count() <= DEFAULT_EXCERPT_LENGTH {
+        return plain;
+    }
+    let cut: String = plain.chars().take(DEFAULT_EXCERPT_LENGTH - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_is_stripped_and_clamped() {
+        assert_eq!(
+            strip_markdown("# gm\n\n> **bold** and _soft_ [docs](https://x.io) ![pic](ipfs://a)\n- one\n1. two\n```rust\nlet `x`;\n```"),
+            "gm bold and soft docs pic one two let x;"
+        );
+
+        let long = "word ".repeat(100);
+        let clamped = excerpt(&long);
+        assert_eq!(clamped.chars().count(), DEFAULT_EXCERPT_LENGTH);
+        assert!(clamped.ends_with("word…"));
+        assert_eq!(excerpt("gm *frens*"), "gm frens");
+
+        let article = serde_json::json!({
+            "sections": [{ "heading": "Intro", "body": "**gm**" }, { "body": "more" }]
+        });
+        assert_eq!(excerpt(&article_text(&article).unwrap()), "Intro gm more");
+    }
+}
@@ -0,0 +1,61 @@
+use crate::defaults::DEFAULT_COLLECTION_NAME;
+use crate::metadata::activitystreams::{post_url, thread_url};
+use crate::metadata::escape_markup;
+use crate::metadata::jsonld::headline;
+
+pub static FEED_ALIAS: &str = "feed";
+
+pub struct FeedItem {
+    pub cid: String,
+    pub author: String,
+    pub text: String,
+}
+
+fn item_xml(data_key: &str, item: &FeedItem) -> String {
+    format!(
+        "<item><title>{}</title><link>{}</link><guid isPermaLink=\"false\">{}</guid><dc:creator>{}</dc:creator><description>{}</description></item>",
+        escape_markup(&headline(&item.text)),
+        escape_markup(&post_url(data_key, &item.cid)),
+        escape_markup(&item.cid),
+        escape_markup(&item.author),
+        escape_markup(&item.text),
+    )
+}
+
+/**
+ * RSS 2.0 document of the given posts, newest first. Posts carry no date of their own, so items
+ * go without pubDate.
+ */
+pub fn render_rss(data_key: &str, items: &[FeedItem]) -> String {
+    let link = escape_markup(&thread_url(data_key));
+    let items: String = items.iter().map(|item| item_xml(data_key, item)).collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><channel><title>{} {}</title><link>{}</link><description>Latest posts of the thread</description>{}</channel></rss>",
+        DEFAULT_COLLECTION_NAME,
+        escape_markup(data_key),
+        link,
+        items
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_are_escaped() {
+        let rss = render_rss(
+            "wall",
+            &[FeedItem {
+                cid: "cid-1".to_string(),
+                author: "0xabc".to_string(),
+                text: "fish & <chips>".to_string(),
+            }],
+        );
+        assert!(rss.starts_with("<?xml"));
+        assert!(rss.contains("<title>fish &amp; &lt;chips&gt;</title>"));
+        assert!(rss.contains("<link>https://w3wall.xyz/topic/wall/post/cid-1</link>"));
+        assert!(rss.ends_with("</item></channel></rss>"));
+    }
+}
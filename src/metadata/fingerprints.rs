@@ -0,0 +1,91 @@
+use crate::data::DuplicateRules;
+use crate::metadata::timestamp_sec;
+use crate::moderation::simhash::distance;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static FINGERPRINTS_ALIAS: &str = "w3wall.fingerprints";
+
+/**
+ * Simhash of one recent post's text, as 16 hex digits since JSON numbers lose 64-bit precision
+ * in most clients
+ */
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Fingerprint {
+    pub simhash: String,
+    pub author: String,
+    pub at: u64,
+}
+
+/**
+ * Fingerprints of the wall's latest posts, oldest first, so a post is compared against one
+ * record instead of the whole wall
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct Fingerprints {
+    pub recent: Vec<Fingerprint>,
+}
+
+impl Fingerprints {
+    /**
+     * Whether one of the recent posts is within `max_distance` bits of `hash`
+     */
+    pub fn is_near_duplicate(&self, hash: u64, rules: &DuplicateRules) -> bool {
+        self.recent
+            .iter()
+            .filter_map(|f| u64::from_str_radix(&f.simhash, 16).ok())
+            .any(|recent| distance(recent, hash) <= rules.max_distance)
+    }
+
+    /**
+     * Remembers an accepted post, forgetting the oldest past the rules' window
+     */
+    pub fn add(&mut self, hash: u64, transaction: &Transaction, rules: &DuplicateRules) {
+        self.recent.push(Fingerprint {
+            simhash: format!("{:016x}", hash),
+            author: transaction.public_key.clone(),
+            at: timestamp_sec(transaction.timestamp),
+        });
+        let excess = self.recent.len().saturating_sub(rules.window);
+        self.recent.drain(..excess);
+    }
+}
+
+pub fn load_fingerprints(metadatas: &[Metadata], wall: &str) -> Fingerprints {
+    read_record(metadatas, FINGERPRINTS_ALIAS, wall).unwrap_or_default()
+}
+
+pub fn fingerprints_record(contract: &MetaContract, fingerprints: &Fingerprints) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: FINGERPRINTS_ALIAS.to_string(),
+        content: serde_json::to_string(fingerprints).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_latest_posts_are_remembered() {
+        let rules = DuplicateRules {
+            window: 2,
+            max_distance: 3,
+            ..Default::default()
+        };
+        let transaction = Transaction::default();
+        let mut fingerprints = Fingerprints::default();
+        for hash in [0b1111, 0xff00, 0xffff_0000] {
+            fingerprints.add(hash, &transaction, &rules);
+        }
+        assert_eq!(fingerprints.recent.len(), 2);
+        assert_eq!(fingerprints.recent[0].simhash, "000000000000ff00");
+        assert!(!fingerprints.is_near_duplicate(0b1111, &rules));
+        assert!(fingerprints.is_near_duplicate(0xff07, &rules));
+    }
+}
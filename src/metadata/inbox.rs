@@ -0,0 +1,164 @@
+use crate::crypto::eth_address::parse_address;
+use crate::defaults::{DEFAULT_INBOX_SIZE, DEFAULT_MAX_MENTIONS};
+use crate::metadata::timestamp_sec;
+use crate::storage::blocklist::load_block_list;
+use crate::storage::{find_post, read_record};
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub static INBOX_ALIAS_PREFIX: &str = "w3wall.inbox.";
+
+static MENTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(0x[0-9a-fA-F]{40})\b").unwrap());
+
+pub fn inbox_alias(public_key: &str) -> String {
+    format!("{}{}", INBOX_ALIAS_PREFIX, public_key.to_lowercase())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Reply,
+    Mention,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub from: String,
+    pub thread: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    pub at: u64,
+}
+
+/**
+ * Latest notifications of one key, newest first, kept by the contract key
+ */
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Inbox {
+    pub notifications: Vec<Notification>,
+}
+
+/**
+ * Keys `@0x…` mentioned in `text`, lowercased, first DEFAULT_MAX_MENTIONS only. A mention with
+ * a broken checksum names nobody.
+ */
+pub fn mentions(text: &str) -> Vec<String> {
+    let mut keys: Vec<String> = vec![];
+    for capture in MENTION.captures_iter(text) {
+        let Ok(key) = parse_address(&capture[1]) else {
+            continue;
+        };
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+        if keys.len() == DEFAULT_MAX_MENTIONS {
+            break;
+        }
+    }
+    keys
+}
+
+/**
+ * An inbox record for the author replied to and for every key mentioned, never for the sender
+ * or for keys that blocked them
+ */
+pub fn notify(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    payload: &Value,
+) -> Vec<FinalMetadata> {
+    let reply_to = payload["reply_to"].as_str();
+    let mut targets: Vec<(String, NotificationKind)> = vec![];
+    if let Some(post) = reply_to.and_then(|cid| find_post(metadatas, cid)) {
+        targets.push((post.public_key.to_lowercase(), NotificationKind::Reply));
+    }
+    for key in mentions(payload["text"].as_str().unwrap_or_default()) {
+        if !targets.iter().any(|(target, _)| target == &key) {
+            targets.push((key, NotificationKind::Mention));
+        }
+    }
+
+    targets
+        .into_iter()
+        .filter(|(target, _)| {
+            !target.eq_ignore_ascii_case(&transaction.public_key)
+                && !load_block_list(metadatas, target).is_blocked(&transaction.public_key)
+        })
+        .map(|(target, kind)| {
+            let alias = inbox_alias(&target);
+            let mut inbox: Inbox =
+                read_record(metadatas, &alias, &contract.public_key).unwrap_or_default();
+            inbox.notifications.insert(
+                0,
+                Notification {
+                    kind,
+                    from: transaction.public_key.clone(),
+                    thread: transaction.data_key.clone(),
+                    reply_to: reply_to.map(str::to_string),
+                    at: timestamp_sec(transaction.timestamp),
+                },
+            );
+            inbox.notifications.truncate(DEFAULT_INBOX_SIZE);
+            FinalMetadata {
+                public_key: contract.public_key.clone(),
+                alias,
+                content: serde_json::to_string(&inbox).unwrap(),
+                loose: 1,
+                version: "".to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+    #[test]
+    fn replies_and_mentions_notify_once() {
+        let metadatas = vec![Metadata {
+            public_key: ALICE.to_string(),
+            cid: "inbox-post".to_string(),
+            ..Default::default()
+        }];
+        let transaction = Transaction {
+            public_key: "0xbob".to_string(),
+            data_key: "wall".to_string(),
+            ..Default::default()
+        };
+        let payload = serde_json::json!({
+            "text": format!("@{} agreed, cc @{}", ALICE, ALICE.to_lowercase()),
+            "reply_to": "inbox-post",
+        });
+
+        let finals = notify(&MetaContract::default(), &metadatas, &transaction, &payload);
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].alias, inbox_alias(ALICE));
+        let inbox: Inbox = serde_json::from_str(&finals[0].content).unwrap();
+        assert_eq!(inbox.notifications[0].kind, NotificationKind::Reply);
+        assert_eq!(inbox.notifications[0].from, "0xbob");
+
+        let own = Transaction {
+            public_key: ALICE.to_string(),
+            ..transaction
+        };
+        assert!(notify(&MetaContract::default(), &metadatas, &own, &payload).is_empty());
+    }
+
+    #[test]
+    fn mistyped_mentions_name_nobody() {
+        let typo = ALICE.replace("7E5F", "7e5F");
+        assert_eq!(
+            mentions(&format!("@{} @{}", typo, ALICE)),
+            vec![ALICE.to_lowercase()]
+        );
+    }
+}
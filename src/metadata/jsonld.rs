@@ -0,0 +1,125 @@
+use crate::crypto::eth_address::short_address;
+use crate::data::ThreadActivity;
+use crate::metadata::activitystreams::{actor_url, post_url, thread_url};
+use crate::metadata::{iso8601, timestamp_sec};
+use crate::types::Transaction;
+use serde_json::{json, Value};
+
+pub static JSONLD_ALIAS: &str = "jsonld";
+
+/**
+ * Search engines truncate longer headlines
+ */
+const MAX_HEADLINE_CHARS: usize = 110;
+
+pub fn headline(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(MAX_HEADLINE_CHARS - 1) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+fn author(public_key: &str) -> Value {
+    json!({
+        "@type": "Person",
+        "identifier": public_key.to_lowercase(),
+        "name": short_address(public_key),
+        "url": actor_url(public_key),
+    })
+}
+
+/**
+ * schema.org DiscussionForumPosting of a new post, None for private posts
+ */
+pub fn post_jsonld(transaction: &Transaction, stored: &Value) -> Option<Value> {
+    if stored["visibility"].as_str() == Some("private") {
+        return None;
+    }
+    let text = stored["text"].as_str().unwrap_or_default();
+
+    let mut posting = json!({
+        "@context": "https://schema.org",
+        "@type": "DiscussionForumPosting",
+        "url": post_url(&transaction.data_key, &transaction.hash),
+        "headline": headline(text),
+        "text": text,
+        "author": author(&transaction.public_key),
+        "datePublished": iso8601(timestamp_sec(transaction.timestamp)),
+        "commentCount": 0,
+        "isPartOf": thread_url(&transaction.data_key),
+    });
+    if let Some(image) = stored["image"].as_str().filter(|image| !image.is_empty()) {
+        posting["image"] = image.into();
+    }
+    if let Some(license) = stored["license"].as_str() {
+        posting["license"] = license.into();
+    }
+    Some(posting)
+}
+
+/**
+ * The thread as a whole, refreshed with its live reply count
+ */
+pub fn thread_jsonld(data_key: &str, activity: &ThreadActivity) -> Value {
+    let mut thread = json!({
+        "@context": "https://schema.org",
+        "@type": "DiscussionForumPosting",
+        "url": thread_url(data_key),
+        "commentCount": activity.replies,
+        "interactionStatistic": {
+            "@type": "InteractionCounter",
+            "interactionType": "https://schema.org/CommentAction",
+            "userInteractionCount": activity.replies,
+        },
+    });
+    if activity.created > 0 {
+        thread["datePublished"] = iso8601(activity.created).into();
+    }
+    if activity.last_activity > 0 {
+        thread["dateModified"] = iso8601(activity.last_activity).into();
+    }
+    thread
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posts_map_to_discussion_forum_postings() {
+        let transaction = Transaction {
+            public_key: "0xABC".to_string(),
+            data_key: "wall".to_string(),
+            hash: "0xtx".to_string(),
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        };
+        let text = format!("{}\nsecond line", "a".repeat(200));
+        let posting =
+            post_jsonld(&transaction, &json!({ "text": text, "license": "CC0" })).unwrap();
+        assert_eq!(posting["@type"], "DiscussionForumPosting");
+        assert_eq!(posting["author"]["identifier"], "0xabc");
+        assert_eq!(posting["author"]["name"], "0xABC");
+        assert_eq!(posting["datePublished"], "2023-11-14T22:13:20Z");
+        assert_eq!(
+            posting["headline"].as_str().unwrap().chars().count(),
+            MAX_HEADLINE_CHARS
+        );
+        assert_eq!(posting["license"], "CC0");
+        assert!(post_jsonld(&transaction, &json!({ "visibility": "private" })).is_none());
+    }
+
+    #[test]
+    fn thread_counts_comments() {
+        let thread = thread_jsonld(
+            "wall",
+            &ThreadActivity {
+                replies: 3,
+                ..Default::default()
+            },
+        );
+        assert_eq!(thread["commentCount"], 3);
+        assert!(thread.get("datePublished").is_none());
+    }
+}
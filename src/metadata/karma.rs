@@ -0,0 +1,180 @@
+use crate::data::KarmaConfig;
+use crate::defaults::{DEFAULT_KARMA_PER_POST, DEFAULT_KARMA_PER_REACTION};
+use crate::storage::alias::Alias;
+use crate::storage::envelope;
+use crate::types::{FinalMetadata, MetaContract, Metadata};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+pub static KARMA_ALIAS_PREFIX: &str = "w3wall.karma.";
+pub static REACTION_ALIAS_PREFIX: &str = "w3wall.reaction.";
+
+/**
+ * Reactions are stored under an alias naming the author they go to, so karma can be counted
+ * from the metadata list alone without reading every record from IPFS
+ */
+pub fn reaction_alias(target: &str) -> String {
+    format!("{}{}", REACTION_ALIAS_PREFIX, target.to_lowercase())
+}
+
+pub fn karma_alias(public_key: &str) -> String {
+    format!("{}{}", KARMA_ALIAS_PREFIX, public_key.to_lowercase())
+}
+
+/**
+ * Which reactions count toward karma and trending: on walls with a `min_reactor_age_sec`, only
+ * those of keys whose first post on the wall is at least that old at `now`. Posts stored before
+ * envelopes recorded a creation time are old enough.
+ */
+pub struct ReactionFilter<'a> {
+    metadatas: &'a [Metadata],
+    min_age_sec: u64,
+    now: u64,
+    checked: RefCell<HashMap<String, bool>>,
+}
+
+impl<'a> ReactionFilter<'a> {
+    pub fn new(metadatas: &'a [Metadata], karma: &KarmaConfig, now: u64) -> Self {
+        ReactionFilter {
+            metadatas,
+            min_age_sec: karma.min_reactor_age_sec,
+            now,
+            checked: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn counts(&self, reactor: &str) -> bool {
+        if self.min_age_sec == 0 {
+            return true;
+        }
+        let key = reactor.to_lowercase();
+        if let Some(counts) = self.checked.borrow().get(&key) {
+            return *counts;
+        }
+        let first_post = self
+            .metadatas
+            .iter()
+            .find(|m| m.alias == Alias::Post && m.public_key.eq_ignore_ascii_case(reactor));
+        let counts = first_post.is_some_and(|post| match envelope::created_at(post) {
+            Some(at) => self.now.saturating_sub(at) >= self.min_age_sec,
+            None => true,
+        });
+        self.checked.borrow_mut().insert(key, counts);
+        counts
+    }
+}
+
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct Karma {
+    pub posts: u64,
+    pub reactions: u64,
+    pub karma: u64,
+}
+
+impl Karma {
+    /**
+     * Accepted posts on the wall plus reactions received from other keys, of those `reactions`
+     * lets count
+     */
+    pub fn of(metadatas: &[Metadata], public_key: &str, reactions: &ReactionFilter) -> Self {
+        let alias = reaction_alias(public_key);
+
+        let posts = metadatas
+            .iter()
+            .filter(|m| m.alias == Alias::Post && m.public_key.eq_ignore_ascii_case(public_key))
+            .count() as u64;
+        let reactions = metadatas
+            .iter()
+            .filter(|m| m.alias == alias && !m.public_key.eq_ignore_ascii_case(public_key))
+            .filter(|m| reactions.counts(&m.public_key))
+            .count() as u64;
+
+        Karma::new(posts, reactions)
+    }
+
+    pub fn new(posts: u64, reactions: u64) -> Self {
+        Karma {
+            posts,
+            reactions,
+            karma: posts * DEFAULT_KARMA_PER_POST + reactions * DEFAULT_KARMA_PER_REACTION,
+        }
+    }
+}
+
+pub fn karma_record(contract: &MetaContract, public_key: &str, karma: &Karma) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: karma_alias(public_key),
+        content: serde_json::to_string(karma).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(public_key: &str, alias: &str) -> Metadata {
+        Metadata {
+            public_key: public_key.to_string(),
+            alias: alias.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn karma_counts_posts_and_foreign_reactions() {
+        let metadatas = vec![
+            record("0xA", ""),
+            record("0xa", ""),
+            record("0xb", &reaction_alias("0xA")),
+            record("0xa", &reaction_alias("0xa")),
+            record("0xb", ""),
+        ];
+
+        let reactions = ReactionFilter::new(&metadatas, &KarmaConfig::default(), 0);
+        assert_eq!(
+            Karma::of(&metadatas, "0xa", &reactions),
+            Karma {
+                posts: 2,
+                reactions: 1,
+                karma: 2 * DEFAULT_KARMA_PER_POST + DEFAULT_KARMA_PER_REACTION,
+            }
+        );
+        assert_eq!(karma_alias("0xA"), "w3wall.karma.0xa");
+    }
+
+    #[test]
+    fn reactions_of_young_keys_do_not_count() {
+        crate::storage::ipfs::test_store::put(
+            "karma-old-post",
+            r#"{"v":1,"type":"post","author":"0xold","created_at":100,"body":"gm","refs":[]}"#,
+        );
+        crate::storage::ipfs::test_store::put(
+            "karma-new-post",
+            r#"{"v":1,"type":"post","author":"0xnew","created_at":900,"body":"gm","refs":[]}"#,
+        );
+        let post = |public_key: &str, cid: &str| Metadata {
+            cid: cid.to_string(),
+            ..record(public_key, "")
+        };
+        let metadatas = vec![
+            post("0xold", "karma-old-post"),
+            post("0xnew", "karma-new-post"),
+            record("0xold", &reaction_alias("0xa")),
+            record("0xnew", &reaction_alias("0xa")),
+            record("0xnobody", &reaction_alias("0xa")),
+        ];
+        let karma = KarmaConfig {
+            min_reactor_age_sec: 500,
+            ..Default::default()
+        };
+        let reactions = ReactionFilter::new(&metadatas, &karma, 1_000);
+        assert!(reactions.counts("0xOLD"));
+        assert!(!reactions.counts("0xnew"));
+        assert!(!reactions.counts("0xnobody"));
+        assert_eq!(Karma::of(&metadatas, "0xa", &reactions).reactions, 1);
+    }
+}
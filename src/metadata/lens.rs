@@ -0,0 +1,67 @@
+use crate::metadata::activitystreams::post_url;
+use crate::metadata::jsonld::headline;
+use crate::types::Transaction;
+use crate::validation::image::image_mime;
+use serde_json::{json, Value};
+
+pub static LENS_ALIAS: &str = "lens";
+pub static LENS_APP_ID: &str = "w3wall";
+
+/**
+ * Lens publication metadata (v2.0.0) of a post, what a Lens app needs to mirror it. Private
+ * posts are not mirrored.
+ */
+pub fn publication_metadata(transaction: &Transaction, stored: &Value) -> Option<Value> {
+    if stored["visibility"].as_str() == Some("private") {
+        return None;
+    }
+    let content = stored["text"].as_str().unwrap_or_default();
+    let image = stored["image"].as_str().filter(|image| !image.is_empty());
+
+    let mut metadata = json!({
+        "version": "2.0.0",
+        "metadata_id": transaction.hash,
+        "content": content,
+        "name": headline(content),
+        "description": content,
+        "locale": "en",
+        "mainContentFocus": if image.is_some() { "IMAGE" } else { "TEXT_ONLY" },
+        "external_url": post_url(&transaction.data_key, &transaction.hash),
+        "attributes": [{ "traitType": "origin", "displayType": "string", "value": "w3wall" }],
+        "tags": [],
+        "appId": LENS_APP_ID,
+    });
+    if let Some(image) = image {
+        metadata["image"] = image.into();
+        metadata["media"] =
+            json!([{ "item": image, "type": image_mime(image).unwrap_or("image/jpeg") }]);
+    }
+    Some(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posts_map_to_lens_v2_metadata() {
+        let transaction = Transaction {
+            data_key: "wall".to_string(),
+            hash: "0xtx".to_string(),
+            ..Default::default()
+        };
+        let metadata = publication_metadata(&transaction, &json!({ "text": "gm lens" })).unwrap();
+        assert_eq!(metadata["version"], "2.0.0");
+        assert_eq!(metadata["mainContentFocus"], "TEXT_ONLY");
+        assert_eq!(metadata["content"], "gm lens");
+        assert_eq!(metadata["appId"], "w3wall");
+
+        let metadata = publication_metadata(
+            &transaction,
+            &json!({ "image": "https://nftstorage.link/ipfs/x" }),
+        )
+        .unwrap();
+        assert_eq!(metadata["mainContentFocus"], "IMAGE");
+        assert!(publication_metadata(&transaction, &json!({ "visibility": "private" })).is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::storage::alias::Alias;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static METRICS_ALIAS: &str = "w3wall.metrics";
+
+/**
+ * Running counters kept by the contract itself. Rejected transactions carry no metadata back
+ * to the node, so only accepted work can be counted.
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct WallMetrics {
+    pub executions: u64,
+    pub posts: u64,
+    pub edits: u64,
+    /**
+     * Work budget units the last post took, deterministic unlike a clock
+     */
+    pub last_validation_units: u64,
+}
+
+pub fn load_metrics(contract: &MetaContract, metadatas: &[Metadata]) -> WallMetrics {
+    read_record(metadatas, METRICS_ALIAS, &contract.public_key).unwrap_or_default()
+}
+
+/**
+ * A post from a key that already has a post record on the wall is an edit
+ */
+pub fn count_execution(
+    metrics: &mut WallMetrics,
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    is_post: bool,
+    validation_units: u64,
+) {
+    metrics.executions += 1;
+    if is_post {
+        let existing = metadatas
+            .iter()
+            .any(|m| m.alias == Alias::Post && m.public_key == transaction.public_key);
+        if existing {
+            metrics.edits += 1;
+        } else {
+            metrics.posts += 1;
+        }
+        metrics.last_validation_units = validation_units;
+    }
+}
+
+pub fn metrics_record(contract: &MetaContract, metrics: &WallMetrics) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: METRICS_ALIAS.to_string(),
+        content: serde_json::to_string(metrics).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posts_and_edits_are_told_apart() {
+        let mut metrics = WallMetrics::default();
+        let transaction = Transaction {
+            public_key: "0xa".to_string(),
+            ..Default::default()
+        };
+        count_execution(&mut metrics, &[], &transaction, true, 12);
+
+        let existing = vec![Metadata {
+            public_key: "0xa".to_string(),
+            ..Default::default()
+        }];
+        count_execution(&mut metrics, &existing, &transaction, true, 7);
+        count_execution(&mut metrics, &existing, &transaction, false, 0);
+
+        assert_eq!(
+            metrics,
+            WallMetrics {
+                executions: 3,
+                posts: 1,
+                edits: 1,
+                last_validation_units: 7,
+            }
+        );
+    }
+}
@@ -0,0 +1,222 @@
+pub mod activitystreams;
+pub mod attributes;
+pub mod badges;
+pub mod board;
+pub mod cosmetics;
+pub mod digest;
+pub mod embeds;
+pub mod excerpt;
+pub mod feed;
+pub mod fingerprints;
+pub mod inbox;
+pub mod jsonld;
+pub mod karma;
+pub mod lens;
+pub mod metrics;
+pub mod quarantine;
+pub mod quota;
+pub mod reading;
+pub mod rollup;
+pub mod search;
+#[cfg(feature = "mint")]
+pub mod svg;
+#[cfg(feature = "tipping")]
+pub mod tips;
+pub mod trending;
+
+#[cfg(feature = "mint")]
+use crate::defaults::DEFAULT_EXTERNAL_URL_TEMPLATE;
+use crate::defaults::{DEFAULT_MAX_DESCRIPTION_LENGTH, DEFAULT_MAX_DESCRIPTION_TITLE_LENGTH};
+
+/**
+ * Link back to the live thread on the w3wall frontend
+ */
+#[cfg(feature = "mint")]
+pub fn external_url(data_key: &str, token_id: &str) -> String {
+    DEFAULT_EXTERNAL_URL_TEMPLATE
+        .replace("{data_key}", data_key)
+        .replace("{token_id}", token_id)
+}
+
+/**
+ * Topic description from the wall's template, with `{title}`, `{author}` and `{reply_count}`
+ * filled in. The title is clamped before it is substituted, the result after.
+ */
+pub fn description(template: &str, title: &str, author: &str, reply_count: u64) -> String {
+    let title: String = title
+        .chars()
+        .take(DEFAULT_MAX_DESCRIPTION_TITLE_LENGTH)
+        .collect();
+    template
+        .replace("{title}", &title)
+        .replace("{author}", author)
+        .replace("{reply_count}", &reply_count.to_string())
+        .chars()
+        .take(DEFAULT_MAX_DESCRIPTION_LENGTH)
+        .collect()
+}
+
+/**
+ * Marketplace-safe text: control characters go (newlines and tabs stay in multiline fields),
+ * as do bidi overrides that can disguise a name. JS line separators, which break JSON embedded
+ * in scripts, become newlines or spaces. Clamped to `max` characters.
+ */
+pub fn sanitize(text: &str, max: usize, multiline: bool) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\n' | '\t' if multiline => Some(c),
+            '\u{2028}' | '\u{2029}' => Some(if multiline { '\n' } else { ' ' }),
+            '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => None,
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .take(max)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/**
+ * Transaction timestamps are sent by clients in milliseconds
+ */
+pub fn timestamp_sec(timestamp: u64) -> u64 {
+    if timestamp > 10_000_000_000 {
+        timestamp / 1000
+    } else {
+        timestamp
+    }
+}
+
+/**
+ * Escapes text for HTML and XML bodies and attribute values
+ */
+pub fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/**
+ * UTC `YYYY-MM-DDTHH:MM:SSZ` of a unix timestamp, days to civil date after Howard Hinnant
+ */
+pub fn iso8601(sec: u64) -> String {
+    let days = (sec / 86_400) as i64;
+    let rem = sec % 86_400;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/**
+ * Unix seconds of a UTC `YYYY-MM-DDTHH:MM:SS[.fff]Z` timestamp, None for anything else
+ */
+pub fn parse_iso8601(value: &str) -> Option<u64> {
+    let value = value.strip_suffix('Z')?;
+    let (date, time) = value.split_once('T')?;
+    let time = time.split('.').next()?;
+
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let time: Vec<u64> = time
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let (&[year, month, day], &[hour, minute, second]) = (date.as_slice(), time.as_slice()) else {
+        return None;
+    };
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    u64::try_from(days)
+        .ok()
+        .map(|days| days * 86_400 + hour * 3600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn external_url_interpolates_keys() {
+        assert_eq!(
+            external_url("abc", "7"),
+            "https://w3wall.xyz/topic/abc/7".to_string()
+        );
+    }
+
+    #[test]
+    fn description_fills_placeholders_within_limits() {
+        assert_eq!(
+            description("{title} by {author}, {reply_count} replies", "gm", "0xa", 3),
+            "gm by 0xa, 3 replies"
+        );
+        let long = "t".repeat(DEFAULT_MAX_DESCRIPTION_TITLE_LENGTH + 1);
+        assert_eq!(
+            description("{title}", &long, "", 0).len(),
+            DEFAULT_MAX_DESCRIPTION_TITLE_LENGTH
+        );
+        assert_eq!(
+            description(&"{title}".repeat(20), &long, "", 0).len(),
+            DEFAULT_MAX_DESCRIPTION_LENGTH
+        );
+    }
+
+    #[test]
+    fn timestamps_are_normalized_to_seconds() {
+        assert_eq!(timestamp_sec(1_700_000_000_000), 1_700_000_000);
+        assert_eq!(timestamp_sec(1_700_000_000), 1_700_000_000);
+    }
+
+    #[test]
+    fn iso8601_formats_utc_dates() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00Z");
+        assert_eq!(iso8601(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(iso8601(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn iso8601_parses_back() {
+        assert_eq!(
+            parse_iso8601("2023-11-14T22:13:20.000Z"),
+            Some(1_700_000_000)
+        );
+        assert_eq!(parse_iso8601("2000-02-29T00:00:00Z"), Some(951_782_400));
+        assert_eq!(parse_iso8601("2023-13-14T22:13:20Z"), None);
+        assert_eq!(parse_iso8601("yesterday"), None);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::data::WallConfig;
+use crate::metadata::timestamp_sec;
+use crate::storage::alias::Alias;
+use crate::storage::envelope;
+use crate::types::{Metadata, Transaction};
+
+/**
+ * When a key first wrote anything on the wall, None when it never has. Records stored before
+ * envelopes existed carry no time, a key owning one is as old as the wall.
+ */
+pub fn first_seen(metadatas: &[Metadata], public_key: &str) -> Option<u64> {
+    metadatas
+        .iter()
+        .filter(|m| m.public_key.eq_ignore_ascii_case(public_key))
+        .map(|m| envelope::created_at(m).unwrap_or(0))
+        .min()
+}
+
+/**
+ * Whether the sender is still within its first `posts` posts and first `hours` since it
+ * appeared, the post being sent counts as an appearance
+ */
+pub fn is_new_account(
+    metadatas: &[Metadata],
+    transaction: &Transaction,
+    config: &WallConfig,
+) -> bool {
+    let rules = &config.rules.quarantine;
+    let sender = &transaction.public_key;
+    if rules.posts == 0 || config.is_moderator(sender) || config.is_trusted(sender) {
+        return false;
+    }
+    let posts = metadatas
+        .iter()
+        .filter(|m| m.alias == Alias::Post && m.public_key == transaction.public_key)
+        .count();
+    let now = timestamp_sec(transaction.timestamp);
+    let since = first_seen(metadatas, &transaction.public_key).unwrap_or(now);
+    posts < rules.posts && now.saturating_sub(since) < rules.hours * 60 * 60
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{QuarantineRules, WallRules};
+
+    #[test]
+    fn keys_leave_quarantine_by_posting_or_by_age() {
+        let config = WallConfig {
+            rules: WallRules {
+                quarantine: QuarantineRules {
+                    posts: 2,
+                    hours: 24,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let transaction = |timestamp: u64| Transaction {
+            public_key: "0xnew".to_string(),
+            timestamp,
+            ..Default::default()
+        };
+        assert!(is_new_account(&[], &transaction(100), &config));
+        assert!(!is_new_account(
+            &[],
+            &transaction(100),
+            &WallConfig::default()
+        ));
+        let owned = WallConfig {
+            owner: "0xNEW".to_string(),
+            ..config.clone()
+        };
+        assert!(!is_new_account(&[], &transaction(100), &owned));
+
+        crate::storage::ipfs::test_store::put(
+            "quarantine-first",
+            r#"{"v":1,"type":"post","author":"0xnew","created_at":100,"body":{},"refs":[]}"#,
+        );
+        let post = Metadata {
+            public_key: "0xnew".to_string(),
+            cid: "quarantine-first".to_string(),
+            ..Default::default()
+        };
+        let one = [post.clone()];
+        assert_eq!(first_seen(&one, "0xNEW"), Some(100));
+        assert!(is_new_account(&one, &transaction(200), &config));
+        assert!(!is_new_account(
+            &one,
+            &transaction(100 + 24 * 60 * 60),
+            &config
+        ));
+        assert!(!is_new_account(
+            &[post.clone(), post],
+            &transaction(200),
+            &config
+        ));
+
+        let legacy = [Metadata {
+            public_key: "0xnew".to_string(),
+            alias: "w3wall.profile".to_string(),
+            cid: "quarantine-legacy".to_string(),
+            ..Default::default()
+        }];
+        assert_eq!(first_seen(&legacy, "0xnew"), Some(0));
+        assert!(!is_new_account(
+            &legacy,
+            &transaction(1_700_000_000),
+            &config
+        ));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::metadata::timestamp_sec;
+use crate::storage::alias::Alias;
+use crate::storage::envelope;
+use crate::types::{Metadata, Transaction};
+
+const DAY_SEC: u64 = 24 * 60 * 60;
+
+pub fn day_of(transaction: &Transaction) -> u64 {
+    timestamp_sec(transaction.timestamp) / DAY_SEC
+}
+
+/**
+ * Posts the sender already made today (UTC), counted from the wall's own post records by their
+ * envelope time. Nothing the poster writes can lower the count. Posts stored before envelopes
+ * carry no time and are left out.
+ */
+pub fn posts_today(metadatas: &[Metadata], transaction: &Transaction) -> u64 {
+    let today = day_of(transaction);
+    metadatas
+        .iter()
+        .filter(|m| {
+            m.alias == Alias::Post && m.public_key.eq_ignore_ascii_case(&transaction.public_key)
+        })
+        .filter(|post| envelope::created_at(post).is_some_and(|at| at / DAY_SEC == today))
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ipfs::test_store;
+
+    fn post(cid: &str, author: &str, created_at: u64) -> Metadata {
+        test_store::put(
+            cid,
+            &format!(
+                r#"{{"v":1,"type":"post","author":"{}","created_at":{},"body":{{"text":"gm"}},"refs":[]}}"#,
+                author, created_at
+            ),
+        );
+        Metadata {
+            public_key: author.to_string(),
+            cid: cid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_the_senders_posts_of_the_day() {
+        let day = 19_675;
+        let transaction = Transaction {
+            public_key: "0xA".to_string(),
+            timestamp: day * DAY_SEC * 1000 + 5_000,
+            ..Default::default()
+        };
+        let metadatas = vec![
+            post("quota-a-1", "0xa", day * DAY_SEC + 1),
+            post("quota-a-2", "0xa", day * DAY_SEC + 2),
+            post("quota-a-yesterday", "0xa", day * DAY_SEC - 1),
+            post("quota-b", "0xb", day * DAY_SEC + 3),
+        ];
+
+        assert_eq!(posts_today(&metadatas, &transaction), 2);
+        let tomorrow = Transaction {
+            timestamp: (day + 1) * DAY_SEC * 1000,
+            ..transaction
+        };
+        assert_eq!(posts_today(&metadatas, &tomorrow), 0);
+    }
+}
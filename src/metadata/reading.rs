@@ -0,0 +1,48 @@
+use crate::defaults::DEFAULT_WORDS_PER_MINUTE;
+use serde::Serialize;
+
+pub static READING_ALIAS: &str = "reading";
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Reading {
+    pub words: u64,
+    pub minutes: u64,
+}
+
+impl Reading {
+    /**
+     * Whitespace separated words, reading time rounded up to whole minutes
+     */
+    pub fn of(text: &str) -> Self {
+        let words = text.split_whitespace().count() as u64;
+        Reading {
+            words,
+            minutes: words.div_ceil(DEFAULT_WORDS_PER_MINUTE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minutes_round_up() {
+        assert_eq!(
+            Reading::of("  "),
+            Reading {
+                words: 0,
+                minutes: 0
+            }
+        );
+        assert_eq!(
+            Reading::of("gm\nfrens"),
+            Reading {
+                words: 2,
+                minutes: 1
+            }
+        );
+        let long = "word ".repeat(DEFAULT_WORDS_PER_MINUTE as usize + 1);
+        assert_eq!(Reading::of(&long).minutes, 2);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::data::ThreadActivity;
+use crate::metadata::timestamp_sec;
+use crate::storage::alias::Alias;
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub static ROLLUP_ALIAS: &str = "w3wall.rollup";
+
+/**
+ * Thread totals for forum index pages, so a frontend reads one record instead of the thread
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct ThreadRollup {
+    pub replies: u64,
+    pub authors: u64,
+    pub last_activity: u64,
+}
+
+/**
+ * The stored rollup, or one counted from the posts when the thread predates rollups
+ */
+pub fn load_rollup(contract: &MetaContract, metadatas: &[Metadata]) -> ThreadRollup {
+    read_record(metadatas, ROLLUP_ALIAS, &contract.public_key).unwrap_or_else(|| {
+        let activity = ThreadActivity::from_metadatas(metadatas);
+        ThreadRollup {
+            replies: activity.replies,
+            authors: activity.participants,
+            last_activity: 0,
+        }
+    })
+}
+
+/**
+ * Counts an accepted post, its author is new when they have no post record on the wall yet
+ */
+pub fn count_reply(rollup: &mut ThreadRollup, metadatas: &[Metadata], transaction: &Transaction) {
+    let known = metadatas
+        .iter()
+        .any(|m| m.alias == Alias::Post && m.public_key == transaction.public_key);
+    rollup.replies += 1;
+    if !known {
+        rollup.authors += 1;
+    }
+    rollup.last_activity = rollup
+        .last_activity
+        .max(timestamp_sec(transaction.timestamp));
+}
+
+pub fn rollup_record(contract: &MetaContract, rollup: &ThreadRollup) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: ROLLUP_ALIAS.to_string(),
+        content: serde_json::to_string(rollup).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(public_key: &str) -> Metadata {
+        Metadata {
+            public_key: public_key.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rollup_starts_from_the_thread_and_counts_new_authors() {
+        let contract = MetaContract::default();
+        let metadatas = vec![post("0xa"), post("0xa"), post("0xb")];
+        let mut rollup = load_rollup(&contract, &metadatas);
+        assert_eq!((rollup.replies, rollup.authors), (3, 2));
+
+        let transaction = Transaction {
+            public_key: "0xb".to_string(),
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        };
+        count_reply(&mut rollup, &metadatas, &transaction);
+        let transaction = Transaction {
+            public_key: "0xc".to_string(),
+            timestamp: 1_600_000_000_000,
+            ..transaction
+        };
+        count_reply(&mut rollup, &metadatas, &transaction);
+
+        assert_eq!(
+            rollup,
+            ThreadRollup {
+                replies: 5,
+                authors: 3,
+                last_activity: 1_700_000_000,
+            }
+        );
+    }
+}
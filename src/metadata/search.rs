@@ -0,0 +1,43 @@
+use crate::defaults::{DEFAULT_MAX_SEARCH_TERMS, DEFAULT_STOPWORDS};
+
+pub static SEARCH_INDEX_ALIAS: &str = "search_index";
+
+/**
+ * Lowercased words of `text` in first-seen order, without stopwords, one-letter words or
+ * repeats, capped at DEFAULT_MAX_SEARCH_TERMS
+ */
+pub fn search_terms(text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = vec![];
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+    {
+        if terms.len() == DEFAULT_MAX_SEARCH_TERMS {
+            break;
+        }
+        if word.chars().count() < 2
+            || DEFAULT_STOPWORDS.contains(&word.as_str())
+            || terms.contains(&word)
+        {
+            continue;
+        }
+        terms.push(word);
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terms_are_normalized() {
+        assert_eq!(
+            search_terms("The Merge is live, the merge IS LIVE! a 🎉 Ünïcode"),
+            vec!["merge", "live", "ünïcode"]
+        );
+        assert_eq!(search_terms(&"word ".repeat(10)).len(), 1);
+        let many: String = (0..100).map(|i| format!("w{} ", i)).collect();
+        assert_eq!(search_terms(&many).len(), DEFAULT_MAX_SEARCH_TERMS);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::crypto::hash::keccak256;
+use crate::defaults::{DEFAULT_THEME_ACCENT, DEFAULT_THEME_BACKGROUND, DEFAULT_THEME_FOREGROUND};
+
+const TITLE_MAX_CHARS: usize = 48;
+const IDENTICON_CELLS: usize = 5;
+const IDENTICON_CELL_PX: usize = 24;
+
+/**
+ * Deterministic image for topics minted without one: title text over the wall theme
+ * plus a symmetric identicon derived from the data_key
+ */
+pub fn topic_image(title: &str, data_key: &str) -> String {
+    let svg = topic_svg(title, data_key);
+    format!(
+        "data:image/svg+xml;base64,{}",
+        base64_encode(svg.as_bytes())
+    )
+}
+
+fn topic_svg(title: &str, data_key: &str) -> String {
+    let title: String = title.trim().chars().take(TITLE_MAX_CHARS).collect();
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"512\" height=\"512\" viewBox=\"0 0 512 512\">\
+         <rect width=\"512\" height=\"512\" fill=\"{}\"/>\
+         <g transform=\"translate(196 96)\">{}</g>\
+         <text x=\"256\" y=\"360\" fill=\"{}\" font-family=\"sans-serif\" font-size=\"24\" text-anchor=\"middle\">{}</text>\
+         </svg>",
+        DEFAULT_THEME_BACKGROUND,
+        identicon(data_key),
+        DEFAULT_THEME_FOREGROUND,
+        escape_xml(&title),
+    )
+}
+
+/**
+ * 5x5 grid mirrored on the vertical axis, one bit of the keccak hash per cell
+ */
+fn identicon(data_key: &str) -> String {
+    let hash = keccak256(data_key);
+    let mut cells = String::new();
+
+    for row in 0..IDENTICON_CELLS {
+        for col in 0..IDENTICON_CELLS.div_ceil(2) {
+            let bit = row * 3 + col;
+            if hash[bit / 8] & (1 << (bit % 8)) == 0 {
+                continue;
+            }
+
+            for x in [col, IDENTICON_CELLS - 1 - col] {
+                cells.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x * IDENTICON_CELL_PX,
+                    row * IDENTICON_CELL_PX,
+                    IDENTICON_CELL_PX,
+                    IDENTICON_CELL_PX,
+                    DEFAULT_THEME_ACCENT,
+                ));
+                if x == IDENTICON_CELLS - 1 - x {
+                    break;
+                }
+            }
+        }
+    }
+
+    cells
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+    }
+
+    #[test]
+    fn image_is_deterministic_and_escaped() {
+        let image = topic_image("<b>hi</b>", "key");
+        assert!(image.starts_with("data:image/svg+xml;base64,"));
+        assert_eq!(image, topic_image("<b>hi</b>", "key"));
+        assert_ne!(identicon("key"), identicon("other"));
+        assert!(topic_svg("<b>hi</b>", "key").contains("&lt;b&gt;hi&lt;/b&gt;"));
+    }
+}
@@ -0,0 +1,80 @@
+use crate::storage::read_record;
+use crate::types::{FinalMetadata, MetaContract, Metadata};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub static TIP_ALIAS_PREFIX: &str = "w3wall.tip.";
+pub static TIPS_ALIAS_PREFIX: &str = "w3wall.tips.";
+
+/**
+ * A tipper's record of a tip on the post stored at `cid`
+ */
+pub fn tip_alias(cid: &str) -> String {
+    format!("{}{}", TIP_ALIAS_PREFIX, cid)
+}
+
+/**
+ * The contract's running total of the tips on the post stored at `cid`
+ */
+pub fn tips_alias(cid: &str) -> String {
+    format!("{}{}", TIPS_ALIAS_PREFIX, cid)
+}
+
+/**
+ * Amounts are decimal strings in the token's base units, keyed by token ("native" or the token
+ * contract). The tip transaction hashes are kept so the same on-chain tip can't be counted twice.
+ */
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct TipTotals {
+    pub count: u64,
+    pub totals: BTreeMap<String, String>,
+    pub tx_hashes: Vec<String>,
+}
+
+impl TipTotals {
+    pub fn add(&mut self, tx_hash: &str, token: &str, amount: U256) {
+        let total = self
+            .totals
+            .get(token)
+            .and_then(|t| U256::from_dec_str(t).ok())
+            .unwrap_or_default();
+        self.totals
+            .insert(token.to_string(), total.saturating_add(amount).to_string());
+        self.tx_hashes.push(tx_hash.to_string());
+        self.count += 1;
+    }
+}
+
+pub fn load_totals(contract: &MetaContract, metadatas: &[Metadata], cid: &str) -> TipTotals {
+    read_record(metadatas, &tips_alias(cid), &contract.public_key).unwrap_or_default()
+}
+
+pub fn totals_record(contract: &MetaContract, cid: &str, totals: &TipTotals) -> FinalMetadata {
+    FinalMetadata {
+        public_key: contract.public_key.clone(),
+        alias: tips_alias(cid),
+        content: serde_json::to_string(totals).unwrap(),
+        loose: 1,
+        version: "".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_add_up_per_token() {
+        let mut totals = TipTotals::default();
+        totals.add("0x1", "native", U256::from(5));
+        totals.add("0x2", "native", U256::from(7));
+        totals.add("0x3", "0xtoken", U256::from(1));
+
+        assert_eq!(totals.count, 3);
+        assert_eq!(totals.totals["native"], "12");
+        assert_eq!(totals.totals["0xtoken"], "1");
+        assert_eq!(tips_alias("bafy"), "w3wall.tips.bafy");
+    }
+}
@@ -0,0 +1,96 @@
+use crate::defaults::{
+    DEFAULT_TRENDING_HALF_LIFE_SEC, DEFAULT_TRENDING_REACTION_WEIGHT, DEFAULT_TRENDING_REPLY_WEIGHT,
+};
+use crate::metadata::karma::{ReactionFilter, REACTION_ALIAS_PREFIX};
+use crate::metadata::rollup::ThreadRollup;
+use crate::types::Metadata;
+use serde::{Deserialize, Serialize};
+
+pub static TRENDING_ALIAS: &str = "w3wall.trending";
+
+/**
+ * Heat of a thread at `computed_at`, comparable across threads refreshed at the same time
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Trending {
+    pub score: u64,
+    pub computed_at: u64,
+}
+
+/**
+ * `weight` halved for every DEFAULT_TRENDING_HALF_LIFE_SEC of `age`, linear between half-lives.
+ * Integer only so every node computes the same score.
+ */
+fn decay(weight: u64, age: u64) -> u64 {
+    let half_lives = age / DEFAULT_TRENDING_HALF_LIFE_SEC;
+    if half_lives >= 64 {
+        return 0;
+    }
+    let current = weight >> half_lives;
+    let next = current >> 1;
+    let into = age % DEFAULT_TRENDING_HALF_LIFE_SEC;
+    current - (current - next) * into / DEFAULT_TRENDING_HALF_LIFE_SEC
+}
+
+/**
+ * Reactions that count and replies, weighted, decayed by the time since the thread's last post. In
+ * thousandths so young threads with a few interactions still rank apart. A thread with no
+ * recorded activity time is scored as fresh.
+ */
+pub fn trending_score(
+    metadatas: &[Metadata],
+    rollup: &ThreadRollup,
+    now: u64,
+    reactions: &ReactionFilter,
+) -> u64 {
+    let reactions = metadatas
+        .iter()
+        .filter(|m| m.alias.starts_with(REACTION_ALIAS_PREFIX) && reactions.counts(&m.public_key))
+        .count() as u64;
+    let weight = (reactions * DEFAULT_TRENDING_REACTION_WEIGHT
+        + rollup.replies * DEFAULT_TRENDING_REPLY_WEIGHT)
+        * 1000;
+    let age = match rollup.last_activity {
+        0 => 0,
+        last => now.saturating_sub(last),
+    };
+    decay(weight, age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::KarmaConfig;
+
+    #[test]
+    fn score_halves_every_half_life() {
+        let metadatas = vec![Metadata {
+            alias: "w3wall.reaction.0xa".to_string(),
+            ..Default::default()
+        }];
+        let rollup = ThreadRollup {
+            replies: 2,
+            authors: 1,
+            last_activity: 1_000,
+        };
+        let all = ReactionFilter::new(&metadatas, &KarmaConfig::default(), 0);
+        let fresh = trending_score(&metadatas, &rollup, 1_000, &all);
+        assert_eq!(
+            fresh,
+            (DEFAULT_TRENDING_REACTION_WEIGHT + 2 * DEFAULT_TRENDING_REPLY_WEIGHT) * 1000
+        );
+        let day = 1_000 + DEFAULT_TRENDING_HALF_LIFE_SEC;
+        assert_eq!(trending_score(&metadatas, &rollup, day, &all), fresh / 2);
+        assert_eq!(
+            trending_score(
+                &metadatas,
+                &rollup,
+                day + DEFAULT_TRENDING_HALF_LIFE_SEC / 2,
+                &all
+            ),
+            fresh * 3 / 8
+        );
+        assert_eq!(trending_score(&metadatas, &rollup, u64::MAX, &all), 0);
+        assert_eq!(trending_score(&[], &ThreadRollup::default(), 10, &all), 0);
+    }
+}
@@ -0,0 +1,78 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/**
+ * Look-alike characters mapped to the ascii letter they imitate, so "fr3e" and "frее" (cyrillic)
+ * match the same word list entry as "free"
+ */
+static CONFUSABLES: Lazy<HashMap<char, char>> = Lazy::new(|| {
+    [
+        ('0', 'o'),
+        ('1', 'i'),
+        ('3', 'e'),
+        ('4', 'a'),
+        ('5', 's'),
+        ('7', 't'),
+        ('@', 'a'),
+        ('$', 's'),
+        ('а', 'a'),
+        ('е', 'e'),
+        ('о', 'o'),
+        ('р', 'p'),
+        ('с', 'c'),
+        ('у', 'y'),
+        ('х', 'x'),
+        ('і', 'i'),
+        ('ο', 'o'),
+        ('α', 'a'),
+        ('ν', 'v'),
+    ]
+    .into_iter()
+    .collect()
+});
+
+/**
+ * Folds every char to exactly one char, so a match in the normalized text maps back to the same
+ * char positions in the original
+ */
+pub fn normalize(text: &str) -> String {
+    text.chars().map(fold).collect()
+}
+
+/**
+ * What a name reads as: look-alikes folded, `l` read as `i` like `1` is, and everything but
+ * letters and digits dropped, so "Ad_min" and "аdmin" (cyrillic a) read the same
+ */
+pub fn skeleton(text: &str) -> String {
+    normalize(text)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| if c == 'l' { 'i' } else { c })
+        .collect()
+}
+
+fn fold(c: char) -> char {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    *CONFUSABLES.get(&lower).unwrap_or(&lower)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn look_alikes_fold_to_ascii() {
+        assert_eq!(normalize("Fr3E"), "free");
+        assert_eq!(normalize("frее"), "free");
+        assert_eq!(normalize("w3wall"), "wewall");
+        assert_eq!(normalize("İx").chars().count(), 2);
+    }
+
+    #[test]
+    fn skeletons_ignore_separators_and_look_alikes() {
+        assert_eq!(skeleton("Ad_min"), "admin");
+        assert_eq!(skeleton("аdmin"), "admin");
+        assert_eq!(skeleton("Vita1ik"), skeleton("vitalik"));
+        assert_ne!(skeleton("alice"), skeleton("alicia"));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod confusables;
+pub mod profanity;
+pub mod simhash;
+pub mod spam;
+
+pub use profanity::WordFilter;
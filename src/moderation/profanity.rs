@@ -0,0 +1,124 @@
+use super::confusables::normalize;
+use crate::budget;
+use crate::defaults::DEFAULT_PROFANE_WORDS;
+use aho_corasick::AhoCorasick;
+use once_cell::sync::Lazy;
+
+/**
+ * Built once per module instance, on_execute only pays for the scan
+ */
+static PROFANITY: Lazy<AhoCorasick> = Lazy::new(|| build(DEFAULT_PROFANE_WORDS.iter().copied()));
+
+fn build<'a>(words: impl Iterator<Item = &'a str>) -> AhoCorasick {
+    let patterns: Vec<String> = words.filter(|w| !w.is_empty()).map(normalize).collect();
+    AhoCorasick::new(patterns).expect("profanity word list must build")
+}
+
+/**
+ * The global word list, for now empty (freedom of speech), plus the wall's own list. Walls
+ * without their own list share the prebuilt global automaton.
+ */
+#[derive(Default)]
+pub enum WordFilter {
+    #[default]
+    Global,
+    Wall(AhoCorasick),
+}
+
+impl WordFilter {
+    pub fn new(wall_words: &[String]) -> Self {
+        if wall_words.iter().all(|w| w.is_empty()) {
+            return WordFilter::Global;
+        }
+
+        let words = DEFAULT_PROFANE_WORDS
+            .iter()
+            .copied()
+            .chain(wall_words.iter().map(String::as_str));
+        WordFilter::Wall(build(words))
+    }
+
+    fn automaton(&self) -> &AhoCorasick {
+        match self {
+            WordFilter::Global => &PROFANITY,
+            WordFilter::Wall(automaton) => automaton,
+        }
+    }
+
+    /**
+     * Text the budget can't pay for counts as profane, it was never looked at
+     */
+    pub fn is_profane(&self, text: &str) -> bool {
+        !budget::charge(text.len()) || self.automaton().is_match(&normalize(text))
+    }
+
+    /**
+     * Replaces every listed word with asterisks of the same length, None when nothing matched
+     */
+    pub fn mask(&self, text: &str) -> Option<String> {
+        if !budget::charge(text.len()) {
+            return None;
+        }
+        let normalized = normalize(text);
+        let mut chars: Vec<char> = text.chars().collect();
+        let mut masked = false;
+
+        // char positions are counted on from the previous match, not from the start each time
+        let (mut byte, mut char) = (0, 0);
+        for found in self.automaton().find_iter(&normalized) {
+            char += normalized[byte..found.start()].chars().count();
+            let len = normalized[found.start()..found.end()].chars().count();
+            chars[char..char + len].fill('*');
+            (byte, char) = (found.end(), char + len);
+            masked = true;
+        }
+
+        masked.then(|| chars.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spam_filter() -> WordFilter {
+        WordFilter::new(&["Spam".to_string()])
+    }
+
+    #[test]
+    fn empty_word_list_accepts_everything() {
+        let filter = WordFilter::new(&[]);
+        assert!(matches!(filter, WordFilter::Global));
+        assert!(!filter.is_profane(""));
+        assert!(!filter.is_profane("anything goes"));
+    }
+
+    #[test]
+    fn matching_sees_through_look_alikes() {
+        let filter = spam_filter();
+        assert!(filter.is_profane("buy SP4M now"));
+        assert!(filter.is_profane("$pаm"));
+        assert!(!filter.is_profane("sparkle"));
+    }
+
+    #[test]
+    fn masking_preserves_length() {
+        let filter = spam_filter();
+        assert_eq!(filter.mask("no SPАM, ok?").as_deref(), Some("no ****, ok?"));
+        assert_eq!(filter.mask("clean"), None);
+        assert_eq!(
+            filter.mask("é spam, ü $pаm spam").as_deref(),
+            Some("é ****, ü **** ****")
+        );
+    }
+
+    #[test]
+    fn text_past_the_budget_is_not_scanned() {
+        crate::budget::start_with(8);
+        let filter = spam_filter();
+        assert!(!filter.is_profane("all fine"));
+        assert!(filter.is_profane("x"));
+        assert_eq!(filter.mask("spam"), None);
+        crate::budget::start();
+    }
+}
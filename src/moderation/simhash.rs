@@ -0,0 +1,67 @@
+use crate::budget;
+use crate::crypto::hash::keccak256;
+use crate::defaults::DEFAULT_FINGERPRINT_MIN_WORDS;
+
+/**
+ * 64-bit simhash of the text's words. Words are lowercased and stripped of anything but
+ * letters and digits, so a swapped emoji or punctuation mark leaves the hash as it was and a
+ * changed word moves only a few bits. None for texts too short to tell apart.
+ */
+pub fn simhash(text: &str) -> Option<u64> {
+    if !budget::charge(text.len()) {
+        return None;
+    }
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.len() < DEFAULT_FINGERPRINT_MIN_WORDS {
+        return None;
+    }
+
+    let mut weights = [0i64; 64];
+    for word in &words {
+        let hash = keccak256(word.as_bytes());
+        let bits = u64::from_be_bytes(hash[..8].try_into().expect("keccak256 is 32 bytes"));
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if bits >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    Some(
+        weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0)
+            .fold(0, |hash, (bit, _)| hash | 1 << bit),
+    )
+}
+
+/**
+ * Bits two fingerprints differ in, 0 for the same text give or take emoji and punctuation
+ */
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_changed_emoji_keeps_the_fingerprint() {
+        let spam = simhash("Claim your FREE airdrop now at example dot com 🚀").unwrap();
+        let again = simhash("claim your free airdrop now at example dot com 💰!!").unwrap();
+        assert_eq!(distance(spam, again), 0);
+
+        let reworded = simhash("Claim your FREE airdrop today at example dot com").unwrap();
+        let unrelated = simhash("the meetup moves to thursday, same room as last time").unwrap();
+        assert!(distance(spam, reworded) < distance(spam, unrelated));
+        assert_eq!(simhash("gm gm"), None);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::budget;
+use crate::data::SpamRules;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:https?://|www\.)").unwrap());
+
+/**
+ * Caps ratio is only meaningful on text long enough to shout in
+ */
+const CAPS_MIN_LETTERS: usize = 10;
+
+pub fn link_count(text: &str) -> usize {
+    if !budget::charge(text.len()) {
+        return 0;
+    }
+    LINK.find_iter(text).count()
+}
+
+/**
+ * Links in the post's text plus its `url`
+ */
+pub fn post_link_count(payload: &serde_json::Value) -> usize {
+    let text = payload["text"].as_str().map(link_count).unwrap_or_default();
+    text + payload["url"].is_string() as usize
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF)
+}
+
+/**
+ * Emoji and whitespace only, joiners and variation selectors included so sequences pass whole
+ */
+pub fn is_emoji_only(text: &str) -> bool {
+    text.chars()
+        .all(|c| is_emoji(c) || c.is_whitespace() || matches!(c, '\u{200D}' | '\u{FE0F}'))
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/**
+ * Variation selector, skin tones and the tag characters of subdivision flags
+ */
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0xFE0F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F)
+}
+
+/**
+ * Exactly one emoji as a reader sees it: a flag, or emoji with their modifiers joined by
+ * zero width joiners, the way 👩‍💻 is one grapheme cluster made of three characters
+ */
+pub fn is_single_emoji(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() == 2 && chars.iter().all(|c| is_regional_indicator(*c)) {
+        return true;
+    }
+    let mut rest = chars.into_iter().peekable();
+    loop {
+        match rest.next() {
+            Some(c) if is_emoji(c) && !is_regional_indicator(c) => {}
+            _ => return false,
+        }
+        while rest.next_if(|c| is_emoji_modifier(*c)).is_some() {}
+        match rest.next() {
+            None => return true,
+            Some('\u{200D}') => continue,
+            Some(_) => return false,
+        }
+    }
+}
+
+/**
+ * One point per heuristic the text trips: too many links, mostly uppercase, the same line
+ * repeated, emoji floods
+ */
+pub fn spam_score(text: &str, rules: &SpamRules) -> u32 {
+    let mut score = 0;
+    if !budget::charge(text.len()) {
+        return score;
+    }
+
+    if link_count(text) > rules.max_links {
+        score += 1;
+    }
+
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() >= CAPS_MIN_LETTERS {
+        let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+        if upper * 100 > letters.len() * rules.max_caps_percent {
+            score += 1;
+        }
+    }
+
+    let mut lines: HashMap<&str, usize> = HashMap::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        *lines.entry(line).or_default() += 1;
+    }
+    if lines.values().any(|&n| n > rules.max_repeated_lines) {
+        score += 1;
+    }
+
+    if text.chars().filter(|&c| is_emoji(c)).count() > rules.max_emoji {
+        score += 1;
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_heuristic_scores_a_point() {
+        let rules = SpamRules::default();
+        assert_eq!(spam_score("gm frens, see you at the raid", &rules), 0);
+        assert_eq!(spam_score("BUY NOW BEFORE IT IS GONE", &rules), 1);
+        assert_eq!(spam_score("http://a http://b www.c https://d", &rules), 1);
+        assert_eq!(spam_score("join\njoin\njoin\njoin", &rules), 1);
+        assert_eq!(spam_score(&"🚀".repeat(30), &rules), 1);
+        assert_eq!(
+            spam_score("CLICK HTTP://A HTTP://B HTTP://C HTTP://D", &rules),
+            2
+        );
+    }
+
+    #[test]
+    fn single_emoji_are_whole_grapheme_clusters() {
+        for emoji in [
+            "👍",
+            "❤️",
+            "👍🏽",
+            "👩‍💻",
+            "🇵🇹",
+            "🏴\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}",
+        ] {
+            assert!(is_single_emoji(emoji), "{}", emoji);
+        }
+        for text in ["", "+1", "👍👍", "👍 ", "🇵", "👩‍", "a\u{FE0F}"] {
+            assert!(!is_single_emoji(text), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Fixed aliases of posts and the records stored alongside them: token metadata and the fields
+//! copied out of a post. A typo in a string alias silently creates an orphaned record, a typo in
+//! a variant doesn't compile. The contract's own `w3wall.*` records, most of them a prefix and a
+//! key, keep their `*_ALIAS` consts and alias functions next to the code that writes them.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alias {
+    /**
+     * Posts are stored under the empty alias
+     */
+    Post,
+    Name,
+    Image,
+    Body,
+    Description,
+    ExternalUrl,
+    Attributes,
+    SellerFeeBasisPoints,
+    FeeRecipient,
+    Decimals,
+    Properties,
+    ContractMetadata,
+    Blurhash,
+    Thumbnail,
+    Provenance,
+    Geo,
+    Template,
+    License,
+    Quote,
+    EmbedNft,
+    Code,
+    Attachments,
+    AuthoredByToken,
+    Media,
+}
+
+impl Alias {
+    pub const ALL: &'static [Alias] = &[
+        Alias::Post,
+        Alias::Name,
+        Alias::Image,
+        Alias::Body,
+        Alias::Description,
+        Alias::ExternalUrl,
+        Alias::Attributes,
+        Alias::SellerFeeBasisPoints,
+        Alias::FeeRecipient,
+        Alias::Decimals,
+        Alias::Properties,
+        Alias::ContractMetadata,
+        Alias::Blurhash,
+        Alias::Thumbnail,
+        Alias::Provenance,
+        Alias::Geo,
+        Alias::Template,
+        Alias::License,
+        Alias::Quote,
+        Alias::EmbedNft,
+        Alias::Code,
+        Alias::Attachments,
+        Alias::AuthoredByToken,
+        Alias::Media,
+    ];
+
+    /**
+     * The stored alias, these strings are what the lineage already holds so they never change
+     */
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Alias::Post => "",
+            Alias::Name => "name",
+            Alias::Image => "image",
+            Alias::Body => "body",
+            Alias::Description => "description",
+            Alias::ExternalUrl => "external_url",
+            Alias::Attributes => "attributes",
+            Alias::SellerFeeBasisPoints => "seller_fee_basis_points",
+            Alias::FeeRecipient => "fee_recipient",
+            Alias::Decimals => "decimals",
+            Alias::Properties => "properties",
+            Alias::ContractMetadata => "contract_metadata",
+            Alias::Blurhash => "blurhash",
+            Alias::Thumbnail => "thumbnail",
+            Alias::Provenance => "provenance",
+            Alias::Geo => "geo",
+            Alias::Template => "template",
+            Alias::License => "license",
+            Alias::Quote => "quote",
+            Alias::EmbedNft => "embed_nft",
+            Alias::Code => "code",
+            Alias::Attachments => "attachments",
+            Alias::AuthoredByToken => "authored_by_token",
+            Alias::Media => "media",
+        }
+    }
+
+    /**
+     * Records that make up the token's NFT metadata, read by marketplaces verbatim
+     */
+    pub fn is_token_metadata(self) -> bool {
+        matches!(
+            self,
+            Alias::Name
+                | Alias::Image
+                | Alias::Body
+                | Alias::Description
+                | Alias::ExternalUrl
+                | Alias::Attributes
+                | Alias::SellerFeeBasisPoints
+                | Alias::FeeRecipient
+                | Alias::Decimals
+                | Alias::Properties
+                | Alias::ContractMetadata
+        )
+    }
+
+    pub fn parse(alias: &str) -> Option<Alias> {
+        Alias::ALL.iter().copied().find(|a| a.as_str() == alias)
+    }
+}
+
+impl fmt::Display for Alias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<Alias> for String {
+    fn eq(&self, alias: &Alias) -> bool {
+        self == alias.as_str()
+    }
+}
+
+impl PartialEq<Alias> for str {
+    fn eq(&self, alias: &Alias) -> bool {
+        self == alias.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_round_trip_and_are_distinct() {
+        for alias in Alias::ALL {
+            assert_eq!(Alias::parse(alias.as_str()), Some(*alias));
+        }
+        assert_eq!(Alias::Post.to_string(), "");
+        assert_eq!(Alias::ExternalUrl.to_string(), "external_url");
+        assert!(*"name" == Alias::Name);
+        assert_eq!(Alias::parse("nmae"), None);
+    }
+}
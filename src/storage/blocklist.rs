@@ -0,0 +1,12 @@
+use crate::data::BlockList;
+use crate::storage::read_record;
+use crate::types::Metadata;
+
+pub static BLOCKLIST_ALIAS: &str = "w3wall.blocklist";
+
+/**
+ * Keys `public_key` blocked from replying to them, only their own record counts
+ */
+pub fn load_block_list(metadatas: &[Metadata], public_key: &str) -> BlockList {
+    read_record(metadatas, BLOCKLIST_ALIAS, public_key).unwrap_or_default()
+}
@@ -0,0 +1,97 @@
+use crate::data::WallConfig;
+use crate::error::W3WallError;
+use crate::storage::{find_record, load_record};
+use crate::types::{MetaContract, Metadata};
+
+pub static CONFIG_ALIAS: &str = "config";
+
+/**
+ * Only the record written by the contract owner counts, anybody else can write a loose `config`
+ */
+pub fn find_config<'a>(contract: &MetaContract, metadatas: &'a [Metadata]) -> Option<&'a Metadata> {
+    find_record(metadatas, CONFIG_ALIAS, &contract.public_key)
+}
+
+/**
+ * Current wall configuration, falling back to the defaults for walls that were never initialized.
+ * A config record that can't be read is an error, the defaults would drop the owner, the
+ * moderators and the wall's rules.
+ */
+pub fn load_config(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+) -> Result<WallConfig, W3WallError> {
+    let mut config: WallConfig =
+        load_record(metadatas, CONFIG_ALIAS, &contract.public_key)?.unwrap_or_default();
+
+    if config.owner.is_empty() {
+        config.owner = contract.public_key.clone();
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_record_must_come_from_the_owner() {
+        let contract = MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let shadow = Metadata {
+            alias: CONFIG_ALIAS.to_string(),
+            public_key: "0xother".to_string(),
+            ..Default::default()
+        };
+
+        assert!(find_config(&contract, std::slice::from_ref(&shadow)).is_none());
+        assert_eq!(load_config(&contract, &[]).unwrap().owner, "0xowner");
+        assert_eq!(load_config(&contract, &[shadow]).unwrap().owner, "0xowner");
+    }
+
+    #[test]
+    fn stored_config_is_read_back() {
+        let contract = MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let record = Metadata {
+            alias: CONFIG_ALIAS.to_string(),
+            public_key: "0xowner".to_string(),
+            cid: "config-cid".to_string(),
+            ..Default::default()
+        };
+        crate::storage::ipfs::test_store::put(
+            "config-cid",
+            r#"{"moderators": ["0xmod"], "rules": {"max_text_length": 10}}"#,
+        );
+
+        let config = load_config(&contract, &[record]).unwrap();
+        assert_eq!(config.moderators, vec!["0xmod".to_string()]);
+        assert_eq!(config.rules.max_text_length, 10);
+        assert_eq!(config.owner, "0xowner");
+    }
+
+    #[test]
+    fn unreadable_config_is_an_error() {
+        let contract = MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let record = Metadata {
+            alias: CONFIG_ALIAS.to_string(),
+            public_key: "0xowner".to_string(),
+            cid: "config-not-stored".to_string(),
+            ..Default::default()
+        };
+
+        let err = load_config(&contract, &[record]).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "The config record can't be read, try again later."
+        );
+    }
+}
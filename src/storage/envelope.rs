@@ -0,0 +1,238 @@
+//! Versioned envelope around stored content, `{v, type, author, created_at, body, refs}`, so
+//! consumers parse one structure whatever they read. Metadata records carry no times of their
+//! own, the envelope records when a record was first written and, for a rewrite by the same
+//! key, when it last changed. Token metadata stays bare, marketplaces read those records as is.
+
+use super::alias::Alias;
+use super::{find_record, ipfs};
+use crate::defaults::{DEFAULT_ENVELOPE_REF_FIELDS, ENVELOPE_VERSION};
+use crate::metadata::timestamp_sec;
+use crate::types::{FinalMetadata, Metadata, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Envelope {
+    v: u64,
+    /**
+     * The post type for posts, the alias for every other record
+     */
+    #[serde(rename = "type")]
+    kind: String,
+    /**
+     * Key that sent the transaction, which for wall records differs from the record's key
+     */
+    author: String,
+    created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<u64>,
+    /**
+     * JSON objects and arrays are kept verbatim, anything else as a JSON string
+     */
+    body: Box<RawValue>,
+    /**
+     * CIDs of the posts the body points at
+     */
+    refs: Vec<String>,
+}
+
+/**
+ * Who wrote a record and when, everything the envelope adds besides what the body holds
+ */
+struct Seal<'a> {
+    alias: &'a str,
+    author: &'a str,
+    created_at: u64,
+    updated_at: Option<u64>,
+}
+
+fn is_sealed(alias: &str) -> bool {
+    !Alias::parse(alias).is_some_and(Alias::is_token_metadata)
+}
+
+fn kind_and_refs(alias: &str, body: &Value) -> (String, Vec<String>) {
+    let kind = match (alias, body["type"].as_str()) {
+        ("", Some(kind)) => kind.to_string(),
+        ("", None) => "post".to_string(),
+        (alias, _) => alias.to_string(),
+    };
+    let mut refs: Vec<String> = DEFAULT_ENVELOPE_REF_FIELDS
+        .iter()
+        .chain(&["quote"])
+        .filter_map(|field| match &body[*field] {
+            Value::Object(quote) => quote.get("cid")?.as_str(),
+            value => value.as_str(),
+        })
+        .filter(|cid| !cid.is_empty())
+        .map(str::to_string)
+        .collect();
+    refs.dedup();
+    (kind, refs)
+}
+
+fn seal(content: &str, seal: Seal) -> String {
+    let trimmed = content.trim_start();
+    let json = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        serde_json::from_str::<Value>(content)
+            .ok()
+            .zip(RawValue::from_string(content.to_string()).ok())
+    } else {
+        None
+    };
+    let (value, body) = match json {
+        Some(json) => json,
+        None => (
+            Value::Null,
+            RawValue::from_string(Value::from(content).to_string())
+                .expect("a JSON string is valid JSON"),
+        ),
+    };
+    let (kind, refs) = kind_and_refs(seal.alias, &value);
+    serde_json::to_string(&Envelope {
+        v: ENVELOPE_VERSION,
+        kind,
+        author: seal.author.to_string(),
+        created_at: seal.created_at,
+        updated_at: seal.updated_at,
+        body,
+        refs,
+    })
+    .unwrap_or_else(|_| content.to_string())
+}
+
+fn envelope_of(content: &str) -> Option<Envelope> {
+    if !content.starts_with(r#"{"v":"#) {
+        return None;
+    }
+    serde_json::from_str(content).ok()
+}
+
+/**
+ * Content as it was written, records stored before envelopes existed are returned as is
+ */
+pub fn open(content: String) -> String {
+    let Some(envelope) = envelope_of(&content) else {
+        return content;
+    };
+    let body = envelope.body.get();
+    if body.starts_with('"') {
+        serde_json::from_str(body).unwrap_or_else(|_| body.to_string())
+    } else {
+        body.to_string()
+    }
+}
+
+/**
+ * When a stored record was first written, None for records stored before envelopes existed
+ */
+pub fn created_at(stored: &Metadata) -> Option<u64> {
+    let content = ipfs::get_metadata(&stored.cid).ok()?.content;
+    envelope_of(&content).map(|envelope| envelope.created_at)
+}
+
+/**
+ * Seals every record a transaction emits, a record replacing one of the same alias and key
+ * keeps its creation time and gets an update time
+ */
+pub fn seal_all(finals: &mut [FinalMetadata], metadatas: &[Metadata], transaction: &Transaction) {
+    let now = timestamp_sec(transaction.timestamp);
+    for record in finals.iter_mut().filter(|r| is_sealed(&r.alias)) {
+        let (created_at, updated_at) =
+            match find_record(metadatas, &record.alias, &record.public_key) {
+                Some(stored) => (created_at(stored).unwrap_or(now), Some(now)),
+                None => (now, None),
+            };
+        let sealed = Seal {
+            alias: &record.alias,
+            author: &transaction.public_key,
+            created_at,
+            updated_at,
+        };
+        record.content = seal(&record.content, sealed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(alias: &str, created_at: u64, updated_at: Option<u64>) -> Seal<'_> {
+        Seal {
+            alias,
+            author: "0xa",
+            created_at,
+            updated_at,
+        }
+    }
+
+    #[test]
+    fn content_survives_a_seal_exactly() {
+        for content in [
+            r#"{"text": "gm",  "b":1}"#,
+            "[1, 2]",
+            "hello",
+            r#""quoted""#,
+            "",
+        ] {
+            let sealed = seal(content, at("", 10, None));
+            assert!(sealed.starts_with(r#"{"v":1,"type":"post","author":"0xa","created_at":10,"#));
+            assert_eq!(open(sealed), content);
+        }
+        assert_eq!(
+            seal(r#"{"a":1}"#, at("w3wall.profile", 10, Some(20))),
+            r#"{"v":1,"type":"w3wall.profile","author":"0xa","created_at":10,"updated_at":20,"body":{"a":1},"refs":[]}"#
+        );
+        assert_eq!(
+            open(r#"{"text":"legacy"}"#.to_string()),
+            r#"{"text":"legacy"}"#
+        );
+    }
+
+    #[test]
+    fn replies_carry_their_type_and_refs() {
+        let reply = r#"{"type":"reply","reply_to":"bafyparent","quote":{"cid":"bafyquoted"}}"#;
+        let sealed = seal(reply, at("", 10, None));
+        assert!(sealed.starts_with(r#"{"v":1,"type":"reply","#));
+        assert!(sealed.ends_with(r#""refs":["bafyparent","bafyquoted"]}"#));
+    }
+
+    #[test]
+    fn rewrites_keep_their_creation_time() {
+        crate::storage::ipfs::test_store::put(
+            "envelope-old",
+            &seal("bio", at("w3wall.profile", 100, None)),
+        );
+        let metadatas = [Metadata {
+            alias: "w3wall.profile".to_string(),
+            public_key: "0xa".to_string(),
+            cid: "envelope-old".to_string(),
+            ..Default::default()
+        }];
+        let record = |alias: &str, public_key: &str| FinalMetadata {
+            public_key: public_key.to_string(),
+            alias: alias.to_string(),
+            content: "x".to_string(),
+            loose: 1,
+            version: "".to_string(),
+        };
+        let mut finals = vec![
+            record("w3wall.profile", "0xa"),
+            record("w3wall.profile", "0xb"),
+            record("attributes", "0xa"),
+        ];
+        let transaction = Transaction {
+            public_key: "0xa".to_string(),
+            timestamp: 200,
+            ..Default::default()
+        };
+        seal_all(&mut finals, &metadatas, &transaction);
+        assert_eq!(
+            finals[0].content,
+            r#"{"v":1,"type":"w3wall.profile","author":"0xa","created_at":100,"updated_at":200,"body":"x","refs":[]}"#
+        );
+        assert!(finals[1].content.contains(r#""created_at":200,"body""#));
+        assert_eq!(finals[2].content, "x");
+    }
+}
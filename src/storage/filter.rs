@@ -0,0 +1,46 @@
+use crate::data::WallFilter;
+use crate::moderation::WordFilter;
+use crate::storage::read_record;
+use crate::types::{MetaContract, Metadata};
+
+pub static FILTER_ALIAS: &str = "filter";
+
+/**
+ * Wall specific banned words, stored by the contract owner like the config
+ */
+pub fn load_filter(contract: &MetaContract, metadatas: &[Metadata]) -> WallFilter {
+    read_record(metadatas, FILTER_ALIAS, &contract.public_key).unwrap_or_default()
+}
+
+/**
+ * Global word list merged with the wall's own
+ */
+pub fn load_word_filter(contract: &MetaContract, metadatas: &[Metadata]) -> WordFilter {
+    WordFilter::new(&load_filter(contract, metadatas).words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_record_is_read_back() {
+        let contract = MetaContract {
+            public_key: "0xowner".to_string(),
+            ..Default::default()
+        };
+        let record = Metadata {
+            alias: FILTER_ALIAS.to_string(),
+            public_key: "0xowner".to_string(),
+            cid: "filter-cid".to_string(),
+            ..Default::default()
+        };
+        crate::storage::ipfs::test_store::put("filter-cid", r#"{"words": ["griefer"]}"#);
+
+        assert_eq!(
+            load_filter(&contract, &[record]).words,
+            vec!["griefer".to_string()]
+        );
+        assert!(load_filter(&contract, &[]).words.is_empty());
+    }
+}
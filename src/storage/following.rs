@@ -0,0 +1,12 @@
+use crate::data::FollowList;
+use crate::storage::read_record;
+use crate::types::Metadata;
+
+pub static FOLLOWING_ALIAS: &str = "w3wall.following";
+
+/**
+ * Authors and threads `public_key` follows, only their own record counts
+ */
+pub fn load_following(metadatas: &[Metadata], public_key: &str) -> FollowList {
+    read_record(metadatas, FOLLOWING_ALIAS, public_key).unwrap_or_default()
+}
@@ -0,0 +1,87 @@
+use crate::budget;
+use crate::defaults::{DEFAULT_IPFS_MULTIADDR, DEFAULT_TIMEOUT_SEC};
+use crate::types::SerdeMetadata;
+use marine_rs_sdk::MountedBinaryResult;
+
+#[cfg(target_arch = "wasm32")]
+use marine_rs_sdk::marine;
+
+/**
+ * Reads a stored metadata record (an IPFS dag node) by its cid
+ */
+pub fn get_metadata(cid: &str) -> Result<SerdeMetadata, String> {
+    if budget::overrun() {
+        return Err(format!("{} was not read, the work budget is spent", cid));
+    }
+    let args = vec![
+        "dag".to_string(),
+        "get".to_string(),
+        cid.to_string(),
+        "--api".to_string(),
+        DEFAULT_IPFS_MULTIADDR.to_string(),
+        "--timeout".to_string(),
+        format!("{}s", DEFAULT_TIMEOUT_SEC),
+    ];
+
+    let stdout = match ipfs(args).into_std() {
+        Some(Ok(stdout)) => stdout,
+        Some(Err(e)) => return Err(format!("ipfs dag get {} failed: {}", cid, e)),
+        None => return Err(format!("ipfs dag get {} returned non utf8 output", cid)),
+    };
+    if !budget::charge(stdout.len()) {
+        return Err(format!("{} was not parsed, the work budget is spent", cid));
+    }
+
+    serde_json::from_str(&stdout).map_err(|e| format!("Invalid metadata {}: {}", cid, e))
+}
+
+#[cfg(target_arch = "wasm32")]
+#[marine]
+#[link(wasm_import_module = "host")]
+extern "C" {
+    pub fn ipfs(cmd: Vec<String>) -> MountedBinaryResult;
+}
+
+/**
+ * The mounted binary only exists inside Marine, native builds see an empty store
+ * (tests can seed it through `test_store::put`)
+ */
+#[cfg(not(target_arch = "wasm32"))]
+pub fn ipfs(cmd: Vec<String>) -> MountedBinaryResult {
+    #[cfg(test)]
+    if let Some(content) = cmd.get(2).and_then(|cid| test_store::get(cid)) {
+        return MountedBinaryResult {
+            ret_code: 0,
+            error: "".to_string(),
+            stdout: serde_json::json!({ "content": content })
+                .to_string()
+                .into_bytes(),
+            stderr: Vec::new(),
+        };
+    }
+
+    let _ = cmd;
+    MountedBinaryResult::from_error(1, "ipfs is not mounted outside of Marine")
+}
+
+#[cfg(test)]
+pub mod test_store {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    thread_local! {
+        static STORE: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    }
+
+    pub fn put(cid: &str, content: &str) {
+        STORE.with(|store| {
+            store
+                .borrow_mut()
+                .insert(cid.to_string(), content.to_string())
+        });
+    }
+
+    pub fn get(cid: &str) -> Option<String> {
+        STORE.with(|store| store.borrow().get(cid).cloned())
+    }
+}
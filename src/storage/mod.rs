@@ -0,0 +1,145 @@
+pub mod alias;
+pub mod blocklist;
+pub mod config;
+pub mod envelope;
+pub mod filter;
+pub mod following;
+pub mod ipfs;
+pub mod moderation;
+pub mod muted;
+pub mod stickers;
+
+use crate::data::CompressedBody;
+use crate::defaults::{DEFAULT_LOOSE, DEFAULT_LOOSE_POLICY, DEFAULT_MAX_DECOMPRESSED_BYTES};
+use crate::encoding::{decode_gzip_base64, GZIP_ENCODING};
+use crate::error::W3WallError;
+use crate::types::Metadata;
+use alias::Alias;
+use serde::de::DeserializeOwned;
+
+/**
+ * Loose flag `alias` is written with, from DEFAULT_LOOSE_POLICY
+ */
+pub fn loose_flag(alias: &str) -> i64 {
+    DEFAULT_LOOSE_POLICY
+        .iter()
+        .find(|(entry, _)| match entry.strip_suffix('.') {
+            Some(_) => alias.starts_with(entry),
+            None => alias == *entry,
+        })
+        .map_or(DEFAULT_LOOSE, |(_, loose)| *loose)
+}
+
+/**
+ * Record stored under `alias` by `public_key`
+ */
+pub fn find_record<'a>(
+    metadatas: &'a [Metadata],
+    alias: &str,
+    public_key: &str,
+) -> Option<&'a Metadata> {
+    metadatas
+        .iter()
+        .find(|m| m.alias == alias && m.public_key.eq_ignore_ascii_case(public_key))
+}
+
+/**
+ * Post record (empty alias) stored at `cid`
+ */
+pub fn find_post<'a>(metadatas: &'a [Metadata], cid: &str) -> Option<&'a Metadata> {
+    metadatas
+        .iter()
+        .find(|m| m.alias == Alias::Post && m.cid == cid)
+}
+
+/**
+ * The `type` field of a stored post, None when it can't be read or has none
+ */
+pub fn post_type(post: &Metadata) -> Option<String> {
+    let content = read_content(post)?;
+    let payload: serde_json::Value = serde_json::from_str(&content).ok()?;
+    payload["type"].as_str().map(str::to_string)
+}
+
+/**
+ * Inflated text of a post stored compressed, anything else is returned as is
+ */
+fn inflate_content(content: String) -> String {
+    if !content.starts_with(&format!(r#"{{"encoding":"{}""#, GZIP_ENCODING)) {
+        return content;
+    }
+
+    let inflated = serde_json::from_str::<CompressedBody>(&content)
+        .map_err(|e| e.to_string())
+        .and_then(|body| {
+            decode_gzip_base64(&body.data, DEFAULT_MAX_DECOMPRESSED_BYTES).map_err(str::to_string)
+        });
+    match inflated {
+        Ok(text) => text,
+        Err(e) => {
+            log::warn!("unable to inflate stored record: {}", e);
+            content
+        }
+    }
+}
+
+/**
+ * Content of a stored record, None (and a warning) when IPFS can't serve it. The envelope is
+ * taken off and compressed posts are inflated.
+ */
+pub fn read_content(metadata: &Metadata) -> Option<String> {
+    match ipfs::get_metadata(&metadata.cid) {
+        Ok(stored) => Some(inflate_content(envelope::open(stored.content))),
+        Err(e) => {
+            log::warn!(
+                "unable to read {} record {}: {}",
+                metadata.alias,
+                metadata.cid,
+                e
+            );
+            None
+        }
+    }
+}
+
+/**
+ * Like read_record, for records a handler rewrites in full: a record that exists but can't be
+ * read or decoded is an error, only a missing one is None
+ */
+pub fn load_record<T: DeserializeOwned>(
+    metadatas: &[Metadata],
+    alias: &str,
+    public_key: &str,
+) -> Result<Option<T>, W3WallError> {
+    let record = match find_record(metadatas, alias, public_key) {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    let content = read_content(record).ok_or_else(|| {
+        W3WallError::Rejected(format!(
+            "The {} record can't be read, try again later.",
+            alias
+        ))
+    })?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| W3WallError::Rejected(format!("The {} record is invalid: {}", alias, e)))
+}
+
+/**
+ * JSON record stored under `alias` by `public_key`, decoded into T
+ */
+pub fn read_record<T: DeserializeOwned>(
+    metadatas: &[Metadata],
+    alias: &str,
+    public_key: &str,
+) -> Option<T> {
+    let content = read_content(find_record(metadatas, alias, public_key)?)?;
+    match serde_json::from_str(&content) {
+        Ok(record) => Some(record),
+        Err(e) => {
+            log::warn!("invalid {} record: {}", alias, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::data::ModerationState;
+use crate::error::W3WallError;
+use crate::storage::{find_record, load_record};
+use crate::types::{MetaContract, Metadata};
+
+pub static MODERATION_ALIAS: &str = "w3wall.moderation";
+
+/**
+ * Removed posts, banned keys and the lock flag, kept by the contract key like the config. None
+ * before the first action, an error when the record is there but can't be read.
+ */
+pub fn load_moderation(
+    contract: &MetaContract,
+    metadatas: &[Metadata],
+) -> Result<Option<ModerationState>, W3WallError> {
+    load_record(metadatas, MODERATION_ALIAS, &contract.public_key)
+}
+
+/**
+ * CID of the current moderation record, empty before the first action
+ */
+pub fn moderation_cid(contract: &MetaContract, metadatas: &[Metadata]) -> String {
+    find_record(metadatas, MODERATION_ALIAS, &contract.public_key)
+        .map(|record| record.cid.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,12 @@
+use crate::data::MutedWords;
+use crate::storage::read_record;
+use crate::types::Metadata;
+
+pub static MUTED_ALIAS: &str = "w3wall.muted";
+
+/**
+ * Terms `public_key` muted in replies to them, only their own record counts
+ */
+pub fn load_muted_words(metadatas: &[Metadata], public_key: &str) -> MutedWords {
+    read_record(metadatas, MUTED_ALIAS, public_key).unwrap_or_default()
+}
@@ -0,0 +1,12 @@
+use crate::data::StickerRegistry;
+use crate::storage::read_record;
+use crate::types::Metadata;
+
+pub static STICKERS_ALIAS: &str = "w3wall.stickers";
+
+/**
+ * Sticker packs of the wall stored under `wall`, empty for walls that never registered one
+ */
+pub fn load_sticker_registry(metadatas: &[Metadata], wall: &str) -> StickerRegistry {
+    read_record(metadatas, STICKERS_ALIAS, wall).unwrap_or_default()
+}
@@ -0,0 +1,150 @@
+//! Timings of the validation hot paths, natively and off by default since they take a while:
+//!
+//! `cargo test --release --features bench bench -- --nocapture --test-threads=1`
+//!
+//! Each path is warmed up, then timed over SAMPLES batches sized to about SAMPLE_TIME each, and
+//! the median time per call is reported. `W3WALL_BENCH_SAVE=1` stores the medians as the
+//! baseline under target/, later runs fail when a path got REGRESSION_MARGIN slower than that.
+//! Natively is not Wasm, the numbers only mean something against a baseline of the same machine.
+
+use crate::budget;
+use crate::moderation::WordFilter;
+use crate::validation::media::is_cid;
+use crate::validation::parse_payload;
+use serde_json::{Map, Value};
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const WARM_UP: Duration = Duration::from_millis(200);
+const SAMPLE_TIME: Duration = Duration::from_millis(50);
+const SAMPLES: usize = 20;
+const REGRESSION_MARGIN: f64 = 0.25;
+
+fn baseline_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target/w3wall-bench.json")
+}
+
+/**
+ * Median nanoseconds per call of `f`
+ */
+fn measure<T>(mut f: impl FnMut() -> T) -> f64 {
+    let started = Instant::now();
+    let mut calls: u64 = 0;
+    while started.elapsed() < WARM_UP {
+        black_box(f());
+        calls += 1;
+    }
+    let per_sample = (calls as f64 * SAMPLE_TIME.as_secs_f64() / WARM_UP.as_secs_f64()).max(1.0);
+
+    let mut samples: Vec<f64> = (0..SAMPLES)
+        .map(|_| {
+            let started = Instant::now();
+            for _ in 0..per_sample as u64 {
+                black_box(f());
+            }
+            started.elapsed().as_nanos() as f64 / per_sample.floor()
+        })
+        .collect();
+    samples.sort_by(f64::total_cmp);
+    samples[SAMPLES / 2]
+}
+
+fn post_payload() -> String {
+    serde_json::json!({
+        "schema_version": 2,
+        "type": "post",
+        "text": "gm wall, the new drop is live at https://example.org/drop ".repeat(20),
+        "image": "https://nftstorage.link/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/gm.png",
+        "tags": ["art", "drop", "gm"],
+    })
+    .to_string()
+}
+
+fn hot_paths() -> Vec<(&'static str, f64)> {
+    let payload = post_payload();
+    let text =
+        "This is a perfectly normal sentence about the wall, nothing to see here. ".repeat(40);
+    let wall_filter = WordFilter::new(&["rug".to_string(), "scam".to_string()]);
+    let cids = [
+        "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+        "not-a-cid",
+    ];
+
+    let mut results = vec![
+        ("parse_payload", measure(|| parse_payload(&payload))),
+        (
+            "profanity_global",
+            measure(|| WordFilter::Global.is_profane(&text)),
+        ),
+        ("profanity_wall", measure(|| wall_filter.is_profane(&text))),
+        ("profanity_mask", measure(|| wall_filter.mask(&text))),
+        (
+            "is_cid",
+            measure(|| cids.iter().filter(|cid| is_cid(cid)).count()),
+        ),
+    ];
+    results.extend(abi_hot_paths(&text));
+    results
+}
+
+/**
+ * ABI decoding is only built with on_mint
+ */
+#[cfg(feature = "mint")]
+fn abi_hot_paths(text: &str) -> Vec<(&'static str, f64)> {
+    use ethabi::{encode, ParamType, Token};
+    let strings = [ParamType::String, ParamType::String, ParamType::String];
+    let data = encode(&[
+        Token::String("topic".repeat(20)),
+        Token::String("https://nftstorage.link/ipfs/bafy/gm.png".to_string()),
+        Token::String(text.to_string()),
+    ]);
+    vec![(
+        "abi_decode",
+        measure(|| crate::encoding::abi::decode_canonical(&strings, &data)),
+    )]
+}
+
+#[cfg(not(feature = "mint"))]
+fn abi_hot_paths(_text: &str) -> Vec<(&'static str, f64)> {
+    Vec::new()
+}
+
+#[test]
+fn bench_validation_hot_paths() {
+    // the work budget is per transaction, a benchmark is one very long one
+    budget::start_with(u64::MAX);
+    let results = hot_paths();
+    for (name, nanos) in &results {
+        println!("{:<20} {:>12.0} ns/call", name, nanos);
+    }
+
+    let medians: Map<String, Value> = results
+        .iter()
+        .map(|(name, nanos)| (name.to_string(), Value::from(*nanos)))
+        .collect();
+    if std::env::var_os("W3WALL_BENCH_SAVE").is_some() {
+        let baseline = serde_json::to_string_pretty(&medians).unwrap();
+        std::fs::write(baseline_path(), baseline).unwrap();
+        return;
+    }
+    let baseline: Map<String, Value> = match std::fs::read_to_string(baseline_path()) {
+        Ok(saved) => serde_json::from_str(&saved).unwrap(),
+        Err(_) => return,
+    };
+    let regressions: Vec<String> = results
+        .iter()
+        .filter_map(|(name, nanos)| {
+            let before = baseline.get(*name)?.as_f64()?;
+            (*nanos > before * (1.0 + REGRESSION_MARGIN))
+                .then(|| format!("{} {:.0} ns/call, was {:.0}", name, nanos, before))
+        })
+        .collect();
+    assert!(
+        regressions.is_empty(),
+        "slower than the baseline:\n{}",
+        regressions.join("\n")
+    );
+}
@@ -0,0 +1,116 @@
+//! Randomized property tests: whatever the client sends, the exports must not panic and must
+//! return a well-formed MetaContractResult. Seeded so failures are reproducible.
+
+#[cfg(feature = "mint")]
+use super::mint;
+use super::post;
+use crate::types::MetaContractResult;
+#[cfg(feature = "mint")]
+use ethabi::{encode, Token};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{Map, Value};
+
+const CASES: usize = 500;
+const KEYS: [&str; 6] = ["text", "image", "blurhash", "thumbnail", "type", "x"];
+
+fn assert_well_formed(result: &MetaContractResult, input: &str) {
+    if result.result {
+        assert!(result.error_string.is_empty(), "input: {:?}", input);
+    } else {
+        assert!(result.metadatas.is_empty(), "input: {:?}", input);
+        assert!(!result.error_string.is_empty(), "input: {:?}", input);
+    }
+}
+
+fn random_string(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(0..40);
+    (0..len)
+        .map(|_| match rng.gen_range(0..4) {
+            0 => rng.gen_range(0u8..0x20) as char,
+            1 => rng.gen_range('a'..='z'),
+            2 => ['<', '"', '\\', '{', '/', ':'][rng.gen_range(0..6)],
+            _ => rng.gen::<char>(),
+        })
+        .collect()
+}
+
+fn random_json(rng: &mut StdRng, depth: usize) -> Value {
+    match rng.gen_range(0..if depth == 0 { 4 } else { 6 }) {
+        0 => Value::Null,
+        1 => Value::Bool(rng.gen()),
+        2 => Value::from(rng.gen::<i64>()),
+        3 => Value::String(random_string(rng)),
+        4 => Value::Array(
+            (0..rng.gen_range(0..4))
+                .map(|_| random_json(rng, depth - 1))
+                .collect(),
+        ),
+        _ => {
+            let mut map = Map::new();
+            for _ in 0..rng.gen_range(0..5) {
+                let key = if rng.gen_bool(0.8) {
+                    KEYS[rng.gen_range(0..KEYS.len())].to_string()
+                } else {
+                    random_string(rng)
+                };
+                map.insert(key, random_json(rng, depth - 1));
+            }
+            Value::Object(map)
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+fn random_hex(rng: &mut StdRng) -> String {
+    match rng.gen_range(0..4) {
+        // plausible mint data, possibly truncated or with a flipped digit
+        0 | 1 => {
+            let tokens: Vec<Token> = (0..rng.gen_range(0..5))
+                .map(|_| Token::String(random_string(rng)))
+                .collect();
+            let mut data = hex::encode(encode(&tokens));
+            if rng.gen_bool(0.5) && !data.is_empty() {
+                let cut = rng.gen_range(0..data.len());
+                data.truncate(cut);
+            }
+            if rng.gen_bool(0.3) && !data.is_empty() {
+                let i = rng.gen_range(0..data.len());
+                data.replace_range(i..i + 1, "f");
+            }
+            data
+        }
+        2 => (0..rng.gen_range(0..300))
+            .map(|_| "0123456789abcdef".as_bytes()[rng.gen_range(0..16)] as char)
+            .collect(),
+        _ => random_string(rng),
+    }
+}
+
+#[test]
+fn execute_never_panics_on_random_json() {
+    let mut rng = StdRng::seed_from_u64(0x3a11);
+    for _ in 0..CASES {
+        let data = random_json(&mut rng, 3).to_string();
+        assert_well_formed(&post(&data), &data);
+    }
+}
+
+#[test]
+fn execute_never_panics_on_random_text() {
+    let mut rng = StdRng::seed_from_u64(0x3a12);
+    for _ in 0..CASES {
+        let data = random_string(&mut rng);
+        assert_well_formed(&post(&data), &data);
+    }
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint_never_panics_on_random_hex() {
+    let mut rng = StdRng::seed_from_u64(0x3a13);
+    for _ in 0..CASES {
+        let data = random_hex(&mut rng);
+        assert_well_formed(&mint(&data), &data);
+    }
+}
@@ -0,0 +1,183 @@
+//! Golden vectors: every `tests/fixtures/*.json` is a call to one of the exports and the exact
+//! result it gave, so integrators and auditors can check a release byte for byte. Run with
+//! `W3WALL_BLESS=1` to record the current results after an intended change, and review the diff.
+//!
+//! A fixture holds the `entrypoint` (on_execute, on_mint, on_clone or validate), its inputs
+//! (`contract`, `metadatas`, `transaction`, or `mint` for on_mint and `data` for validate), the
+//! `ipfs` contents the stored records point at, and the `expected` result. on_mint stamps the
+//! topic with the node's clock, that one attribute is zeroed before comparing.
+
+#[cfg(feature = "mint")]
+use crate::on_mint;
+use crate::types::{MetaContract, MetaContractResult, Metadata, Transaction, ValidationReport};
+use crate::{on_clone, on_execute, validate};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn text(value: &Value, name: &str) -> String {
+    value[name].as_str().unwrap_or_default().to_string()
+}
+
+fn contract(value: &Value) -> MetaContract {
+    MetaContract {
+        hash: text(value, "hash"),
+        token_key: text(value, "token_key"),
+        meta_contract_id: text(value, "meta_contract_id"),
+        public_key: text(value, "public_key"),
+        cid: text(value, "cid"),
+    }
+}
+
+fn metadata(value: &Value) -> Metadata {
+    Metadata {
+        hash: text(value, "hash"),
+        token_key: text(value, "token_key"),
+        data_key: text(value, "data_key"),
+        meta_contract_id: text(value, "meta_contract_id"),
+        token_id: text(value, "token_id"),
+        alias: text(value, "alias"),
+        cid: text(value, "cid"),
+        public_key: text(value, "public_key"),
+        version: text(value, "version"),
+        loose: value["loose"].as_i64().unwrap_or_default(),
+    }
+}
+
+fn transaction(value: &Value) -> Transaction {
+    Transaction {
+        hash: text(value, "hash"),
+        method: text(value, "method"),
+        meta_contract_id: text(value, "meta_contract_id"),
+        data_key: text(value, "data_key"),
+        token_key: text(value, "token_key"),
+        data: text(value, "data"),
+        public_key: text(value, "public_key"),
+        alias: text(value, "alias"),
+        timestamp: value["timestamp"].as_u64().unwrap_or_default(),
+        chain_id: text(value, "chain_id"),
+        token_address: text(value, "token_address"),
+        token_id: text(value, "token_id"),
+        version: text(value, "version"),
+        status: value["status"].as_i64().unwrap_or_default(),
+        mcdata: text(value, "mcdata"),
+    }
+}
+
+fn contract_result(result: MetaContractResult) -> Value {
+    let metadatas: Vec<Value> = result
+        .metadatas
+        .iter()
+        .map(|m| {
+            json!({
+                "public_key": m.public_key,
+                "alias": m.alias,
+                "content": m.content,
+                "loose": m.loose,
+                "version": m.version,
+            })
+        })
+        .collect();
+    json!({
+        "result": result.result,
+        "error_string": result.error_string,
+        "metadatas": metadatas,
+    })
+}
+
+fn report(report: ValidationReport) -> Value {
+    json!({
+        "valid": report.valid,
+        "code": report.code,
+        "error_string": report.error_string,
+        "warnings": report.warnings,
+    })
+}
+
+/**
+ * What the fixture's entrypoint returns today, None for entrypoints this build leaves out
+ */
+fn run(fixture: &Value) -> Option<Value> {
+    if let Some(ipfs) = fixture["ipfs"].as_object() {
+        for (cid, content) in ipfs {
+            crate::storage::ipfs::test_store::put(cid, content.as_str().unwrap_or_default());
+        }
+    }
+    let metadatas = || {
+        fixture["metadatas"]
+            .as_array()
+            .map(|records| records.iter().map(metadata).collect())
+            .unwrap_or_default()
+    };
+
+    match fixture["entrypoint"].as_str() {
+        Some("on_execute") => Some(contract_result(on_execute(
+            contract(&fixture["contract"]),
+            metadatas(),
+            transaction(&fixture["transaction"]),
+        ))),
+        #[cfg(feature = "mint")]
+        Some("on_mint") => {
+            let mint = &fixture["mint"];
+            Some(contract_result(on_mint(
+                contract(&fixture["contract"]),
+                metadatas(),
+                text(mint, "data_key"),
+                text(mint, "token_id"),
+                text(mint, "data"),
+            )))
+        }
+        #[cfg(not(feature = "mint"))]
+        Some("on_mint") => None,
+        Some("on_clone") => Some(json!({
+            "result": on_clone(
+                contract(&fixture["contract"]),
+                transaction(&fixture["transaction"])
+            )
+        })),
+        Some("validate") => Some(report(validate(text(fixture, "data")))),
+        other => panic!("unknown entrypoint {:?}", other),
+    }
+}
+
+#[test]
+fn exports_match_the_golden_vectors() {
+    let bless = std::env::var_os("W3WALL_BLESS").is_some();
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    let mut mismatches = vec![];
+    for path in paths {
+        let mut fixture: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let actual = match run(&fixture) {
+            Some(actual) => actual,
+            None => continue,
+        };
+        if bless {
+            fixture["expected"] = actual;
+            let pretty = serde_json::to_string_pretty(&fixture).unwrap();
+            std::fs::write(&path, pretty + "\n").unwrap();
+        } else if fixture["expected"] != actual {
+            mismatches.push(format!(
+                "{}\n  expected {}\n  got      {}",
+                path.display(),
+                fixture["expected"],
+                actual
+            ));
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "results differ from the golden vectors, run with W3WALL_BLESS=1 if intended:\n{}",
+        mismatches.join("\n")
+    );
+}
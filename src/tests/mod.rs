@@ -0,0 +1,163 @@
+//! Entrypoint level tests. They call the `#[marine]` exports natively, the same way the
+//! lineage node calls them through Marine, so behavioral changes show up before deployment.
+
+#[cfg(feature = "bench")]
+mod bench;
+mod fuzz;
+mod golden;
+
+#[cfg(feature = "mint")]
+use crate::on_mint;
+use crate::types::{MetaContract, MetaContractResult, Transaction};
+use crate::{on_clone, on_execute};
+#[cfg(feature = "mint")]
+use ethabi::{encode, Token};
+
+fn contract() -> MetaContract {
+    MetaContract {
+        hash: "hash".to_string(),
+        token_key: "token".to_string(),
+        meta_contract_id: "w3wall".to_string(),
+        public_key: "0xowner".to_string(),
+        cid: "".to_string(),
+    }
+}
+
+fn post(data: &str) -> MetaContractResult {
+    on_execute(
+        contract(),
+        Vec::new(),
+        Transaction {
+            method: "metadata".to_string(),
+            public_key: "0xposter".to_string(),
+            data: data.to_string(),
+            version: "1".to_string(),
+            ..Default::default()
+        },
+    )
+}
+
+#[cfg(feature = "mint")]
+fn mint(data: &str) -> MetaContractResult {
+    on_mint(
+        contract(),
+        vec![],
+        "key".to_string(),
+        "1".to_string(),
+        data.to_string(),
+    )
+}
+
+#[test]
+fn execute_valid_post() {
+    let result =
+        post(r#"{"text": "gm wall", "image": "https://nftstorage.link/ipfs/bafy/gm.png"}"#);
+    assert!(result.result, "{}", result.error_string);
+    // the post, its reading time, its image, its excerpt, the thread rollup and the thread digest
+    assert_eq!(result.metadatas.len(), 6);
+    assert_eq!(result.metadatas[0].public_key, "0xposter");
+    assert_eq!(result.metadatas[0].version, "1");
+    assert_eq!(result.metadatas[1].alias, "reading");
+    assert_eq!(result.metadatas[2].alias, "media");
+    assert_eq!(result.metadatas[3].alias, "excerpt");
+    assert_eq!(result.metadatas[4].alias, "w3wall.rollup");
+    assert_eq!(result.metadatas[5].alias, "w3wall.digest");
+}
+
+#[test]
+fn execute_invalid_json() {
+    let result = post("{text: gm");
+    assert!(!result.result);
+    assert!(result.metadatas.is_empty());
+    assert!(result.error_string.contains("invalid_format"));
+}
+
+#[test]
+fn execute_non_object_payload() {
+    let result = post(r#""just a string""#);
+    assert!(!result.result);
+    assert!(result.error_string.contains("schema"));
+}
+
+#[test]
+fn execute_bad_image_link() {
+    let result = post(r#"{"text": "gm", "image": "https://evil.example/x.png"}"#);
+    assert!(!result.result);
+    assert!(result.error_string.contains("/properties/image/anyOf"));
+}
+
+#[test]
+fn execute_missing_text_and_image() {
+    let result = post(r#"{}"#);
+    assert!(!result.result);
+    assert!(result.error_string.contains("needs text or image"));
+
+    let result = post(r#"{"img": "https://nftstorage.link/ipfs/bafy/gm.png"}"#);
+    assert!(!result.result);
+    assert_eq!(
+        result.error_string,
+        "[unknown_field] Unknown field img, did you mean image?"
+    );
+}
+
+#[test]
+fn execute_missing_text_with_image() {
+    let result = post(r#"{"image": "https://nftstorage.link/ipfs/bafy/gm.png"}"#);
+    assert!(result.result, "{}", result.error_string);
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint_valid_abi() {
+    let data = hex::encode(encode(&[
+        Token::String("topic".to_string()),
+        Token::String("https://nftstorage.link/ipfs/bafy/gm.png".to_string()),
+        Token::String("body".to_string()),
+    ]));
+
+    let result = mint(&data);
+    assert!(result.result, "{}", result.error_string);
+    let aliases: Vec<&str> = result.metadatas.iter().map(|m| m.alias.as_str()).collect();
+    for alias in ["name", "image", "body", "description", "attributes"] {
+        assert!(aliases.contains(&alias), "missing {}", alias);
+    }
+    assert!(result.metadatas.iter().all(|m| m.public_key == "0xowner"));
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint_malformed_hex() {
+    let result = mint("0xnothex");
+    assert!(!result.result);
+    assert!(result.metadatas.is_empty());
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint_truncated_abi() {
+    let result = mint("00ff");
+    assert!(!result.result);
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint_empty_data() {
+    let result = mint("");
+    assert!(result.result);
+    assert!(result.metadatas.iter().all(|m| m.alias != "name"));
+}
+
+#[test]
+fn clone_by_owner_and_stranger() {
+    let owner = Transaction {
+        public_key: "0xowner".to_string(),
+        ..Default::default()
+    };
+    let stranger = Transaction {
+        public_key: "0xstranger".to_string(),
+        ..Default::default()
+    };
+
+    assert!(on_clone(contract(), owner));
+    assert!(!on_clone(contract(), stranger));
+}
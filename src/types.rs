@@ -2,13 +2,37 @@ use marine_rs_sdk::marine;
 use serde::Deserialize;
 
 #[marine]
+#[derive(Debug)]
 pub struct MetaContractResult {
     pub result: bool,
     pub metadatas: Vec<FinalMetadata>,
     pub error_string: String,
 }
 
+/**
+ * Outcome of one token of a batch mint, `metadatas` is only set when `result` is true
+ */
+#[cfg(feature = "mint")]
 #[marine]
+#[derive(Debug, Default, Clone)]
+pub struct MintItemResult {
+    pub token_id: String,
+    pub result: bool,
+    pub metadatas: Vec<FinalMetadata>,
+    pub error_string: String,
+}
+
+#[cfg(feature = "mint")]
+#[marine]
+#[derive(Debug, Default, Clone)]
+pub struct BatchMintResult {
+    pub result: bool,
+    pub items: Vec<MintItemResult>,
+    pub error_string: String,
+}
+
+#[marine]
+#[derive(Debug, Clone)]
 pub struct FinalMetadata {
     pub public_key: String,
     pub alias: String,
@@ -18,7 +42,7 @@ pub struct FinalMetadata {
 }
 
 #[marine]
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Metadata {
     pub hash: String,
     pub token_key: String,
@@ -33,7 +57,7 @@ pub struct Metadata {
 }
 
 #[marine]
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Transaction {
     pub hash: String,
     pub method: String,
@@ -64,5 +88,39 @@ pub struct MetaContract {
 
 #[derive(Debug, Default, Deserialize)]
 pub struct SerdeMetadata {
-  pub loose: i64,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[marine]
+#[derive(Debug, Default, Clone)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub code: String,
+    pub error_string: String,
+    /**
+     * Issues that do not reject the post, each as `[code] message`
+     */
+    pub warnings: Vec<String>,
+}
+
+#[marine]
+#[derive(Debug, Default, Clone)]
+pub struct ContractLimit {
+    pub name: String,
+    pub value: u64,
+}
+
+#[marine]
+#[derive(Debug, Default, Clone)]
+pub struct ContractInfo {
+    pub version: String,
+    pub schema_version: u64,
+    /**
+     * Major `version` of the transactions taken and records written, see RECORD_VERSION
+     */
+    pub record_version: u64,
+    pub methods: Vec<String>,
+    pub features: Vec<String>,
+    pub limits: Vec<ContractLimit>,
 }
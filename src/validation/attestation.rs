@@ -0,0 +1,154 @@
+use crate::crypto::{keccak_hex, verify_personal_signature};
+use crate::data::{ClassificationRules, WallConfig};
+use serde::Deserialize;
+
+/**
+ * EAS style credential from a wall attester: `subject` holds the `kind` credential (passed the
+ * sybil check, is a rights holder, ...) until `expires` (unix seconds)
+ */
+#[derive(Deserialize, Debug)]
+pub struct Credential {
+    pub signer: String,
+    pub expires: u64,
+    pub signature: String,
+}
+
+pub fn credential_message(kind: &str, subject: &str, expires: u64) -> String {
+    format!("w3wall:{}:{}:{}", kind, subject.to_lowercase(), expires)
+}
+
+pub fn check_credential(
+    kind: &str,
+    credential: &Credential,
+    subject: &str,
+    now: u64,
+    config: &WallConfig,
+) -> Result<(), &'static str> {
+    if !config.is_attester(&credential.signer) {
+        return Err("Attestation signer is not a trusted attester.");
+    }
+    if credential.expires <= now {
+        return Err("Attestation has expired.");
+    }
+    if !verify_personal_signature(
+        &credential.signer,
+        &credential_message(kind, subject, credential.expires),
+        &credential.signature,
+    ) {
+        return Err("Attestation signature is not valid.");
+    }
+    Ok(())
+}
+
+/**
+ * Label an off-chain classifier gave a post's text and image, signed by the classifier
+ */
+#[derive(Deserialize, Debug)]
+pub struct Verdict {
+    pub signer: String,
+    pub label: String,
+    pub signature: String,
+}
+
+/**
+ * The text is hashed so long posts sign the same as short ones, the image is a CID or link
+ */
+pub fn verdict_message(label: &str, text: &str, image: &str) -> String {
+    format!(
+        "w3wall:verdict:{}:{}:{}",
+        label,
+        keccak_hex(text.as_bytes()),
+        image
+    )
+}
+
+pub fn check_verdict(
+    verdict: &Verdict,
+    text: &str,
+    image: &str,
+    rules: &ClassificationRules,
+) -> Result<(), String> {
+    if !rules.is_classifier(&verdict.signer) {
+        return Err("Verdict signer is not a registered classifier.".to_string());
+    }
+    if !verify_personal_signature(
+        &verdict.signer,
+        &verdict_message(&verdict.label, text, image),
+        &verdict.signature,
+    ) {
+        return Err("Verdict signature is not valid for this post.".to_string());
+    }
+    if rules.blocked_labels.contains(&verdict.label) {
+        return Err(format!(
+            "The classifier labelled this post {}.",
+            verdict.label
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::test_wallet;
+
+    fn credential(expires: u64) -> Credential {
+        Credential {
+            signer: test_wallet::address(4),
+            expires,
+            signature: test_wallet::sign(4, &credential_message("human", "0xa", expires)),
+        }
+    }
+
+    #[test]
+    fn verdicts_are_bound_to_the_post_and_a_classifier() {
+        let verdict = |label: &str| Verdict {
+            signer: test_wallet::address(5),
+            label: label.to_string(),
+            signature: test_wallet::sign(5, &verdict_message(label, "gm", "")),
+        };
+        let mut rules = ClassificationRules::default();
+        assert_eq!(
+            check_verdict(&verdict("ok"), "gm", "", &rules).unwrap_err(),
+            "Verdict signer is not a registered classifier."
+        );
+
+        rules.classifiers = vec![test_wallet::address(5)];
+        assert_eq!(check_verdict(&verdict("ok"), "gm", "", &rules), Ok(()));
+        assert_eq!(
+            check_verdict(&verdict("ok"), "gn", "", &rules).unwrap_err(),
+            "Verdict signature is not valid for this post."
+        );
+        assert_eq!(
+            check_verdict(&verdict("nsfw"), "gm", "", &rules).unwrap_err(),
+            "The classifier labelled this post nsfw."
+        );
+    }
+
+    #[test]
+    fn attester_expiry_and_signature_are_checked() {
+        let mut config = WallConfig::default();
+        assert_eq!(
+            check_credential("human", &credential(10), "0xa", 5, &config),
+            Err("Attestation signer is not a trusted attester.")
+        );
+
+        config.attesters = vec![test_wallet::address(4)];
+        assert_eq!(
+            check_credential("human", &credential(10), "0xa", 10, &config),
+            Err("Attestation has expired.")
+        );
+        assert_eq!(
+            check_credential("human", &credential(10), "0xA", 5, &config),
+            Ok(())
+        );
+        assert_eq!(
+            check_credential("claimant", &credential(10), "0xa", 5, &config),
+            Err("Attestation signature is not valid.")
+        );
+        assert_eq!(
+            credential_message("human", "0xA", 10),
+            "w3wall:human:0xa:10"
+        );
+    }
+}
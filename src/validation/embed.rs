@@ -0,0 +1,158 @@
+use crate::crypto::eth_address::is_address;
+use crate::crypto::verify_personal_signature;
+use crate::data::{NftEmbed, TokenBound, WallConfig};
+
+/**
+ * Message an attester signs to vouch that `author` holds the embedded token
+ */
+pub fn attestation_message(embed: &NftEmbed, author: &str) -> String {
+    format!(
+        "w3wall:holds:{}:{}:{}:{}",
+        embed.chain_id,
+        embed.contract.to_lowercase(),
+        embed.token_id,
+        author.to_lowercase()
+    )
+}
+
+/**
+ * Decimal uint256 or 0x hex token id
+ */
+pub fn is_token_id(token_id: &str) -> bool {
+    match token_id.strip_prefix("0x") {
+        Some(hex) => {
+            !hex.is_empty() && hex.len() <= 64 && hex.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => {
+            !token_id.is_empty()
+                && token_id.len() <= 78
+                && token_id.chars().all(|c| c.is_ascii_digit())
+        }
+    }
+}
+
+pub fn check_reference(embed: &NftEmbed) -> Result<(), &'static str> {
+    if embed.chain_id.is_empty() || !embed.chain_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err("Embedded NFT chain_id is not valid.");
+    }
+    if !is_address(&embed.contract) {
+        return Err("Embedded NFT contract is not a valid address.");
+    }
+    if !is_token_id(&embed.token_id) {
+        return Err("Embedded NFT token_id is not valid.");
+    }
+    Ok(())
+}
+
+/**
+ * True when the embed carries a valid attestation from one of the wall's attesters
+ */
+pub fn held_by_author(embed: &NftEmbed, author: &str, config: &WallConfig) -> bool {
+    let attestation = match &embed.attestation {
+        Some(attestation) => attestation,
+        None => return false,
+    };
+
+    config.is_attester(&attestation.signer)
+        && verify_personal_signature(
+            &attestation.signer,
+            &attestation_message(embed, author),
+            &attestation.signature,
+        )
+}
+
+/**
+ * Message the token holder signs to post as the token-bound account, bound to one wall
+ */
+pub fn token_bound_message(bound: &TokenBound, data_key: &str) -> String {
+    format!(
+        "w3wall:tba:{}:{}:{}:{}:{}",
+        bound.token.chain_id,
+        bound.token.contract.to_lowercase(),
+        bound.token.token_id,
+        bound.account.to_lowercase(),
+        data_key
+    )
+}
+
+/**
+ * The signature chain behind a token-bound post: the author signed the account binding, and a
+ * wall attester signed that the author holds the token
+ */
+pub fn check_token_bound(
+    bound: &TokenBound,
+    author: &str,
+    data_key: &str,
+    config: &WallConfig,
+) -> Result<(), &'static str> {
+    check_reference(&bound.token)?;
+    if !is_address(&bound.account) {
+        return Err("Token-bound account is not a valid address.");
+    }
+    if !verify_personal_signature(
+        author,
+        &token_bound_message(bound, data_key),
+        &bound.signature,
+    ) {
+        return Err("Token-bound account signature is not valid.");
+    }
+    if !held_by_author(&bound.token, author, config) {
+        return Err("Token ownership is not attested.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embed() -> NftEmbed {
+        NftEmbed {
+            chain_id: "1".to_string(),
+            contract: "0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D".to_string(),
+            token_id: "42".to_string(),
+            attestation: None,
+        }
+    }
+
+    #[test]
+    fn references_are_validated() {
+        assert!(check_reference(&embed()).is_ok());
+        assert!(check_reference(&NftEmbed {
+            token_id: "0x".to_string(),
+            ..embed()
+        })
+        .is_err());
+        assert!(check_reference(&NftEmbed {
+            chain_id: "mainnet".to_string(),
+            ..embed()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn attestation_message_is_normalized() {
+        assert_eq!(
+            attestation_message(&embed(), "0xABC"),
+            "w3wall:holds:1:0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d:42:0xabc"
+        );
+        assert!(!held_by_author(&embed(), "0xabc", &WallConfig::default()));
+    }
+
+    #[test]
+    fn token_bound_posts_need_the_signature_chain() {
+        let bound = TokenBound {
+            token: embed(),
+            account: "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf".to_string(),
+            signature: "0x00".to_string(),
+        };
+        assert_eq!(
+            token_bound_message(&bound, "key"),
+            "w3wall:tba:1:0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d:42:0x7e5f4552091a69125d5dfcb7b8c2659029395bdf:key"
+        );
+        assert_eq!(
+            check_token_bound(&bound, "0xabc", "key", &WallConfig::default()),
+            Err("Token-bound account signature is not valid.")
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::defaults::{DEFAULT_GEO_DECIMALS, DEFAULT_MAX_PLACE_LENGTH};
+use serde_json::{json, Value};
+
+fn truncate(degrees: f64) -> f64 {
+    let scale = 10f64.powi(DEFAULT_GEO_DECIMALS);
+    (degrees * scale).trunc() / scale
+}
+
+/**
+ * `{"lat", "lon"}` truncated to DEFAULT_GEO_DECIMALS (about a kilometre), or a named place as
+ * `{"place"}`. The exact position of the author is never stored.
+ */
+pub fn normalize_geo(geo: &Value) -> Result<Value, &'static str> {
+    if let Some(place) = geo.as_str() {
+        let place = place.trim();
+        if place.is_empty() || place.chars().count() > DEFAULT_MAX_PLACE_LENGTH {
+            return Err("Place name must be 1 to 100 characters.");
+        }
+        return Ok(json!({ "place": place }));
+    }
+
+    match (geo["lat"].as_f64(), geo["lon"].as_f64()) {
+        (Some(lat), Some(lon))
+            if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) =>
+        {
+            Ok(json!({ "lat": truncate(lat), "lon": truncate(lon) }))
+        }
+        (Some(_), Some(_)) => Err("Latitude must be within ±90 and longitude within ±180."),
+        _ => Err("Geo must be a place name or an object with lat and lon."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_are_truncated_and_ranged() {
+        assert_eq!(
+            normalize_geo(&json!({ "lat": 48.858_37, "lon": -2.294_48 })).unwrap(),
+            json!({ "lat": 48.85, "lon": -2.29 })
+        );
+        assert_eq!(
+            normalize_geo(&json!(" Lisbon ")).unwrap(),
+            json!({ "place": "Lisbon" })
+        );
+        assert!(normalize_geo(&json!({ "lat": 91, "lon": 0 })).is_err());
+        assert!(normalize_geo(&json!({ "lat": "1", "lon": 0 })).is_err());
+        assert!(normalize_geo(&json!("")).is_err());
+        assert!(normalize_geo(&json!(3)).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! The one place image references are checked: post images, profile avatars and wall banners.
+//! An image is a bare CID, or a link through one of the known gateways whose extension, or the
+//! MIME type declared next to it, names one of the wall's image types. Walls accepting SVG
+//! also take inline base64 SVGs, whose source is checked for active content. Linked SVGs can't
+//! be read from here and are the wall's call.
+
+use crate::crypto::hash::raw_cid;
+use crate::defaults::{DEFAULT_IMAGE_EXTENSIONS, DEFAULT_IMAGE_GATEWAYS};
+use crate::encoding::base64;
+use crate::validation::media::is_cid;
+use crate::validation::svg::{check_svg_data_uri, SVG_DATA_PREFIX};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static GATEWAYS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    DEFAULT_IMAGE_GATEWAYS
+        .iter()
+        .map(|prefix| Regex::new(&format!("^{}", regex::escape(prefix))).unwrap())
+        .collect()
+});
+
+fn is_gateway_link(link: &str) -> bool {
+    GATEWAYS.iter().any(|gateway| gateway.is_match(link))
+}
+
+/**
+ * MIME type named by the link's extension, gateway links rarely carry a content type
+ */
+pub fn image_mime(link: &str) -> Option<&'static str> {
+    let name = link.rsplit('/').next()?.to_lowercase();
+    let (_, extension) = name.rsplit_once('.')?;
+    let mime = DEFAULT_IMAGE_EXTENSIONS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension))
+        .map(|(mime, _)| *mime);
+    Some(mime.unwrap_or("application/octet-stream"))
+}
+
+/**
+ * `ipfs://` URI of an image, whichever accepted gateway it was linked through. An inline SVG
+ * gets the CID its file has once added as a raw block, so the inline copy and a pinned one are
+ * known to be the same image.
+ */
+pub fn canonical_uri(link: &str) -> Option<String> {
+    if is_cid(link) {
+        return Some(format!("ipfs://{}", link));
+    }
+    if let Some(encoded) = link.strip_prefix(SVG_DATA_PREFIX) {
+        return base64::decode(encoded).map(|svg| format!("ipfs://{}", raw_cid(svg)));
+    }
+    DEFAULT_IMAGE_GATEWAYS
+        .iter()
+        .find_map(|prefix| link.strip_prefix(prefix))
+        .filter(|path| !path.is_empty())
+        .map(|path| format!("ipfs://{}", path))
+}
+
+fn is_accepted(mime: &str, accepted: &[String]) -> bool {
+    DEFAULT_IMAGE_EXTENSIONS
+        .iter()
+        .any(|(known, _)| known.eq_ignore_ascii_case(mime))
+        && accepted.iter().any(|t| t.eq_ignore_ascii_case(mime))
+}
+
+/**
+ * `declared` is the MIME type the payload gives for the image, which links without an
+ * extension need. `accepted` is the wall's `image_types`.
+ */
+pub fn check_image(
+    link: &str,
+    declared: Option<&str>,
+    accepted: &[String],
+) -> Result<(), &'static str> {
+    let mime = if link.starts_with("data:") {
+        if !is_accepted("image/svg+xml", accepted) {
+            return Err("Image type is not accepted by this wall.");
+        }
+        return check_svg_data_uri(link);
+    } else if is_cid(link) {
+        declared
+    } else if !is_gateway_link(link) {
+        return Err("Image must be a CID or an nftstorage.link URL.");
+    } else {
+        match (image_mime(link), declared) {
+            (None, None) => return Err("Image link needs an extension or a declared type."),
+            (Some(named), Some(declared)) if !named.eq_ignore_ascii_case(declared) => {
+                return Err("Image extension does not match its declared type.")
+            }
+            (named, declared) => named.or(declared),
+        }
+    };
+    match mime {
+        Some(mime) if !is_accepted(mime, accepted) => {
+            Err("Image type is not accepted by this wall.")
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::WallConfig;
+
+    #[test]
+    fn cids_and_gateway_images() {
+        let accepted = WallConfig::default().image_types;
+        let check = |link: &str, declared: Option<&str>| check_image(link, declared, &accepted);
+        assert!(check("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", None).is_ok());
+        assert!(check("https://nftstorage.link/ipfs/bafy/cat.WEBP", None).is_ok());
+        assert!(check("https://nftstorage.link/ipfs/bafy", Some("image/png")).is_ok());
+        assert!(check("https://nftstorage.link/ipfs/bafy", None).is_err());
+        assert!(check("https://nftstorage.link/ipfs/bafy", Some("text/html")).is_err());
+        assert!(check("https://nftstorage.link/ipfs/bafy/x.png", Some("image/gif")).is_err());
+        assert!(check("https://nftstorage.link/ipfs/bafy/x.svg", None).is_err());
+        assert!(check("https://nftstorage.link/ipfs/bafy/x.html", None).is_err());
+        assert!(check("https://nftstorage.link/ipfs/bafy/setup.exe", None).is_err());
+        assert!(check("https://ipfs.io/ipfs/bafy/x.png", None).is_err());
+        assert!(check("", None).is_err());
+
+        let svg = ["image/svg+xml".to_string(), "text/html".to_string()];
+        assert!(check_image("https://nftstorage.link/ipfs/bafy/x.svg", None, &svg).is_ok());
+        assert!(check_image("https://nftstorage.link/ipfs/b/x", Some("text/html"), &svg).is_err());
+        let inline = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciPjxyZWN0IHdpZHRoPSI4IiBoZWlnaHQ9IjgiLz48L3N2Zz4=";
+        assert!(check_image(inline, None, &svg).is_ok());
+        assert!(check(inline, None).is_err());
+
+        assert_eq!(
+            canonical_uri("https://nftstorage.link/ipfs/bafy/a.png").as_deref(),
+            Some("ipfs://bafy/a.png")
+        );
+        assert_eq!(
+            canonical_uri(inline).as_deref(),
+            Some("ipfs://bafkreic5koae7rz3k4xdlxn6ki2ug6ppcgzkykk3slmeyfmjxvdtoagt4a")
+        );
+
+        assert_eq!(image_mime("https://x/ipfs/bafy/a.JPG"), Some("image/jpeg"));
+        assert_eq!(image_mime("https://x/ipfs/bafy"), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Evaluator for the subset of JSON Schema (draft 2020-12) the embedded schemas use: type,
+//! const, enum, anyOf, required, properties, additionalProperties, items, pattern, min/maxLength,
+//! min/maxItems and minimum/maximum. Annotations such as description are ignored.
+
+use crate::budget;
+use crate::defaults::DEFAULT_REGEX_SIZE_LIMIT;
+use regex::RegexBuilder;
+use serde_json::Value;
+
+/**
+ * First place an instance breaks its schema, both paths are JSON pointers
+ */
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    pub schema_path: String,
+    pub instance_path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let at = if self.instance_path.is_empty() {
+            "/"
+        } else {
+            &self.instance_path
+        };
+        write!(
+            f,
+            "{} at {} (schema {})",
+            self.message, at, self.schema_path
+        )
+    }
+}
+
+pub fn first_violation(schema: &Value, instance: &Value) -> Option<Violation> {
+    check(schema, instance, "", "")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + (ca != *cb) as usize);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/**
+ * Closest of `candidates` to a mistyped `name`, None when nothing is near enough to be a typo
+ */
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2 && *distance < name.chars().count())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/**
+ * First field not in `allowed`, with the closest allowed name when one is near
+ */
+pub fn unknown_field(
+    fields: &serde_json::Map<String, Value>,
+    allowed: &[&str],
+    path: &str,
+    at: &str,
+) -> Option<Violation> {
+    let name = fields
+        .keys()
+        .find(|name| !allowed.contains(&name.as_str()))?;
+    let message = match closest(name, allowed.iter().copied()) {
+        Some(suggestion) => format!("Unknown field {}, did you mean {}?", name, suggestion),
+        None => format!("Unknown field {}", name),
+    };
+    Some(Violation {
+        schema_path: format!("{}/additionalProperties", path),
+        instance_path: format!("{}/{}", at, name),
+        message,
+    })
+}
+
+fn violation(schema_path: &str, keyword: &str, instance_path: &str, message: String) -> Violation {
+    Violation {
+        schema_path: format!("{}/{}", schema_path, keyword),
+        instance_path: instance_path.to_string(),
+        message,
+    }
+}
+
+fn type_matches(name: &str, instance: &Value) -> bool {
+    match name {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        "number" => instance.is_number(),
+        "integer" => instance.is_i64() || instance.is_u64(),
+        _ => true,
+    }
+}
+
+fn check(schema: &Value, instance: &Value, path: &str, at: &str) -> Option<Violation> {
+    let schema = match schema {
+        Value::Bool(true) => return None,
+        Value::Bool(false) => {
+            return Some(Violation {
+                schema_path: path.to_string(),
+                instance_path: at.to_string(),
+                message: "Value is not allowed".to_string(),
+            })
+        }
+        Value::Object(schema) => schema,
+        _ => return None,
+    };
+
+    if let Some(name) = schema.get("type").and_then(Value::as_str) {
+        if !type_matches(name, instance) {
+            return Some(violation(path, "type", at, format!("Expected {}", name)));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != instance {
+            return Some(violation(
+                path,
+                "const",
+                at,
+                format!("Expected {}", expected),
+            ));
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(instance) {
+            return Some(violation(
+                path,
+                "enum",
+                at,
+                "Value is not one of the allowed".into(),
+            ));
+        }
+    }
+    // an unknown field is usually a typo of a known one, which says more than what's missing
+    if let (Value::Object(fields), Some(Value::Bool(false))) =
+        (instance, schema.get("additionalProperties"))
+    {
+        let allowed = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|properties| properties.keys().map(String::as_str))
+            .collect::<Vec<_>>();
+        if let Some(violation) = unknown_field(fields, &allowed, path, at) {
+            return Some(violation);
+        }
+    }
+    if let Some(branches) = schema.get("anyOf").and_then(Value::as_array) {
+        // which branch was meant is guesswork, so point at the alternatives as a whole
+        if branches.iter().enumerate().all(|(i, branch)| {
+            check(branch, instance, &format!("{}/anyOf/{}", path, i), at).is_some()
+        }) {
+            let message = "Matches none of the allowed forms".to_string();
+            return Some(violation(path, "anyOf", at, message));
+        }
+    }
+
+    match instance {
+        Value::String(s) => check_string(schema, s, path, at),
+        Value::Number(n) => check_number(schema, n.as_f64().unwrap_or_default(), path, at),
+        Value::Array(items) => check_array(schema, items, path, at),
+        Value::Object(fields) => check_object(schema, fields, path, at),
+        _ => None,
+    }
+}
+
+fn check_string(
+    schema: &serde_json::Map<String, Value>,
+    s: &str,
+    path: &str,
+    at: &str,
+) -> Option<Violation> {
+    let len = s.chars().count() as u64;
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if len > max {
+            let message = format!("Longer than {} characters", max);
+            return Some(violation(path, "maxLength", at, message));
+        }
+    }
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if len < min {
+            let message = format!("Shorter than {} characters", min);
+            return Some(violation(path, "minLength", at, message));
+        }
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+        // an unparsable pattern is a bug in the embedded schema, not in the payload
+        let regex = RegexBuilder::new(pattern)
+            .size_limit(DEFAULT_REGEX_SIZE_LIMIT)
+            .build();
+        if !budget::charge(pattern.len() + s.len()) || regex.is_ok_and(|re| !re.is_match(s)) {
+            let message = format!("Does not match {}", pattern);
+            return Some(violation(path, "pattern", at, message));
+        }
+    }
+    None
+}
+
+fn check_number(
+    schema: &serde_json::Map<String, Value>,
+    n: f64,
+    path: &str,
+    at: &str,
+) -> Option<Violation> {
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        if n > max {
+            return Some(violation(path, "maximum", at, format!("Above {}", max)));
+        }
+    }
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        if n < min {
+            return Some(violation(path, "minimum", at, format!("Below {}", min)));
+        }
+    }
+    None
+}
+
+fn check_array(
+    schema: &serde_json::Map<String, Value>,
+    items: &[Value],
+    path: &str,
+    at: &str,
+) -> Option<Violation> {
+    let len = items.len() as u64;
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if len > max {
+            let message = format!("More than {} items", max);
+            return Some(violation(path, "maxItems", at, message));
+        }
+    }
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if len < min {
+            let message = format!("Fewer than {} items", min);
+            return Some(violation(path, "minItems", at, message));
+        }
+    }
+    let item_schema = schema.get("items")?;
+    items.iter().enumerate().find_map(|(i, item)| {
+        check(
+            item_schema,
+            item,
+            &format!("{}/items", path),
+            &format!("{}/{}", at, i),
+        )
+    })
+}
+
+fn check_object(
+    schema: &serde_json::Map<String, Value>,
+    fields: &serde_json::Map<String, Value>,
+    path: &str,
+    at: &str,
+) -> Option<Violation> {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        if let Some(missing) = required
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|name| !fields.contains_key(*name))
+        {
+            let message = format!("Missing required field {}", missing);
+            return Some(violation(path, "required", at, message));
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (name, value) in fields {
+        let field_at = format!("{}/{}", at, name);
+        let found = match properties.and_then(|p| p.get(name)) {
+            Some(property) => check(
+                property,
+                value,
+                &format!("{}/properties/{}", path, name),
+                &field_at,
+            ),
+            None => schema.get("additionalProperties").and_then(|additional| {
+                check(
+                    additional,
+                    value,
+                    &format!("{}/additionalProperties", path),
+                    &field_at,
+                )
+            }),
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_the_schema_path_of_the_first_violation() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string", "maxLength": 3 },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] } }
+            },
+            "additionalProperties": false
+        });
+
+        assert_eq!(first_violation(&schema, &json!({ "name": "abc" })), None);
+        let v = first_violation(&schema, &json!({ "name": "abcd" })).unwrap();
+        assert_eq!(v.schema_path, "/properties/name/maxLength");
+        assert_eq!(v.instance_path, "/name");
+
+        let v = first_violation(&schema, &json!({ "name": "a", "tags": ["a", "c"] })).unwrap();
+        assert_eq!(v.schema_path, "/properties/tags/items/enum");
+        assert_eq!(v.instance_path, "/tags/1");
+
+        let v = first_violation(&schema, &json!({ "name": "a", "x": 1 })).unwrap();
+        assert_eq!(v.schema_path, "/additionalProperties");
+        assert_eq!(v.message, "Unknown field x");
+        assert_eq!(
+            first_violation(&schema, &json!({})).unwrap().to_string(),
+            "Missing required field name at / (schema /required)"
+        );
+    }
+
+    #[test]
+    fn unknown_fields_come_first_and_suggest_a_name() {
+        let schema = json!({
+            "type": "object",
+            "required": ["image"],
+            "properties": { "image": { "type": "string" }, "text": { "type": "string" } },
+            "additionalProperties": false
+        });
+        assert_eq!(
+            first_violation(&schema, &json!({ "img": "bafy" }))
+                .unwrap()
+                .to_string(),
+            "Unknown field img, did you mean image? at /img (schema /additionalProperties)"
+        );
+        assert_eq!(closest("Txet", ["image", "text"]), Some("text"));
+        assert_eq!(closest("ab", ["image", "text"]), None);
+    }
+
+    #[test]
+    fn any_of_passes_when_one_branch_does() {
+        let schema = json!({ "anyOf": [{ "const": "" }, { "pattern": "^https://" }] });
+        assert_eq!(first_violation(&schema, &json!("")), None);
+        assert_eq!(first_violation(&schema, &json!("https://a")), None);
+        let v = first_violation(&schema, &json!("ftp://a")).unwrap();
+        assert_eq!(v.schema_path, "/anyOf");
+    }
+}
@@ -0,0 +1,38 @@
+use crate::defaults::DEFAULT_LICENSES;
+
+/**
+ * One of the known license identifiers, or the URI of a custom license
+ */
+pub fn is_license(license: &str) -> bool {
+    DEFAULT_LICENSES
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(license))
+        || is_license_uri(license)
+}
+
+fn is_license_uri(license: &str) -> bool {
+    ["https://", "ipfs://", "ar://"].iter().any(|scheme| {
+        license
+            .strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_licenses_and_uris_are_accepted() {
+        assert!(is_license("CC0"));
+        assert!(is_license("cc-by"));
+        assert!(is_license("ARR"));
+        assert!(is_license("https://example.com/license.txt"));
+        assert!(is_license(
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+        ));
+        assert!(!is_license("WTFPL"));
+        assert!(!is_license("https://"));
+        assert!(!is_license("http://example.com/license"));
+    }
+}
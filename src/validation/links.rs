@@ -0,0 +1,171 @@
+//! Canonical form of the links in a post, so the same page linked twice reads the same to
+//! duplicate checks and previews: lowercase scheme and host, no default port, escapes of
+//! unreserved characters decoded and the rest in uppercase hex, tracking parameters dropped.
+
+use crate::budget;
+use crate::defaults::DEFAULT_TRACKING_PARAMS;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)\bhttps?://[^\s<>"'`]+"#).unwrap());
+
+fn is_tracking(param: &str) -> bool {
+    let name = param.split('=').next().unwrap_or_default().to_lowercase();
+    name.starts_with("utm_") || DEFAULT_TRACKING_PARAMS.contains(&name.as_str())
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/**
+ * RFC 3986 percent-encoding normalization of a path, query or fragment
+ */
+fn normalize_escapes(part: &str) -> String {
+    let bytes = part.as_bytes();
+    let mut out = String::with_capacity(part.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| {
+                Some((
+                    hex_value(*bytes.get(i + 1)?)?,
+                    hex_value(*bytes.get(i + 2)?)?,
+                ))
+            })
+            .flatten();
+        match escape {
+            Some((high, low)) => {
+                let c = (high << 4 | low) as char;
+                if c.is_ascii_alphanumeric() || "-._~".contains(c) {
+                    out.push(c);
+                } else {
+                    out.push_str(&format!("%{:02X}", high << 4 | low));
+                }
+                i += 3;
+            }
+            None => {
+                let c = part[i..].chars().next().unwrap_or_default();
+                out.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    out
+}
+
+pub fn canonical_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let scheme = scheme.to_lowercase();
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(end);
+    let (fragment, rest) = match rest.split_once('#') {
+        Some((rest, fragment)) => (Some(fragment), rest),
+        None => (None, rest),
+    };
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (rest, None),
+    };
+
+    let (userinfo, host) = match authority.rsplit_once('@') {
+        Some((userinfo, host)) => (Some(userinfo), host),
+        None => (None, authority),
+    };
+    let mut host = host.to_lowercase();
+    let default_port = match scheme.as_str() {
+        "https" => ":443",
+        _ => ":80",
+    };
+    if let Some(stripped) = host.strip_suffix(default_port) {
+        host = stripped.to_string();
+    }
+
+    let mut canonical = format!("{}://", scheme);
+    if let Some(userinfo) = userinfo {
+        canonical.push_str(userinfo);
+        canonical.push('@');
+    }
+    canonical.push_str(&host);
+    canonical.push_str(&normalize_escapes(path));
+    if let Some(query) = query {
+        let kept: Vec<String> = query
+            .split('&')
+            .filter(|param| !param.is_empty() && !is_tracking(param))
+            .map(normalize_escapes)
+            .collect();
+        if !kept.is_empty() {
+            canonical.push('?');
+            canonical.push_str(&kept.join("&"));
+        }
+    }
+    if let Some(fragment) = fragment {
+        canonical.push('#');
+        canonical.push_str(&normalize_escapes(fragment));
+    }
+    canonical
+}
+
+fn trim_punctuation(link: &str) -> &str {
+    // sentence punctuation right after a link belongs to the sentence
+    link.trim_end_matches(['.', ',', ';', ':', '!', '?', ')'])
+}
+
+/**
+ * Every link of `text`, as written
+ */
+pub fn links(text: &str) -> impl Iterator<Item = &str> {
+    LINK.find_iter(text)
+        .map(|link| trim_punctuation(link.as_str()))
+}
+
+/**
+ * `text` with every link in canonical form, None when no link changes
+ */
+pub fn canonical_links(text: &str) -> Option<String> {
+    if !budget::charge(text.len()) {
+        return None;
+    }
+    let mut changed = false;
+    let canonical = LINK.replace_all(text, |link: &regex::Captures| {
+        let link = &link[0];
+        let url = trim_punctuation(link);
+        let canonical = canonical_url(url);
+        changed |= canonical != url;
+        format!("{}{}", canonical, &link[url.len()..])
+    });
+    changed.then(|| canonical.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_canonicalized() {
+        assert_eq!(
+            canonical_url("HTTPS://Example.COM:443/a%2fb%7E?utm_source=x&id=1&FBCLID=y#Top"),
+            "https://example.com/a%2Fb~?id=1#Top"
+        );
+        assert_eq!(
+            canonical_url("http://Example.com:8080/?utm_medium=feed"),
+            "http://example.com:8080/"
+        );
+        assert_eq!(
+            canonical_url("https://a.b/caf%C3%A9"),
+            "https://a.b/caf%C3%A9"
+        );
+        assert_eq!(canonical_url("https://a.b/%zz"), "https://a.b/%zz");
+    }
+
+    #[test]
+    fn links_in_text_keep_their_punctuation() {
+        assert_eq!(
+            canonical_links("see https://Example.com/post?utm_source=tw. and www.x.y").as_deref(),
+            Some("see https://example.com/post. and www.x.y")
+        );
+        assert_eq!(canonical_links("see https://example.com/post"), None);
+    }
+}
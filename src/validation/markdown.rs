@@ -0,0 +1,64 @@
+//! Checks on the markdown of long-form posts. Nothing is rendered here, the point is to turn
+//! away what renders badly or unsafely in every client: unclosed fences, raw HTML and links to
+//! schemes other than https, ipfs and ar.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static FENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*```").unwrap());
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"</?[A-Za-z][A-Za-z0-9-]*[\s/>]").unwrap());
+static LINK_TARGET: Lazy<Regex> = Lazy::new(|| Regex::new(r"\]\(\s*<?([^)\s>]*)").unwrap());
+
+/**
+ * Why `text` is not acceptable markdown, the text inside code fences is left alone
+ */
+pub fn check_markdown(text: &str) -> Result<(), String> {
+    let fences = FENCE.find_iter(text).count();
+    if fences % 2 == 1 {
+        return Err("Code fence is not closed.".to_string());
+    }
+
+    // odd pieces are inside a fence
+    let prose: Vec<&str> = FENCE.split(text).step_by(2).collect();
+    if prose.iter().any(|piece| HTML_TAG.is_match(piece)) {
+        return Err("Raw HTML is not allowed.".to_string());
+    }
+    for piece in prose {
+        for target in LINK_TARGET.captures_iter(piece) {
+            let scheme = target[1]
+                .split_once(':')
+                .map(|(scheme, _)| scheme.to_lowercase());
+            match scheme {
+                Some(scheme) if !["https", "ipfs", "ar"].contains(&scheme.as_str()) => {
+                    return Err(format!("Links to {}: are not allowed.", scheme));
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fences_html_and_link_schemes_are_checked() {
+        assert!(
+            check_markdown("## Intro\n\nSee [docs](https://x.io) and ![pic](ipfs://bafy).").is_ok()
+        );
+        assert!(check_markdown("```html\n<b>shown as code</b>\n```").is_ok());
+        assert!(check_markdown("a < b and 2<3").is_ok());
+
+        assert_eq!(
+            check_markdown("```\nlet x;").unwrap_err(),
+            "Code fence is not closed."
+        );
+        assert!(check_markdown("<script>alert(1)</script>").is_err());
+        assert_eq!(
+            check_markdown("[click](javascript:alert(1))").unwrap_err(),
+            "Links to javascript: are not allowed."
+        );
+    }
+}
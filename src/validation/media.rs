@@ -0,0 +1,86 @@
+/**
+ * Base83 alphabet, the first character encodes the component counts and thus the length
+ */
+pub fn is_blurhash(hash: &str) -> bool {
+    const BASE83: &str =
+        "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+    if hash.len() < 6 || !hash.chars().all(|c| BASE83.contains(c)) {
+        return false;
+    }
+
+    let size_flag = BASE83.find(hash.chars().next().unwrap()).unwrap();
+    let components_x = size_flag % 9 + 1;
+    let components_y = size_flag / 9 + 1;
+
+    hash.len() == 4 + 2 * components_x * components_y
+}
+
+use crate::encoding::{base58, multibase};
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut n = 0u64;
+    for shift in (0..63).step_by(7) {
+        let (byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(n);
+        }
+    }
+    None
+}
+
+/**
+ * CIDv0 (base58btc "Qm...") or CIDv1 in any multibase, decoded down to a multihash whose
+ * length matches its digest
+ */
+pub fn is_cid(cid: &str) -> bool {
+    if cid.starts_with("Qm") {
+        // a bare sha256 multihash
+        return cid.len() == 46
+            && base58::decode(cid).is_some_and(|b| b.len() == 34 && b[..2] == [0x12, 0x20]);
+    }
+
+    let Ok(bytes) = multibase::decode(cid) else {
+        return false;
+    };
+    let mut rest = bytes.as_slice();
+    let (Some(1), Some(_codec), Some(_hash), Some(length)) = (
+        read_varint(&mut rest),
+        read_varint(&mut rest),
+        read_varint(&mut rest),
+        read_varint(&mut rest),
+    ) else {
+        return false;
+    };
+    length > 0 && rest.len() as u64 == length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blurhash_length_matches_components() {
+        assert!(is_blurhash("LEHV6nWB2yk8pyo0adR*.7kCMdnj"));
+        assert!(!is_blurhash("LEHV6nWB2yk8pyo0adR*.7kCMdn"));
+        assert!(!is_blurhash("LEHV6nWB2yk8pyo0adR*.7kCMd\"j"));
+    }
+
+    #[test]
+    fn cid_versions() {
+        assert!(is_cid("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"));
+        assert!(is_cid(
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+        ));
+        assert!(is_cid("zb2rhZfjRh2FHHB2RkHVEvL2vJnCTcu7kwRqgVsf9gpkLgteo"));
+        assert!(!is_cid("Qm0000"));
+        assert!(!is_cid("bafyBEIG"));
+        // one character short of its digest, and a CIDv0 string with a multibase prefix
+        assert!(!is_cid(
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzd"
+        ));
+        assert!(!is_cid("bQmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"));
+    }
+}
@@ -0,0 +1,79 @@
+//! Payloads from older clients are lifted to the current `schema_version` before validation, one
+//! step at a time, so posts are always validated and stored in the latest form.
+
+use crate::defaults::SCHEMA_VERSION;
+use crate::validation::ValidationError;
+use serde_json::Value;
+
+/**
+ * Lifts a payload of version n to version n + 1
+ */
+type Migration = fn(Value) -> Value;
+
+/**
+ * `MIGRATIONS[i]` takes version i + 1 to i + 2, the list ends at SCHEMA_VERSION
+ */
+static MIGRATIONS: &[Migration] = &[v1_to_v2];
+
+/**
+ * Version 1 is the legacy untyped `{image, text}` post, payloads without `schema_version` are
+ * taken to be version 1
+ */
+pub fn payload_version(payload: &Value) -> u64 {
+    payload["schema_version"].as_u64().unwrap_or(1)
+}
+
+/**
+ * v2 posts name their type, legacy posts are plain posts
+ */
+fn v1_to_v2(mut payload: Value) -> Value {
+    if payload.get("type").is_none() {
+        payload["type"] = "post".into();
+    }
+    payload["schema_version"] = 2.into();
+    payload
+}
+
+/**
+ * The payload at SCHEMA_VERSION and whether it had to be migrated
+ */
+pub fn migrate(mut payload: Value) -> Result<(Value, bool), ValidationError> {
+    let version = payload_version(&payload);
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(ValidationError {
+            code: "schema_version",
+            message: format!("schema_version {} is not supported.", version),
+        });
+    }
+
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        payload = migration(payload);
+    }
+    Ok((payload, version < SCHEMA_VERSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn legacy_posts_become_typed() {
+        let (payload, migrated) = migrate(json!({ "text": "gm", "image": "" })).unwrap();
+        assert!(migrated);
+        assert_eq!(
+            payload,
+            json!({ "text": "gm", "image": "", "type": "post", "schema_version": 2 })
+        );
+
+        let (payload, _) = migrate(json!({ "type": "code", "schema_version": 1 })).unwrap();
+        assert_eq!(payload["type"], "code");
+
+        let current = json!({ "text": "gm", "type": "post", "schema_version": 2 });
+        assert_eq!(migrate(current.clone()).unwrap(), (current, false));
+        assert_eq!(
+            migrate(json!({ "schema_version": 3 })).unwrap_err().code,
+            "schema_version"
+        );
+    }
+}
@@ -0,0 +1,449 @@
+pub mod attestation;
+pub mod embed;
+pub mod geo;
+pub mod image;
+pub mod jsonschema;
+pub mod license;
+pub mod links;
+pub mod markdown;
+pub mod media;
+pub mod migration;
+pub mod reference;
+pub mod rules;
+pub mod schema;
+pub mod svg;
+
+use crate::data::WallConfig;
+use crate::defaults::{
+    DEFAULT_MAX_DECOMPRESSED_BYTES, DEFAULT_MAX_PAYLOAD_BYTES, DEFAULT_NEAR_LIMIT_PERCENT,
+    DEFAULT_SOFT_RULES, SCHEMA_VERSION,
+};
+use crate::encoding::{
+    decode_brotli_base64, decode_cbor_base64, decode_gzip_base64, BROTLI_ENCODING, CBOR_PREFIX,
+    GZIP_ENCODING,
+};
+use crate::moderation::WordFilter;
+use crate::types::{Metadata, Transaction};
+use serde::Serialize;
+use serde_json::Value;
+
+/**
+ * Warnings a lenient wall stores next to a post, under the sender's key
+ */
+pub static WARNINGS_ALIAS: &str = "w3wall.warnings";
+
+/**
+ * Everything a rule may look at when judging a post
+ */
+pub struct PostContext<'a> {
+    pub transaction: &'a Transaction,
+    pub metadatas: &'a [Metadata],
+    /**
+     * Key the wall's own records are stored under
+     */
+    pub wall: &'a str,
+    pub config: &'a WallConfig,
+    pub filter: &'a WordFilter,
+    pub payload: &'a Value,
+}
+
+impl<'a> PostContext<'a> {
+    pub fn str_field(&self, field: &str) -> Option<&'a str> {
+        self.payload[field].as_str()
+    }
+
+    /**
+     * Sent by a key on the wall's `trusted` list, whose posts skip the heavy moderation checks
+     */
+    pub fn is_trusted(&self) -> bool {
+        self.config.is_trusted(&self.transaction.public_key)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidationError {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(code: &'static str, message: &str) -> Self {
+        ValidationError {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub trait Validator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError>;
+}
+
+/**
+ * Runs the rules in order and stops at the first one that rejects the post
+ */
+pub fn run_pipeline(
+    validators: &[&dyn Validator],
+    ctx: &PostContext,
+) -> Result<(), ValidationError> {
+    validators
+        .iter()
+        .try_for_each(|validator| validator.validate(ctx))
+}
+
+/**
+ * Runs the pipeline again without the fields of each cosmetic rule that rejects, so the post
+ * goes through with those rules as warnings. The payload comes back when fields were dropped.
+ */
+pub fn run_pipeline_lenient(
+    validators: &[&dyn Validator],
+    ctx: &PostContext,
+) -> Result<(Option<Value>, Vec<ValidationError>), ValidationError> {
+    let mut payload = ctx.payload.clone();
+    let mut warnings = vec![];
+    loop {
+        let attempt = PostContext {
+            payload: &payload,
+            ..*ctx
+        };
+        let Err(e) = run_pipeline(validators, &attempt) else {
+            let stripped = !warnings.is_empty();
+            return Ok((stripped.then_some(payload), warnings));
+        };
+        let fields = DEFAULT_SOFT_RULES
+            .iter()
+            .find(|(code, _)| *code == e.code)
+            .map(|(_, fields)| *fields)
+            .unwrap_or_default();
+        let Some(object) = payload
+            .as_object_mut()
+            .filter(|object| fields.iter().any(|field| object.contains_key(*field)))
+        else {
+            return Err(e);
+        };
+        for field in fields {
+            object.remove(*field);
+        }
+        warnings.push(e);
+    }
+}
+
+/**
+ * Issues worth telling the author about that never reject a post
+ */
+pub fn advisories(ctx: &PostContext) -> Vec<ValidationError> {
+    let mut warnings = vec![];
+    if ctx
+        .str_field("image")
+        .is_some_and(|image| !image.is_empty())
+        && ctx.str_field("alt").unwrap_or_default().trim().is_empty()
+    {
+        warnings.push(ValidationError::new(
+            "missing_alt",
+            "The image has no alt text for readers who cannot see it.",
+        ));
+    }
+    let max = ctx.config.rules.max_text_length;
+    let len = ctx.str_field("text").map_or(0, |text| text.chars().count());
+    if max > 0 && len <= max && len * 100 > max * DEFAULT_NEAR_LIMIT_PERCENT {
+        warnings.push(ValidationError {
+            code: "near_length_limit",
+            message: format!("Text is {} of the {} characters allowed.", len, max),
+        });
+    }
+    warnings
+}
+
+/**
+ * Rules applied to every post, wall specific rules are appended to this list
+ */
+pub fn post_validators() -> Vec<&'static dyn Validator> {
+    use rules::*;
+
+    vec![
+        &SchemaValidator,
+        #[cfg(feature = "encryption")]
+        &PrivatePostValidator,
+        &ProofOfWorkValidator,
+        &TimestampValidator,
+        &HumanityValidator,
+        &LinkValidator,
+        &LengthValidator,
+        &ProfanityValidator,
+        &SpamValidator,
+        &DuplicateValidator,
+        &ClassifierValidator,
+        &LinkCountValidator,
+        &KarmaValidator,
+        &MediaHintValidator,
+        &LicenseValidator,
+        &GeoValidator,
+        &TemplateValidator,
+        &CategoryValidator,
+        &QuoteValidator,
+        &ReplyOnlyValidator,
+        &ReplyValidator,
+        &EmbedNftValidator,
+        &TokenBoundValidator,
+        &BountyValidator,
+        &EventValidator,
+        &CodeValidator,
+        &ArticleValidator,
+        &StickerValidator,
+        &AttachmentValidator,
+        &QuotaValidator,
+    ]
+}
+
+/**
+ * Hard cap checked before any parsing so oversized garbage never costs a full JSON parse
+ */
+pub fn check_payload_size(data: &str) -> Result<(), ValidationError> {
+    if data.len() > DEFAULT_MAX_PAYLOAD_BYTES {
+        return Err(ValidationError {
+            code: "payload_too_large",
+            message: format!(
+                "Data is {} bytes, the limit is {} bytes.",
+                data.len(),
+                DEFAULT_MAX_PAYLOAD_BYTES
+            ),
+        });
+    }
+    Ok(())
+}
+
+/**
+ * Checks a payload against the embedded schema of `method` for the payload's `schema_version`,
+ * the error names the schema path of the first violation
+ */
+pub fn check_schema(method: &str, payload: &Value) -> Result<(), ValidationError> {
+    let version = payload["schema_version"].as_u64().unwrap_or(SCHEMA_VERSION);
+    let schema = schema::method_schema(method, version).ok_or_else(|| ValidationError {
+        code: "schema_version",
+        message: format!("schema_version {} is not supported.", version),
+    })?;
+    match jsonschema::first_violation(schema, payload) {
+        Some(violation) => Err(ValidationError {
+            code: "schema",
+            message: violation.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Rejects top-level fields the schema of `method` doesn't declare, for payloads whose schema
+ * stays open because `extra` fields (a template's) are only known at run time
+ */
+pub fn check_fields(
+    method: &str,
+    payload: &Value,
+    extra: &[String],
+) -> Result<(), ValidationError> {
+    let (Some(schema), Value::Object(fields)) =
+        (schema::method_schema(method, SCHEMA_VERSION), payload)
+    else {
+        return Ok(());
+    };
+    let allowed: Vec<&str> = schema["properties"]
+        .as_object()
+        .into_iter()
+        .flat_map(|properties| properties.keys())
+        .chain(extra)
+        .map(String::as_str)
+        .collect();
+    match jsonschema::unknown_field(fields, &allowed, "", "") {
+        Some(violation) => Err(ValidationError {
+            code: "unknown_field",
+            message: violation.message,
+        }),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Schema check of a non-post method's data, data that is not JSON is left to the handler
+ */
+pub fn check_method_schema(method: &str, data: &str) -> Result<(), ValidationError> {
+    match serde_json::from_str::<Value>(data) {
+        Ok(payload) => check_schema(method, &payload),
+        Err(_) => Ok(()),
+    }
+}
+
+/**
+ * Data sent in a compact encoding rather than as JSON text, the post is then stored as the
+ * decoded JSON
+ */
+pub fn is_encoded(data: &str) -> bool {
+    data.starts_with(CBOR_PREFIX)
+        || serde_json::from_str::<Value>(data).is_ok_and(|payload| {
+            payload.get("content_encoding").is_some() || payload.get("encoding").is_some()
+        })
+}
+
+/**
+ * The base64 body of gzip transaction data, for walls that store what was sent
+ */
+pub fn gzip_body(data: &str) -> Option<String> {
+    let payload: Value = serde_json::from_str(data).ok()?;
+    if payload["encoding"].as_str() != Some(GZIP_ENCODING) {
+        return None;
+    }
+    payload["data"].as_str().map(str::to_string)
+}
+
+/**
+ * Parses the raw transaction data, the pipeline only ever sees JSON objects. `cbor:<base64>`
+ * data, or a `{"content_encoding": "cbor", "data": "<base64>"}` wrapper, decodes to the same,
+ * and so does a gzip or brotli compressed JSON body (`{"encoding": "gzip+base64", "data":
+ * "<base64>"}`, `br+base64`). Only gzip bodies can be stored compressed, brotli ones are always
+ * stored decompressed.
+ */
+pub fn parse_payload(data: &str) -> Result<Value, ValidationError> {
+    let invalid = |message: &str| ValidationError::new("invalid_format", message);
+
+    let mut payload: Value = match data.strip_prefix(CBOR_PREFIX) {
+        Some(encoded) => decode_cbor_base64(encoded).map_err(invalid)?,
+        None => serde_json::from_str(data).map_err(|_| invalid("Data is not a valid format."))?,
+    };
+    if let Some(encoding) = payload["encoding"]
+        .as_str()
+        .filter(|encoding| *encoding == GZIP_ENCODING || *encoding == BROTLI_ENCODING)
+    {
+        let encoded = payload["data"]
+            .as_str()
+            .ok_or_else(|| invalid("Compressed data is missing."))?;
+        let text = if encoding == GZIP_ENCODING {
+            decode_gzip_base64(encoded, DEFAULT_MAX_DECOMPRESSED_BYTES)
+        } else {
+            decode_brotli_base64(encoded, DEFAULT_MAX_DECOMPRESSED_BYTES)
+        }
+        .map_err(invalid)?;
+        payload = serde_json::from_str(&text)
+            .map_err(|_| invalid("Compressed data is not valid JSON."))?;
+    }
+    if payload["content_encoding"].as_str() == Some("cbor") {
+        let encoded = payload["data"]
+            .as_str()
+            .ok_or_else(|| invalid("CBOR data is missing."))?;
+        payload = decode_cbor_base64(encoded).map_err(invalid)?;
+    }
+
+    if !payload.is_object() {
+        return Err(ValidationError::new(
+            "schema",
+            "Data does not follow the required JSON schema.",
+        ));
+    }
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Reject(&'static str);
+
+    impl Validator for Reject {
+        fn validate(&self, _ctx: &PostContext) -> Result<(), ValidationError> {
+            Err(ValidationError::new(self.0, "rejected"))
+        }
+    }
+
+    #[test]
+    fn parse_payload_rejects_invalid_json_and_non_objects() {
+        assert_eq!(parse_payload("{").unwrap_err().code, "invalid_format");
+        assert_eq!(parse_payload("[1, 2]").unwrap_err().code, "schema");
+        assert!(parse_payload(r#"{"text": "hi"}"#).is_ok());
+    }
+
+    #[test]
+    fn cbor_payloads_decode_to_json() {
+        // {"text": "hi"}
+        let expected = serde_json::json!({ "text": "hi" });
+        assert_eq!(parse_payload("cbor:oWR0ZXh0Ymhp").unwrap(), expected);
+        assert_eq!(
+            parse_payload(r#"{"content_encoding": "cbor", "data": "oWR0ZXh0Ymhp"}"#).unwrap(),
+            expected
+        );
+        assert!(is_encoded("cbor:oWR0ZXh0Ymhp"));
+        assert!(!is_encoded(r#"{"text": "hi"}"#));
+        assert_eq!(
+            parse_payload("cbor:oWR0").unwrap_err().code,
+            "invalid_format"
+        );
+        // [1] is valid CBOR but not an object
+        assert_eq!(parse_payload("cbor:gQE=").unwrap_err().code, "schema");
+    }
+
+    #[test]
+    fn gzip_bodies_are_inflated() {
+        let data = r#"{"encoding": "gzip+base64", "data": "H4sIAAAAAAACA6tWKkmtKFGyUlBKz1VAQkq1AFlqpwsaAAAA"}"#;
+        assert_eq!(
+            parse_payload(data).unwrap(),
+            serde_json::json!({ "text": "gm gm gm gm gm" })
+        );
+        assert!(is_encoded(data));
+
+        // 300000 zero bytes, far past the decompressed size limit
+        let bomb = format!(
+            r#"{{"encoding": "gzip+base64", "data": "H4sIAAAAAAACA+3BAQ0AAADCoPdPbQ8HFA{}/Bj74rL24JMEAA=="}}"#,
+            "A".repeat(386)
+        );
+        assert_eq!(parse_payload(&bomb).unwrap_err().code, "invalid_format");
+        assert!(parse_payload(r#"{"encoding": "br+base64", "data": ""}"#).is_err());
+    }
+
+    #[test]
+    fn brotli_bodies_are_decompressed() {
+        let data =
+            r#"{"encoding": "br+base64", "data": "GxkA+I3EOCbxQHciShGQvdY5a+tTksBTBIhgj1gY"}"#;
+        assert_eq!(
+            parse_payload(data).unwrap(),
+            serde_json::json!({ "text": "gm gm gm gm gm" })
+        );
+    }
+
+    #[test]
+    fn oversized_payloads_are_rejected_before_parsing() {
+        let data = "x".repeat(DEFAULT_MAX_PAYLOAD_BYTES + 1);
+        assert_eq!(
+            check_payload_size(&data).unwrap_err().code,
+            "payload_too_large"
+        );
+        assert!(check_payload_size("{}").is_ok());
+    }
+
+    #[test]
+    fn pipeline_stops_at_first_rejection() {
+        let transaction = Transaction::default();
+        let config = WallConfig::default();
+        let payload = parse_payload(r#"{"text": "hi"}"#).unwrap();
+        let ctx = PostContext {
+            transaction: &transaction,
+            metadatas: &[],
+            wall: "",
+            config: &config,
+            filter: &WordFilter::Global,
+            payload: &payload,
+        };
+
+        let err = run_pipeline(
+            &[&rules::SchemaValidator, &Reject("first"), &Reject("second")],
+            &ctx,
+        )
+        .unwrap_err();
+        assert_eq!(err.code, "first");
+        assert_eq!(err.to_string(), "[first] rejected");
+    }
+}
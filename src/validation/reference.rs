@@ -0,0 +1,19 @@
+/**
+ * Lineage data keys are 32 byte hashes in hex
+ */
+pub fn is_data_key(data_key: &str) -> bool {
+    let key = data_key.strip_prefix("0x").unwrap_or(data_key);
+    key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_keys() {
+        assert!(is_data_key(&"ab".repeat(32)));
+        assert!(is_data_key(&format!("0x{}", "AB".repeat(32))));
+        assert!(!is_data_key("abc"));
+    }
+}
@@ -0,0 +1,1753 @@
+use super::attestation::{check_credential, check_verdict, Credential, Verdict};
+use super::embed::{check_reference, check_token_bound, held_by_author};
+use super::geo::normalize_geo;
+use super::image::check_image;
+use super::license::is_license;
+use super::markdown::check_markdown;
+use super::media::{is_blurhash, is_cid};
+use super::{check_fields, check_schema, parse_payload, PostContext, ValidationError, Validator};
+use crate::crypto::{keccak_hex, pow};
+#[cfg(feature = "encryption")]
+use crate::data::EncryptionEnvelope;
+use crate::data::{
+    ArticleSection, Attachment, Dimensions, NftEmbed, ProfanityAction, Quote, SpamAction, Sticker,
+    TokenBound, WallMode,
+};
+use crate::defaults::{
+    DEFAULT_CODE_LANGUAGES, DEFAULT_MAX_ARTICLE_LENGTH, DEFAULT_MAX_ARTICLE_SECTIONS,
+    DEFAULT_MAX_ARTICLE_TITLE_LENGTH, DEFAULT_MAX_CODE_LENGTH, DEFAULT_MAX_SECTION_LENGTH,
+    DEFAULT_TIMESTAMP_FIELDS, TIMEZONE_PATTERN,
+};
+#[cfg(feature = "encryption")]
+use crate::defaults::{DEFAULT_ENCRYPTION_ALGORITHMS, DEFAULT_MAX_RECIPIENTS};
+use crate::metadata::fingerprints::load_fingerprints;
+use crate::metadata::karma::{Karma, ReactionFilter};
+use crate::metadata::quota::posts_today;
+use crate::metadata::{parse_iso8601, timestamp_sec};
+use crate::moderation::simhash::simhash;
+use crate::moderation::spam::{is_emoji_only, link_count, post_link_count, spam_score};
+use crate::storage::alias::Alias;
+use crate::storage::blocklist::load_block_list;
+use crate::storage::stickers::load_sticker_registry;
+use crate::storage::{find_post, read_content};
+use crate::types::Metadata;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub struct SchemaValidator;
+
+impl Validator for SchemaValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let template_fields = ctx
+            .str_field("template")
+            .and_then(|name| ctx.config.templates.get(name))
+            .map(|template| template.required.as_slice())
+            .unwrap_or_default();
+        check_fields("post", ctx.payload, template_fields)?;
+
+        let kind = match ctx.str_field("visibility") {
+            Some("private") => "private",
+            _ => ctx.str_field("type").unwrap_or("post"),
+        };
+        if let Some(group) = ctx.config.required_fields(kind).into_iter().find(|group| {
+            group
+                .iter()
+                .all(|field| ctx.payload[field.as_str()].is_null())
+        }) {
+            let subject = match kind {
+                "post" => "A post".to_string(),
+                kind if kind.starts_with(['a', 'e', 'i', 'o', 'u']) => format!("An {} post", kind),
+                kind => format!("A {} post", kind),
+            };
+            return Err(ValidationError {
+                code: "required",
+                message: format!("{} needs {}.", subject, group.join(" or ")),
+            });
+        }
+        check_schema("post", ctx.payload)
+    }
+}
+
+pub struct LinkValidator;
+
+impl Validator for LinkValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        match ctx.str_field("image").filter(|image| !image.is_empty()) {
+            Some(image) => check_image(image, ctx.str_field("image_mime"), &ctx.config.image_types)
+                .map_err(|e| ValidationError::new("image_link", e)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct LengthValidator;
+
+impl Validator for LengthValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let max = ctx.config.rules.max_text_length;
+        match ctx.str_field("text") {
+            Some(text) if text.chars().count() > max => Err(ValidationError {
+                code: "text_length",
+                message: format!("Text exceeds {} characters.", max),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * Only rejects when the wall asks for it, masking walls get the text bleeped at storage time
+ */
+pub struct ProfanityValidator;
+
+impl Validator for ProfanityValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if ctx.config.rules.profanity_action == ProfanityAction::Mask || ctx.is_trusted() {
+            return Ok(());
+        }
+
+        match ctx.str_field("text") {
+            Some(text) if ctx.filter.is_profane(text) => Err(ValidationError::new(
+                "profanity",
+                "Profanity found in the text.",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * Optional blurhash / thumbnail placeholders supplied by the client
+ */
+pub struct MediaHintValidator;
+
+impl Validator for MediaHintValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if let Some(blurhash) = ctx.str_field("blurhash") {
+            if !is_blurhash(blurhash) {
+                return Err(ValidationError::new("blurhash", "Invalid blurhash."));
+            }
+        }
+
+        if let Some(thumbnail) = ctx.str_field("thumbnail") {
+            if !is_cid(thumbnail) {
+                return Err(ValidationError::new("thumbnail", "Invalid thumbnail CID."));
+            }
+        }
+
+        let (size, dimensions) = media_hints(ctx.payload)
+            .map_err(|_| ValidationError::new("media_hint", "Invalid media size hints."))?;
+        if size.is_none() && dimensions.is_none() {
+            return Ok(());
+        }
+        if ctx.str_field("image").is_none_or(str::is_empty) {
+            return Err(ValidationError::new(
+                "media_hint",
+                "Media size hints describe the post image.",
+            ));
+        }
+        let rules = &ctx.config.media;
+        if size.is_some_and(|size| size == 0 || size > rules.max_size_bytes) {
+            return Err(ValidationError {
+                code: "media_size",
+                message: format!("Image size must be 1 to {} bytes.", rules.max_size_bytes),
+            });
+        }
+        if dimensions.is_some_and(|d| {
+            d.width == 0
+                || d.height == 0
+                || d.width > rules.max_width
+                || d.height > rules.max_height
+        }) {
+            return Err(ValidationError {
+                code: "media_dimensions",
+                message: format!(
+                    "Image dimensions are limited to {}x{}.",
+                    rules.max_width, rules.max_height
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/**
+ * The `size_bytes` and `dimensions` a post declares for its image, either may be absent
+ */
+pub fn media_hints(
+    payload: &serde_json::Value,
+) -> Result<(Option<u64>, Option<Dimensions>), serde_json::Error> {
+    let field = |name: &str| payload.get(name).cloned().unwrap_or_default();
+    Ok((
+        serde_json::from_value(field("size_bytes"))?,
+        serde_json::from_value(field("dimensions"))?,
+    ))
+}
+
+/**
+ * Link-stuffed posts are spam and break previews, walls tagging them instead get the count
+ * stored with the post
+ */
+pub struct LinkCountValidator;
+
+impl Validator for LinkCountValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let rules = &ctx.config.rules;
+        if rules.link_action != SpamAction::Reject || rules.max_links == 0 {
+            return Ok(());
+        }
+        let count = post_link_count(ctx.payload);
+        if count > rules.max_links {
+            return Err(ValidationError {
+                code: "too_many_links",
+                message: format!(
+                    "A post may carry {} links, this one has {}.",
+                    rules.max_links, count
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Heuristic spam score, walls tagging spam instead get the score stored with the post and
+ * borderline posts are stored with limited visibility
+ */
+pub struct SpamValidator;
+
+impl Validator for SpamValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let rules = &ctx.config.rules.spam;
+        if rules.action != SpamAction::Reject || ctx.is_trusted() {
+            return Ok(());
+        }
+
+        match ctx.str_field("text") {
+            Some(text) => {
+                let score = spam_score(text, rules);
+                if rules.is_spam(score) && !rules.is_borderline(score) {
+                    return Err(ValidationError::new("spam", "Post looks like spam."));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * Near-duplicates of the wall's recent posts, caught by simhash so changing an emoji or a
+ * word doesn't get the same spam through
+ */
+pub struct DuplicateValidator;
+
+impl Validator for DuplicateValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let rules = &ctx.config.rules.duplicates;
+        if rules.window == 0 || rules.action != SpamAction::Reject || ctx.is_trusted() {
+            return Ok(());
+        }
+
+        match ctx.str_field("text").and_then(simhash) {
+            Some(hash)
+                if load_fingerprints(ctx.metadatas, ctx.wall).is_near_duplicate(hash, rules) =>
+            {
+                Err(ValidationError::new(
+                    "duplicate",
+                    "Post is a near-duplicate of a recent post.",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * Permissionless spam deterrent for anonymous walls, the client pays in hashes
+ */
+pub struct ProofOfWorkValidator;
+
+impl Validator for ProofOfWorkValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let difficulty = ctx.config.rules.pow_difficulty;
+        if difficulty == 0 {
+            return Ok(());
+        }
+
+        // the client worked on what it sent, not on the migrated or stripped payload
+        let sent = parse_payload(&ctx.transaction.data)?;
+        match sent["nonce"].as_str() {
+            Some(nonce)
+                if pow::verify(
+                    &ctx.transaction.data_key,
+                    &ctx.transaction.public_key,
+                    &pow::work_content(&sent),
+                    nonce,
+                    difficulty,
+                ) =>
+            {
+                Ok(())
+            }
+            Some(_) => Err(ValidationError::new("pow", "Proof of work is not valid.")),
+            None => Err(ValidationError::new(
+                "pow",
+                "Proof of work nonce is missing.",
+            )),
+        }
+    }
+}
+
+/**
+ * Links in the text are a privilege of keys with enough karma on this wall
+ */
+pub struct KarmaValidator;
+
+impl Validator for KarmaValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let min = ctx.config.karma.min_for_links;
+        if min == 0 {
+            return Ok(());
+        }
+
+        match ctx.str_field("text") {
+            Some(text) if link_count(text) > 0 => {
+                let now = timestamp_sec(ctx.transaction.timestamp);
+                let reactions = ReactionFilter::new(ctx.metadatas, &ctx.config.karma, now);
+                let karma = Karma::of(ctx.metadatas, &ctx.transaction.public_key, &reactions).karma;
+                if karma < min {
+                    return Err(ValidationError {
+                        code: "karma",
+                        message: format!(
+                            "Posting links requires {} karma, you have {}.",
+                            min, karma
+                        ),
+                    });
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * A quoted post must be a post on this wall by the named author, whose content still hashes to
+ * the referenced content_hash
+ */
+pub struct QuoteValidator;
+
+impl Validator for QuoteValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let quote = match ctx.payload.get("quote") {
+            Some(quote) => quote,
+            None => return Ok(()),
+        };
+        let invalid = |message: &str| Err(ValidationError::new("quote", message));
+
+        let quote: Quote = match serde_json::from_value(quote.clone()) {
+            Ok(quote) => quote,
+            Err(_) => return invalid("Quote needs cid, author and content_hash."),
+        };
+
+        let quoted = ctx
+            .metadatas
+            .iter()
+            .find(|m| m.alias == Alias::Post && m.cid == quote.cid);
+        let quoted = match quoted {
+            Some(quoted) if quoted.public_key.eq_ignore_ascii_case(&quote.author) => quoted,
+            Some(_) => return invalid("Quoted post has a different author."),
+            None => return invalid("Quoted post is not on this wall."),
+        };
+
+        check_pinned(
+            quoted,
+            "Quoted",
+            Some(&quote.content_hash),
+            quote.version.as_deref(),
+        )
+        .map_err(|message| ValidationError {
+            code: "quote",
+            message,
+        })
+    }
+}
+
+/**
+ * A referenced post must still be what the reference pinned, its content hashing to `hash` and
+ * its record at `version`, whichever of the two the reference names
+ */
+fn check_pinned(
+    post: &Metadata,
+    noun: &str,
+    hash: Option<&str>,
+    version: Option<&str>,
+) -> Result<(), String> {
+    if version.is_some_and(|version| version != post.version) {
+        return Err(format!("{} post is at another version.", noun));
+    }
+    let Some(hash) = hash else {
+        return Ok(());
+    };
+    match read_content(post) {
+        Some(content) if keccak_hex(content.as_bytes()).eq_ignore_ascii_case(hash) => Ok(()),
+        Some(_) => Err(format!("{} content does not match its hash.", noun)),
+        None => Err(format!("{} post can't be read.", noun)),
+    }
+}
+
+/**
+ * `license` is optional, when set it must be a known identifier or a license URI
+ */
+pub struct LicenseValidator;
+
+impl Validator for LicenseValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        match ctx.payload.get("license") {
+            None => Ok(()),
+            Some(license) if license.as_str().is_some_and(is_license) => Ok(()),
+            Some(_) => Err(ValidationError::new(
+                "license",
+                "License must be CC0, CC-BY, CC-BY-SA, CC-BY-NC, ARR or a license URI.",
+            )),
+        }
+    }
+}
+
+/**
+ * `category` is optional, when set it must be one of the wall's boards
+ */
+pub struct CategoryValidator;
+
+impl Validator for CategoryValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        match ctx.payload.get("category") {
+            None => Ok(()),
+            Some(category)
+                if category
+                    .as_str()
+                    .is_some_and(|c| ctx.config.boards.iter().any(|b| b == c)) =>
+            {
+                Ok(())
+            }
+            Some(_) => Err(ValidationError::new(
+                "category",
+                "Category is not a board of this wall.",
+            )),
+        }
+    }
+}
+
+/**
+ * A post naming a `template` must use one the wall has and carry all of its required fields
+ */
+pub struct TemplateValidator;
+
+impl Validator for TemplateValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let name = match ctx.payload.get("template") {
+            None => return Ok(()),
+            Some(name) => name.as_str().unwrap_or_default(),
+        };
+        let template = match ctx.config.templates.get(name) {
+            Some(template) => template,
+            None => {
+                return Err(ValidationError::new(
+                    "template",
+                    "Template does not exist on this wall.",
+                ))
+            }
+        };
+        match template
+            .required
+            .iter()
+            .find(|field| ctx.payload[field.as_str()].is_null())
+        {
+            Some(field) => Err(ValidationError {
+                code: "template",
+                message: format!("Template {} requires the {} field.", name, field),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/**
+ * `geo` is optional, a place name or coordinates in range
+ */
+pub struct GeoValidator;
+
+impl Validator for GeoValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        match ctx.payload.get("geo").map(normalize_geo) {
+            None | Some(Ok(_)) => Ok(()),
+            Some(Err(message)) => Err(ValidationError::new("geo", message)),
+        }
+    }
+}
+
+/**
+ * On `reply_only` walls only owners and moderators start threads
+ */
+pub struct ReplyOnlyValidator;
+
+impl Validator for ReplyOnlyValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if ctx.config.mode == WallMode::ReplyOnly
+            && ctx.str_field("reply_to").is_none()
+            && !ctx.config.is_moderator(&ctx.transaction.public_key)
+        {
+            return Err(ValidationError::new(
+                "reply_only",
+                "Only the owner and moderators can post here, replies are open.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/**
+ * `reply_to` must be a post on this wall, still matching the `reply_hash` and `reply_version`
+ * the reply pins, and neither the replied nor the quoted author may have blocked the sender
+ */
+pub struct ReplyValidator;
+
+impl Validator for ReplyValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let mut targets = Vec::new();
+        let hash = ctx.str_field("reply_hash");
+        let version = ctx.str_field("reply_version");
+        match ctx.str_field("reply_to") {
+            Some(cid) => match find_post(ctx.metadatas, cid) {
+                Some(post) => {
+                    check_pinned(post, "Replied", hash, version).map_err(|message| {
+                        ValidationError {
+                            code: "reply",
+                            message,
+                        }
+                    })?;
+                    targets.push(post);
+                }
+                None => {
+                    return Err(ValidationError::new(
+                        "reply",
+                        "Replied post is not on this wall.",
+                    ))
+                }
+            },
+            None if hash.is_some() || version.is_some() => {
+                return Err(ValidationError::new(
+                    "reply",
+                    "reply_hash and reply_version need a reply_to.",
+                ))
+            }
+            None => {}
+        }
+        if let Some(post) = ctx.payload["quote"]["cid"]
+            .as_str()
+            .and_then(|cid| find_post(ctx.metadatas, cid))
+        {
+            targets.push(post);
+        }
+
+        let sender = &ctx.transaction.public_key;
+        if targets
+            .iter()
+            .any(|post| load_block_list(ctx.metadatas, &post.public_key).is_blocked(sender))
+        {
+            return Err(ValidationError::new(
+                "blocked",
+                "The author has blocked replies from this key.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/**
+ * `embed_nft` must point at a well-formed token. An attestation is optional but, when present,
+ * has to be a valid signature by one of the wall's attesters.
+ */
+pub struct EmbedNftValidator;
+
+impl Validator for EmbedNftValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let embed = match ctx.payload.get("embed_nft") {
+            Some(embed) => embed,
+            None => return Ok(()),
+        };
+
+        let embed: NftEmbed = serde_json::from_value(embed.clone()).map_err(|_| {
+            ValidationError::new(
+                "embed_nft",
+                "Embedded NFT needs chain_id, contract and token_id.",
+            )
+        })?;
+        check_reference(&embed).map_err(|message| ValidationError::new("embed_nft", message))?;
+
+        if embed.attestation.is_some()
+            && !held_by_author(&embed, &ctx.transaction.public_key, ctx.config)
+        {
+            return Err(ValidationError::new(
+                "embed_nft",
+                "Ownership attestation is not valid.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/**
+ * `token_bound` posts are attributed to an NFT, only with a complete signature chain
+ */
+pub struct TokenBoundValidator;
+
+impl Validator for TokenBoundValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let bound = match ctx.payload.get("token_bound") {
+            Some(bound) => bound,
+            None => return Ok(()),
+        };
+
+        let bound: TokenBound = serde_json::from_value(bound.clone()).map_err(|_| {
+            ValidationError::new(
+                "token_bound",
+                "Token-bound posts need chain_id, contract, token_id, account and signature.",
+            )
+        })?;
+        check_token_bound(
+            &bound,
+            &ctx.transaction.public_key,
+            &ctx.transaction.data_key,
+            ctx.config,
+        )
+        .map_err(|message| ValidationError::new("token_bound", message))
+    }
+}
+
+/**
+ * `type: "bounty"` posts need a reward and a deadline that hasn't passed yet
+ */
+pub struct BountyValidator;
+
+impl Validator for BountyValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if ctx.str_field("type") != Some("bounty") {
+            return Ok(());
+        }
+        let invalid = |message: &str| Err(ValidationError::new("bounty", message));
+
+        let reward = &ctx.payload["reward"];
+        let amount = reward["amount"].as_str().unwrap_or("");
+        if amount.is_empty() || !amount.chars().all(|c| c.is_ascii_digit()) {
+            return invalid("Bounty reward amount must be an integer in base units.");
+        }
+        if reward["token"].as_str().unwrap_or("").is_empty() {
+            return invalid("Bounty reward token is missing.");
+        }
+
+        match ctx.payload["deadline"].as_u64() {
+            Some(deadline) if deadline > timestamp_sec(ctx.transaction.timestamp) => Ok(()),
+            Some(_) => invalid("Bounty deadline is in the past."),
+            None => invalid("Bounty deadline is missing."),
+        }
+    }
+}
+
+/**
+ * `type: "event"` posts carry a schedule: start before end, a timezone, and a location or url
+ */
+pub struct EventValidator;
+
+impl Validator for EventValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if ctx.str_field("type") != Some("event") {
+            return Ok(());
+        }
+        let invalid = |message: &str| Err(ValidationError::new("event", message));
+
+        let (start, end) = match (ctx.payload["start"].as_u64(), ctx.payload["end"].as_u64()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return invalid("Event start and end timestamps are required."),
+        };
+        if start == 0 || end < start {
+            return invalid("Event must end after it starts.");
+        }
+
+        let timezone = ctx
+            .str_field("timezone")
+            .or(ctx.config.locale.timezone.as_deref());
+        match timezone {
+            Some(timezone) if is_timezone(timezone) => {}
+            _ => return invalid("Event timezone is not valid."),
+        }
+
+        let location = ctx.str_field("location").unwrap_or("").trim();
+        let url = ctx.str_field("url").unwrap_or("");
+        if location.is_empty() && url.is_empty() {
+            return invalid("Event needs a location or an url.");
+        }
+        if !url.is_empty() && !url.starts_with("https://") {
+            return invalid("Event url must be https.");
+        }
+        Ok(())
+    }
+}
+
+fn is_timezone(timezone: &str) -> bool {
+    static TIMEZONE: Lazy<Regex> = Lazy::new(|| Regex::new(TIMEZONE_PATTERN).unwrap());
+    TIMEZONE.is_match(timezone)
+}
+
+/**
+ * `type: "article"` posts: a title, an optional cover CID and markdown sections, with limits of
+ * their own. Sections get the profanity check `text` gets, masking walls mask them at storage.
+ */
+pub struct ArticleValidator;
+
+impl Validator for ArticleValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if ctx.str_field("type") != Some("article") {
+            return Ok(());
+        }
+        let invalid = |message: String| {
+            Err(ValidationError {
+                code: "article",
+                message,
+            })
+        };
+
+        let title = ctx.str_field("title").unwrap_or("").trim();
+        if title.is_empty() || title.chars().count() > DEFAULT_MAX_ARTICLE_TITLE_LENGTH {
+            return invalid(format!(
+                "Article title must be 1 to {} characters.",
+                DEFAULT_MAX_ARTICLE_TITLE_LENGTH
+            ));
+        }
+        if let Some(cover) = ctx.str_field("cover") {
+            if !is_cid(cover) {
+                return invalid("Article cover is not a CID.".to_string());
+            }
+        }
+
+        let Ok(sections) =
+            serde_json::from_value::<Vec<ArticleSection>>(ctx.payload["sections"].clone())
+        else {
+            return invalid("Article sections must each have a body.".to_string());
+        };
+        if sections.is_empty() || sections.len() > DEFAULT_MAX_ARTICLE_SECTIONS {
+            return invalid(format!(
+                "An article has 1 to {} sections.",
+                DEFAULT_MAX_ARTICLE_SECTIONS
+            ));
+        }
+        let mut total = 0;
+        for (i, section) in sections.iter().enumerate() {
+            let len = section.body.chars().count();
+            if section.body.trim().is_empty() || len > DEFAULT_MAX_SECTION_LENGTH {
+                return invalid(format!(
+                    "Section {} must be 1 to {} characters.",
+                    i + 1,
+                    DEFAULT_MAX_SECTION_LENGTH
+                ));
+            }
+            if let Err(e) = check_markdown(&section.body) {
+                return invalid(format!("Section {}: {}", i + 1, e));
+            }
+            total += len;
+        }
+        if total > DEFAULT_MAX_ARTICLE_LENGTH {
+            return invalid(format!(
+                "Article exceeds {} characters.",
+                DEFAULT_MAX_ARTICLE_LENGTH
+            ));
+        }
+
+        let checked =
+            ctx.config.rules.profanity_action != ProfanityAction::Mask && !ctx.is_trusted();
+        if checked
+            && std::iter::once(title)
+                .chain(sections.iter().filter_map(|s| s.heading.as_deref()))
+                .chain(sections.iter().map(|s| s.body.as_str()))
+                .any(|text| ctx.filter.is_profane(text))
+        {
+            return Err(ValidationError::new(
+                "profanity",
+                "Profanity found in the article.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/**
+ * `type: "sticker"` posts show one sticker of a pack the wall registered, and no other media.
+ * Their text, if any, is emoji only.
+ */
+pub struct StickerValidator;
+
+impl Validator for StickerValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let invalid = |message: &str| Err(ValidationError::new("sticker", message));
+        if ctx.str_field("type") != Some("sticker") {
+            return match ctx.payload.get("sticker") {
+                Some(_) => invalid("Only sticker posts carry a sticker."),
+                None => Ok(()),
+            };
+        }
+
+        let Ok(sticker) = serde_json::from_value::<Sticker>(ctx.payload["sticker"].clone()) else {
+            return invalid("Sticker must name a pack and a cid.");
+        };
+        let registry = load_sticker_registry(ctx.metadatas, ctx.wall);
+        match registry.packs.get(&sticker.pack) {
+            Some(pack) if pack.retired_at.is_some() => {
+                return invalid("Sticker pack is retired.");
+            }
+            Some(pack) if pack.stickers.contains(&sticker.cid) => {}
+            Some(_) => return invalid("Sticker is not in that pack."),
+            None => return invalid("Sticker pack is not registered on this wall."),
+        }
+        if ["image", "attachments", "embed_nft"]
+            .iter()
+            .any(|field| !ctx.payload[*field].is_null())
+        {
+            return invalid("Sticker posts carry no other media.");
+        }
+        match ctx.str_field("text") {
+            Some(text) if !is_emoji_only(text) => invalid("Sticker post text may only be emoji."),
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * `type: "code"` snippets: a known language and a length cap. The snippet is left alone by the
+ * text rules (profanity, spam) since those only look at `text`.
+ */
+pub struct CodeValidator;
+
+impl Validator for CodeValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if ctx.str_field("type") != Some("code") {
+            return Ok(());
+        }
+
+        match ctx.str_field("language") {
+            Some(language) if DEFAULT_CODE_LANGUAGES.contains(&language) => {}
+            _ => {
+                return Err(ValidationError::new(
+                    "code",
+                    "Code language is not supported.",
+                ))
+            }
+        }
+
+        match ctx.str_field("code") {
+            Some(code) if code.trim().is_empty() => {
+                Err(ValidationError::new("code", "Code snippet is empty."))
+            }
+            Some(code) if code.chars().count() > DEFAULT_MAX_CODE_LENGTH => Err(ValidationError {
+                code: "code",
+                message: format!(
+                    "Code snippet exceeds {} characters.",
+                    DEFAULT_MAX_CODE_LENGTH
+                ),
+            }),
+            Some(_) => Ok(()),
+            None => Err(ValidationError::new("code", "Code snippet is missing.")),
+        }
+    }
+}
+
+/**
+ * `attachments` entries need a CID, a plain file name and a MIME type the wall accepts, within
+ * that type's size cap
+ */
+pub struct AttachmentValidator;
+
+impl Validator for AttachmentValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let attachments = match ctx.payload.get("attachments") {
+            Some(attachments) => attachments,
+            None => return Ok(()),
+        };
+        let invalid = |message: String| {
+            Err(ValidationError {
+                code: "attachment",
+                message,
+            })
+        };
+
+        let attachments: Vec<Attachment> = match serde_json::from_value(attachments.clone()) {
+            Ok(attachments) => attachments,
+            Err(_) => return invalid("Attachments need cid, filename, mime and size.".to_string()),
+        };
+        let rules = &ctx.config.attachments;
+        if attachments.len() > rules.max_count {
+            return invalid(format!("At most {} attachments per post.", rules.max_count));
+        }
+
+        for attachment in &attachments {
+            if !is_cid(&attachment.cid) {
+                return invalid(format!("{}: invalid CID.", attachment.filename));
+            }
+            let name = attachment.filename.trim();
+            if name.is_empty() || name.len() > 255 || name.contains(['/', '\\']) {
+                return invalid("Attachment file name is not valid.".to_string());
+            }
+            match rules.max_size(&attachment.mime) {
+                None => return invalid(format!("{}: {} is not accepted.", name, attachment.mime)),
+                Some(max) if attachment.size == 0 || attachment.size > max => {
+                    return invalid(format!("{}: size must be 1 to {} bytes.", name, max))
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * `visibility: "private"` posts: `content` is ciphertext the contract can't read, so only the
+ * envelope structure is checked. Plain `text` next to it is still subject to the text rules.
+ * Builds without `encryption` leave private posts out of the schema instead.
+ */
+#[cfg(feature = "encryption")]
+pub struct PrivatePostValidator;
+
+#[cfg(feature = "encryption")]
+impl Validator for PrivatePostValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        match ctx.str_field("visibility") {
+            None | Some("public") => return Ok(()),
+            Some("private") if !ctx.config.has_feature("encryption") => {
+                return Err(ValidationError::new(
+                    "private",
+                    "Private posts are off on this wall.",
+                ))
+            }
+            Some("private") => {}
+            Some(_) => {
+                return Err(ValidationError::new(
+                    "private",
+                    "Visibility must be public or private.",
+                ))
+            }
+        }
+        let invalid = |message: &str| Err(ValidationError::new("private", message));
+
+        if ctx.str_field("content").is_none_or(str::is_empty) {
+            return invalid("Private post content is missing.");
+        }
+
+        let envelope: EncryptionEnvelope =
+            match serde_json::from_value(ctx.payload["encryption"].clone()) {
+                Ok(envelope) => envelope,
+                Err(_) => return invalid("Encryption envelope needs algorithm and recipients."),
+            };
+        if !DEFAULT_ENCRYPTION_ALGORITHMS.contains(&envelope.algorithm.as_str()) {
+            return invalid("Encryption algorithm is not supported.");
+        }
+        if envelope.recipients.is_empty() || envelope.recipients.len() > DEFAULT_MAX_RECIPIENTS {
+            return Err(ValidationError {
+                code: "private",
+                message: format!(
+                    "Private posts need 1 to {} recipients.",
+                    DEFAULT_MAX_RECIPIENTS
+                ),
+            });
+        }
+        if envelope
+            .recipients
+            .iter()
+            .any(|r| r.public_key.is_empty() || r.encrypted_key.is_empty())
+        {
+            return invalid("Every recipient needs a public key and an encrypted key.");
+        }
+        Ok(())
+    }
+}
+
+/**
+ * A time the post claims for itself (`timestamp`, `created_at`, unix seconds or milliseconds or
+ * ISO 8601) must be within the wall's window around the transaction time
+ */
+pub struct TimestampValidator;
+
+impl Validator for TimestampValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let now = timestamp_sec(ctx.transaction.timestamp);
+        if now == 0 {
+            return Ok(());
+        }
+        let rules = &ctx.config.rules;
+
+        for field in DEFAULT_TIMESTAMP_FIELDS {
+            let claimed = match ctx.payload.get(*field) {
+                None => continue,
+                Some(value) => value
+                    .as_u64()
+                    .map(timestamp_sec)
+                    .or_else(|| value.as_str().and_then(parse_iso8601)),
+            };
+            let message = match claimed {
+                None => format!("{} is not a timestamp.", field),
+                Some(at) if at > now.saturating_add(rules.max_clock_skew_sec) => {
+                    format!("{} is in the future.", field)
+                }
+                Some(at) if at.saturating_add(rules.max_post_age_sec) < now => {
+                    format!("{} is too old.", field)
+                }
+                Some(_) => continue,
+            };
+            return Err(ValidationError {
+                code: "timestamp",
+                message,
+            });
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Content checks delegated to an off-chain classifier: a `verdict` is verified whenever a post
+ * carries one, and required on walls that ask for it
+ */
+pub struct ClassifierValidator;
+
+impl Validator for ClassifierValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let rules = &ctx.config.classification;
+        let verdict = match ctx.payload.get("verdict") {
+            Some(verdict) => verdict,
+            None if rules.required => {
+                return Err(ValidationError::new(
+                    "verdict",
+                    "This wall needs a classifier verdict on every post.",
+                ))
+            }
+            None => return Ok(()),
+        };
+
+        let verdict: Verdict = serde_json::from_value(verdict.clone()).map_err(|_| {
+            ValidationError::new("verdict", "Verdict needs signer, label and signature.")
+        })?;
+        let text = ctx.str_field("text").unwrap_or_default();
+        let image = ctx.str_field("image").unwrap_or_default();
+        check_verdict(&verdict, text, image, rules).map_err(|message| ValidationError {
+            code: "verdict",
+            message,
+        })
+    }
+}
+
+/**
+ * Sybil gate for walls that require it: a current attestation, signed by a wall attester, that
+ * the poster is human
+ */
+pub struct HumanityValidator;
+
+impl Validator for HumanityValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        if !ctx.config.rules.require_humanity {
+            return Ok(());
+        }
+
+        let credential: Credential = serde_json::from_value(ctx.payload["humanity"].clone())
+            .map_err(|_| {
+                ValidationError::new("humanity", "Proof-of-humanity attestation is missing.")
+            })?;
+        check_credential(
+            "human",
+            &credential,
+            &ctx.transaction.public_key,
+            timestamp_sec(ctx.transaction.timestamp),
+            ctx.config,
+        )
+        .map_err(|message| ValidationError::new("humanity", message))
+    }
+}
+
+/**
+ * Caps posts per key per UTC day, moderators are exempt
+ */
+pub struct QuotaValidator;
+
+impl Validator for QuotaValidator {
+    fn validate(&self, ctx: &PostContext) -> Result<(), ValidationError> {
+        let quota = ctx.config.rules.daily_post_quota;
+        if quota == 0 || ctx.config.is_moderator(&ctx.transaction.public_key) {
+            return Ok(());
+        }
+        if posts_today(ctx.metadatas, ctx.transaction) >= quota {
+            return Err(ValidationError {
+                code: "quota",
+                message: format!("Daily limit of {} posts reached.", quota),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Locale, WallConfig};
+    use crate::moderation::WordFilter;
+    use crate::types::Transaction;
+    use crate::validation::parse_payload;
+
+    fn check(
+        validator: &dyn Validator,
+        data: &str,
+        config: &WallConfig,
+    ) -> Result<(), ValidationError> {
+        let transaction = Transaction {
+            public_key: "0xabc".to_string(),
+            data: data.to_string(),
+            ..Default::default()
+        };
+        let metadatas = vec![Metadata {
+            public_key: "0xabc".to_string(),
+            ..Default::default()
+        }];
+        let payload = parse_payload(data).unwrap();
+        validator.validate(&PostContext {
+            transaction: &transaction,
+            metadatas: &metadatas,
+            wall: "",
+            config,
+            filter: &WordFilter::Global,
+            payload: &payload,
+        })
+    }
+
+    #[test]
+    fn schema_requires_image_or_text() {
+        let config = WallConfig::default();
+        assert_eq!(
+            check(&SchemaValidator, "{}", &config)
+                .unwrap_err()
+                .to_string(),
+            "[required] A post needs text or image."
+        );
+        assert!(check(&SchemaValidator, r#"{"image": ""}"#, &config).is_ok());
+        assert!(check(&SchemaValidator, r#"{"text": "hi"}"#, &config).is_ok());
+        let event = r#"{"type": "event", "start": 1, "end": 2, "timezone": "UTC"}"#;
+        assert_eq!(
+            check(&SchemaValidator, event, &config).unwrap_err().message,
+            "An event post needs location or url."
+        );
+    }
+
+    #[test]
+    fn walls_can_require_fields_of_their_own() {
+        let config: WallConfig = serde_json::from_str(
+            r#"{"required_fields": {"post": [["text"]], "bounty": [], "event": [["url"]]}}"#,
+        )
+        .unwrap();
+        assert!(check(&SchemaValidator, r#"{"image": ""}"#, &config).is_err());
+        let event = r#"{"type": "event", "text": "meetup", "url": "https://w3wall.xyz"}"#;
+        assert!(check(&SchemaValidator, event, &config).is_ok());
+        assert!(config.required_fields("bounty").is_empty());
+        assert_eq!(config.required_fields("code"), [["language"], ["code"]]);
+    }
+
+    #[test]
+    fn link_must_be_nft_storage() {
+        let config = WallConfig::default();
+        let bad = r#"{"image": "https://example.com/a.png"}"#;
+        assert_eq!(
+            check(&LinkValidator, bad, &config).unwrap_err().code,
+            "image_link"
+        );
+        let good = r#"{"image": "https://nftstorage.link/ipfs/bafy/a.png"}"#;
+        assert!(check(&LinkValidator, good, &config).is_ok());
+
+        let declared =
+            r#"{"image": "https://nftstorage.link/ipfs/bafy", "image_mime": "image/svg+xml"}"#;
+        assert!(check(&LinkValidator, declared, &config).is_err());
+        let config: WallConfig =
+            serde_json::from_str(r#"{"image_types": ["image/svg+xml"]}"#).unwrap();
+        assert!(check(&LinkValidator, declared, &config).is_ok());
+        assert!(check(&LinkValidator, good, &config).is_err());
+    }
+
+    #[test]
+    fn length_follows_wall_rules() {
+        let mut config = WallConfig::default();
+        config.rules.max_text_length = 3;
+        assert!(check(&LengthValidator, r#"{"text": "abc"}"#, &config).is_ok());
+        assert_eq!(
+            check(&LengthValidator, r#"{"text": "abcd"}"#, &config)
+                .unwrap_err()
+                .code,
+            "text_length"
+        );
+    }
+
+    #[test]
+    fn media_hints_are_validated() {
+        let config = WallConfig::default();
+        let blurhash = r#"{"blurhash": "LEHV6nWB2yk8pyo0adR*.7kCMdnj"}"#;
+        assert!(check(&MediaHintValidator, blurhash, &config).is_ok());
+        let bad = r#"{"thumbnail": "not-a-cid"}"#;
+        assert_eq!(
+            check(&MediaHintValidator, bad, &config).unwrap_err().code,
+            "thumbnail"
+        );
+
+        let code = |data: &str, config: &WallConfig| {
+            check(&MediaHintValidator, data, config).map_err(|e| e.code)
+        };
+        let sized = r#"{"image": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "size_bytes": 2048, "dimensions": {"width": 1200, "height": 800}}"#;
+        assert_eq!(code(sized, &config), Ok(()));
+        assert_eq!(
+            code(r#"{"text": "gm", "size_bytes": 2048}"#, &config),
+            Err("media_hint")
+        );
+        let absurd = r#"{"image": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "size_bytes": 18446744073709551615}"#;
+        assert_eq!(code(absurd, &config), Err("media_size"));
+        let huge = r#"{"image": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "dimensions": {"width": 100000, "height": 1}}"#;
+        assert_eq!(code(huge, &config), Err("media_dimensions"));
+
+        let mut config = WallConfig::default();
+        config.media.max_width = 1000;
+        assert_eq!(code(sized, &config), Err("media_dimensions"));
+    }
+
+    #[test]
+    fn link_count_follows_wall_rules() {
+        let mut config = WallConfig::default();
+        let five = r#"{"text": "a.b http://a http://b http://c www.d", "url": "https://e"}"#;
+        assert!(check(&LinkCountValidator, five, &config).is_ok());
+        config.rules.max_links = 4;
+        assert_eq!(
+            check(&LinkCountValidator, five, &config)
+                .unwrap_err()
+                .message,
+            "A post may carry 4 links, this one has 5."
+        );
+        config.rules.link_action = SpamAction::Tag;
+        assert!(check(&LinkCountValidator, five, &config).is_ok());
+    }
+
+    #[test]
+    fn spam_is_rejected_unless_tagged_or_disabled() {
+        let mut config = WallConfig::default();
+        let spam = r#"{"text": "CLICK HTTP://A HTTP://B HTTP://C HTTP://D"}"#;
+        assert_eq!(
+            check(&SpamValidator, spam, &config).unwrap_err().code,
+            "spam"
+        );
+        config.rules.spam.limit_margin = 3;
+        assert!(check(&SpamValidator, spam, &config).is_ok());
+        config.rules.spam.limit_margin = 0;
+        config.rules.spam.action = SpamAction::Tag;
+        assert!(check(&SpamValidator, spam, &config).is_ok());
+        config.rules.spam.action = SpamAction::Reject;
+        config.rules.spam.threshold = 0;
+        assert!(check(&SpamValidator, spam, &config).is_ok());
+    }
+
+    #[test]
+    fn claimed_times_stay_near_the_transaction() {
+        let config = WallConfig::default();
+        let transaction = Transaction {
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        };
+        let validate = |data: &str| {
+            TimestampValidator.validate(&PostContext {
+                transaction: &transaction,
+                metadatas: &[],
+                wall: "",
+                config: &config,
+                filter: &WordFilter::new(&[]),
+                payload: &parse_payload(data).unwrap(),
+            })
+        };
+
+        assert!(validate(r#"{"text": "hi"}"#).is_ok());
+        assert!(validate(r#"{"text": "hi", "timestamp": 1700000100}"#).is_ok());
+        assert!(validate(r#"{"text": "hi", "created_at": "2023-11-14T22:10:00Z"}"#).is_ok());
+        assert_eq!(
+            validate(r#"{"text": "hi", "timestamp": 1700001000000}"#)
+                .unwrap_err()
+                .message,
+            "timestamp is in the future."
+        );
+        assert_eq!(
+            validate(r#"{"text": "hi", "created_at": "2020-01-01T00:00:00Z"}"#)
+                .unwrap_err()
+                .message,
+            "created_at is too old."
+        );
+        assert!(validate(r#"{"text": "hi", "timestamp": "yesterday"}"#).is_err());
+    }
+
+    #[test]
+    fn unbounded_windows_do_not_overflow() {
+        let mut config = WallConfig::default();
+        config.rules.max_clock_skew_sec = u64::MAX;
+        config.rules.max_post_age_sec = u64::MAX;
+        let transaction = Transaction {
+            timestamp: 1_700_000_000_000,
+            ..Default::default()
+        };
+        let validate = |data: &str| {
+            TimestampValidator.validate(&PostContext {
+                transaction: &transaction,
+                metadatas: &[],
+                wall: "",
+                config: &config,
+                filter: &WordFilter::new(&[]),
+                payload: &parse_payload(data).unwrap(),
+            })
+        };
+
+        assert!(validate(r#"{"text": "hi", "timestamp": 18446744073709551615}"#).is_ok());
+        assert!(validate(r#"{"text": "hi", "created_at": "1970-01-01T00:00:01Z"}"#).is_ok());
+    }
+
+    #[test]
+    fn pow_is_checked_only_when_required() {
+        let mut config = WallConfig::default();
+        assert!(check(&ProofOfWorkValidator, r#"{"text": "hi"}"#, &config).is_ok());
+
+        config.rules.pow_difficulty = 8;
+        let content = pow::work_content(&serde_json::json!({ "text": "hi" }));
+        let nonce = (0u64..)
+            .map(|n| n.to_string())
+            .find(|n| pow::verify("", "0xabc", &content, n, 8))
+            .unwrap();
+        let data = serde_json::json!({ "text": "hi", "nonce": nonce }).to_string();
+        assert!(check(&ProofOfWorkValidator, &data, &config).is_ok());
+        let other_post = serde_json::json!({ "text": "hi again", "nonce": nonce }).to_string();
+        assert!(check(&ProofOfWorkValidator, &other_post, &config).is_err());
+        assert_eq!(
+            check(&ProofOfWorkValidator, r#"{"text": "hi"}"#, &config)
+                .unwrap_err()
+                .code,
+            "pow"
+        );
+    }
+
+    #[test]
+    fn links_need_karma_when_gated() {
+        let mut config = WallConfig::default();
+        let link = r#"{"text": "see https://example.com"}"#;
+        assert!(check(&KarmaValidator, link, &config).is_ok());
+
+        config.karma.min_for_links = 5;
+        assert_eq!(
+            check(&KarmaValidator, link, &config).unwrap_err().code,
+            "karma"
+        );
+        assert!(check(&KarmaValidator, r#"{"text": "no links"}"#, &config).is_ok());
+    }
+
+    #[test]
+    fn quotes_must_match_the_quoted_content() {
+        let config = WallConfig::default();
+        crate::storage::ipfs::test_store::put("quoted-cid", r#"{"text": "original"}"#);
+        let quoted = Metadata {
+            public_key: "0xauthor".to_string(),
+            cid: "quoted-cid".to_string(),
+            ..Default::default()
+        };
+        let quote = |hash: &str| {
+            serde_json::json!({
+                "text": "so true",
+                "quote": { "cid": "quoted-cid", "author": "0xAuthor", "content_hash": hash }
+            })
+        };
+        let validate = |data: serde_json::Value| {
+            let transaction = Transaction::default();
+            let metadatas = vec![quoted.clone()];
+            let payload = parse_payload(&data.to_string()).unwrap();
+            QuoteValidator.validate(&PostContext {
+                transaction: &transaction,
+                metadatas: &metadatas,
+                wall: "",
+                config: &config,
+                filter: &WordFilter::Global,
+                payload: &payload,
+            })
+        };
+
+        let hash = keccak_hex(br#"{"text": "original"}"#);
+        assert!(validate(quote(&hash)).is_ok());
+        assert_eq!(validate(quote("0x00")).unwrap_err().code, "quote");
+        let mut pinned = quote(&hash);
+        pinned["quote"]["version"] = "2".into();
+        assert_eq!(
+            validate(pinned).unwrap_err().message,
+            "Quoted post is at another version."
+        );
+        assert!(check(&QuoteValidator, r#"{"text": "no quote"}"#, &config).is_ok());
+    }
+
+    #[test]
+    fn license_is_optional_but_checked() {
+        let config = WallConfig::default();
+        assert!(check(&LicenseValidator, r#"{"text": "gm"}"#, &config).is_ok());
+        assert!(check(
+            &LicenseValidator,
+            r#"{"text": "gm", "license": "CC0"}"#,
+            &config
+        )
+        .is_ok());
+        assert_eq!(
+            check(
+                &LicenseValidator,
+                r#"{"text": "gm", "license": 1}"#,
+                &config
+            )
+            .unwrap_err()
+            .code,
+            "license"
+        );
+    }
+
+    #[test]
+    fn reply_only_walls_leave_threads_to_moderators() {
+        let mut config = WallConfig {
+            mode: WallMode::ReplyOnly,
+            ..Default::default()
+        };
+        let reply = r#"{"text": "hi", "reply_to": "cid"}"#;
+        assert_eq!(
+            check(&ReplyOnlyValidator, r#"{"text": "hi"}"#, &config)
+                .unwrap_err()
+                .code,
+            "reply_only"
+        );
+        assert!(check(&ReplyOnlyValidator, reply, &config).is_ok());
+
+        config.moderators = vec!["0xABC".to_string()];
+        assert!(check(&ReplyOnlyValidator, r#"{"text": "hi"}"#, &config).is_ok());
+    }
+
+    #[test]
+    fn blocked_keys_cannot_reply() {
+        crate::storage::ipfs::test_store::put("blocks-cid", r#"{"blocked": ["0xabc"]}"#);
+        let metadatas = vec![
+            Metadata {
+                public_key: "0xauthor".to_string(),
+                cid: "author-post".to_string(),
+                ..Default::default()
+            },
+            Metadata {
+                public_key: "0xauthor".to_string(),
+                alias: crate::storage::blocklist::BLOCKLIST_ALIAS.to_string(),
+                cid: "blocks-cid".to_string(),
+                ..Default::default()
+            },
+        ];
+        let transaction = Transaction {
+            public_key: "0xABC".to_string(),
+            ..Default::default()
+        };
+        let config = WallConfig::default();
+        let validate = |data: &str| {
+            let payload = parse_payload(data).unwrap();
+            ReplyValidator.validate(&PostContext {
+                transaction: &transaction,
+                metadatas: &metadatas,
+                wall: "",
+                config: &config,
+                filter: &WordFilter::Global,
+                payload: &payload,
+            })
+        };
+
+        assert_eq!(
+            validate(r#"{"text": "hi", "reply_to": "author-post"}"#)
+                .unwrap_err()
+                .code,
+            "blocked"
+        );
+        assert_eq!(
+            validate(r#"{"text": "hi", "reply_to": "missing"}"#)
+                .unwrap_err()
+                .code,
+            "reply"
+        );
+        assert!(validate(r#"{"text": "hi"}"#).is_ok());
+        assert_eq!(
+            validate(r#"{"text": "hi", "reply_to": "author-post", "reply_version": "2"}"#)
+                .unwrap_err()
+                .message,
+            "Replied post is at another version."
+        );
+        assert_eq!(
+            validate(r#"{"text": "hi", "reply_version": "2"}"#)
+                .unwrap_err()
+                .code,
+            "reply"
+        );
+    }
+
+    #[test]
+    fn embedded_nfts_are_validated() {
+        let config = WallConfig::default();
+        let good = r#"{"text": "mine", "embed_nft": {"chain_id": "1", "contract": "0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D", "token_id": "42"}}"#;
+        assert!(check(&EmbedNftValidator, good, &config).is_ok());
+
+        let bad = r#"{"text": "mine", "embed_nft": {"chain_id": "1", "contract": "0xnope", "token_id": "42"}}"#;
+        assert_eq!(
+            check(&EmbedNftValidator, bad, &config).unwrap_err().code,
+            "embed_nft"
+        );
+
+        let unattested = r#"{"embed_nft": {"chain_id": "1", "contract": "0xBC4CA0EdA7647A8aB7C2061c2E118A18a936f13D", "token_id": "42", "attestation": {"signer": "0x1", "signature": "0x00"}}}"#;
+        assert!(check(&EmbedNftValidator, unattested, &config).is_err());
+    }
+
+    #[test]
+    fn bounties_need_reward_and_future_deadline() {
+        let config = WallConfig::default();
+        let good = r#"{"type": "bounty", "text": "fix", "reward": {"amount": "100", "token": "native"}, "deadline": 4102444800}"#;
+        assert!(check(&BountyValidator, good, &config).is_ok());
+
+        let no_reward = r#"{"type": "bounty", "text": "fix", "deadline": 4102444800}"#;
+        assert_eq!(
+            check(&BountyValidator, no_reward, &config)
+                .unwrap_err()
+                .code,
+            "bounty"
+        );
+        assert!(check(&BountyValidator, r#"{"text": "plain"}"#, &config).is_ok());
+    }
+
+    #[test]
+    fn events_need_a_schedule() {
+        let config = WallConfig::default();
+        let good = r#"{"type": "event", "text": "raid", "start": 100, "end": 200, "timezone": "Europe/Berlin", "url": "https://meet.example"}"#;
+        assert!(check(&EventValidator, good, &config).is_ok());
+
+        let offset = r#"{"type": "event", "text": "raid", "start": 100, "end": 100, "timezone": "+02:00", "location": "Berlin"}"#;
+        assert!(check(&EventValidator, offset, &config).is_ok());
+
+        let backwards = r#"{"type": "event", "text": "raid", "start": 200, "end": 100, "timezone": "UTC", "location": "x"}"#;
+        assert_eq!(
+            check(&EventValidator, backwards, &config).unwrap_err().code,
+            "event"
+        );
+
+        let nowhere =
+            r#"{"type": "event", "text": "raid", "start": 100, "end": 200, "timezone": "UTC"}"#;
+        assert!(check(&EventValidator, nowhere, &config).is_err());
+    }
+
+    #[test]
+    fn events_fall_back_to_the_wall_timezone() {
+        let local =
+            r#"{"type": "event", "text": "raid", "start": 100, "end": 200, "location": "Berlin"}"#;
+        assert!(check(&SchemaValidator, local, &WallConfig::default()).is_err());
+        assert!(check(&EventValidator, local, &WallConfig::default()).is_err());
+
+        let config = WallConfig {
+            locale: Locale {
+                timezone: Some("Europe/Berlin".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(check(&SchemaValidator, local, &config).is_ok());
+        assert!(check(&EventValidator, local, &config).is_ok());
+    }
+
+    #[test]
+    fn code_snippets_need_a_known_language() {
+        let config = WallConfig::default();
+        let good = r#"{"type": "code", "language": "rust", "code": "fn main() {}"}"#;
+        assert!(check(&SchemaValidator, good, &config).is_ok());
+        assert!(check(&CodeValidator, good, &config).is_ok());
+
+        let unknown = r#"{"type": "code", "language": "cobol", "code": "DISPLAY 'HI'."}"#;
+        assert_eq!(
+            check(&CodeValidator, unknown, &config).unwrap_err().code,
+            "code"
+        );
+        let empty = r#"{"type": "code", "language": "rust", "code": " "}"#;
+        assert!(check(&CodeValidator, empty, &config).is_err());
+    }
+
+    #[test]
+    fn articles_need_a_title_and_clean_markdown_sections() {
+        let config = WallConfig::default();
+        let article = |sections: &str| {
+            format!(
+                r#"{{"type": "article", "title": "Field notes", "cover": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "sections": {}}}"#,
+                sections
+            )
+        };
+        let good = article(r#"[{"heading": "Intro", "body": "Longer than a post."}]"#);
+        assert!(check(&SchemaValidator, &good, &config).is_ok());
+        assert!(check(&ArticleValidator, &good, &config).is_ok());
+
+        let html = article(r#"[{"body": "<iframe src=x>"}]"#);
+        assert_eq!(
+            check(&ArticleValidator, &html, &config)
+                .unwrap_err()
+                .to_string(),
+            "[article] Section 1: Raw HTML is not allowed."
+        );
+        assert!(check(&ArticleValidator, &article("[]"), &config).is_err());
+        let long = article(&format!(
+            r#"[{{"body": "{}"}}]"#,
+            "a".repeat(DEFAULT_MAX_SECTION_LENGTH + 1)
+        ));
+        assert!(check(&ArticleValidator, &long, &config).is_err());
+        let untitled = r#"{"type": "article", "sections": [{"body": "x"}]}"#;
+        assert!(check(&SchemaValidator, untitled, &config).is_err());
+    }
+
+    #[test]
+    fn stickers_come_from_a_registered_pack() {
+        let config = WallConfig::default();
+        let registry = r#"{"packs": {"frogs": {"stickers": ["bafyfrog"], "license": "CC0"}}}"#;
+        crate::storage::ipfs::test_store::put("stickers-registry", registry);
+        let metadatas = [Metadata {
+            alias: crate::storage::stickers::STICKERS_ALIAS.to_string(),
+            cid: "stickers-registry".to_string(),
+            ..Default::default()
+        }];
+        let check = |validator: &dyn Validator, data: &str, config: &WallConfig| {
+            let payload = parse_payload(data).unwrap();
+            validator.validate(&PostContext {
+                transaction: &Transaction::default(),
+                metadatas: &metadatas,
+                wall: "",
+                config,
+                filter: &WordFilter::Global,
+                payload: &payload,
+            })
+        };
+        let good = r#"{"type": "sticker", "sticker": {"pack": "frogs", "cid": "bafyfrog"}, "text": "🐸 ❤️"}"#;
+        assert!(check(&SchemaValidator, good, &config).is_ok());
+        assert!(check(&StickerValidator, good, &config).is_ok());
+
+        let stranger = r#"{"type": "sticker", "sticker": {"pack": "frogs", "cid": "bafytoad"}}"#;
+        assert_eq!(
+            check(&StickerValidator, stranger, &config)
+                .unwrap_err()
+                .to_string(),
+            "[sticker] Sticker is not in that pack."
+        );
+        let chatty = r#"{"type": "sticker", "sticker": {"pack": "frogs", "cid": "bafyfrog"}, "text": "ribbit"}"#;
+        assert!(check(&StickerValidator, chatty, &config).is_err());
+        let missing = r#"{"type": "sticker", "text": "🐸"}"#;
+        assert!(check(&SchemaValidator, missing, &config).is_err());
+        let smuggled = r#"{"text": "hi", "sticker": {"pack": "frogs", "cid": "bafyfrog"}}"#;
+        assert!(check(&StickerValidator, smuggled, &config).is_err());
+    }
+
+    #[test]
+    fn attachments_follow_wall_mime_rules() {
+        let mut config = WallConfig::default();
+        let pdf = r#"{"text": "spec", "attachments": [{"cid": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "filename": "spec.pdf", "mime": "application/pdf", "size": 1000}]}"#;
+        assert_eq!(
+            check(&AttachmentValidator, pdf, &config)
+                .unwrap_err()
+                .message,
+            "spec.pdf: application/pdf is not accepted."
+        );
+
+        config.attachments.types.push(crate::data::MimeLimit {
+            mime: "application/*".to_string(),
+            max_size: 500,
+        });
+        assert!(check(&AttachmentValidator, pdf, &config).is_err());
+        config.attachments.types.last_mut().unwrap().max_size = 5000;
+        assert!(check(&AttachmentValidator, pdf, &config).is_ok());
+
+        let traversal = r#"{"attachments": [{"cid": "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG", "filename": "../a.png", "mime": "image/png", "size": 1}]}"#;
+        assert!(check(&AttachmentValidator, traversal, &config).is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn private_posts_need_a_valid_envelope() {
+        let config = WallConfig::default();
+        let good = r#"{"visibility": "private", "content": "c2VjcmV0", "encryption": {"algorithm": "x25519-xsalsa20-poly1305", "recipients": [{"public_key": "0xa", "encrypted_key": "a2V5"}]}}"#;
+        assert!(check(&SchemaValidator, good, &config).is_ok());
+        assert!(check(&PrivatePostValidator, good, &config).is_ok());
+
+        let rot13 = r#"{"visibility": "private", "content": "x", "encryption": {"algorithm": "rot13", "recipients": [{"public_key": "0xa", "encrypted_key": "k"}]}}"#;
+        assert_eq!(
+            check(&PrivatePostValidator, rot13, &config)
+                .unwrap_err()
+                .code,
+            "private"
+        );
+        let nobody = r#"{"visibility": "private", "content": "x", "encryption": {"algorithm": "aes-256-gcm", "recipients": []}}"#;
+        assert!(check(&PrivatePostValidator, nobody, &config).is_err());
+
+        let config = WallConfig {
+            disabled_features: vec!["encryption".to_string()],
+            ..config
+        };
+        assert_eq!(
+            check(&PrivatePostValidator, good, &config)
+                .unwrap_err()
+                .message,
+            "Private posts are off on this wall."
+        );
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[test]
+    fn private_posts_are_refused_without_encryption() {
+        let config = WallConfig::default();
+        let private = r#"{"visibility": "private", "content": "c2VjcmV0", "encryption": {"algorithm": "aes-256-gcm", "recipients": [{"public_key": "0xa", "encrypted_key": "a2V5"}]}}"#;
+        assert_eq!(
+            check(&SchemaValidator, private, &config).unwrap_err().code,
+            "unknown_field"
+        );
+        let schema = crate::validation::schema::post_schema();
+        assert!(!schema["properties"]["visibility"]["enum"]
+            .as_array()
+            .unwrap()
+            .contains(&"private".into()));
+    }
+
+    #[test]
+    fn verdicts_are_required_only_when_configured() {
+        let mut config = WallConfig::default();
+        assert!(check(&ClassifierValidator, r#"{"text": "gm"}"#, &config).is_ok());
+        let forged =
+            r#"{"text": "gm", "verdict": {"signer": "0xa", "label": "ok", "signature": "0x"}}"#;
+        assert_eq!(
+            check(&ClassifierValidator, forged, &config)
+                .unwrap_err()
+                .message,
+            "Verdict signer is not a registered classifier."
+        );
+
+        config.classification.required = true;
+        assert_eq!(
+            check(&ClassifierValidator, r#"{"text": "gm"}"#, &config)
+                .unwrap_err()
+                .code,
+            "verdict"
+        );
+    }
+
+    #[test]
+    fn humanity_is_required_only_when_configured() {
+        let mut config = WallConfig::default();
+        assert!(check(&HumanityValidator, r#"{"text": "gm"}"#, &config).is_ok());
+
+        config.rules.require_humanity = true;
+        assert_eq!(
+            check(&HumanityValidator, r#"{"text": "gm"}"#, &config)
+                .unwrap_err()
+                .message,
+            "Proof-of-humanity attestation is missing."
+        );
+    }
+}
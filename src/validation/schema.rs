@@ -0,0 +1,1161 @@
+use crate::defaults::{
+    DEFAULT_CODE_LANGUAGES, DEFAULT_EMBED_PROVIDERS, DEFAULT_IMAGE_EXTENSIONS, DEFAULT_LICENSES,
+    DEFAULT_MAX_ALT_LENGTH, DEFAULT_MAX_ANNOUNCEMENT_LENGTH, DEFAULT_MAX_APPEAL_LENGTH,
+    DEFAULT_MAX_ARTICLE_SECTIONS, DEFAULT_MAX_ARTICLE_TITLE_LENGTH, DEFAULT_MAX_ATTACHMENTS,
+    DEFAULT_MAX_BIO_LENGTH, DEFAULT_MAX_BLOCKED_KEYS, DEFAULT_MAX_CHUNKS, DEFAULT_MAX_CODE_LENGTH,
+    DEFAULT_MAX_DESCRIPTION_TEMPLATE_LENGTH, DEFAULT_MAX_DISPLAY_NAME_LENGTH,
+    DEFAULT_MAX_FILTER_WORDS, DEFAULT_MAX_MINT_BODY_LENGTH, DEFAULT_MAX_MINT_NAME_LENGTH,
+    DEFAULT_MAX_MUTED_WORDS, DEFAULT_MAX_MUTED_WORD_LENGTH, DEFAULT_MAX_PAYLOAD_BYTES,
+    DEFAULT_MAX_PLACE_LENGTH, DEFAULT_MAX_PROFILE_LINKS, DEFAULT_MAX_PROFILE_LINK_LENGTH,
+    DEFAULT_MAX_PROPOSAL_BODY_LENGTH, DEFAULT_MAX_PROPOSAL_TITLE_LENGTH,
+    DEFAULT_MAX_REASON_CODE_LENGTH, DEFAULT_MAX_RELATED_LABEL_LENGTH, DEFAULT_MAX_SECTION_LENGTH,
+    DEFAULT_MAX_STICKERS_PER_PACK, DEFAULT_MAX_SUMMARY_LENGTH, DEFAULT_MAX_TEMPLATE_NAME_LENGTH,
+    DEFAULT_MAX_TEXT_LENGTH, DEFAULT_MAX_THEME_DESCRIPTION_LENGTH, DEFAULT_MAX_THEME_TITLE_LENGTH,
+    DEFAULT_MAX_WEBHOOK_URL_LENGTH, DEFAULT_WEBHOOK_EVENTS, MAX_FEED_SIZE, MAX_STATS_TOP_TAGS,
+    OPTIONAL_FEATURES, SCHEMA_VERSION, TIMEZONE_PATTERN,
+};
+#[cfg(feature = "encryption")]
+use crate::defaults::{DEFAULT_ENCRYPTION_ALGORITHMS, DEFAULT_MAX_RECIPIENTS};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+/**
+ * Built on first use, every payload is checked against it
+ */
+static SCHEMA: Lazy<Value> = Lazy::new(schema);
+
+/**
+ * Schema of a method's transaction data as of `schema_version`, methods without their own entry
+ * are posts. None when the version is not supported.
+ */
+pub fn method_schema(method: &str, schema_version: u64) -> Option<&'static Value> {
+    if schema_version != SCHEMA_VERSION {
+        return None;
+    }
+    let methods = &SCHEMA["methods"];
+    Some(match methods.get(method) {
+        Some(schema) => schema,
+        None => &methods["post"],
+    })
+}
+
+/**
+ * JSON Schema of a post payload, kept in sync with the rules in `post_validators`. Private post
+ * fields are only there in builds with `encryption`.
+ */
+pub fn post_schema() -> Value {
+    with_private_posts(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "description": "the fields each post type needs are in the wall's required_fields",
+        "properties": {
+            "schema_version": {
+                "type": "integer",
+                "description": "older versions are migrated on write, 1 when left out"
+            },
+            "text": { "type": "string", "maxLength": DEFAULT_MAX_TEXT_LENGTH },
+            "reply_to": { "type": "string", "description": "CID of the post replied to" },
+            "reply_hash": {
+                "type": "string",
+                "pattern": "^0x[0-9a-fA-F]{64}$",
+                "description": "0x keccak256 of the replied content"
+            },
+            "reply_version": { "type": "string", "description": "record version of the replied post" },
+            "template": { "type": "string", "description": "name of a template set with set_template" },
+            "from_draft": { "type": "string", "description": "set by publish, id of the draft the post came from" },
+            "category": { "type": "string", "description": "one of the wall's boards" },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "geo": {
+                "description": "stored truncated to about a kilometre",
+                "anyOf": [
+                    { "type": "string", "minLength": 1, "maxLength": DEFAULT_MAX_PLACE_LENGTH },
+                    {
+                        "type": "object",
+                        "required": ["lat", "lon"],
+                        "properties": {
+                            "lat": { "type": "number", "minimum": -90, "maximum": 90 },
+                            "lon": { "type": "number", "minimum": -180, "maximum": 180 }
+                        }
+                    }
+                ]
+            },
+            "license": {
+                "type": "string",
+                "anyOf": [{ "enum": DEFAULT_LICENSES }, { "pattern": "^(https|ipfs|ar)://\\S+$" }]
+            },
+            "image": {
+                "type": "string",
+                "anyOf": [
+                    { "const": "" },
+                    { "pattern": "^(Qm[1-9A-HJ-NP-Za-km-z]{44}|b[a-z2-7]{8,})$", "description": "CID" },
+                    { "pattern": "^https://nftstorage\\.link/ipfs/" },
+                    { "pattern": "^data:image/svg\\+xml;base64,", "description": "walls accepting SVG" }
+                ],
+                "description": "links without an extension need image_mime"
+            },
+            "image_mime": { "enum": image_types() },
+            "alt": { "type": "string", "maxLength": DEFAULT_MAX_ALT_LENGTH, "description": "of the image" },
+            "blurhash": { "type": "string", "pattern": "^[0-9A-Za-z#$%*+,\\-.:;=?@\\[\\]^_{|}~]{6,}$" },
+            "thumbnail": { "type": "string", "description": "CIDv0 or base32 CIDv1" },
+            "size_bytes": { "type": "integer", "minimum": 1, "description": "of the image" },
+            "dimensions": {
+                "type": "object",
+                "description": "of the image, in pixels",
+                "required": ["width", "height"],
+                "properties": {
+                    "width": { "type": "integer", "minimum": 1 },
+                    "height": { "type": "integer", "minimum": 1 }
+                },
+                "additionalProperties": false
+            },
+            "quote": {
+                "type": "object",
+                "required": ["cid", "author", "content_hash"],
+                "properties": {
+                    "cid": { "type": "string", "description": "post record on the same wall" },
+                    "author": { "type": "string" },
+                    "content_hash": { "type": "string", "description": "0x keccak256 of the quoted content" },
+                    "version": { "type": "string", "description": "record version of the quoted post" }
+                }
+            },
+            "embed_nft": nft_schema(),
+            "type": { "type": "string", "description": "post type, e.g. post, code, bounty, article or sticker" },
+            "title": { "type": "string", "maxLength": DEFAULT_MAX_ARTICLE_TITLE_LENGTH, "description": "article posts" },
+            "cover": { "type": "string", "description": "article cover image, CIDv0 or base32 CIDv1" },
+            "sections": {
+                "type": "array",
+                "description": "article posts, markdown without raw HTML",
+                "minItems": 1,
+                "maxItems": DEFAULT_MAX_ARTICLE_SECTIONS,
+                "items": {
+                    "type": "object",
+                    "required": ["body"],
+                    "properties": {
+                        "heading": { "type": "string" },
+                        "body": { "type": "string", "maxLength": DEFAULT_MAX_SECTION_LENGTH }
+                    }
+                }
+            },
+            "sticker": {
+                "type": "object",
+                "description": "sticker posts, a cid of one of the wall's registered sticker packs",
+                "required": ["pack", "cid"],
+                "properties": {
+                    "pack": { "type": "string" },
+                    "cid": { "type": "string" }
+                }
+            },
+            "reward": {
+                "type": "object",
+                "description": "bounty posts",
+                "properties": {
+                    "amount": { "type": "string", "pattern": "^[0-9]+$" },
+                    "token": { "type": "string" }
+                }
+            },
+            "deadline": { "type": "integer", "description": "bounty deadline, unix seconds" },
+            "start": { "type": "integer", "description": "event start, unix seconds" },
+            "end": { "type": "integer", "description": "event end, unix seconds" },
+            "timezone": { "type": "string", "description": "IANA name or +hh:mm offset, the wall's locale timezone when left out" },
+            "location": { "type": "string" },
+            "url": { "type": "string", "pattern": "^https://" },
+            "language": { "enum": DEFAULT_CODE_LANGUAGES },
+            "code": { "type": "string", "maxLength": DEFAULT_MAX_CODE_LENGTH },
+            "attachments": attachments_schema(),
+            "visibility": {
+                "enum": visibilities(),
+                "description": "limited is set by the wall on borderline spam, clients demote those posts"
+            },
+            "token_bound": token_bound_schema(),
+            "verdict": {
+                "type": "object",
+                "description": "classifier's personal_sign of w3wall:verdict:<label>:<keccak256 of text>:<image>",
+                "required": ["signer", "label", "signature"],
+                "properties": {
+                    "signer": { "type": "string" },
+                    "label": { "type": "string" },
+                    "signature": { "type": "string" }
+                }
+            },
+            "humanity": {
+                "type": "object",
+                "description": "attester's personal_sign of w3wall:human:<author>:<expires>",
+                "required": ["signer", "expires", "signature"],
+                "properties": {
+                    "signer": { "type": "string" },
+                    "expires": { "type": "integer" },
+                    "signature": { "type": "string" }
+                }
+            },
+            "nonce": {
+                "type": "string",
+                "description": "proof of work, keccak256(len || data_key || len || lowercase sender || keccak256(payload as sent without nonce, sorted compact JSON) || len || nonce) must start with rules.pow_difficulty zero bits, len being the byte length of the next field as 4 byte big endian"
+            }
+        }
+    }))
+}
+
+#[cfg(feature = "encryption")]
+fn with_private_posts(mut schema: Value) -> Value {
+    let properties = &mut schema["properties"];
+    properties["content"] =
+        json!({ "type": "string", "description": "ciphertext of a private post" });
+    properties["encryption"] = encryption_schema();
+    schema
+}
+
+#[cfg(not(feature = "encryption"))]
+fn with_private_posts(schema: Value) -> Value {
+    schema
+}
+
+fn visibilities() -> Vec<&'static str> {
+    let mut visibilities = vec!["public", "limited"];
+    if cfg!(feature = "encryption") {
+        visibilities.insert(1, "private");
+    }
+    visibilities
+}
+
+fn image_types() -> Vec<&'static str> {
+    DEFAULT_IMAGE_EXTENSIONS
+        .iter()
+        .map(|(mime, _)| *mime)
+        .collect()
+}
+
+fn nft_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["chain_id", "contract", "token_id"],
+        "properties": {
+            "chain_id": { "type": "string", "pattern": "^[0-9]+$" },
+            "contract": { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+            "token_id": { "type": "string" },
+            "attestation": {
+                "type": "object",
+                "required": ["signer", "signature"],
+                "properties": {
+                    "signer": { "type": "string" },
+                    "signature": { "type": "string" }
+                }
+            }
+        }
+    })
+}
+
+fn attachments_schema() -> Value {
+    json!({
+        "type": "array",
+        "maxItems": DEFAULT_MAX_ATTACHMENTS,
+        "items": {
+            "type": "object",
+            "required": ["cid", "filename", "mime", "size"],
+            "properties": {
+                "cid": { "type": "string" },
+                "filename": { "type": "string", "maxLength": 255 },
+                "mime": { "type": "string", "description": "must be in the wall's attachments.types" },
+                "size": { "type": "integer", "minimum": 1 }
+            }
+        }
+    })
+}
+
+#[cfg(feature = "encryption")]
+fn encryption_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["algorithm", "recipients"],
+        "properties": {
+            "algorithm": { "enum": DEFAULT_ENCRYPTION_ALGORITHMS },
+            "recipients": {
+                "type": "array",
+                "minItems": 1,
+                "maxItems": DEFAULT_MAX_RECIPIENTS,
+                "items": {
+                    "type": "object",
+                    "required": ["public_key", "encrypted_key"],
+                    "properties": {
+                        "public_key": { "type": "string" },
+                        "encrypted_key": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn token_bound_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "post as an NFT through its ERC-6551 account",
+        "required": ["chain_id", "contract", "token_id", "account", "signature", "attestation"],
+        "properties": {
+            "chain_id": { "type": "string", "pattern": "^[0-9]+$" },
+            "contract": { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+            "token_id": { "type": "string" },
+            "account": { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+            "signature": { "type": "string", "description": "author's personal_sign of w3wall:tba:<chain_id>:<contract>:<token_id>:<account>:<data_key>" },
+            "attestation": {
+                "type": "object",
+                "required": ["signer", "signature"],
+                "properties": {
+                    "signer": { "type": "string" },
+                    "signature": { "type": "string" }
+                }
+            }
+        }
+    })
+}
+
+pub fn config_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "properties": {
+            "owner": { "type": "string" },
+            "owners": { "type": "array", "items": { "type": "string" } },
+            "owner_threshold": { "type": "integer", "minimum": 1 },
+            "moderators": { "type": "array", "items": { "type": "string" } },
+            "rules": {
+                "type": "object",
+                "properties": {
+                    "max_text_length": { "type": "integer", "minimum": 0 },
+                    "profanity_action": { "enum": ["reject", "mask"] },
+                    "pow_difficulty": { "type": "integer", "minimum": 0, "maximum": 256 },
+                    "require_humanity": { "type": "boolean" },
+                    "max_clock_skew_sec": { "type": "integer", "minimum": 0 },
+                    "max_post_age_sec": { "type": "integer", "minimum": 0 },
+                    "daily_post_quota": { "type": "integer", "minimum": 0 },
+                    "max_links": { "type": "integer", "minimum": 0 },
+                    "link_action": { "enum": ["reject", "tag"] },
+                    "validation_mode": { "enum": ["strict", "lenient"] },
+                    "spam": {
+                        "type": "object",
+                        "properties": {
+                            "threshold": { "type": "integer", "minimum": 0 },
+                            "action": { "enum": ["reject", "tag"] },
+                            "max_links": { "type": "integer", "minimum": 0 },
+                            "max_caps_percent": { "type": "integer", "minimum": 0, "maximum": 100 },
+                            "max_repeated_lines": { "type": "integer", "minimum": 0 },
+                            "max_emoji": { "type": "integer", "minimum": 0 },
+                            "limit_margin": { "type": "integer", "minimum": 0 }
+                        }
+                    },
+                    "quarantine": {
+                        "type": "object",
+                        "properties": {
+                            "posts": { "type": "integer", "minimum": 0 },
+                            "hours": { "type": "integer", "minimum": 0 }
+                        }
+                    },
+                    "duplicates": {
+                        "type": "object",
+                        "properties": {
+                            "window": { "type": "integer", "minimum": 0 },
+                            "max_distance": { "type": "integer", "minimum": 0, "maximum": 64 },
+                            "action": { "enum": ["reject", "tag"] }
+                        }
+                    }
+                }
+            },
+            "seller_fee_basis_points": { "type": "integer", "minimum": 0, "maximum": 10000 },
+            "fee_recipient": { "type": "string" },
+            "log_level": { "enum": ["off", "error", "warn", "info", "debug", "trace"] },
+            "metrics": { "type": "boolean" },
+            "rollups": { "type": "boolean" },
+            "digests": { "type": "boolean" },
+            "search_index": { "type": "boolean" },
+            "media": {
+                "type": "object",
+                "properties": {
+                    "max_size_bytes": { "type": "integer", "minimum": 1 },
+                    "max_width": { "type": "integer", "minimum": 1 },
+                    "max_height": { "type": "integer", "minimum": 1 }
+                }
+            },
+            "attachments": {
+                "type": "object",
+                "properties": {
+                    "max_count": { "type": "integer", "minimum": 0 },
+                    "types": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["mime", "max_size"],
+                            "properties": {
+                                "mime": { "type": "string" },
+                                "max_size": { "type": "integer", "minimum": 0 }
+                            }
+                        }
+                    }
+                }
+            },
+            "attesters": { "type": "array", "items": { "type": "string" } },
+            "classification": {
+                "type": "object",
+                "properties": {
+                    "classifiers": { "type": "array", "items": { "type": "string" } },
+                    "required": { "type": "boolean" },
+                    "blocked_labels": { "type": "array", "items": { "type": "string" } }
+                }
+            },
+            "claimants": { "type": "array", "items": { "type": "string" } },
+            "trusted": { "type": "array", "items": { "type": "string" } },
+            "reactions": {
+                "type": "array",
+                "items": { "type": "string", "minLength": 1, "description": "one emoji" }
+            },
+            "governance": {
+                "type": "object",
+                "properties": {
+                    "attester": { "type": "string", "description": "signs snapshot balances" },
+                    "token": { "type": "string", "description": "governance token contract" },
+                    "quorum": { "type": "string", "pattern": "^[0-9]{1,78}$" },
+                    "voting_hours": { "type": "integer", "minimum": 1 }
+                }
+            },
+            "disabled_features": {
+                "type": "array",
+                "items": { "enum": OPTIONAL_FEATURES.iter().map(|(name, _)| *name).collect::<Vec<_>>() }
+            },
+            "compressed_storage": { "enum": ["decompressed", "compressed"] },
+            "templates": { "type": "object", "additionalProperties": template_schema() },
+            "required_fields": {
+                "type": "object",
+                "description": "per post type, every group needs one of its fields",
+                "additionalProperties": {
+                    "type": "array",
+                    "items": { "type": "array", "minItems": 1, "items": { "type": "string" } }
+                }
+            },
+            "image_types": {
+                "type": "array",
+                "items": { "enum": image_types() }
+            },
+            "gateways": {
+                "type": "array",
+                "description": "in order of preference, the CID path is appended",
+                "items": { "type": "string", "pattern": "^https://\\S+/$" }
+            },
+            "reserved_names": {
+                "type": "array",
+                "description": "display names only owners and moderators may use",
+                "items": { "type": "string" }
+            },
+            "embed_providers": { "type": "array", "items": { "enum": DEFAULT_EMBED_PROVIDERS } },
+            "boards": { "type": "array", "items": { "type": "string" } },
+            "locale": {
+                "type": "object",
+                "properties": {
+                    "timezone": {
+                        "type": "string",
+                        "pattern": TIMEZONE_PATTERN,
+                        "description": "IANA name or +hh:mm offset, the default of event posts"
+                    },
+                    "date_format": { "enum": ["iso", "dmy", "mdy"] },
+                    "language": {
+                        "type": "string",
+                        "pattern": "^[a-z]{2,3}(-[A-Za-z0-9]{2,8})*$",
+                        "description": "BCP 47 tag, e.g. en or pt-BR"
+                    }
+                },
+                "additionalProperties": false
+            },
+            "mode": { "enum": ["open", "reply_only", "approval"] },
+            "description_template": {
+                "type": "string",
+                "maxLength": DEFAULT_MAX_DESCRIPTION_TEMPLATE_LENGTH,
+                "description": "placeholders {title}, {author} and {reply_count}"
+            },
+            "syndication": {
+                "type": "object",
+                "properties": {
+                    "activitystreams": { "type": "boolean" },
+                    "jsonld": { "type": "boolean" },
+                    "lens": { "type": "boolean" }
+                }
+            },
+            "karma": {
+                "type": "object",
+                "properties": {
+                    "enabled": { "type": "boolean" },
+                    "min_for_links": { "type": "integer", "minimum": 0 },
+                    "min_reactor_age_sec": { "type": "integer", "minimum": 0 }
+                }
+            }
+        }
+    })
+}
+
+pub fn filter_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["words"],
+        "properties": {
+            "words": {
+                "type": "array",
+                "items": { "type": "string" },
+                "maxItems": DEFAULT_MAX_FILTER_WORDS
+            }
+        }
+    })
+}
+
+fn reason_code_schema() -> Value {
+    json!({
+        "type": "string",
+        "pattern": format!("^[a-z0-9_]{{0,{}}}$", DEFAULT_MAX_REASON_CODE_LENGTH),
+        "description": "recorded in the w3wall.audit log, e.g. spam"
+    })
+}
+
+/**
+ * Co-owners' personal_sign of `message`, counted towards `owner_threshold`
+ */
+fn approvals_schema(message: &str) -> Value {
+    json!({
+        "type": "array",
+        "description": format!("co-owners' personal_sign of {}", message),
+        "items": {
+            "type": "object",
+            "required": ["signer", "signature"],
+            "properties": {
+                "signer": { "type": "string" },
+                "signature": { "type": "string" }
+            }
+        }
+    })
+}
+
+pub fn moderate_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["action"],
+        "properties": {
+            "action": { "enum": ["remove", "ban", "lock"] },
+            "target": { "type": "string", "description": "post cid to remove or key to ban" },
+            "reason": reason_code_schema(),
+            "approvals": approvals_schema(
+                "w3wall:moderate:<data_key>:<moderation record cid>:<action>:<target>"
+            )
+        }
+    })
+}
+
+fn template_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "required": { "type": "array", "items": { "type": "string" } },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "attributes": { "type": "object", "additionalProperties": { "type": "string" } }
+        }
+    })
+}
+
+pub fn set_template_schema() -> Value {
+    let mut schema = template_schema();
+    schema["$schema"] = "https://json-schema.org/draft/2020-12/schema".into();
+    schema["required"] = json!(["name"]);
+    schema["properties"]["name"] = json!({
+        "type": "string",
+        "pattern": format!("^[a-z0-9_-]{{1,{}}}$", DEFAULT_MAX_TEMPLATE_NAME_LENGTH)
+    });
+    schema["properties"]["remove"] = json!({ "type": "boolean" });
+    schema
+}
+
+pub fn sticker_pack_schema(register: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "description": "owners only",
+        "required": ["pack"],
+        "properties": {
+            "pack": { "type": "string", "pattern": "^[a-z0-9_-]{1,32}$" }
+        }
+    });
+    if register {
+        schema["required"] = json!(["pack", "stickers", "license"]);
+        schema["properties"]["stickers"] = json!({
+            "type": "array",
+            "minItems": 1,
+            "maxItems": DEFAULT_MAX_STICKERS_PER_PACK,
+            "items": { "type": "string", "description": "CID" }
+        });
+        schema["properties"]["license"] = json!({
+            "type": "string",
+            "anyOf": [{ "enum": DEFAULT_LICENSES }, { "pattern": "^(https|ipfs|ar)://\\S+$" }]
+        });
+    }
+    schema
+}
+
+pub fn board_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["board"],
+        "properties": {
+            "board": { "type": "string", "pattern": format!("^[a-z0-9_-]{{1,{}}}$", DEFAULT_MAX_TEMPLATE_NAME_LENGTH) }
+        }
+    })
+}
+
+pub fn webhook_schema(register: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "description": "owners only",
+        "required": ["id"],
+        "properties": {
+            "id": { "type": "string", "pattern": "^[a-z0-9_-]{1,32}$" }
+        }
+    });
+    if register {
+        schema["required"] = json!(["id", "url", "secret_hash", "events"]);
+        schema["properties"]["url"] = json!({
+            "type": "string",
+            "pattern": "^https://",
+            "maxLength": DEFAULT_MAX_WEBHOOK_URL_LENGTH,
+            "description": "may use {data_key}, {event} and {cid} past the host"
+        });
+        schema["properties"]["secret_hash"] = json!({
+            "type": "string",
+            "pattern": "^0x[0-9a-f]{64}$",
+            "description": "keccak256 of the delivery signing secret, never the secret itself"
+        });
+        schema["properties"]["events"] = json!({
+            "type": "array",
+            "minItems": 1,
+            "items": { "enum": DEFAULT_WEBHOOK_EVENTS }
+        });
+    }
+    schema
+}
+
+fn snapshot_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["block", "balance", "signature"],
+        "properties": {
+            "block": { "type": "integer", "minimum": 0 },
+            "balance": { "type": "string", "pattern": "^[0-9]{1,78}$", "description": "base units" },
+            "signature": {
+                "type": "string",
+                "description": "governance attester's personal_sign of w3wall:snapshot:<data_key>:<token>:<voter>:<block>:<balance>"
+            }
+        }
+    })
+}
+
+pub fn proposal_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["title", "snapshot"],
+        "properties": {
+            "title": { "type": "string", "minLength": 1, "maxLength": DEFAULT_MAX_PROPOSAL_TITLE_LENGTH },
+            "body": { "type": "string", "maxLength": DEFAULT_MAX_PROPOSAL_BODY_LENGTH },
+            "snapshot": snapshot_schema(),
+            "changes": {
+                "type": "object",
+                "description": "JSON merge patch of the wall config, applied with apply_config once passed"
+            }
+        }
+    })
+}
+
+pub fn governance_vote_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["proposal", "choice", "snapshot"],
+        "properties": {
+            "proposal": { "type": "string", "pattern": "^[0-9a-f]{16}$" },
+            "choice": { "enum": ["for", "against", "abstain"] },
+            "snapshot": snapshot_schema()
+        }
+    })
+}
+
+pub fn relate_wall_schema(relate: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["data_key"],
+        "properties": {
+            "data_key": { "type": "string", "pattern": "^(0x)?[0-9a-fA-F]{64}$" }
+        }
+    });
+    if relate {
+        schema["required"] = json!(["data_key", "label", "owner", "signature"]);
+        schema["properties"]["label"] =
+            json!({ "type": "string", "maxLength": DEFAULT_MAX_RELATED_LABEL_LENGTH });
+        schema["properties"]["owner"] =
+            json!({ "type": "string", "description": "owner of the related wall" });
+        schema["properties"]["signature"] = json!({
+            "type": "string",
+            "description": "owner's personal_sign of w3wall:relate:<related data_key>:<this data_key>"
+        });
+    }
+    schema
+}
+
+pub fn transfer_ownership_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["new_owner"],
+        "properties": {
+            "new_owner": { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+            "approvals": approvals_schema(
+                "w3wall:transfer_ownership:<data_key>:<ownership record cid>:<new_owner>"
+            )
+        }
+    })
+}
+
+pub fn chunk_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["root", "index", "data"],
+        "properties": {
+            "root": { "type": "string", "description": "keccak256 over the keccak256 of every chunk, in order" },
+            "index": { "type": "integer", "minimum": 0, "maximum": DEFAULT_MAX_CHUNKS - 1 },
+            "data": { "type": "string", "description": "slice of the post JSON text" }
+        }
+    })
+}
+
+pub fn assemble_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["root", "chunks"],
+        "properties": {
+            "root": { "type": "string" },
+            "chunks": { "type": "integer", "minimum": 1, "maximum": DEFAULT_MAX_CHUNKS }
+        }
+    })
+}
+
+pub fn draft_schema(save: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["id"],
+        "properties": {
+            "id": { "type": "string", "pattern": "^[a-z0-9_-]{1,32}$" }
+        }
+    });
+    if save {
+        schema["required"] = json!(["id", "payload"]);
+        schema["properties"]["payload"] =
+            json!({ "type": "object", "description": "post payload, validated on publish" });
+    }
+    schema
+}
+
+pub fn review_schema(approve: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["author", "id"],
+        "properties": {
+            "author": { "type": "string", "description": "key the post is held under" },
+            "id": { "type": "string", "pattern": "^[0-9a-f]{16}$", "description": "suffix of its w3wall.pending. alias" }
+        },
+        "additionalProperties": false
+    });
+    if approve {
+        schema["properties"]["approve_author"] =
+            json!({ "type": "boolean", "description": "later posts of the author skip the queue" });
+    } else {
+        schema["properties"]["reason"] = json!({
+            "type": "string",
+            "pattern": format!("^[a-z0-9_]{{0,{}}}$", DEFAULT_MAX_REASON_CODE_LENGTH),
+            "description": "kept on the rejected post, e.g. off_topic"
+        });
+    }
+    schema
+}
+
+pub fn announce_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["text", "expires"],
+        "properties": {
+            "text": { "type": "string", "minLength": 1, "maxLength": DEFAULT_MAX_ANNOUNCEMENT_LENGTH },
+            "expires": { "type": "integer", "description": "unix seconds, at most 30 days ahead" }
+        }
+    })
+}
+
+pub fn summary_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["text"],
+        "properties": {
+            "text": {
+                "type": "string",
+                "minLength": 1,
+                "maxLength": DEFAULT_MAX_SUMMARY_LENGTH,
+                "description": "becomes the topic's NFT description"
+            }
+        }
+    })
+}
+
+pub fn theme_schema() -> Value {
+    let color = json!({ "type": "string", "pattern": "^#([0-9a-fA-F]{3}|[0-9a-fA-F]{6})$" });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "colors": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "primary": color,
+                    "accent": color,
+                    "background": color,
+                    "text": color
+                }
+            },
+            "banner": { "type": "string", "description": "CID or nftstorage.link URL" },
+            "title": { "type": "string", "maxLength": DEFAULT_MAX_THEME_TITLE_LENGTH },
+            "description": { "type": "string", "maxLength": DEFAULT_MAX_THEME_DESCRIPTION_LENGTH }
+        }
+    })
+}
+
+pub fn reveal_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["name"],
+        "additionalProperties": false,
+        "properties": {
+            "name": { "type": "string", "maxLength": DEFAULT_MAX_MINT_NAME_LENGTH },
+            "image": {
+                "type": "string",
+                "description": "CID or nftstorage.link URL, empty keeps the placeholder image"
+            },
+            "body": { "type": "string", "maxLength": DEFAULT_MAX_MINT_BODY_LENGTH }
+        }
+    })
+}
+
+pub fn profile_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "display_name": { "type": "string", "maxLength": DEFAULT_MAX_DISPLAY_NAME_LENGTH },
+            "avatar": { "type": "string", "description": "CID or nftstorage.link URL" },
+            "bio": { "type": "string", "maxLength": DEFAULT_MAX_BIO_LENGTH },
+            "links": {
+                "type": "array",
+                "maxItems": DEFAULT_MAX_PROFILE_LINKS,
+                "items": { "type": "string", "pattern": "^https://\\S+$", "maxLength": DEFAULT_MAX_PROFILE_LINK_LENGTH }
+            }
+        }
+    })
+}
+
+pub fn follow_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "oneOf": [{ "required": ["author"] }, { "required": ["thread"] }],
+        "properties": {
+            "author": { "type": "string", "description": "public key of the author" },
+            "thread": { "type": "string", "description": "data_key of the thread" }
+        }
+    })
+}
+
+pub fn mute_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["muted"],
+        "properties": {
+            "muted": {
+                "type": "array",
+                "items": { "type": "string", "maxLength": DEFAULT_MAX_MUTED_WORD_LENGTH },
+                "maxItems": DEFAULT_MAX_MUTED_WORDS
+            }
+        }
+    })
+}
+
+pub fn block_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["blocked"],
+        "properties": {
+            "blocked": {
+                "type": "array",
+                "items": { "type": "string" },
+                "maxItems": DEFAULT_MAX_BLOCKED_KEYS
+            }
+        }
+    })
+}
+
+pub fn claim_schema(counter: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["post"],
+        "properties": {
+            "post": { "type": "string", "description": "CID of the disputed post" },
+            "reason": { "type": "string" }
+        }
+    });
+    if !counter {
+        schema["properties"]["credential"] = json!({
+            "type": "object",
+            "description": "attester's personal_sign of w3wall:claimant:<claimant>:<expires>, not needed for config.claimants",
+            "required": ["signer", "expires", "signature"],
+            "properties": {
+                "signer": { "type": "string" },
+                "expires": { "type": "integer" },
+                "signature": { "type": "string" }
+            }
+        });
+    }
+    schema
+}
+
+pub fn reaction_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["target"],
+        "properties": {
+            "target": { "type": "string", "description": "public key of the author reacted to" },
+            "reaction": { "type": "string" }
+        }
+    })
+}
+
+pub fn crosspost_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["origin"],
+        "properties": {
+            "origin": {
+                "type": "object",
+                "required": ["data_key", "cid", "author", "timestamp"],
+                "properties": {
+                    "data_key": { "type": "string", "pattern": "^(0x)?[0-9a-fA-F]{64}$" },
+                    "cid": { "type": "string" },
+                    "author": { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+                    "timestamp": { "type": "integer", "minimum": 1 }
+                }
+            },
+            "text": { "type": "string", "maxLength": DEFAULT_MAX_TEXT_LENGTH }
+        }
+    })
+}
+
+pub fn tip_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["post", "tx_hash", "amount", "token"],
+        "properties": {
+            "post": { "type": "string", "description": "CID of the tipped post" },
+            "tx_hash": { "type": "string", "pattern": "^0x[0-9a-fA-F]{64}$" },
+            "amount": { "type": "string", "pattern": "^[0-9]+$", "description": "base units" },
+            "token": { "type": "string", "description": "\"native\" or the token contract" }
+        }
+    })
+}
+
+pub fn bounty_action_schema(award: bool) -> Value {
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": ["bounty"],
+        "properties": {
+            "bounty": { "type": "string", "description": "CID of the bounty post" }
+        }
+    });
+    if award {
+        schema["required"] = json!(["bounty", "reply"]);
+        schema["properties"]["reply"] =
+            json!({ "type": "string", "description": "CID of the winning reply" });
+    }
+    schema
+}
+
+/**
+ * Accepted transaction data per on_execute method. Methods not listed are handled as posts.
+ */
+pub fn schema() -> Value {
+    json!({
+        "schema_version": SCHEMA_VERSION,
+        "max_payload_bytes": DEFAULT_MAX_PAYLOAD_BYTES,
+        "methods": {
+            "post": post_schema(),
+            "init": config_schema(),
+            "refresh_metadata": { "description": "transaction data is ignored" },
+            "set_filter": filter_schema(),
+            "moderate": moderate_schema(),
+            "transfer_ownership": transfer_ownership_schema(),
+            "block": block_schema(),
+            "mute": mute_schema(),
+            "set_template": set_template_schema(),
+            "register_sticker_pack": sticker_pack_schema(true),
+            "retire_sticker_pack": sticker_pack_schema(false),
+            "register_webhook": webhook_schema(true),
+            "remove_webhook": webhook_schema(false),
+            "announce": announce_schema(),
+            "set_theme": theme_schema(),
+            "reveal": reveal_schema(),
+            "set_summary": summary_schema(),
+            "set_profile": profile_schema(),
+            "follow": follow_schema(),
+            "unfollow": follow_schema(),
+            "add_board": board_schema(),
+            "remove_board": board_schema(),
+            "relate_wall": relate_wall_schema(true),
+            "unrelate_wall": relate_wall_schema(false),
+            "claim": claim_schema(false),
+            "counter_claim": claim_schema(true),
+            "import": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["source", "message"],
+                "properties": {
+                    "source": { "enum": ["farcaster", "lens", "w3wall"] },
+                    "message": { "type": "object", "description": "Farcaster hub CAST_ADD message, Lens publication with v2 metadata, or w3wall export bundle {manifest, signature, records} (owners only)" }
+                }
+            },
+            "merkle_root": { "description": "transaction data is ignored" },
+            "digest": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "day": {
+                        "type": "string",
+                        "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}$",
+                        "description": "UTC day that is over, the day before the transaction when left out"
+                    }
+                }
+            },
+            "export": { "description": "owners only, transaction data is ignored" },
+            "refresh_pages": { "description": "transaction data is ignored" },
+            "refresh_pins": { "description": "transaction data is ignored" },
+            "migrate": { "description": "owners only, transaction data is ignored" },
+            "refresh_feed": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": { "limit": { "type": "integer", "minimum": 0, "maximum": MAX_FEED_SIZE } }
+            },
+            "refresh_stats": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "days": { "type": "integer", "minimum": 1, "description": "active author window" },
+                    "top": { "type": "integer", "minimum": 0, "maximum": MAX_STATS_TOP_TAGS }
+                }
+            },
+            "resolve_claim": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["post", "uphold"],
+                "properties": {
+                    "post": { "type": "string" },
+                    "uphold": { "type": "boolean" },
+                    "reason": reason_code_schema()
+                }
+            },
+            "appeal": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["post"],
+                "properties": {
+                    "post": { "type": "string", "description": "CID of the author's removed post" },
+                    "text": { "type": "string", "maxLength": DEFAULT_MAX_APPEAL_LENGTH }
+                }
+            },
+            "resolve_appeal": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["post", "grant", "reason"],
+                "properties": {
+                    "post": { "type": "string" },
+                    "grant": { "type": "boolean", "description": "true restores the post" },
+                    "reason": reason_code_schema(),
+                    "approvals": approvals_schema(
+                        "w3wall:grant_appeal:<data_key>:<moderation record cid>:<post>, granting needs owner_threshold of them"
+                    )
+                }
+            },
+            "chunk": chunk_schema(),
+            "assemble": assemble_schema(),
+            "save_draft": draft_schema(true),
+            "publish": draft_schema(false),
+            "proposal": proposal_schema(),
+            "governance_vote": governance_vote_schema(),
+            "apply_config": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["proposal"],
+                "properties": { "proposal": { "type": "string", "pattern": "^[0-9a-f]{16}$" } }
+            },
+            "approve": review_schema(true),
+            "reject": review_schema(false),
+            "redact": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["post"],
+                "properties": {
+                    "post": { "type": "string", "description": "CID of the author's own post" },
+                    "reason": reason_code_schema()
+                }
+            },
+            "accept_ownership": { "description": "sent by the offered key, transaction data is ignored" },
+            "react": reaction_schema(),
+            "crosspost": crosspost_schema(),
+            "tip": tip_schema(),
+            "award": bounty_action_schema(true),
+            "close_bounty": bounty_action_schema(false),
+            "rsvp": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "required": ["event"],
+                "properties": { "event": { "type": "string", "description": "CID of the event post" } }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_method() {
+        let schema = schema();
+        assert_eq!(schema["schema_version"], SCHEMA_VERSION);
+        for method in ["post", "init", "refresh_metadata"] {
+            assert!(schema["methods"][method].is_object(), "{}", method);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Active content checks for SVG images, which browsers render as documents: no scripts, no
+//! embedded HTML, no event handlers and nothing fetched from elsewhere. Checks run on the
+//! lowercased source, so casing and namespace prefixes don't slip anything through.
+
+use crate::budget;
+use crate::defaults::DEFAULT_SVG_BLOCKED_ELEMENTS;
+use crate::encoding::base64;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub static SVG_DATA_PREFIX: &str = "data:image/svg+xml;base64,";
+
+static ELEMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"<\s*(?:[a-z0-9_.-]+:)?([a-z]+)").unwrap());
+static HANDLER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\s/'\x22]on[a-z]+\s*=").unwrap());
+static HREF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?:^|[\s:])href\s*=\s*["']?\s*([^"'\s>]*)"#).unwrap());
+static URL: Lazy<Regex> = Lazy::new(|| Regex::new(r#"url\s*\(\s*["']?\s*(.)"#).unwrap());
+static INLINE_RASTER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^data:image/(png|jpeg|gif|webp)[;,]").unwrap());
+
+fn is_local_ref(target: &str) -> bool {
+    target.starts_with('#') || INLINE_RASTER.is_match(target)
+}
+
+pub fn check_svg(source: &str) -> Result<(), &'static str> {
+    if !budget::charge(source.len()) {
+        return Err("SVG image is too large to check.");
+    }
+    let source = source.to_lowercase();
+    if !source.contains("<svg") {
+        return Err("SVG image has no svg element.");
+    }
+    // entities can spell out any of the names below, so none are expanded
+    if source.contains("<!doctype") || source.contains("<!entity") {
+        return Err("SVG must not declare a doctype or entities.");
+    }
+    if ELEMENT
+        .captures_iter(&source)
+        .any(|element| DEFAULT_SVG_BLOCKED_ELEMENTS.contains(&&element[1]))
+    {
+        return Err("SVG must not contain scripts or embedded documents.");
+    }
+    if HANDLER.is_match(&source) || source.contains("javascript:") {
+        return Err("SVG must not contain event handlers or scripts.");
+    }
+    // an animation can point an href anywhere once the document is loaded
+    let retargets_href = source.contains("attributename=\"href\"")
+        || source.contains("attributename=\"xlink:href\"")
+        || source.contains("attributename='href'")
+        || source.contains("attributename='xlink:href'");
+    if retargets_href
+        || source.contains("@import")
+        || HREF
+            .captures_iter(&source)
+            .any(|href| !is_local_ref(&href[1]))
+        || URL.captures_iter(&source).any(|url| &url[1] != "#")
+    {
+        return Err("SVG must not reference external resources.");
+    }
+    Ok(())
+}
+
+/**
+ * Checks an inline `data:image/svg+xml;base64,` image, other data URIs are not images a wall
+ * stores inline
+ */
+pub fn check_svg_data_uri(link: &str) -> Result<(), &'static str> {
+    let encoded = link
+        .get(..SVG_DATA_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(SVG_DATA_PREFIX))
+        .map(|_| &link[SVG_DATA_PREFIX.len()..])
+        .ok_or("Inline images must be base64 encoded SVG.")?;
+    let source = base64::decode(encoded)
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or("Inline SVG is not valid base64 UTF-8.")?;
+    check_svg(&source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_content_is_rejected() {
+        let plain = r##"<svg xmlns="http://www.w3.org/2000/svg"><defs><linearGradient id="g"/></defs><rect fill="url(#g)"/><use href="#g"/></svg>"##;
+        assert_eq!(check_svg(plain), Ok(()));
+        for svg in [
+            r#"<svg><script>alert(1)</script></svg>"#,
+            r#"<svg><SVG:Script>alert(1)</SVG:Script></svg>"#,
+            r#"<svg><foreignObject><iframe src="x"/></foreignObject></svg>"#,
+            r#"<svg onload="alert(1)"/>"#,
+            r#"<svg><rect/onclick="alert(1)"/></svg>"#,
+            r#"<svg><a href="javascript:alert(1)">x</a></svg>"#,
+            r#"<svg><image xlink:href="https://tracker.example/p.png"/></svg>"#,
+            r#"<svg><use href="&#106;avascript:alert(1)"/></svg>"#,
+            r#"<svg><rect style="fill: url('https://x/p')"/></svg>"#,
+            r#"<svg><style>@import "https://x/a.css";</style></svg>"#,
+            r#"<svg><set attributeName="href" to="https://x"/></svg>"#,
+            r#"<!DOCTYPE svg [<!ENTITY s "script">]><svg/>"#,
+            "<html><body/></html>",
+        ] {
+            assert!(check_svg(svg).is_err(), "{}", svg);
+        }
+    }
+
+    #[test]
+    fn inline_svgs_are_decoded_and_checked() {
+        let rect = "data:image/svg+xml;base64,PHN2ZyB4bWxucz0iaHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmciPjxyZWN0IHdpZHRoPSI4IiBoZWlnaHQ9IjgiLz48L3N2Zz4=";
+        assert_eq!(check_svg_data_uri(rect), Ok(()));
+        let script = "data:image/svg+xml;base64,PHN2Zz48c2NyaXB0Lz48L3N2Zz4=";
+        assert!(check_svg_data_uri(script).is_err());
+        assert!(check_svg_data_uri("data:image/svg+xml;utf8,<svg/>").is_err());
+        assert!(check_svg_data_uri("data:text/html;base64,PGh0bWw+").is_err());
+    }
+}